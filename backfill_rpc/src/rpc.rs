@@ -3,7 +3,7 @@ use entities::models::{BufferedTransaction, SignatureWithSlot};
 use flatbuffers::FlatBufferBuilder;
 use futures::{stream, StreamExt, TryStreamExt};
 use interface::error::UsecaseError;
-use interface::slot_getter::FinalizedSlotGetter;
+use interface::slot_getter::{ConfirmedSlotGetter, FinalizedSlotGetter};
 use interface::solana_rpc::TransactionsGetter;
 use plerkle_serialization::serializer::seralize_encoded_transaction_with_status;
 use solana_client::nonblocking::rpc_client::RpcClient;
@@ -178,6 +178,18 @@ impl FinalizedSlotGetter for BackfillRPC {
     }
 }
 
+#[async_trait]
+impl ConfirmedSlotGetter for BackfillRPC {
+    async fn get_confirmed_slot(&self) -> Result<u64, UsecaseError> {
+        Ok(self
+            .client
+            .get_slot_with_commitment(CommitmentConfig {
+                commitment: CommitmentLevel::Confirmed,
+            })
+            .await?)
+    }
+}
+
 #[cfg(feature = "rpc_tests")]
 #[tokio::test]
 async fn test_rpc_get_signatures_by_address() {
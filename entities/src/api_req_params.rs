@@ -1,4 +1,4 @@
-use crate::enums::{Interface, OwnershipModel, RoyaltyModel, TokenType};
+use crate::enums::{AssetEventType, Interface, OwnershipModel, RoyaltyModel, TokenType};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -50,11 +50,21 @@ pub enum AssetSortDirection {
     Desc,
 }
 
+// the commitment level the returned data has to be observed at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Commitment {
+    #[default]
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
 const fn default_show_unverified_collections() -> bool {
     true
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash, JsonSchema, Default)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub struct Options {
     #[serde(default = "default_show_unverified_collections")]
@@ -124,6 +134,34 @@ pub enum SearchConditionType {
     Any,
 }
 
+/// Defines how the `name` filter of `searchAssets` is compared against asset names
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema, Default)]
+pub enum NameMatchType {
+    #[serde(rename = "exact")]
+    #[default]
+    Exact,
+    #[serde(rename = "prefix")]
+    Prefix,
+    #[serde(rename = "fuzzy")]
+    Fuzzy,
+}
+
+/// A single offchain JSON attribute to match, as in `{"trait_type": .., "value": ..}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct SearchAssetAttribute {
+    pub trait_type: String,
+    pub value: String,
+}
+
+/// A single key/value pair of the MPL Core Attributes plugin to match
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct SearchCoreAttribute {
+    pub key: String,
+    pub value: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub struct GetAssetsByGroup {
@@ -149,6 +187,9 @@ pub struct GetAssetsByOwner {
     pub after: Option<String>,
     pub cursor: Option<String>,
     pub options: Option<GetByMethodsOptions>,
+    // the assets owned at the latest slot of the commitment, can't be combined with the fungible tokens
+    #[serde(default)]
+    pub commitment: Option<Commitment>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -156,6 +197,12 @@ pub struct GetAssetsByOwner {
 pub struct GetAsset {
     pub id: String,
     pub options: Option<Options>,
+    // reconstructs the asset as it was at the given slot, requires the asset history to be stored
+    #[serde(default)]
+    pub at_slot: Option<u64>,
+    // returns the asset as it was at the latest slot of the commitment
+    #[serde(default)]
+    pub commitment: Option<Commitment>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -163,12 +210,19 @@ pub struct GetAsset {
 pub struct GetAssetBatch {
     pub ids: Vec<String>,
     pub options: Option<Options>,
+    #[serde(default)]
+    pub at_slot: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub struct GetAssetProof {
     pub id: String,
+    // builds the proof at a prior tree state, requires the changelog history to be stored
+    #[serde(default)]
+    pub root_seq: Option<u64>,
+    #[serde(default)]
+    pub slot: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -251,7 +305,8 @@ pub struct GetTokenAccounts {
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub struct SearchAssets {
-    // todo: negate and condition_type are not used in the current implementation
+    // todo: negate is not used in the current implementation,
+    // condition_type is only applied to the attributes and core_attributes filters
     pub negate: Option<bool>,
     pub condition_type: Option<SearchConditionType>,
     pub interface: Option<Interface>,
@@ -287,7 +342,22 @@ pub struct SearchAssets {
     pub cursor: Option<String>,
     #[serde(default)]
     pub name: Option<String>,
+    #[serde(default)]
+    pub name_match: Option<NameMatchType>,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub attributes: Option<Vec<SearchAssetAttribute>>,
+    // MPL Core plugin types (e.g. "FreezeDelegate", "Oracle"), the asset must have all of them
+    #[serde(default)]
+    pub core_plugins: Option<Vec<String>>,
+    #[serde(default)]
+    pub core_attributes: Option<Vec<SearchCoreAttribute>>,
     pub options: Option<SearchAssetsOptions>,
+    // the assets as they were at the latest slot of the commitment, the owner address
+    // can't be combined with the fungible tokens then
+    #[serde(default)]
+    pub commitment: Option<Commitment>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
@@ -300,6 +370,136 @@ pub struct GetCoreFees {
     pub cursor: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct GetOwnersSnapshot {
+    pub grouping: Option<(String, String)>,
+    pub creator_address: Option<String>,
+    pub creator_verified: Option<bool>,
+    pub limit: Option<u32>,
+    // owners are sorted by the key, pass the cursor of the previous page here
+    pub after: Option<String>,
+    pub options: Option<GetByMethodsOptions>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct RefreshAssetMetadata {
+    // assets whose offchain metadata should be downloaded again
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct GetDeadLetters {
+    pub limit: Option<u32>,
+    // dead letters are sorted by the id, pass the cursor of the previous page here
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct AssetSubscribe {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct OwnerSubscribe {
+    pub owner_address: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct GroupSubscribe {
+    // only the collection grouping is supported
+    pub group_key: String,
+    pub group_value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct Unsubscribe {
+    pub subscription: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct GetTreeGapRepairs {
+    pub limit: Option<u32>,
+    // repairs are sorted by the tree, pass the cursor of the previous page here
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct GetDeadLetter {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct RedriveDeadLetters {
+    // dead letters which should be fed back into the processors
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct RegisterWebhook {
+    // the notifications are POSTed to this URL
+    pub url: String,
+    // key of the HMAC signature of the notifications, generated if not passed
+    pub secret: Option<String>,
+    // the lists filter the asset events, a missing or empty list matches any value
+    #[serde(default)]
+    pub owners: Vec<String>,
+    #[serde(default)]
+    pub collections: Vec<String>,
+    #[serde(default)]
+    pub creators: Vec<String>,
+    #[serde(default)]
+    pub trees: Vec<String>,
+    #[serde(default)]
+    pub asset_ids: Vec<String>,
+    #[serde(default)]
+    pub event_types: Vec<AssetEventType>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct GetWebhooks {
+    pub limit: Option<u32>,
+    // webhooks are sorted by the id, pass the cursor of the previous page here
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct DeleteWebhook {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct GetNftEditions {
+    // mint of the master edition NFT
+    pub mint_address: String,
+    pub limit: Option<u32>,
+    pub page: Option<u32>,
+    // before, after and cursor are edition numbers
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct GetTreeInfo {
+    // address of the merkle tree account
+    pub tree: String,
+}
+
 impl SearchAssets {
     pub fn extract_some_fields(&self) -> String {
         let mut result = String::new();
@@ -340,6 +540,11 @@ impl SearchAssets {
         check_and_append(&mut result, &self.json_uri, "json_uri");
         check_and_append(&mut result, &self.cursor, "cursor");
         check_and_append(&mut result, &self.name, "name");
+        check_and_append(&mut result, &self.name_match, "name_match");
+        check_and_append(&mut result, &self.symbol, "symbol");
+        check_and_append(&mut result, &self.attributes, "attributes");
+        check_and_append(&mut result, &self.core_plugins, "core_plugins");
+        check_and_append(&mut result, &self.core_attributes, "core_attributes");
         check_and_append(&mut result, &self.token_type, "token_type");
 
         if result.is_empty() {
@@ -412,10 +617,17 @@ impl From<SearchAssetsV0> for SearchAssets {
             json_uri: value.json_uri,
             cursor: None,
             name: None,
+            name_match: None,
+            symbol: None,
+            attributes: None,
+            core_plugins: None,
+            core_attributes: None,
             options: Some(SearchAssetsOptions {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+
+            commitment: None,
         }
     }
 }
@@ -436,6 +648,8 @@ impl From<GetAssetV0> for GetAsset {
                 show_inscription: false,
                 show_fungible: false,
             }),
+            at_slot: None,
+            commitment: None,
         }
     }
 }
@@ -456,6 +670,7 @@ impl From<GetAssetBatchV0> for GetAssetBatch {
                 show_inscription: false,
                 show_fungible: false,
             }),
+            at_slot: None,
         }
     }
 }
@@ -545,6 +760,7 @@ impl From<GetAssetsByOwnerV0> for GetAssetsByOwner {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            commitment: None,
         }
     }
 }
@@ -2,6 +2,7 @@ use crate::models::{
     BurntMetadataSlot, CoreAssetFee, EditionMetadata, EditionV1, IndexableAssetWithAccountInfo,
     InscriptionDataInfo, InscriptionInfo, MasterEdition, MetadataInfo, Mint, TokenAccount,
 };
+use mpl_bubblegum::InstructionName;
 use num_derive::FromPrimitive;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -372,6 +373,69 @@ pub enum UnprocessedAccount {
     MplCoreFee(CoreAssetFee),
 }
 
+impl UnprocessedAccount {
+    /// Slot and write version of the account update
+    pub fn update_version(&self) -> (u64, u64) {
+        match self {
+            UnprocessedAccount::MetadataInfo(info) => (info.slot_updated, info.write_version),
+            UnprocessedAccount::Token(token) => (token.slot_updated as u64, token.write_version),
+            UnprocessedAccount::Mint(mint) => (mint.slot_updated as u64, mint.write_version),
+            UnprocessedAccount::Edition(edition) => (edition.slot_updated, edition.write_version),
+            UnprocessedAccount::BurnMetadata(burnt) | UnprocessedAccount::BurnMplCore(burnt) => {
+                (burnt.slot_updated, burnt.write_version)
+            }
+            UnprocessedAccount::MplCore(asset) => (asset.slot_updated, asset.write_version),
+            UnprocessedAccount::Inscription(inscription) => {
+                (inscription.slot_updated, inscription.write_version)
+            }
+            UnprocessedAccount::InscriptionData(data) => (data.slot_updated, data.write_version),
+            UnprocessedAccount::MplCoreFee(fee) => (fee.slot_updated, fee.write_version),
+        }
+    }
+
+    /// What the account is processed as once it's closed
+    pub fn tombstone(&self) -> AccountTombstone {
+        match self {
+            UnprocessedAccount::MetadataInfo(_) | UnprocessedAccount::BurnMetadata(_) => {
+                AccountTombstone::BurntMetadata
+            }
+            UnprocessedAccount::MplCore(_)
+            | UnprocessedAccount::BurnMplCore(_)
+            | UnprocessedAccount::MplCoreFee(_) => AccountTombstone::BurntMplCore,
+            _ => AccountTombstone::Unknown,
+        }
+    }
+}
+
+/// Update replacing the state of a closed account, e.g. the one missing
+/// at the confirmed commitment after its forked update is rolled back
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum AccountTombstone {
+    // the closed account is kept as is
+    #[default]
+    Unknown,
+    BurntMetadata,
+    BurntMplCore,
+}
+
+impl AccountTombstone {
+    pub fn to_unprocessed_account(
+        self,
+        slot: u64,
+        write_version: u64,
+    ) -> Option<UnprocessedAccount> {
+        let burnt = BurntMetadataSlot {
+            slot_updated: slot,
+            write_version,
+        };
+        match self {
+            AccountTombstone::Unknown => None,
+            AccountTombstone::BurntMetadata => Some(UnprocessedAccount::BurnMetadata(burnt)),
+            AccountTombstone::BurntMplCore => Some(UnprocessedAccount::BurnMplCore(burnt)),
+        }
+    }
+}
+
 impl From<UnprocessedAccount> for &str {
     fn from(value: UnprocessedAccount) -> Self {
         match value {
@@ -400,3 +464,54 @@ pub enum TokenType {
     CompressedNFT,
     All,
 }
+
+/// Changes of an asset the webhooks can be notified about
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetEventType {
+    Mint,
+    Transfer,
+    Burn,
+    MetadataUpdate,
+}
+
+impl AssetEventType {
+    /// Asset event made by the Bubblegum instruction, if the instruction makes any
+    pub fn from_instruction(instruction: &InstructionName) -> Option<Self> {
+        match instruction {
+            InstructionName::MintV1 | InstructionName::MintToCollectionV1 => Some(Self::Mint),
+            InstructionName::Transfer => Some(Self::Transfer),
+            InstructionName::Burn => Some(Self::Burn),
+            InstructionName::UpdateMetadata
+            | InstructionName::VerifyCreator
+            | InstructionName::UnverifyCreator
+            | InstructionName::VerifyCollection
+            | InstructionName::UnverifyCollection
+            | InstructionName::SetAndVerifyCollection => Some(Self::MetadataUpdate),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Mint => "mint",
+            Self::Transfer => "transfer",
+            Self::Burn => "burn",
+            Self::MetadataUpdate => "metadataUpdate",
+        }
+    }
+}
+
+impl std::str::FromStr for AssetEventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mint" => Ok(Self::Mint),
+            "transfer" => Ok(Self::Transfer),
+            "burn" => Ok(Self::Burn),
+            "metadataUpdate" => Ok(Self::MetadataUpdate),
+            _ => Err(format!("Unknown asset event type: {}", s)),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use crate::enums::{
-    BatchMintState, ChainMutability, FailedBatchMintState, OwnerType, PersistingBatchMintState,
-    RoyaltyTargetType, SpecificationAssetClass, SpecificationVersions, TaskStatus,
-    TokenMetadataEdition, TokenStandard, UnprocessedAccount, UseMethod,
+    AccountTombstone, AssetEventType, BatchMintState, ChainMutability, FailedBatchMintState,
+    OwnerType, PersistingBatchMintState, RoyaltyTargetType, SpecificationAssetClass,
+    SpecificationVersions, TaskStatus, TokenMetadataEdition, TokenStandard, UnprocessedAccount,
+    UseMethod,
 };
 use base64::engine::general_purpose;
 use base64::Engine;
@@ -70,6 +71,11 @@ pub struct AssetIndex {
     pub update_authority: Option<Pubkey>,
     pub slot_updated: i64,
     pub fungible_tokens: Vec<FungibleToken>,
+    pub name: Option<String>,
+    // MPL Core plugin types, including the external plugin adapters, in snake_case
+    pub core_plugins: Vec<String>,
+    // (key, value) pairs of the MPL Core Attributes plugin
+    pub core_attributes: Vec<(String, String)>,
 }
 
 /// FungibleToken is associated token account
@@ -293,7 +299,7 @@ pub struct SignatureWithSlot {
     pub slot: u64,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone, Debug)]
 pub struct TreeState {
     pub tree: Pubkey,
     pub seq: u64,
@@ -337,6 +343,14 @@ pub struct ForkedItem {
     pub node_idx: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotAccountUpdate {
+    pub slot: u64,
+    pub pubkey: Pubkey,
+    pub write_version: u64,
+    pub tombstone: AccountTombstone,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct AssetSignatureKey {
     pub tree: Pubkey,
@@ -354,8 +368,8 @@ pub struct AssetSignature {
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct AssetSignatureWithPagination {
     pub asset_signatures: Vec<AssetSignature>,
-    pub before: Option<u64>,
-    pub after: Option<u64>,
+    pub before: Option<String>,
+    pub after: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq)]
@@ -419,6 +433,34 @@ pub struct JsonDownloadTask {
     pub status: TaskStatus,
     pub attempts: i16,
     pub max_attempts: i16,
+    // validators of the previously downloaded file, set for the refreshed tasks
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Downloaded offchain JSON along with the HTTP validators to re-download it only if modified
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DownloadedJson {
+    pub metadata: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    // the gateway the file was downloaded through
+    pub gateway: Option<String>,
+}
+
+impl DownloadedJson {
+    pub fn new(metadata: String) -> Self {
+        Self {
+            metadata,
+            ..Default::default()
+        }
+    }
+
+    pub fn content_hash(&self) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.metadata);
+        hasher.finalize().to_vec()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -460,6 +502,8 @@ impl Default for JsonDownloadTask {
             status: TaskStatus::Pending,
             attempts: 1,
             max_attempts: 10,
+            etag: None,
+            last_modified: None,
         }
     }
 }
@@ -602,6 +646,29 @@ pub struct BufferedTxWithID {
     pub id: String,
 }
 
+/// Asset events a webhook is notified about, an empty list matches any value
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WebhookFilter {
+    pub owners: Vec<Pubkey>,
+    pub collections: Vec<Pubkey>,
+    pub creators: Vec<Pubkey>,
+    pub trees: Vec<Pubkey>,
+    pub asset_ids: Vec<Pubkey>,
+    pub event_types: Vec<AssetEventType>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Webhook {
+    pub id: u64,
+    pub url: String,
+    // key of the HMAC signature of the notifications
+    pub secret: String,
+    pub filter: WebhookFilter,
+    // name of the API key which registered the webhook
+    pub key_name: String,
+    pub created_at: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SplMint {
     pub pubkey: Pubkey,
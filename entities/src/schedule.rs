@@ -47,6 +47,19 @@ impl ScheduledJob {
             .unwrap_or(false)
     }
 
+    /// Number of seconds left until the next run of a periodic job, None for one time jobs.
+    pub fn seconds_to_next_run(&self) -> Option<u64> {
+        self.run_interval_sec.map(|sec_interval| {
+            let since_the_epoch = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            (self.last_run_epoch_time + sec_interval)
+                .saturating_sub(since_the_epoch)
+                .max(1)
+        })
+    }
+
     /// Check if the given schedule belongs to a one-time job, that has been finished already.
     pub fn wont_run_again(&self) -> bool {
         matches!(self.last_run_status, JobRunState::Finished) && self.run_interval_sec.is_none()
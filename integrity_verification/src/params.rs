@@ -89,6 +89,7 @@ pub fn generate_get_assets_by_owner_params(
         after,
         cursor: None,
         options: None,
+        commitment: None,
     }
 }
 
@@ -139,9 +140,18 @@ pub fn generate_get_assets_by_authority_params(
 }
 
 pub fn generate_get_asset_params(id: String) -> GetAsset {
-    GetAsset { id, options: None }
+    GetAsset {
+        id,
+        options: None,
+        at_slot: None,
+        commitment: None,
+    }
 }
 
 pub fn generate_get_asset_proof_params(id: String) -> GetAssetProof {
-    GetAssetProof { id }
+    GetAssetProof {
+        id,
+        root_seq: None,
+        slot: None,
+    }
 }
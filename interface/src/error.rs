@@ -134,6 +134,11 @@ pub enum JsonDownloaderError {
     CouldNotReadHeader,
     ErrorStatusCode(String),
     ErrorDownloading(String),
+    // the file didn't change since the previous download
+    NotModified,
+    // every host of the file is backed off by its circuit breaker for at least the given time,
+    // so nothing was requested
+    HostBackedOff(std::time::Duration),
     IndexStorageError(String),
     MainStorageError(String),
 }
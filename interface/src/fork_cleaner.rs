@@ -1,5 +1,6 @@
 use async_trait::async_trait;
-use entities::models::{ClItem, ForkedItem, LeafSignatureAllData};
+use entities::models::{ClItem, ForkedItem, LeafSignatureAllData, SlotAccountUpdate};
+use mockall::automock;
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use std::collections::HashSet;
 use tokio::sync::broadcast::Receiver;
@@ -11,6 +12,8 @@ pub trait CompressedTreeChangesManager {
     async fn delete_tree_seq_idx(&self, keys: Vec<ForkedItem>);
     async fn delete_cl_items(&self, keys: Vec<ForkedItem>);
     async fn delete_signatures(&self, keys: Vec<(Signature, Pubkey, u64)>);
+    fn cl_items_history_iter(&self) -> impl Iterator<Item = ClItem>;
+    async fn delete_cl_items_history(&self, items: Vec<ClItem>);
 }
 
 #[async_trait]
@@ -18,3 +21,21 @@ pub trait ForkChecker {
     fn get_all_non_forked_slots(&self, rx: Receiver<()>) -> HashSet<u64>;
     fn last_slot_for_check(&self) -> u64;
 }
+
+#[async_trait]
+pub trait AccountChangesManager {
+    fn account_updates_iter(&self) -> impl Iterator<Item = SlotAccountUpdate>;
+    fn last_account_update_slot(&self) -> Option<u64>;
+    async fn delete_account_updates(&self, keys: Vec<(u64, Pubkey)>);
+    async fn delete_account_updates_before(&self, slot: u64);
+    fn asset_account_signatures_iter(&self) -> impl Iterator<Item = (Pubkey, u64, Signature)>;
+    async fn delete_asset_account_signatures(&self, keys: Vec<(Pubkey, u64, Signature)>);
+}
+
+#[automock]
+#[async_trait]
+pub trait ForkedAccountsRollback {
+    /// Restores the confirmed state of the accounts which were updated in the forked slots,
+    /// returns the number of the accounts restored
+    async fn rollback_accounts(&self, accounts: Vec<SlotAccountUpdate>) -> usize;
+}
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use entities::models::DownloadedJson;
 use mockall::automock;
 
 #[automock]
@@ -13,6 +14,9 @@ pub trait JsonDownloader {
 pub trait JsonPersister {
     async fn persist_response(
         &self,
-        results: Vec<(String, Result<String, crate::error::JsonDownloaderError>)>,
+        results: Vec<(
+            String,
+            Result<DownloadedJson, crate::error::JsonDownloaderError>,
+        )>,
     ) -> Result<(), crate::error::JsonDownloaderError>;
 }
@@ -9,6 +9,12 @@ pub trait FinalizedSlotGetter {
     async fn get_finalized_slot_no_error(&self) -> u64;
 }
 
+#[automock]
+#[async_trait]
+pub trait ConfirmedSlotGetter {
+    async fn get_confirmed_slot(&self) -> Result<u64, UsecaseError>;
+}
+
 #[async_trait]
 pub trait LastProcessedSlotGetter {
     async fn get_last_ingested_slot(&self) -> Result<Option<u64>, StorageError>;
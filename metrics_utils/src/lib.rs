@@ -91,6 +91,18 @@ pub struct MethodLabel {
     pub method_name: String,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct ApiKeyLabel {
+    pub key: String,
+    pub method_name: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct ApiKeyRejectionLabel {
+    pub key: String,
+    pub reason: String,
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
 pub struct MetricLabel {
     pub name: String,
@@ -311,6 +323,11 @@ pub struct ApiMetricsConfig {
     proof_checks: Family<MetricLabelWithStatus, Counter>,
     search_asset_latency: Family<MethodLabel, Histogram>,
     token_info_fetch_errors: Family<MethodLabel, Counter>,
+    api_key_requests: Family<ApiKeyLabel, Counter>,
+    api_key_cost: Family<ApiKeyLabel, Counter>,
+    api_key_rejections: Family<ApiKeyRejectionLabel, Counter>,
+    asset_cache_requests: Family<MetricLabel, Counter>,
+    asset_cache_size: Gauge,
 }
 
 impl ApiMetricsConfig {
@@ -327,6 +344,11 @@ impl ApiMetricsConfig {
                 Histogram::new(exponential_buckets(20.0, 1.8, 10))
             }),
             token_info_fetch_errors: Family::<MethodLabel, Counter>::default(),
+            api_key_requests: Family::<ApiKeyLabel, Counter>::default(),
+            api_key_cost: Family::<ApiKeyLabel, Counter>::default(),
+            api_key_rejections: Family::<ApiKeyRejectionLabel, Counter>::default(),
+            asset_cache_requests: Family::<MetricLabel, Counter>::default(),
+            asset_cache_size: Default::default(),
         }
     }
 
@@ -354,6 +376,36 @@ impl ApiMetricsConfig {
             .inc()
     }
 
+    pub fn inc_api_key_requests(&self, key: &str, method_name: &str, cost: u64) -> u64 {
+        let label = ApiKeyLabel {
+            key: key.to_owned(),
+            method_name: method_name.to_owned(),
+        };
+        self.api_key_cost.get_or_create(&label).inc_by(cost);
+        self.api_key_requests.get_or_create(&label).inc()
+    }
+
+    pub fn inc_api_key_rejections(&self, key: &str, reason: &str) -> u64 {
+        self.api_key_rejections
+            .get_or_create(&ApiKeyRejectionLabel {
+                key: key.to_owned(),
+                reason: reason.to_owned(),
+            })
+            .inc()
+    }
+
+    pub fn inc_asset_cache_requests(&self, result: &str) -> u64 {
+        self.asset_cache_requests
+            .get_or_create(&MetricLabel {
+                name: result.to_owned(),
+            })
+            .inc()
+    }
+
+    pub fn set_asset_cache_size(&self, size: u64) -> i64 {
+        self.asset_cache_size.set(size as i64)
+    }
+
     pub fn start_time(&self) -> i64 {
         self.start_time.set(Utc::now().timestamp())
     }
@@ -419,6 +471,31 @@ impl ApiMetricsConfig {
             "The number of errors while fetching token info",
             self.token_info_fetch_errors.clone(),
         );
+        registry.register(
+            "api_key_requests",
+            "The number of requests made with an API key",
+            self.api_key_requests.clone(),
+        );
+        registry.register(
+            "api_key_cost",
+            "The quota cost spent by the requests made with an API key",
+            self.api_key_cost.clone(),
+        );
+        registry.register(
+            "api_key_rejections",
+            "The number of requests rejected by the API key check",
+            self.api_key_rejections.clone(),
+        );
+        registry.register(
+            "api_asset_cache_requests",
+            "The number of assets looked up in the response cache by the result",
+            self.asset_cache_requests.clone(),
+        );
+        registry.register(
+            "api_asset_cache_size",
+            "The number of asset responses in the cache",
+            self.asset_cache_size.clone(),
+        );
     }
 }
 
@@ -695,6 +772,10 @@ pub struct IngesterMetricsConfig {
     instructions: Family<MetricLabel, Counter>,
     accounts: Family<MetricLabel, Counter>,
     last_processed_slot: Family<MetricLabel, Gauge>,
+    dead_letters: Family<MetricLabel, Counter>,
+    dead_letters_depth: Gauge,
+    webhook_deliveries: Family<MetricLabel, Counter>,
+    webhook_outbox_depth: Gauge,
 }
 
 impl IngesterMetricsConfig {
@@ -716,6 +797,10 @@ impl IngesterMetricsConfig {
             instructions: Family::<MetricLabel, Counter>::default(),
             accounts: Family::<MetricLabel, Counter>::default(),
             last_processed_slot: Family::<MetricLabel, Gauge>::default(),
+            dead_letters: Family::<MetricLabel, Counter>::default(),
+            dead_letters_depth: Default::default(),
+            webhook_deliveries: Family::<MetricLabel, Counter>::default(),
+            webhook_outbox_depth: Default::default(),
         }
     }
 
@@ -783,6 +868,30 @@ impl IngesterMetricsConfig {
             .set(slot)
     }
 
+    pub fn inc_dead_letters(&self, label: &str) -> u64 {
+        self.dead_letters
+            .get_or_create(&MetricLabel {
+                name: label.to_owned(),
+            })
+            .inc()
+    }
+
+    pub fn set_dead_letters_depth(&self, depth: i64) -> i64 {
+        self.dead_letters_depth.set(depth)
+    }
+
+    pub fn inc_webhook_deliveries(&self, label: &str) -> u64 {
+        self.webhook_deliveries
+            .get_or_create(&MetricLabel {
+                name: label.to_owned(),
+            })
+            .inc()
+    }
+
+    pub fn set_webhook_outbox_depth(&self, depth: i64) -> i64 {
+        self.webhook_outbox_depth.set(depth)
+    }
+
     pub fn register(&self, registry: &mut Registry) {
         self.start_time();
         registry.register(
@@ -827,6 +936,26 @@ impl IngesterMetricsConfig {
             "The last processed slot by ingester",
             self.last_processed_slot.clone(),
         );
+        registry.register(
+            "ingester_dead_letters",
+            "Total number of messages moved to the dead letters",
+            self.dead_letters.clone(),
+        );
+        registry.register(
+            "ingester_dead_letters_depth",
+            "Number of the stored dead letters",
+            self.dead_letters_depth.clone(),
+        );
+        registry.register(
+            "ingester_webhook_deliveries",
+            "Total number of webhook notification attempts by the outcome",
+            self.webhook_deliveries.clone(),
+        );
+        registry.register(
+            "ingester_webhook_outbox_depth",
+            "Number of the webhook notifications waiting to be sent",
+            self.webhook_outbox_depth.clone(),
+        );
     }
 }
 
@@ -842,6 +971,11 @@ pub struct JsonDownloaderMetricsConfig {
     latency_task_executed: Family<MetricLabel, Histogram>,
     tasks: Family<MetricLabelWithStatus, Counter>,
     tasks_to_execute: Gauge,
+    gateway_requests: Family<MetricLabelWithStatus, Counter>,
+    domain_requests: Family<MetricLabelWithStatus, Counter>,
+    domain_rejected_requests: Family<MetricLabel, Counter>,
+    domain_circuit_open: Family<MetricLabel, Gauge>,
+    domain_latency: Family<MetricLabel, Histogram>,
 }
 
 impl JsonDownloaderMetricsConfig {
@@ -850,6 +984,13 @@ impl JsonDownloaderMetricsConfig {
             tasks: Family::<MetricLabelWithStatus, Counter>::default(),
             start_time: Default::default(),
             tasks_to_execute: Default::default(),
+            gateway_requests: Family::<MetricLabelWithStatus, Counter>::default(),
+            domain_requests: Family::<MetricLabelWithStatus, Counter>::default(),
+            domain_rejected_requests: Family::<MetricLabel, Counter>::default(),
+            domain_circuit_open: Family::<MetricLabel, Gauge>::default(),
+            domain_latency: Family::<MetricLabel, Histogram>::new_with_constructor(|| {
+                Histogram::new([100.0, 500.0, 1000.0, 2000.0, 5000.0].into_iter())
+            }),
             latency_task_executed: Family::<MetricLabel, Histogram>::new_with_constructor(|| {
                 Histogram::new([100.0, 500.0, 1000.0, 2000.0].into_iter())
             }),
@@ -865,6 +1006,48 @@ impl JsonDownloaderMetricsConfig {
             .inc()
     }
 
+    pub fn inc_gateway_requests(&self, gateway: &str, status: MetricStatus) -> u64 {
+        self.gateway_requests
+            .get_or_create(&MetricLabelWithStatus {
+                name: gateway.to_string(),
+                status,
+            })
+            .inc()
+    }
+
+    pub fn inc_domain_requests(&self, domain: &str, status: MetricStatus) -> u64 {
+        self.domain_requests
+            .get_or_create(&MetricLabelWithStatus {
+                name: domain.to_string(),
+                status,
+            })
+            .inc()
+    }
+
+    pub fn inc_domain_rejected_requests(&self, domain: &str) -> u64 {
+        self.domain_rejected_requests
+            .get_or_create(&MetricLabel {
+                name: domain.to_string(),
+            })
+            .inc()
+    }
+
+    pub fn set_domain_circuit_open(&self, domain: &str, is_open: bool) -> i64 {
+        self.domain_circuit_open
+            .get_or_create(&MetricLabel {
+                name: domain.to_string(),
+            })
+            .set(is_open as i64)
+    }
+
+    pub fn set_domain_latency(&self, domain: &str, duration: f64) {
+        self.domain_latency
+            .get_or_create(&MetricLabel {
+                name: domain.to_string(),
+            })
+            .observe(duration);
+    }
+
     pub fn set_tasks_to_execute(&self, count: i64) -> i64 {
         self.tasks_to_execute.set(count)
     }
@@ -902,6 +1085,36 @@ impl JsonDownloaderMetricsConfig {
             self.tasks_to_execute.clone(),
         );
 
+        registry.register(
+            "json_downloader_gateway_requests",
+            "The number of downloads made through each gateway",
+            self.gateway_requests.clone(),
+        );
+
+        registry.register(
+            "json_downloader_domain_requests",
+            "The number of requests made to each domain",
+            self.domain_requests.clone(),
+        );
+
+        registry.register(
+            "json_downloader_domain_rejected_requests",
+            "The number of requests skipped because the domain is backed off",
+            self.domain_rejected_requests.clone(),
+        );
+
+        registry.register(
+            "json_downloader_domain_circuit_open",
+            "Whether the requests to the domain are backed off",
+            self.domain_circuit_open.clone(),
+        );
+
+        registry.register(
+            "json_downloader_domain_latency",
+            "A histogram of request time per domain",
+            self.domain_latency.clone(),
+        );
+
         registry.register(
             "json_downloader_start_time",
             "Binary start time",
@@ -1032,6 +1245,8 @@ pub struct SequenceConsistentGapfillMetricsConfig {
     total_tree_with_gaps: Gauge,
     total_scans: Counter,
     scans_latency: Histogram,
+    trees_by_repair_state: Family<MetricLabel, Gauge>,
+    gap_repair_attempts: Family<MetricLabelWithStatus, Counter>,
 }
 
 impl Default for SequenceConsistentGapfillMetricsConfig {
@@ -1047,6 +1262,8 @@ impl SequenceConsistentGapfillMetricsConfig {
             total_tree_with_gaps: Default::default(),
             total_scans: Default::default(),
             scans_latency: Histogram::new(exponential_buckets(1.0, 2.0, 12)),
+            trees_by_repair_state: Family::<MetricLabel, Gauge>::default(),
+            gap_repair_attempts: Family::<MetricLabelWithStatus, Counter>::default(),
         }
     }
 
@@ -1056,6 +1273,21 @@ impl SequenceConsistentGapfillMetricsConfig {
     pub fn set_total_tree_with_gaps(&self, count: i64) -> i64 {
         self.total_tree_with_gaps.set(count)
     }
+    pub fn set_trees_by_repair_state(&self, state: &str, count: i64) -> i64 {
+        self.trees_by_repair_state
+            .get_or_create(&MetricLabel {
+                name: state.to_owned(),
+            })
+            .set(count)
+    }
+    pub fn inc_gap_repair_attempts(&self, status: MetricStatus) -> u64 {
+        self.gap_repair_attempts
+            .get_or_create(&MetricLabelWithStatus {
+                name: "gap_repair".to_owned(),
+                status,
+            })
+            .inc()
+    }
     pub fn inc_total_scans(&self) -> u64 {
         self.total_scans.inc()
     }
@@ -1086,6 +1318,18 @@ impl SequenceConsistentGapfillMetricsConfig {
             "A histogram of inconsistent trees scans latency",
             self.scans_latency.clone(),
         );
+
+        registry.register(
+            "trees_by_gap_repair_state",
+            "Count of trees in each state of the automatic gap repair",
+            self.trees_by_repair_state.clone(),
+        );
+
+        registry.register(
+            "tree_gap_repair_attempts",
+            "Total count of the tree gap repair attempts",
+            self.gap_repair_attempts.clone(),
+        );
     }
 }
 
@@ -1096,6 +1340,7 @@ pub struct ForkCleanerMetricsConfig {
     scans_latency: Histogram,
     forks_detected: Gauge,
     deleted_items: Counter,
+    rolled_back_accounts: Counter,
 }
 
 impl Default for ForkCleanerMetricsConfig {
@@ -1112,6 +1357,7 @@ impl ForkCleanerMetricsConfig {
             scans_latency: Histogram::new(exponential_buckets(1.0, 2.0, 12)),
             forks_detected: Default::default(),
             deleted_items: Default::default(),
+            rolled_back_accounts: Default::default(),
         }
     }
     pub fn start_time(&self) -> i64 {
@@ -1129,6 +1375,9 @@ impl ForkCleanerMetricsConfig {
     pub fn inc_by_deleted_items(&self, count: u64) -> u64 {
         self.deleted_items.inc_by(count)
     }
+    pub fn inc_by_rolled_back_accounts(&self, count: u64) -> u64 {
+        self.rolled_back_accounts.inc_by(count)
+    }
     pub fn register(&self, registry: &mut Registry) {
         registry.register(
             "fork_cleaner_start_time",
@@ -1158,6 +1407,11 @@ impl ForkCleanerMetricsConfig {
             "Total count of deleted cl items",
             self.deleted_items.clone(),
         );
+        registry.register(
+            "rolled_back_accounts",
+            "Total count of accounts restored after they were updated in forked slots",
+            self.rolled_back_accounts.clone(),
+        );
     }
 }
 
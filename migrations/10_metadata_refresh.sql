@@ -0,0 +1,8 @@
+-- refresh policy of the downloaded offchain JSONs: successful tasks are re-enqueued once tsk_next_refresh_at passes,
+-- the HTTP validators are sent along to download the file only if it was modified,
+-- and the content hash lets skip the storage updates if the file is the same
+ALTER TABLE tasks ADD COLUMN tsk_next_refresh_at timestamptz NULL;
+ALTER TABLE tasks ADD COLUMN tsk_etag text NULL;
+ALTER TABLE tasks ADD COLUMN tsk_last_modified text NULL;
+ALTER TABLE tasks ADD COLUMN tsk_content_hash bytea NULL;
+CREATE INDEX tasks_next_refresh_at ON tasks (tsk_next_refresh_at) WHERE tsk_next_refresh_at IS NOT NULL;
@@ -0,0 +1,6 @@
+-- MPL Core plugin types (internal plugins and external plugin adapters, snake_case)
+-- and the key/value pairs of the on-chain Attributes plugin as [{"key": .., "value": ..}]
+ALTER TABLE assets_v3 ADD COLUMN ast_core_plugins text[];
+ALTER TABLE assets_v3 ADD COLUMN ast_core_attributes jsonb;
+CREATE INDEX assets_v3_core_plugins ON assets_v3 USING gin (ast_core_plugins) WHERE ast_core_plugins IS NOT NULL;
+CREATE INDEX assets_v3_core_attributes ON assets_v3 USING gin (ast_core_attributes jsonb_path_ops) WHERE ast_core_attributes IS NOT NULL;
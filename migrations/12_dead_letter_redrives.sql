@@ -0,0 +1,6 @@
+-- dead letters stored in RocksDB by the ingester which were asked to be processed again,
+-- the ingester takes the ids from here as the API may only have a read-only RocksDB replica
+CREATE TABLE dead_letter_redrives (
+    dlr_id bigint NOT NULL PRIMARY KEY,
+    dlr_requested_at timestamptz NOT NULL DEFAULT NOW()
+);
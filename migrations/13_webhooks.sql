@@ -0,0 +1,15 @@
+-- webhooks registered via the API, the ingester matches the asset events against their filters
+-- and sends the notifications, as the API may only have a read-only RocksDB replica.
+-- An empty filter list matches any value
+CREATE TABLE webhooks (
+    whk_id bigserial NOT NULL PRIMARY KEY,
+    whk_url text NOT NULL,
+    whk_secret text NOT NULL,
+    whk_owners bytea[] NOT NULL DEFAULT '{}',
+    whk_collections bytea[] NOT NULL DEFAULT '{}',
+    whk_creators bytea[] NOT NULL DEFAULT '{}',
+    whk_trees bytea[] NOT NULL DEFAULT '{}',
+    whk_asset_ids bytea[] NOT NULL DEFAULT '{}',
+    whk_event_types text[] NOT NULL DEFAULT '{}',
+    whk_created_at timestamptz NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,5 @@
+-- webhooks belong to the API key which registered them, by the name of the key,
+-- so the key only lists and deletes its own ones and they survive a rotation of the key.
+-- The webhooks registered before have no key and are only seen by the ingester
+ALTER TABLE webhooks ADD COLUMN whk_key_name text NOT NULL DEFAULT '';
+CREATE INDEX webhooks_key_name_idx ON webhooks (whk_key_name, whk_id);
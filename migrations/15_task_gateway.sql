@@ -0,0 +1,2 @@
+-- the gateway the offchain JSON was downloaded through, e.g. to find the gateways serving stale or broken files
+ALTER TABLE tasks ADD COLUMN tsk_gateway text NULL;
@@ -0,0 +1,6 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE assets_v3 ADD COLUMN ast_name text;
+-- exact matches use the btree index, prefix (ILIKE) and fuzzy (similarity) matches use the trigram one
+CREATE INDEX assets_v3_name ON assets_v3(ast_name) WHERE ast_name IS NOT NULL;
+CREATE INDEX assets_v3_name_trgm ON assets_v3 USING gin (ast_name gin_trgm_ops) WHERE ast_name IS NOT NULL;
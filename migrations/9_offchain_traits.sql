@@ -0,0 +1,15 @@
+-- symbol and attributes extracted from the downloaded offchain JSON, keyed by the tasks id (the metadata url hash),
+-- so a single row is shared by all the assets pointing to the same metadata url
+CREATE TABLE offchain_symbols (
+    ofs_metadata_url_id bytea NOT NULL PRIMARY KEY,
+    ofs_symbol text NOT NULL
+);
+CREATE INDEX offchain_symbols_symbol ON offchain_symbols(ofs_symbol);
+
+CREATE TABLE offchain_attributes (
+    ofa_metadata_url_id bytea NOT NULL,
+    ofa_trait_type text NOT NULL,
+    ofa_value text NOT NULL,
+    CONSTRAINT offchain_attributes_pkey PRIMARY KEY (ofa_metadata_url_id, ofa_trait_type, ofa_value)
+);
+CREATE INDEX offchain_attributes_trait_type_value ON offchain_attributes(ofa_trait_type, ofa_value);
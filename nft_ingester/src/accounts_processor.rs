@@ -1,3 +1,4 @@
+use crate::dead_letters::DeadLetterQueue;
 use crate::error::IngesterError;
 use crate::inscriptions_processor::InscriptionsProcessor;
 use crate::mpl_core_fee_indexing_processor::MplCoreFeeProcessor;
@@ -10,6 +11,8 @@ use interface::unprocessed_data_getter::UnprocessedAccountsGetter;
 use metrics_utils::IngesterMetricsConfig;
 use postgre_client::PgClient;
 use rocks_db::batch_savers::BatchSaveStorage;
+use rocks_db::errors::StorageError;
+use rocks_db::webhooks::{AssetEvent, AssetStateChange};
 use rocks_db::Storage;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_program::pubkey::Pubkey;
@@ -39,6 +42,7 @@ pub async fn run_accounts_processor<AG: UnprocessedAccountsGetter + Sync + Send
     postgre_client: Arc<PgClient>,
     rpc_client: Arc<RpcClient>,
     join_set: Arc<Mutex<JoinSet<Result<(), JoinError>>>>,
+    dead_letters: Arc<DeadLetterQueue>,
 ) {
     mutexed_tasks.lock().await.spawn(async move {
         let account_processor = AccountsProcessor::build(
@@ -49,6 +53,7 @@ pub async fn run_accounts_processor<AG: UnprocessedAccountsGetter + Sync + Send
             postgre_client,
             rpc_client,
             join_set,
+            dead_letters,
         )
         .await
         .expect("Failed to build 'AccountsProcessor'!");
@@ -70,6 +75,7 @@ pub struct AccountsProcessor<T: UnprocessedAccountsGetter> {
     inscription_processor: InscriptionsProcessor,
     core_fees_processor: MplCoreFeeProcessor,
     metrics: Arc<IngesterMetricsConfig>,
+    dead_letters: Arc<DeadLetterQueue>,
 }
 
 // AccountsProcessor responsible for processing all account updates received
@@ -87,6 +93,7 @@ impl<T: UnprocessedAccountsGetter> AccountsProcessor<T> {
         postgre_client: Arc<PgClient>,
         rpc_client: Arc<RpcClient>,
         join_set: Arc<Mutex<JoinSet<Result<(), tokio::task::JoinError>>>>,
+        dead_letters: Arc<DeadLetterQueue>,
     ) -> Result<Self, IngesterError> {
         let mplx_accounts_processor = MplxAccountsProcessor::new(metrics.clone());
         let token_accounts_processor = TokenAccountsProcessor::new(metrics.clone());
@@ -106,6 +113,7 @@ impl<T: UnprocessedAccountsGetter> AccountsProcessor<T> {
             inscription_processor,
             core_fees_processor,
             metrics,
+            dead_letters,
         })
     }
 
@@ -223,8 +231,31 @@ impl<T: UnprocessedAccountsGetter> AccountsProcessor<T> {
                     Ok(())
                 }
             };
+            let processing_result = processing_result.and_then(|_| {
+                // the slot is checked by the fork cleaner once it's old enough
+                let (slot, write_version) = unprocessed_account.account.update_version();
+                batch_storage.account_updated_with_batch(
+                    slot,
+                    unprocessed_account.key,
+                    write_version,
+                    unprocessed_account.account.tombstone(),
+                )
+            });
+            let asset_changes = batch_storage.take_asset_changes();
+            let processing_result = processing_result.and_then(|_| {
+                Self::store_asset_events(batch_storage, &unprocessed_account.account, asset_changes)
+            });
             if let Err(err) = processing_result {
                 error!("Processing account {}: {}", unprocessed_account.key, err);
+                // the message is acked only if the account is kept in the dead letters
+                let (_, write_version) = unprocessed_account.account.update_version();
+                if self.dead_letters.push_account(
+                    unprocessed_account.key,
+                    write_version,
+                    err.to_string(),
+                ) {
+                    ack_ids.push(unprocessed_account.id);
+                }
                 continue;
             }
             self.metrics
@@ -241,6 +272,41 @@ impl<T: UnprocessedAccountsGetter> AccountsProcessor<T> {
         }
     }
 
+    /// Stores the asset events made by the account update, to be sent to the webhooks.
+    /// The events are told by the changes of the merged asset state, so the updates
+    /// which change nothing, e.g. the stale ones and the replays, make no events
+    fn store_asset_events(
+        batch_storage: &mut BatchSaveStorage,
+        account: &UnprocessedAccount,
+        asset_changes: HashMap<Pubkey, AssetStateChange>,
+    ) -> Result<(), StorageError> {
+        let metadata_account = matches!(
+            account,
+            UnprocessedAccount::MetadataInfo(_) | UnprocessedAccount::MplCore(_)
+        );
+        // the token account left without the balance doesn't hold the asset anymore,
+        // even if its owner is taken as the asset owner
+        let drops_asset = matches!(account, UnprocessedAccount::Token(token) if token.amount == 0);
+        let (slot, _) = account.update_version();
+        for (asset_id, mut change) in asset_changes {
+            if drops_asset {
+                change.new_owner = None;
+            }
+            for event_type in change.event_types(metadata_account) {
+                batch_storage.store_asset_event(&AssetEvent {
+                    event_type,
+                    asset_id,
+                    slot,
+                    tree: None,
+                    signature: None,
+                    owner: change.new_owner,
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn flush(
         &self,
         storage: &mut BatchSaveStorage,
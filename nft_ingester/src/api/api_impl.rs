@@ -8,6 +8,8 @@ use tokio::sync::Mutex;
 use tokio::task::{JoinError, JoinSet};
 
 use self::util::ApiRequest;
+use crate::api::asset_cache::AssetCache;
+use crate::api::dapi::asset::RequestedSlot;
 use crate::api::dapi::converters::SearchAssetsQuery;
 use crate::api::dapi::response::{
     AssetList, GetGroupingResponse, TransactionSignatureListDeprecated,
@@ -16,25 +18,42 @@ use crate::api::dapi::rpc_asset_models::Asset;
 use crate::api::error::DasApiError;
 use crate::api::*;
 use crate::config::JsonMiddlewareConfig;
+use crate::webhooks::validate_webhook_url;
+use dapi::dead_letters::{get_dead_letter, get_dead_letters, redrive_dead_letters};
 use dapi::get_asset_signatures::get_asset_signatures;
 use dapi::get_core_fees::get_core_fees;
+use dapi::get_indexer_status::get_indexer_status;
+use dapi::get_nft_editions::get_nft_editions;
+use dapi::get_owners_snapshot::{get_owners_snapshot, owners_snapshot_filter};
 use dapi::get_token_accounts::get_token_accounts;
+use dapi::get_tree_info::get_tree_info;
+use dapi::refresh_asset_metadata::refresh_asset_metadata;
+use dapi::tree_gap_repairs::get_tree_gap_repairs;
+use dapi::webhooks::{delete_webhook, get_webhooks, register_webhook};
 use entities::api_req_params::{
-    GetAsset, GetAssetBatch, GetAssetProof, GetAssetProofBatch, GetAssetSignatures,
-    GetAssetsByAuthority, GetAssetsByCreator, GetAssetsByGroup, GetAssetsByOwner,
-    GetByMethodsOptions, GetCoreFees, GetGrouping, GetTokenAccounts, Pagination, SearchAssets,
+    Commitment, DeleteWebhook, GetAsset, GetAssetBatch, GetAssetProof, GetAssetProofBatch,
+    GetAssetSignatures, GetAssetsByAuthority, GetAssetsByCreator, GetAssetsByGroup,
+    GetAssetsByOwner, GetByMethodsOptions, GetCoreFees, GetDeadLetter, GetDeadLetters, GetGrouping,
+    GetNftEditions, GetOwnersSnapshot, GetTokenAccounts, GetTreeGapRepairs, GetTreeInfo,
+    GetWebhooks, Pagination, RedriveDeadLetters, RefreshAssetMetadata, RegisterWebhook,
+    SearchAssets,
 };
 use entities::enums::TokenType;
+use entities::models::WebhookFilter;
 use interface::account_balance::AccountBalanceGetter;
 use interface::price_fetcher::TokenPriceFetcher;
 use metrics_utils::ApiMetricsConfig;
+use rocks_db::cl_items_history::ClItemsVersion;
+use rocks_db::errors::StorageError;
+use rocks_db::parameters::Parameter;
 use rocks_db::Storage;
 use serde_json::{json, Value};
 use solana_sdk::pubkey::Pubkey;
 use usecase::validation::{validate_opt_pubkey, validate_pubkey};
 
 const MAX_ITEMS_IN_BATCH_REQ: usize = 1000;
-const DEFAULT_LIMIT: usize = MAX_ITEMS_IN_BATCH_REQ;
+pub(crate) const DEFAULT_LIMIT: usize = MAX_ITEMS_IN_BATCH_REQ;
+const MAX_ATTRIBUTES_IN_SEARCH_REQ: usize = 10;
 
 pub struct DasApi<PC, JD, JP, ABG, TPF>
 where
@@ -45,8 +64,8 @@ where
     TPF: TokenPriceFetcher + Sync + Send + 'static,
 {
     pub(crate) pg_client: Arc<PgClient>,
-    rocks_db: Arc<Storage>,
-    metrics: Arc<ApiMetricsConfig>,
+    pub(crate) rocks_db: Arc<Storage>,
+    pub(crate) metrics: Arc<ApiMetricsConfig>,
     proof_checker: Option<Arc<PC>>,
     max_page_limit: u32,
     json_downloader: Option<Arc<JD>>,
@@ -56,12 +75,32 @@ where
     /// E.g. https://storage-service.xyz/
     storage_service_base_path: Option<String>,
     token_price_fetcher: Arc<TPF>,
+    pub(crate) asset_cache: Option<Arc<AssetCache>>,
 }
 
 pub fn not_found() -> DasApiError {
     DasApiError::NoDataFoundError
 }
 
+fn parse_dead_letter_id(id: String) -> Result<u64, DasApiError> {
+    id.parse::<u64>()
+        .map_err(|_| DasApiError::Validation(format!("Invalid dead letter id: {id}")))
+}
+
+fn parse_webhook_id(id: String) -> Result<u64, DasApiError> {
+    id.parse::<u64>()
+        .map_err(|_| DasApiError::Validation(format!("Invalid webhook id: {id}")))
+}
+
+fn validate_webhook_keys(keys: Vec<String>) -> Result<Vec<Pubkey>, DasApiError> {
+    if keys.len() > MAX_ITEMS_IN_BATCH_REQ {
+        return Err(DasApiError::BatchSizeError(MAX_ITEMS_IN_BATCH_REQ));
+    }
+    keys.into_iter()
+        .map(|key| validate_pubkey(key).map_err(Into::into))
+        .collect()
+}
+
 impl<PC, JD, JP, ABG, TPF> DasApi<PC, JD, JP, ABG, TPF>
 where
     PC: ProofChecker + Sync + Send + 'static,
@@ -96,6 +135,20 @@ where
             account_balance_getter,
             storage_service_base_path,
             token_price_fetcher,
+            asset_cache: None,
+        }
+    }
+
+    /// Serves the assets requested without `atSlot` and `commitment` from the cache
+    pub fn with_asset_cache(mut self, asset_cache: Arc<AssetCache>) -> Self {
+        self.asset_cache = Some(asset_cache);
+        self
+    }
+
+    fn latest_state_cache(&self, at_slot: Option<RequestedSlot>) -> Option<&AssetCache> {
+        match at_slot {
+            Some(_) => None,
+            None => self.asset_cache.as_deref(),
         }
     }
 
@@ -115,6 +168,19 @@ where
         Ok(json!("ok"))
     }
 
+    pub async fn get_indexer_status(&self) -> Result<Value, DasApiError> {
+        let label = "get_indexer_status";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        let res = get_indexer_status(self.rocks_db.clone(), self.pg_client.clone()).await?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
     fn validate_options(
         options: &GetByMethodsOptions,
         query: &SearchAssetsQuery,
@@ -136,10 +202,41 @@ where
                 }
             }
         }
+        if let Some(ref attributes) = query.attributes {
+            if attributes.len() > MAX_ATTRIBUTES_IN_SEARCH_REQ {
+                return Err(DasApiError::Validation(format!(
+                    "No more than {MAX_ATTRIBUTES_IN_SEARCH_REQ} `attributes` are allowed"
+                )));
+            }
+        }
+        if let Some(ref core_attributes) = query.core_attributes {
+            if core_attributes.len() > MAX_ATTRIBUTES_IN_SEARCH_REQ {
+                return Err(DasApiError::Validation(format!(
+                    "No more than {MAX_ATTRIBUTES_IN_SEARCH_REQ} `core_attributes` are allowed"
+                )));
+            }
+        }
 
         Ok(())
     }
 
+    /// Returns the latest slot observed at the commitment, None means the latest data is requested
+    async fn commitment_slot(
+        &self,
+        commitment: Option<Commitment>,
+    ) -> Result<Option<RequestedSlot>, DasApiError> {
+        let parameter = match commitment.unwrap_or_default() {
+            Commitment::Processed => return Ok(None),
+            Commitment::Confirmed => Parameter::LastConfirmedSlot,
+            Commitment::Finalized => Parameter::LastFinalizedSlot,
+        };
+        self.rocks_db
+            .get_parameter::<u64>(parameter)
+            .await?
+            .map(|slot| Some(RequestedSlot::Commitment(slot)))
+            .ok_or(DasApiError::CannotServiceRequest)
+    }
+
     fn banned_params_with_fungible_token_type(query: &SearchAssetsQuery) -> Option<String> {
         if query.creator_address.is_some() {
             return Some(String::from("creator_address"));
@@ -237,13 +334,29 @@ where
         let latency_timer = Instant::now();
 
         let id = validate_pubkey(payload.id.clone())?;
+        let version = match (payload.root_seq, payload.slot) {
+            (Some(_), Some(_)) => {
+                return Err(DasApiError::Validation(
+                    "Only one of `rootSeq` and `slot` can be provided".to_string(),
+                ))
+            }
+            (Some(seq), None) => Some(ClItemsVersion::Seq(seq)),
+            (None, Some(slot)) => Some(ClItemsVersion::Slot(slot)),
+            (None, None) => None,
+        };
         let assets = get_proof_for_assets(
             self.rocks_db.clone(),
             vec![id],
             self.proof_checker.clone(),
             self.metrics.clone(),
+            version,
         )
-        .await?;
+        .await
+        .map_err(|e| match e {
+            // the requested state is older than the retained changelog history
+            StorageError::NotFound(msg) if version.is_some() => DasApiError::Validation(msg),
+            e => e.into(),
+        })?;
 
         self.metrics
             .set_latency(label, latency_timer.elapsed().as_millis() as f64);
@@ -281,6 +394,7 @@ where
             ids,
             self.proof_checker.clone(),
             self.metrics.clone(),
+            None,
         )
         .await;
 
@@ -301,11 +415,30 @@ where
 
         let id = validate_pubkey(payload.id.clone())?;
         let options = payload.options.unwrap_or_default();
+        if payload.at_slot.is_some() && payload.commitment.is_some() {
+            return Err(DasApiError::Validation(
+                "Only one of `atSlot` and `commitment` can be provided".to_string(),
+            ));
+        }
+        let at_slot = match payload.at_slot {
+            Some(slot) => Some(RequestedSlot::AtSlot(slot)),
+            None => self.commitment_slot(payload.commitment).await?,
+        };
+
+        let asset_cache = self.latest_state_cache(at_slot);
+        if let Some(asset_cache) = asset_cache {
+            if let Some(asset) = asset_cache.get(&id, &options).await {
+                self.metrics
+                    .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+                return Ok(json!(asset));
+            }
+        }
+        let generation = asset_cache.map(AssetCache::generation);
 
         let res = get_asset(
             self.rocks_db.clone(),
             id,
-            options,
+            options.clone(),
             self.json_downloader.clone(),
             self.json_persister.clone(),
             self.json_middleware_config.max_urls_to_parse,
@@ -313,9 +446,17 @@ where
             self.storage_service_base_path.clone(),
             self.token_price_fetcher.clone(),
             self.metrics.clone(),
+            at_slot,
         )
         .await?;
 
+        if let (Some(asset_cache), Some(generation), Some(asset)) = (asset_cache, generation, &res)
+        {
+            asset_cache
+                .insert(id, options, asset.clone(), generation)
+                .await;
+        }
+
         self.metrics
             .set_latency(label, latency_timer.elapsed().as_millis() as f64);
 
@@ -349,19 +490,47 @@ where
             .collect::<Result<Vec<_>, _>>()?;
         let options = payload.options.unwrap_or_default();
 
-        let res = get_asset_batch(
-            self.rocks_db.clone(),
-            ids,
-            options,
-            self.json_downloader.clone(),
-            self.json_persister.clone(),
-            self.json_middleware_config.max_urls_to_parse,
-            tasks,
-            self.storage_service_base_path.clone(),
-            self.token_price_fetcher.clone(),
-            self.metrics.clone(),
-        )
-        .await?;
+        let at_slot = payload.at_slot.map(RequestedSlot::AtSlot);
+        let asset_cache = self.latest_state_cache(at_slot);
+        let mut res = vec![None; ids.len()];
+        let mut missed = Vec::with_capacity(ids.len());
+        for (position, id) in ids.iter().enumerate() {
+            match asset_cache {
+                Some(asset_cache) => match asset_cache.get(id, &options).await {
+                    Some(asset) => res[position] = Some(asset),
+                    None => missed.push(position),
+                },
+                None => missed.push(position),
+            }
+        }
+        let generation = asset_cache.map(AssetCache::generation);
+
+        if !missed.is_empty() {
+            let fetched = get_asset_batch(
+                self.rocks_db.clone(),
+                missed.iter().map(|position| ids[*position]).collect(),
+                options.clone(),
+                self.json_downloader.clone(),
+                self.json_persister.clone(),
+                self.json_middleware_config.max_urls_to_parse,
+                tasks,
+                self.storage_service_base_path.clone(),
+                self.token_price_fetcher.clone(),
+                self.metrics.clone(),
+                at_slot,
+            )
+            .await?;
+            for (position, asset) in missed.into_iter().zip(fetched) {
+                if let (Some(asset_cache), Some(generation), Some(asset)) =
+                    (asset_cache, generation, &asset)
+                {
+                    asset_cache
+                        .insert(ids[position], options.clone(), asset.clone(), generation)
+                        .await;
+                }
+                res[position] = asset;
+            }
+        }
 
         self.metrics
             .set_latency(label, latency_timer.elapsed().as_millis() as f64);
@@ -560,6 +729,356 @@ where
         Ok(json!(res))
     }
 
+    pub async fn get_nft_editions(&self, payload: GetNftEditions) -> Result<Value, DasApiError> {
+        let label = "get_nft_editions";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        let GetNftEditions {
+            mint_address,
+            limit,
+            page,
+            before,
+            after,
+            cursor,
+        } = payload;
+
+        let pagination = Pagination {
+            limit,
+            page,
+            before,
+            after,
+            cursor,
+        };
+
+        Self::validate_basic_pagination(&pagination, self.max_page_limit)?;
+        let mint = validate_pubkey(mint_address)?;
+        let parse_edition_number = |value: Option<String>| {
+            value
+                .map(|v| {
+                    v.parse::<u64>().map_err(|_| {
+                        DasApiError::Validation(format!("Invalid edition number: {v}"))
+                    })
+                })
+                .transpose()
+        };
+
+        let res = get_nft_editions(
+            self.rocks_db.clone(),
+            mint,
+            limit.unwrap_or(DEFAULT_LIMIT as u32).into(),
+            page.map(|page| page as u64),
+            parse_edition_number(pagination.before)?,
+            parse_edition_number(pagination.after)?,
+            parse_edition_number(pagination.cursor)?,
+        )
+        .await?
+        .ok_or(not_found())?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
+    pub async fn get_tree_info(&self, payload: GetTreeInfo) -> Result<Value, DasApiError> {
+        let label = "get_tree_info";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        let tree = validate_pubkey(payload.tree)?;
+        let res = get_tree_info(self.rocks_db.clone(), tree)
+            .await?
+            .ok_or(not_found())?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
+    pub async fn refresh_asset_metadata(
+        &self,
+        payload: RefreshAssetMetadata,
+    ) -> Result<Value, DasApiError> {
+        let label = "refresh_asset_metadata";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        if payload.ids.len() > MAX_ITEMS_IN_BATCH_REQ {
+            return Err(DasApiError::BatchSizeError(MAX_ITEMS_IN_BATCH_REQ));
+        }
+        let ids: Vec<Pubkey> = payload
+            .ids
+            .into_iter()
+            .map(validate_pubkey)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let res =
+            refresh_asset_metadata(self.rocks_db.clone(), self.pg_client.clone(), ids).await?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
+    pub async fn get_dead_letters(&self, payload: GetDeadLetters) -> Result<Value, DasApiError> {
+        let label = "get_dead_letters";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        let GetDeadLetters { limit, after } = payload;
+        Self::validate_basic_pagination(
+            &Pagination {
+                limit,
+                ..Default::default()
+            },
+            self.max_page_limit,
+        )?;
+        let after = after.map(parse_dead_letter_id).transpose()?;
+
+        let res = get_dead_letters(
+            self.rocks_db.clone(),
+            limit.unwrap_or(DEFAULT_LIMIT as u32).into(),
+            after,
+        )
+        .await?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
+    pub async fn get_dead_letter(&self, payload: GetDeadLetter) -> Result<Value, DasApiError> {
+        let label = "get_dead_letter";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        let id = parse_dead_letter_id(payload.id)?;
+        let res = get_dead_letter(self.rocks_db.clone(), id)
+            .await?
+            .ok_or(not_found())?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
+    pub async fn redrive_dead_letters(
+        &self,
+        payload: RedriveDeadLetters,
+    ) -> Result<Value, DasApiError> {
+        let label = "redrive_dead_letters";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        if payload.ids.len() > MAX_ITEMS_IN_BATCH_REQ {
+            return Err(DasApiError::BatchSizeError(MAX_ITEMS_IN_BATCH_REQ));
+        }
+        let ids = payload
+            .ids
+            .into_iter()
+            .map(parse_dead_letter_id)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let res = redrive_dead_letters(self.rocks_db.clone(), self.pg_client.clone(), ids).await?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
+    pub async fn get_tree_gap_repairs(
+        &self,
+        payload: GetTreeGapRepairs,
+    ) -> Result<Value, DasApiError> {
+        let label = "get_tree_gap_repairs";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        let GetTreeGapRepairs { limit, after } = payload;
+        Self::validate_basic_pagination(
+            &Pagination {
+                limit,
+                ..Default::default()
+            },
+            self.max_page_limit,
+        )?;
+        let after = validate_opt_pubkey(&after)?;
+
+        let res = get_tree_gap_repairs(
+            self.rocks_db.clone(),
+            limit.unwrap_or(DEFAULT_LIMIT as u32).into(),
+            after,
+        )
+        .await?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
+    /// The webhook belongs to the API key it is registered with,
+    /// the key may have up to `max_webhooks` of them
+    pub async fn register_webhook(
+        &self,
+        payload: RegisterWebhook,
+        key_name: String,
+        max_webhooks: u64,
+    ) -> Result<Value, DasApiError> {
+        let label = "register_webhook";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        let url = url::Url::parse(&payload.url)
+            .map_err(|e| DasApiError::Validation(format!("Invalid webhook url: {e}")))?;
+        validate_webhook_url(&url).map_err(DasApiError::Validation)?;
+        let secret = match payload.secret {
+            Some(secret) if secret.is_empty() => {
+                return Err(DasApiError::Validation(
+                    "Webhook secret should not be empty".to_string(),
+                ));
+            }
+            Some(secret) => secret,
+            None => format!(
+                "{}{}",
+                uuid::Uuid::new_v4().simple(),
+                uuid::Uuid::new_v4().simple()
+            ),
+        };
+        let filter = WebhookFilter {
+            owners: validate_webhook_keys(payload.owners)?,
+            collections: validate_webhook_keys(payload.collections)?,
+            creators: validate_webhook_keys(payload.creators)?,
+            trees: validate_webhook_keys(payload.trees)?,
+            asset_ids: validate_webhook_keys(payload.asset_ids)?,
+            event_types: payload.event_types,
+        };
+
+        let res = register_webhook(
+            self.pg_client.clone(),
+            key_name,
+            max_webhooks,
+            url.to_string(),
+            secret,
+            filter,
+        )
+        .await?
+        .ok_or_else(|| {
+            DasApiError::Validation(format!(
+                "The API key already has the maximum of {} webhooks",
+                max_webhooks
+            ))
+        })?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
+    pub async fn get_webhooks(
+        &self,
+        payload: GetWebhooks,
+        key_name: String,
+    ) -> Result<Value, DasApiError> {
+        let label = "get_webhooks";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        let GetWebhooks { limit, after } = payload;
+        Self::validate_basic_pagination(
+            &Pagination {
+                limit,
+                ..Default::default()
+            },
+            self.max_page_limit,
+        )?;
+        let after = after.map(parse_webhook_id).transpose()?;
+
+        let res = get_webhooks(
+            self.pg_client.clone(),
+            key_name,
+            limit.unwrap_or(DEFAULT_LIMIT as u32).into(),
+            after,
+        )
+        .await?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
+    pub async fn delete_webhook(
+        &self,
+        payload: DeleteWebhook,
+        key_name: String,
+    ) -> Result<Value, DasApiError> {
+        let label = "delete_webhook";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        let id = parse_webhook_id(payload.id)?;
+        let res = delete_webhook(self.pg_client.clone(), key_name, id).await?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
+    pub async fn get_owners_snapshot(
+        &self,
+        payload: GetOwnersSnapshot,
+    ) -> Result<Value, DasApiError> {
+        let label = "get_owners_snapshot";
+        self.metrics.inc_requests(label);
+        let latency_timer = Instant::now();
+
+        let GetOwnersSnapshot {
+            grouping,
+            creator_address,
+            creator_verified,
+            limit,
+            after,
+            options,
+        } = payload;
+
+        Self::validate_basic_pagination(
+            &Pagination {
+                limit,
+                ..Default::default()
+            },
+            self.max_page_limit,
+        )?;
+        let filter = owners_snapshot_filter(
+            grouping,
+            creator_address,
+            creator_verified,
+            options.unwrap_or_default().show_unverified_collections,
+        )?;
+        let after = validate_opt_pubkey(&after)?;
+
+        let res = get_owners_snapshot(
+            self.pg_client.clone(),
+            filter,
+            limit.unwrap_or(DEFAULT_LIMIT as u32).into(),
+            after,
+        )
+        .await?;
+
+        self.metrics
+            .set_latency(label, latency_timer.elapsed().as_millis() as f64);
+
+        Ok(json!(res))
+    }
+
     pub async fn search_assets(
         &self,
         payload: SearchAssets,
@@ -693,6 +1212,7 @@ where
         let pagination = payload.get_all_pagination_parameters();
         let sort_by = payload.get_sort_parameter().unwrap_or_default();
         let options = payload.get_options().unwrap_or_default();
+        let at_slot = self.commitment_slot(payload.get_commitment()).await?;
 
         let query: SearchAssetsQuery = payload
             .try_into()
@@ -700,6 +1220,19 @@ where
 
         Self::validate_basic_pagination(&pagination, self.max_page_limit)?;
         Self::validate_options(&options, &query)?;
+        // the balances of the fungible tokens are indexed as of the latest slot only
+        if at_slot.is_some()
+            && (options.show_fungible
+                || matches!(
+                    query.token_type,
+                    Some(TokenType::All) | Some(TokenType::Fungible)
+                ))
+        {
+            return Err(DasApiError::Validation(
+                "`commitment` other than processed can't be combined with the fungible tokens"
+                    .to_string(),
+            ));
+        }
 
         let res = search_assets(
             pg_client,
@@ -720,6 +1253,7 @@ where
             self.storage_service_base_path.clone(),
             self.token_price_fetcher.clone(),
             self.metrics.clone(),
+            at_slot,
         )
         .await?;
 
@@ -0,0 +1,713 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use entities::api_req_params::{
+    GetAssetBatch, GetAssetBatchV0, GetAssetProofBatch, GetAssetSignatures, GetAssetsByAuthority,
+    GetAssetsByAuthorityV0, GetAssetsByCreator, GetAssetsByCreatorV0, GetAssetsByGroup,
+    GetAssetsByGroupV0, GetAssetsByOwner, GetAssetsByOwnerV0, GetCoreFees, GetDeadLetters,
+    GetNftEditions, GetOwnersSnapshot, GetTokenAccounts, GetTreeGapRepairs, GetWebhooks,
+    RedriveDeadLetters, RefreshAssetMetadata, SearchAssets, SearchAssetsV0,
+};
+use jsonrpc_core::Params;
+use jsonrpc_http_server::hyper;
+use metrics_utils::ApiMetricsConfig;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tokio::sync::broadcast::Receiver;
+use tokio::task::JoinError;
+use tokio::time::sleep as tokio_sleep;
+use tracing::{error, info};
+
+use crate::api::api_impl::DEFAULT_LIMIT;
+use crate::api::error::DasApiError;
+use crate::api::openrpc::{canonical_method_name, DISCOVER_METHOD};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+pub const API_KEY_QUERY_PARAM: &str = "api-key";
+const DEFAULT_METHOD_COST: u64 = 1;
+// every webhook gets a notification of every matching event, so their number is bounded
+const DEFAULT_MAX_WEBHOOKS: u64 = 10;
+const UNKNOWN_KEY_LABEL: &str = "unknown";
+// how often a streamed response checks if the quota of the key has room for the next page
+const PAGE_CHARGE_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+// load balancers and the clients discovering the API don't have a key
+const PUBLIC_METHODS: &[&str] = &["health", DISCOVER_METHOD];
+// by the names the methods are registered under, the methods not listed need no scope
+const SCOPED_METHODS: &[(&str, ApiKeyScope)] = &[
+    ("refresh_asset_metadata", ApiKeyScope::Admin),
+    ("get_dead_letters", ApiKeyScope::Admin),
+    ("get_dead_letter", ApiKeyScope::Admin),
+    ("redrive_dead_letters", ApiKeyScope::Admin),
+    ("get_tree_gap_repairs", ApiKeyScope::Admin),
+    ("register_webhook", ApiKeyScope::Webhooks),
+    ("get_webhooks", ApiKeyScope::Webhooks),
+    ("delete_webhook", ApiKeyScope::Webhooks),
+];
+
+/// Content of the API keys file
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ApiKeysConfig {
+    pub keys: Vec<ApiKeyQuota>,
+    /// Costs of the methods by their names or aliases, the methods not listed cost 1
+    #[serde(default)]
+    pub method_costs: HashMap<String, u64>,
+    /// Added to the cost of the requests with the `showGrandTotal` option
+    #[serde(default)]
+    pub grand_total_cost: u64,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiKeyQuota {
+    pub key: String,
+    /// The usage of the key is reported under the name, so the key doesn't get into the metrics
+    pub name: String,
+    /// Cost of the requests the key may spend per second
+    pub requests_per_second: u64,
+    /// The greatest `limit` of a page the key may request
+    pub max_page_size: Option<u64>,
+    /// Scopes of the methods the key may call besides the ones open to any key
+    #[serde(default)]
+    pub scopes: Vec<ApiKeyScope>,
+    /// The most webhooks the key may have registered at once
+    #[serde(default = "default_max_webhooks")]
+    pub max_webhooks: u64,
+}
+
+const fn default_max_webhooks() -> u64 {
+    DEFAULT_MAX_WEBHOOKS
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// Operating the indexer: dead letters, metadata refreshes and tree gap repairs
+    Admin,
+    /// Managing the webhooks of the key, they belong to the name of the key
+    Webhooks,
+}
+
+/// Returns the scope a key needs to call the method
+fn method_scope(method: &str) -> Option<ApiKeyScope> {
+    let method = canonical_method_name(method);
+    SCOPED_METHODS
+        .iter()
+        .find(|(scoped_method, _)| *scoped_method == method)
+        .map(|(_, scope)| *scope)
+}
+
+/// Token bucket refilled with the rate of the key, holding up to a second of requests.
+/// A request passes while there is a token left and may take the bucket below zero,
+/// so the expensive requests delay the following ones instead of never passing
+struct RateLimiter {
+    rate: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: u64) -> Self {
+        Self {
+            rate: rate as f64,
+            tokens: rate as f64,
+            updated_at: Instant::now(),
+        }
+    }
+
+    fn try_spend(&mut self, cost: u64, now: Instant) -> bool {
+        let refilled = now.duration_since(self.updated_at).as_secs_f64() * self.rate;
+        self.tokens = (self.tokens + refilled).min(self.rate);
+        self.updated_at = now;
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= cost as f64;
+        true
+    }
+}
+
+struct ApiKey {
+    quota: ApiKeyQuota,
+    rate_limiter: Mutex<RateLimiter>,
+}
+
+#[derive(Default)]
+struct ApiKeysState {
+    keys: HashMap<String, Arc<ApiKey>>,
+    // by the names the methods are registered under
+    method_costs: HashMap<String, u64>,
+    grand_total_cost: u64,
+    modified_at: Option<SystemTime>,
+}
+
+impl ApiKeysState {
+    fn cost(&self, method: &str, usage: &RequestUsage) -> u64 {
+        let cost = self
+            .method_costs
+            .get(method)
+            .copied()
+            .unwrap_or(DEFAULT_METHOD_COST);
+        if usage.show_grand_total {
+            return cost + self.grand_total_cost;
+        }
+        cost
+    }
+}
+
+/// The part of the request the quotas apply to
+#[derive(Default, Debug, PartialEq)]
+struct RequestUsage {
+    // the limit of the page, the default one if the request has none,
+    // or the number of the ids requested at once
+    page_size: Option<u64>,
+    show_grand_total: bool,
+}
+
+impl RequestUsage {
+    fn page(limit: Option<u32>, show_grand_total: bool) -> Self {
+        Self {
+            page_size: Some(limit.map_or(DEFAULT_LIMIT as u64, u64::from)),
+            show_grand_total,
+        }
+    }
+
+    fn batch(ids: &[String]) -> Self {
+        Self {
+            page_size: Some(ids.len() as u64),
+            show_grand_total: false,
+        }
+    }
+
+    /// Parses the params the way the method does, named or positional,
+    /// the params the method can't parse are rejected by it anyway
+    fn of(method: &str, params: &Params) -> Self {
+        let usage = match method {
+            "get_asset_batch" => {
+                parse_or_v0::<GetAssetBatch, GetAssetBatchV0>(params).map(|r| Self::batch(&r.ids))
+            }
+            "get_asset_proof_batch" => {
+                parse::<GetAssetProofBatch>(params).map(|r| Self::batch(&r.ids))
+            }
+            "refresh_asset_metadata" => {
+                parse::<RefreshAssetMetadata>(params).map(|r| Self::batch(&r.ids))
+            }
+            "redrive_dead_letters" => {
+                parse::<RedriveDeadLetters>(params).map(|r| Self::batch(&r.ids))
+            }
+            "get_assets_by_owner" => parse_or_v0::<GetAssetsByOwner, GetAssetsByOwnerV0>(params)
+                .map(|r| Self::page(r.limit, r.options.is_some_and(|o| o.show_grand_total))),
+            "get_assets_by_group" => parse_or_v0::<GetAssetsByGroup, GetAssetsByGroupV0>(params)
+                .map(|r| Self::page(r.limit, r.options.is_some_and(|o| o.show_grand_total))),
+            "get_assets_by_creator" => {
+                parse_or_v0::<GetAssetsByCreator, GetAssetsByCreatorV0>(params)
+                    .map(|r| Self::page(r.limit, r.options.is_some_and(|o| o.show_grand_total)))
+            }
+            "get_assets_by_authority" => {
+                parse_or_v0::<GetAssetsByAuthority, GetAssetsByAuthorityV0>(params)
+                    .map(|r| Self::page(r.limit, r.options.is_some_and(|o| o.show_grand_total)))
+            }
+            "search_assets" => parse_or_v0::<SearchAssets, SearchAssetsV0>(params)
+                .map(|r| Self::page(r.limit, r.options.is_some_and(|o| o.show_grand_total))),
+            "get_signatures_for_asset" | "get_signatures_for_asset_v2" => {
+                parse::<GetAssetSignatures>(params).map(|r| Self::page(r.limit, false))
+            }
+            "get_token_accounts" => {
+                parse::<GetTokenAccounts>(params).map(|r| Self::page(r.limit, false))
+            }
+            "get_core_fees" => parse::<GetCoreFees>(params).map(|r| Self::page(r.limit, false)),
+            "get_owners_snapshot" => {
+                parse::<GetOwnersSnapshot>(params).map(|r| Self::page(r.limit, false))
+            }
+            "get_nft_editions" => {
+                parse::<GetNftEditions>(params).map(|r| Self::page(r.limit, false))
+            }
+            "get_dead_letters" => {
+                parse::<GetDeadLetters>(params).map(|r| Self::page(r.limit, false))
+            }
+            "get_tree_gap_repairs" => {
+                parse::<GetTreeGapRepairs>(params).map(|r| Self::page(r.limit, false))
+            }
+            "get_webhooks" => parse::<GetWebhooks>(params).map(|r| Self::page(r.limit, false)),
+            _ => None,
+        };
+        usage.unwrap_or_default()
+    }
+}
+
+fn parse<T: DeserializeOwned>(params: &Params) -> Option<T> {
+    params.clone().parse().ok()
+}
+
+// the methods accepting the legacy params try them first
+fn parse_or_v0<T: DeserializeOwned, V0: DeserializeOwned + Into<T>>(params: &Params) -> Option<T> {
+    parse::<V0>(params)
+        .map(Into::into)
+        .or_else(|| parse(params))
+}
+
+/// API keys with their quotas, loaded from a JSON file and reloaded when it is modified
+pub struct ApiKeys {
+    path: PathBuf,
+    state: RwLock<ApiKeysState>,
+    metrics: Arc<ApiMetricsConfig>,
+}
+
+impl ApiKeys {
+    pub fn from_file(
+        path: impl Into<PathBuf>,
+        metrics: Arc<ApiMetricsConfig>,
+    ) -> Result<Self, DasApiError> {
+        let api_keys = Self {
+            path: path.into(),
+            state: Default::default(),
+            metrics,
+        };
+        api_keys.reload()?;
+
+        Ok(api_keys)
+    }
+
+    /// Loads the file if it was modified since the last load, returns if it was loaded.
+    /// The loaded keys are kept if the file is invalid
+    pub fn reload(&self) -> Result<bool, DasApiError> {
+        let modified_at = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| self.configuration_error(e))?;
+        if self.read_state().modified_at == Some(modified_at) {
+            return Ok(false);
+        }
+        let content =
+            std::fs::read_to_string(&self.path).map_err(|e| self.configuration_error(e))?;
+        let config: ApiKeysConfig =
+            serde_json::from_str(&content).map_err(|e| self.configuration_error(e))?;
+
+        let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+        let keys = config
+            .keys
+            .into_iter()
+            .map(|quota| {
+                let key = match state.keys.get(&quota.key) {
+                    // the quota the unchanged keys spent is kept
+                    Some(key) if key.quota == quota => key.clone(),
+                    _ => Arc::new(ApiKey {
+                        rate_limiter: Mutex::new(RateLimiter::new(quota.requests_per_second)),
+                        quota,
+                    }),
+                };
+                (key.quota.key.clone(), key)
+            })
+            .collect();
+        let method_costs = config
+            .method_costs
+            .into_iter()
+            .map(|(method, cost)| (canonical_method_name(&method).to_string(), cost))
+            .collect();
+        *state = ApiKeysState {
+            keys,
+            method_costs,
+            grand_total_cost: config.grand_total_cost,
+            modified_at: Some(modified_at),
+        };
+
+        Ok(true)
+    }
+
+    /// Authenticates the request and spends the cost of the method from the quota of the key
+    pub fn check(
+        &self,
+        api_key: Option<&str>,
+        method: &str,
+        params: Option<&Params>,
+    ) -> Result<(), DasApiError> {
+        let method = canonical_method_name(method);
+        if PUBLIC_METHODS.contains(&method) {
+            return Ok(());
+        }
+        let state = self.read_state();
+        let Some(key) = api_key.and_then(|api_key| state.keys.get(api_key)) else {
+            self.metrics
+                .inc_api_key_rejections(UNKNOWN_KEY_LABEL, "unauthorized");
+            return Err(DasApiError::Unauthorized);
+        };
+        let name = key.quota.name.as_str();
+
+        if let Some(scope) = method_scope(method) {
+            if !key.quota.scopes.contains(&scope) {
+                self.metrics.inc_api_key_rejections(name, "scope");
+                return Err(DasApiError::Forbidden);
+            }
+        }
+        let usage = params
+            .map(|params| RequestUsage::of(method, params))
+            .unwrap_or_default();
+        if let Some(max_page_size) = key.quota.max_page_size {
+            if usage
+                .page_size
+                .is_some_and(|page_size| page_size > max_page_size)
+            {
+                self.metrics.inc_api_key_rejections(name, "page_size");
+                return Err(DasApiError::Validation(format!(
+                    "limit or the number of ids should not be greater than {} for the API key",
+                    max_page_size
+                )));
+            }
+        }
+
+        let cost = state.cost(method, &usage);
+        if !key
+            .rate_limiter
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .try_spend(cost, Instant::now())
+        {
+            self.metrics.inc_api_key_rejections(name, "rate_limit");
+            return Err(DasApiError::RateLimited);
+        }
+        self.metrics.inc_api_key_requests(name, method, cost);
+
+        Ok(())
+    }
+
+    /// Page size and charger of a response streamed page by page, like the owners snapshot CSV.
+    /// The page is capped by the max page size of the key, None if the key is unknown
+    pub fn page_charger(
+        self: &Arc<Self>,
+        api_key: Option<&str>,
+        method: &str,
+        page_size: u64,
+    ) -> Option<(u64, PageCharger)> {
+        let api_key = api_key?;
+        let max_page_size = self.read_state().keys.get(api_key)?.quota.max_page_size;
+        Some((
+            max_page_size.map_or(page_size, |max_page_size| page_size.min(max_page_size)),
+            PageCharger {
+                api_keys: self.clone(),
+                api_key: api_key.to_string(),
+                method: canonical_method_name(method).to_string(),
+            },
+        ))
+    }
+
+    // spends the cost of a page, fails if the key was removed since the request was checked
+    fn try_spend_page(&self, api_key: &str, method: &str) -> Result<bool, DasApiError> {
+        let state = self.read_state();
+        let Some(key) = state.keys.get(api_key) else {
+            return Err(DasApiError::Unauthorized);
+        };
+        let cost = state.cost(method, &RequestUsage::default());
+        if !key
+            .rate_limiter
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .try_spend(cost, Instant::now())
+        {
+            return Ok(false);
+        }
+        self.metrics
+            .inc_api_key_requests(&key.quota.name, method, cost);
+
+        Ok(true)
+    }
+
+    /// Name of the key the webhooks and the usage of the key are recorded under
+    pub fn key_name(&self, api_key: Option<&str>) -> Option<String> {
+        api_key
+            .and_then(|api_key| self.read_state().keys.get(api_key).cloned())
+            .map(|key| key.quota.name.clone())
+    }
+
+    /// Name of the key the webhooks are registered under and the most webhooks it may have
+    pub fn webhooks_quota(&self, api_key: Option<&str>) -> Option<(String, u64)> {
+        api_key
+            .and_then(|api_key| self.read_state().keys.get(api_key).cloned())
+            .map(|key| (key.quota.name.clone(), key.quota.max_webhooks))
+    }
+
+    fn read_state(&self) -> std::sync::RwLockReadGuard<'_, ApiKeysState> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn configuration_error(&self, e: impl std::fmt::Display) -> DasApiError {
+        DasApiError::ConfigurationError(format!("API keys file {:?}: {}", self.path, e))
+    }
+}
+
+/// Charges every page of a response streamed page by page the cost of its method,
+/// the first page is charged along with the request by [ApiKeys::check]
+pub struct PageCharger {
+    api_keys: Arc<ApiKeys>,
+    api_key: String,
+    method: String,
+}
+
+impl PageCharger {
+    /// Waits until the quota of the key has room for the next page, so the stream is throttled
+    /// to the rate of the key instead of being cut off
+    pub async fn charge_page(&self) -> Result<(), DasApiError> {
+        while !self.api_keys.try_spend_page(&self.api_key, &self.method)? {
+            tokio_sleep(PAGE_CHARGE_RETRY_INTERVAL).await;
+        }
+        Ok(())
+    }
+}
+
+/// Takes the key from the header, or from the query string for the clients that can't set headers
+pub fn api_key_from(header: Option<&str>, query: Option<&str>) -> Option<String> {
+    header.map(str::to_string).or_else(|| {
+        url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+            .find(|(name, _)| name == API_KEY_QUERY_PARAM)
+            .map(|(_, value)| value.into_owned())
+    })
+}
+
+pub fn request_api_key(request: &hyper::Request<hyper::Body>) -> Option<String> {
+    api_key_from(
+        request
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok()),
+        request.uri().query(),
+    )
+}
+
+pub async fn run_api_keys_reloader(
+    api_keys: Arc<ApiKeys>,
+    reload_interval: Duration,
+    mut rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    info!("Start reloading API keys...");
+    loop {
+        tokio::select! {
+            _ = tokio_sleep(reload_interval) => {},
+            _ = rx.recv() => {
+                info!("Received stop signal, stopping reloading API keys!");
+                break;
+            }
+        }
+        match api_keys.reload() {
+            Ok(true) => info!("API keys are reloaded"),
+            Ok(false) => {}
+            Err(e) => error!("Reload API keys: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn write_keys(file: &tempfile::NamedTempFile, config: Value) {
+        std::fs::write(file.path(), config.to_string()).unwrap();
+    }
+
+    fn params(params: Value) -> Params {
+        serde_json::from_value(params).unwrap()
+    }
+
+    #[test]
+    fn test_api_keys_quotas() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        write_keys(
+            &file,
+            json!({
+                "keys": [{
+                    "key": "secret",
+                    "name": "partner",
+                    "requests_per_second": 5,
+                    "max_page_size": 100,
+                }],
+                "method_costs": {"searchAssets": 2},
+                "grand_total_cost": 3,
+            }),
+        );
+        let api_keys = ApiKeys::from_file(file.path(), Arc::new(ApiMetricsConfig::new())).unwrap();
+
+        assert!(matches!(
+            api_keys.check(None, "getAsset", None),
+            Err(DasApiError::Unauthorized)
+        ));
+        assert!(matches!(
+            api_keys.check(Some("other"), "getAsset", None),
+            Err(DasApiError::Unauthorized)
+        ));
+        assert!(api_keys.check(None, "getAuraHealth", None).is_ok());
+        let owner = "3pMvTLUA9NzZQd4gi725p89mvND1wRNQM3C8XEv1hTdA";
+        let ids = vec![owner; 101];
+        for (method, params) in [
+            (
+                "getAssetsByOwner",
+                params(json!({"ownerAddress": owner, "limit": 101})),
+            ),
+            // the legacy positional params
+            (
+                "getAssetsByOwner",
+                params(json!([owner, null, 101, null, null, null])),
+            ),
+            // the pages are of the default size if the limit isn't set
+            ("getAssetsByOwner", params(json!({"ownerAddress": owner}))),
+            (
+                "getAssetsByOwner",
+                params(json!({"ownerAddress": owner, "page": 2})),
+            ),
+            ("getAssetBatch", params(json!({"ids": ids}))),
+            ("getAssetProofs", params(json!([ids]))),
+        ] {
+            assert!(
+                matches!(
+                    api_keys.check(Some("secret"), method, Some(&params)),
+                    Err(DasApiError::Validation(_))
+                ),
+                "{} {:?}",
+                method,
+                params
+            );
+        }
+
+        // 2 for the method and 3 for the grand total spend the whole second of the key
+        let search_params = params(json!({
+            "ownerAddress": owner,
+            "limit": 100,
+            "options": {"showGrandTotal": true},
+        }));
+        assert!(api_keys
+            .check(Some("secret"), "search_assets", Some(&search_params))
+            .is_ok());
+        assert!(matches!(
+            api_keys.check(Some("secret"), "getAsset", None),
+            Err(DasApiError::RateLimited)
+        ));
+    }
+
+    #[test]
+    fn test_api_keys_scopes() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        write_keys(
+            &file,
+            json!({"keys": [
+                {"key": "partner", "name": "partner", "requests_per_second": 10},
+                {
+                    "key": "operator",
+                    "name": "operator",
+                    "requests_per_second": 10,
+                    "scopes": ["admin"],
+                },
+                {
+                    "key": "subscriber",
+                    "name": "subscriber",
+                    "requests_per_second": 10,
+                    "scopes": ["webhooks"],
+                },
+            ]}),
+        );
+        let api_keys = ApiKeys::from_file(file.path(), Arc::new(ApiMetricsConfig::new())).unwrap();
+
+        for method in [
+            "getDeadLetters",
+            "redriveDeadLetters",
+            "refreshAssetMetadata",
+        ] {
+            assert!(matches!(
+                api_keys.check(Some("partner"), method, None),
+                Err(DasApiError::Forbidden)
+            ));
+            assert!(matches!(
+                api_keys.check(Some("subscriber"), method, None),
+                Err(DasApiError::Forbidden)
+            ));
+            assert!(api_keys.check(Some("operator"), method, None).is_ok());
+        }
+        for method in ["registerWebhook", "getWebhooks", "deleteWebhook"] {
+            assert!(matches!(
+                api_keys.check(Some("partner"), method, None),
+                Err(DasApiError::Forbidden)
+            ));
+            assert!(api_keys.check(Some("subscriber"), method, None).is_ok());
+        }
+        assert!(api_keys.check(Some("partner"), "getAsset", None).is_ok());
+        assert_eq!(
+            api_keys.key_name(Some("subscriber")),
+            Some("subscriber".to_string())
+        );
+        assert_eq!(api_keys.key_name(Some("unknown")), None);
+    }
+
+    #[test]
+    fn test_api_keys_reload() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        write_keys(
+            &file,
+            json!({"keys": [{"key": "first", "name": "first", "requests_per_second": 1}]}),
+        );
+        let api_keys = ApiKeys::from_file(file.path(), Arc::new(ApiMetricsConfig::new())).unwrap();
+        assert!(api_keys.check(Some("first"), "getAsset", None).is_ok());
+        assert!(!api_keys.reload().unwrap());
+
+        write_keys(&file, json!({"keys": "invalid"}));
+        // makes the modification visible on the file systems with a coarse modification time
+        let modified_at = SystemTime::now() + Duration::from_secs(1);
+        file.as_file().set_modified(modified_at).unwrap();
+        assert!(api_keys.reload().is_err());
+        assert!(matches!(
+            api_keys.check(Some("first"), "getAsset", None),
+            Err(DasApiError::RateLimited)
+        ));
+
+        write_keys(
+            &file,
+            json!({"keys": [{"key": "second", "name": "second", "requests_per_second": 1}]}),
+        );
+        file.as_file()
+            .set_modified(modified_at + Duration::from_secs(1))
+            .unwrap();
+        assert!(api_keys.reload().unwrap());
+        assert!(matches!(
+            api_keys.check(Some("first"), "getAsset", None),
+            Err(DasApiError::Unauthorized)
+        ));
+        assert!(api_keys.check(Some("second"), "getAsset", None).is_ok());
+    }
+
+    #[test]
+    fn test_api_keys_page_charger() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        write_keys(
+            &file,
+            json!({
+                "keys": [{
+                    "key": "secret",
+                    "name": "partner",
+                    "requests_per_second": 2,
+                    "max_page_size": 100,
+                }],
+                "method_costs": {"/owners_snapshot.csv": 2},
+            }),
+        );
+        let api_keys =
+            Arc::new(ApiKeys::from_file(file.path(), Arc::new(ApiMetricsConfig::new())).unwrap());
+
+        assert!(api_keys
+            .page_charger(Some("unknown"), "/owners_snapshot.csv", 10_000)
+            .is_none());
+        // the pages are capped by the max page size of the key
+        let (page_size, _) = api_keys
+            .page_charger(Some("secret"), "/owners_snapshot.csv", 10_000)
+            .unwrap();
+        assert_eq!(page_size, 100);
+
+        // every page spends the cost of the request from the quota
+        assert!(api_keys
+            .check(Some("secret"), "/owners_snapshot.csv", None)
+            .is_ok());
+        assert!(!api_keys
+            .try_spend_page("secret", "/owners_snapshot.csv")
+            .unwrap());
+        assert!(matches!(
+            api_keys.try_spend_page("unknown", "/owners_snapshot.csv"),
+            Err(DasApiError::Unauthorized)
+        ));
+    }
+}
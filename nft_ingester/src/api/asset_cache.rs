@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use entities::api_req_params::Options;
+use metrics_utils::ApiMetricsConfig;
+use moka::future::Cache;
+use rocks_db::Storage;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast::Receiver;
+use tokio::task::JoinError;
+use tracing::{error, info};
+
+use crate::api::dapi::rpc_asset_models::Asset;
+use crate::api::subscriptions::{fetch_updated_keys, last_known_key};
+
+const INVALIDATION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Responses of the latest state of the assets by the options they were requested with.
+/// The entries are dropped once the assets update index reports a change of the asset,
+/// the TTL bounds the staleness of the data the index doesn't cover,
+/// e.g. the collection metadata and the token prices
+pub struct AssetCache {
+    assets: Cache<Pubkey, Arc<HashMap<Options, Asset>>>,
+    // bumped by every invalidation, so the responses read before it aren't cached after it
+    generation: AtomicU64,
+    metrics: Arc<ApiMetricsConfig>,
+}
+
+impl AssetCache {
+    /// `max_capacity` is the number of the cached responses, each set of options counts separately
+    pub fn new(max_capacity: u64, time_to_live: Duration, metrics: Arc<ApiMetricsConfig>) -> Self {
+        let assets = Cache::builder()
+            .max_capacity(max_capacity)
+            .weigher(|_, variants: &Arc<HashMap<Options, Asset>>| variants.len() as u32)
+            .time_to_live(time_to_live)
+            .build();
+
+        Self {
+            assets,
+            generation: AtomicU64::new(0),
+            metrics,
+        }
+    }
+
+    pub async fn get(&self, id: &Pubkey, options: &Options) -> Option<Asset> {
+        let asset = self
+            .assets
+            .get(id)
+            .await
+            .and_then(|variants| variants.get(options).cloned());
+        self.metrics
+            .inc_asset_cache_requests(if asset.is_some() { "hit" } else { "miss" });
+
+        asset
+    }
+
+    /// Has to be taken before reading the asset which is going to be inserted
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Caches the asset read at the generation, unless the cache was invalidated since then
+    pub async fn insert(&self, id: Pubkey, options: Options, asset: Asset, generation: u64) {
+        if self.generation() != generation {
+            return;
+        }
+        self.assets
+            .entry(id)
+            .and_upsert_with(|variants| {
+                let mut variants = variants
+                    .map(|entry| entry.into_value().as_ref().clone())
+                    .unwrap_or_default();
+                variants.insert(options, asset);
+                std::future::ready(Arc::new(variants))
+            })
+            .await;
+        // the invalidation running concurrently with the insert may have missed it
+        if self.generation() != generation {
+            self.assets.invalidate(&id).await;
+        }
+        self.metrics
+            .set_asset_cache_size(self.assets.weighted_size());
+    }
+
+    pub async fn invalidate(&self, ids: impl IntoIterator<Item = Pubkey>) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        for id in ids {
+            self.assets.invalidate(&id).await;
+        }
+        self.metrics
+            .set_asset_cache_size(self.assets.weighted_size());
+    }
+}
+
+/// Tails the assets updates index, which is caught up on the API replicas as well,
+/// and drops the changed assets from the cache
+pub async fn run_asset_cache_invalidator(
+    asset_cache: Arc<AssetCache>,
+    rocks_db: Arc<Storage>,
+    mut rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    info!("Start invalidating asset cache...");
+    // the cache is empty on start, so the earlier changes are of no interest
+    let mut last_key = last_known_key(rocks_db.clone()).await.unwrap_or_else(|e| {
+        error!("Get last known asset updated key: {}", e);
+        None
+    });
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(INVALIDATION_POLL_INTERVAL) => {},
+            _ = rx.recv() => {
+                info!("Received stop signal, stopping invalidating asset cache!");
+                return Ok(());
+            }
+        }
+
+        while rx.is_empty() {
+            let (pubkeys, key) = match fetch_updated_keys(rocks_db.clone(), last_key.clone()).await
+            {
+                Ok(updated_keys) => updated_keys,
+                Err(e) => {
+                    error!("Fetch asset updated keys: {}", e);
+                    break;
+                }
+            };
+            last_key = key;
+            if pubkeys.is_empty() {
+                break;
+            }
+            asset_cache.invalidate(pubkeys).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::dapi::rpc_asset_models::Ownership;
+    use entities::enums::{Interface, OwnershipModel};
+
+    fn asset(id: &Pubkey) -> Asset {
+        Asset {
+            interface: Interface::V1NFT,
+            id: id.to_string(),
+            content: None,
+            authorities: None,
+            compression: None,
+            grouping: None,
+            royalty: None,
+            creators: None,
+            ownership: Ownership {
+                frozen: false,
+                delegated: false,
+                delegate: None,
+                ownership_model: OwnershipModel::Single,
+                owner: id.to_string(),
+            },
+            uses: None,
+            supply: None,
+            mutable: false,
+            burnt: false,
+            lamports: None,
+            executable: None,
+            metadata_owner: None,
+            rent_epoch: None,
+            plugins: None,
+            unknown_plugins: None,
+            mpl_core_info: None,
+            external_plugins: None,
+            unknown_external_plugins: None,
+            inscription: None,
+            spl20: None,
+            mint_extensions: None,
+            token_info: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_asset_cache() {
+        let cache = AssetCache::new(
+            100,
+            Duration::from_secs(60),
+            Arc::new(ApiMetricsConfig::new()),
+        );
+        let id = Pubkey::new_unique();
+        let options = Options::default();
+        let with_collection_metadata = Options {
+            show_collection_metadata: true,
+            ..Default::default()
+        };
+
+        assert!(cache.get(&id, &options).await.is_none());
+        cache
+            .insert(id, options.clone(), asset(&id), cache.generation())
+            .await;
+        assert_eq!(cache.get(&id, &options).await, Some(asset(&id)));
+        assert!(cache.get(&id, &with_collection_metadata).await.is_none());
+        cache
+            .insert(
+                id,
+                with_collection_metadata.clone(),
+                asset(&id),
+                cache.generation(),
+            )
+            .await;
+        assert!(cache.get(&id, &options).await.is_some());
+        assert!(cache.get(&id, &with_collection_metadata).await.is_some());
+
+        cache.invalidate([id]).await;
+        assert!(cache.get(&id, &options).await.is_none());
+        assert!(cache.get(&id, &with_collection_metadata).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_asset_cache_skips_invalidated_reads() {
+        let cache = AssetCache::new(
+            100,
+            Duration::from_secs(60),
+            Arc::new(ApiMetricsConfig::new()),
+        );
+        let id = Pubkey::new_unique();
+        let generation = cache.generation();
+
+        cache.invalidate([Pubkey::new_unique()]).await;
+        cache
+            .insert(id, Options::default(), asset(&id), generation)
+            .await;
+        assert!(cache.get(&id, &Options::default()).await.is_none());
+    }
+}
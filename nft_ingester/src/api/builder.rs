@@ -8,12 +8,15 @@ use entities::api_req_params::{
 use interface::consistency_check::ConsistencyChecker;
 use jsonrpc_core::types::params::Params;
 use jsonrpc_core::MetaIoHandler;
+use serde_json::json;
 use tokio::sync::Mutex;
 use tokio::task::{JoinError, JoinSet};
 use usecase::proofs::MaybeProofChecker;
 
 use crate::api::account_balance::AccountBalanceGetterImpl;
-use crate::api::meta_middleware::RpcMetaMiddleware;
+use crate::api::api_keys::ApiKeys;
+use crate::api::meta_middleware::{RequestMetadata, RpcMetaMiddleware};
+use crate::api::openrpc::{discover_document, DISCOVER_METHOD};
 use crate::api::*;
 use crate::json_worker::JsonWorker;
 use crate::raydium_price_fetcher::RaydiumTokenPriceFetcher;
@@ -22,21 +25,32 @@ pub struct RpcApiBuilder;
 
 impl RpcApiBuilder {
     pub(crate) fn build(
-        api: DasApi<
-            MaybeProofChecker,
-            JsonWorker,
-            JsonWorker,
-            AccountBalanceGetterImpl,
-            RaydiumTokenPriceFetcher,
+        api: Arc<
+            DasApi<
+                MaybeProofChecker,
+                JsonWorker,
+                JsonWorker,
+                AccountBalanceGetterImpl,
+                RaydiumTokenPriceFetcher,
+            >,
         >,
         consistency_checkers: Vec<Arc<dyn ConsistencyChecker>>,
+        api_keys: Option<Arc<ApiKeys>>,
         tasks: Arc<Mutex<JoinSet<Result<(), JoinError>>>>,
-    ) -> Result<MetaIoHandler<RpcMetaMiddleware, RpcMetaMiddleware>, DasApiError> {
-        let mut module = MetaIoHandler::<RpcMetaMiddleware, RpcMetaMiddleware>::new(
+    ) -> Result<MetaIoHandler<RequestMetadata, RpcMetaMiddleware>, DasApiError> {
+        // only the keys with the admin scope may call the operator methods,
+        // so they aren't served to anyone if the keys aren't configured
+        let serve_operator_methods = api_keys.is_some();
+        let webhook_api_keys = api_keys.clone();
+        let mut module = MetaIoHandler::<RequestMetadata, RpcMetaMiddleware>::new(
             Default::default(),
-            RpcMetaMiddleware::new(consistency_checkers),
+            RpcMetaMiddleware::new(
+                consistency_checkers,
+                api.rocks_db.clone(),
+                api.pg_client.clone(),
+                api_keys,
+            ),
         );
-        let api = Arc::new(api);
 
         let cloned_api = api.clone();
         module.add_method("health", move |_rpc_params: Params| {
@@ -45,6 +59,13 @@ impl RpcApiBuilder {
         });
         module.add_alias("getAuraHealth", "health");
 
+        let cloned_api = api.clone();
+        module.add_method("get_indexer_status", move |_rpc_params: Params| {
+            let api = cloned_api.clone();
+            async move { api.get_indexer_status().await.map_err(Into::into) }
+        });
+        module.add_alias("getIndexerStatus", "get_indexer_status");
+
         let cloned_api = api.clone();
         module.add_method("get_asset_proof", move |rpc_params: Params| {
             let api = cloned_api.clone();
@@ -252,6 +273,154 @@ impl RpcApiBuilder {
         });
         module.add_alias("getTokenAccounts", "get_token_accounts");
 
+        let cloned_api = api.clone();
+        module.add_method("get_nft_editions", move |rpc_params: Params| {
+            let api = cloned_api.clone();
+            async move {
+                api.get_nft_editions(rpc_params.parse()?)
+                    .await
+                    .map_err(Into::into)
+            }
+        });
+        module.add_alias("getNftEditions", "get_nft_editions");
+
+        let cloned_api = api.clone();
+        module.add_method("get_tree_info", move |rpc_params: Params| {
+            let api = cloned_api.clone();
+            async move {
+                api.get_tree_info(rpc_params.parse()?)
+                    .await
+                    .map_err(Into::into)
+            }
+        });
+        module.add_alias("getTreeInfo", "get_tree_info");
+
+        let cloned_api = api.clone();
+        module.add_method("get_owners_snapshot", move |rpc_params: Params| {
+            let api = cloned_api.clone();
+            async move {
+                api.get_owners_snapshot(rpc_params.parse()?)
+                    .await
+                    .map_err(Into::into)
+            }
+        });
+        module.add_alias("getOwnersSnapshot", "get_owners_snapshot");
+
+        if serve_operator_methods {
+            let cloned_api = api.clone();
+            module.add_method("refresh_asset_metadata", move |rpc_params: Params| {
+                let api = cloned_api.clone();
+                async move {
+                    api.refresh_asset_metadata(rpc_params.parse()?)
+                        .await
+                        .map_err(Into::into)
+                }
+            });
+            module.add_alias("refreshAssetMetadata", "refresh_asset_metadata");
+        }
+
+        // the webhooks belong to the API keys, so they aren't served if the keys aren't configured
+        if let Some(api_keys) = webhook_api_keys {
+            // the key was checked by the middleware, the name is only missing
+            // if the keys were reloaded in between
+            let cloned_api = api.clone();
+            let cloned_api_keys = api_keys.clone();
+            module.add_method_with_meta(
+                "register_webhook",
+                move |rpc_params: Params, meta: RequestMetadata| {
+                    let api = cloned_api.clone();
+                    let quota = cloned_api_keys.webhooks_quota(meta.api_key.as_deref());
+                    async move {
+                        let (key_name, max_webhooks) = quota.ok_or(DasApiError::Unauthorized)?;
+                        api.register_webhook(rpc_params.parse()?, key_name, max_webhooks)
+                            .await
+                            .map_err(Into::into)
+                    }
+                },
+            );
+            module.add_alias("registerWebhook", "register_webhook");
+
+            let cloned_api = api.clone();
+            let cloned_api_keys = api_keys.clone();
+            module.add_method_with_meta(
+                "get_webhooks",
+                move |rpc_params: Params, meta: RequestMetadata| {
+                    let api = cloned_api.clone();
+                    let key_name = cloned_api_keys.key_name(meta.api_key.as_deref());
+                    async move {
+                        let key_name = key_name.ok_or(DasApiError::Unauthorized)?;
+                        api.get_webhooks(rpc_params.parse()?, key_name)
+                            .await
+                            .map_err(Into::into)
+                    }
+                },
+            );
+            module.add_alias("getWebhooks", "get_webhooks");
+
+            let cloned_api = api.clone();
+            let cloned_api_keys = api_keys.clone();
+            module.add_method_with_meta(
+                "delete_webhook",
+                move |rpc_params: Params, meta: RequestMetadata| {
+                    let api = cloned_api.clone();
+                    let key_name = cloned_api_keys.key_name(meta.api_key.as_deref());
+                    async move {
+                        let key_name = key_name.ok_or(DasApiError::Unauthorized)?;
+                        api.delete_webhook(rpc_params.parse()?, key_name)
+                            .await
+                            .map_err(Into::into)
+                    }
+                },
+            );
+            module.add_alias("deleteWebhook", "delete_webhook");
+        }
+
+        if serve_operator_methods {
+            let cloned_api = api.clone();
+            module.add_method("get_dead_letters", move |rpc_params: Params| {
+                let api = cloned_api.clone();
+                async move {
+                    api.get_dead_letters(rpc_params.parse()?)
+                        .await
+                        .map_err(Into::into)
+                }
+            });
+            module.add_alias("getDeadLetters", "get_dead_letters");
+
+            let cloned_api = api.clone();
+            module.add_method("get_dead_letter", move |rpc_params: Params| {
+                let api = cloned_api.clone();
+                async move {
+                    api.get_dead_letter(rpc_params.parse()?)
+                        .await
+                        .map_err(Into::into)
+                }
+            });
+            module.add_alias("getDeadLetter", "get_dead_letter");
+
+            let cloned_api = api.clone();
+            module.add_method("redrive_dead_letters", move |rpc_params: Params| {
+                let api = cloned_api.clone();
+                async move {
+                    api.redrive_dead_letters(rpc_params.parse()?)
+                        .await
+                        .map_err(Into::into)
+                }
+            });
+            module.add_alias("redriveDeadLetters", "redrive_dead_letters");
+
+            let cloned_api = api.clone();
+            module.add_method("get_tree_gap_repairs", move |rpc_params: Params| {
+                let api = cloned_api.clone();
+                async move {
+                    api.get_tree_gap_repairs(rpc_params.parse()?)
+                        .await
+                        .map_err(Into::into)
+                }
+            });
+            module.add_alias("getTreeGapRepairs", "get_tree_gap_repairs");
+        }
+
         module.add_method("get_core_fees", move |rpc_params: Params| {
             let api = api.clone();
             async move {
@@ -262,6 +431,12 @@ impl RpcApiBuilder {
         });
         module.add_alias("getCoreFees", "get_core_fees");
 
+        let discover_document = json!(discover_document());
+        module.add_method(DISCOVER_METHOD, move |_rpc_params: Params| {
+            let discover_document = discover_document.clone();
+            async move { Ok(discover_document) }
+        });
+
         Ok(module)
     }
 }
@@ -3,7 +3,7 @@ use std::string::ToString;
 use std::sync::Arc;
 
 use entities::api_req_params::{AssetSortDirection, Options};
-use entities::models::{AssetSignatureWithPagination, OffChainData};
+use entities::models::{AssetSignatureWithPagination, DownloadedJson, OffChainData};
 use interface::asset_sigratures::AssetSignaturesGetter;
 use interface::json::{JsonDownloader, JsonPersister};
 use rocks_db::errors::StorageError;
@@ -16,12 +16,23 @@ use interface::price_fetcher::TokenPriceFetcher;
 use interface::processing_possibility::ProcessingPossibilityChecker;
 use metrics_utils::ApiMetricsConfig;
 use rocks_db::asset::{AssetLeaf, AssetSelectedMaps};
+use rocks_db::asset_signatures::decode_asset_account_signature_cursor;
 use rocks_db::{AssetAuthority, Storage};
 use tokio::sync::Mutex;
 use tokio::task::{JoinError, JoinSet};
 
 pub const COLLECTION_GROUP_KEY: &str = "collection";
 
+/// Slot older than the latest one the assets are requested at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestedSlot {
+    /// `atSlot`, the assets are reconstructed from the history
+    AtSlot(u64),
+    /// the last confirmed or finalized slot, the assets are reconstructed
+    /// from the versions replaced since then
+    Commitment(u64),
+}
+
 fn convert_rocks_asset_model(
     asset_pubkey: &Pubkey,
     asset_selected_maps: &AssetSelectedMaps,
@@ -187,6 +198,8 @@ pub async fn get_by_ids<TPF: TokenPriceFetcher>(
     owner_address: &Option<Pubkey>,
     token_price_fetcher: Arc<TPF>,
     metrics: Arc<ApiMetricsConfig>,
+    // if set, the assets are returned as they were at this slot
+    at_slot: Option<RequestedSlot>,
 ) -> Result<Vec<Option<FullAsset>>, StorageError> {
     if asset_ids.is_empty() {
         return Ok(vec![]);
@@ -213,6 +226,19 @@ pub async fn get_by_ids<TPF: TokenPriceFetcher>(
     let (token_prices, token_symbols, asset_selected_maps) =
         tokio::join!(token_prices_fut, token_symbols_fut, asset_selected_maps_fut);
     let mut asset_selected_maps = asset_selected_maps?;
+    match at_slot {
+        Some(RequestedSlot::AtSlot(slot)) => {
+            rocks_db
+                .apply_asset_history_at_slot(&mut asset_selected_maps, &unique_asset_ids, slot)
+                .await?
+        }
+        Some(RequestedSlot::Commitment(slot)) => {
+            rocks_db
+                .apply_previous_versions_at_slot(&mut asset_selected_maps, &unique_asset_ids, slot)
+                .await?
+        }
+        None => {}
+    }
     let token_prices = token_prices.unwrap_or_else(|e| {
         error!("Fetch token prices: {}", e);
         metrics.inc_token_info_fetch_errors("prices");
@@ -266,7 +292,10 @@ pub async fn get_by_ids<TPF: TokenPriceFetcher>(
 
             if let Some(json_persister) = json_persister {
                 if !download_results.is_empty() {
-                    let download_results = download_results.clone();
+                    let download_results = download_results
+                        .into_iter()
+                        .map(|(url, res)| (url, res.map(DownloadedJson::new)))
+                        .collect::<Vec<_>>();
                     tasks.lock().await.spawn(async move {
                         if let Err(e) = json_persister.persist_response(download_results).await {
                             error!("Could not persist downloaded JSONs: {:?}", e);
@@ -310,17 +339,6 @@ pub async fn get_asset_signatures(
     limit: u64,
     sort_direction: Option<AssetSortDirection>,
 ) -> Result<AssetSignatureWithPagination, StorageError> {
-    let before_sequence = before.as_ref().and_then(|b| b.parse::<u64>().ok());
-    let after_sequence = after.as_ref().and_then(|a| a.parse::<u64>().ok());
-    if let (Some(before_sequence), Some(after_sequence)) = (before_sequence, after_sequence) {
-        let invalid_range = match sort_direction {
-            Some(AssetSortDirection::Asc) => before_sequence <= after_sequence,
-            _ => before_sequence >= after_sequence,
-        };
-        if invalid_range {
-            return Ok(AssetSignatureWithPagination::default());
-        }
-    }
     let sort_direction = sort_direction.unwrap_or(AssetSortDirection::Desc);
     let (tree_id, leaf_idx) = match (tree_id, leaf_idx, asset_id) {
         (Some(tree_id), Some(leaf_idx), None) => {
@@ -330,17 +348,25 @@ pub async fn get_asset_signatures(
         (None, None, Some(asset_id)) => {
             // if only asset_id is provided, fetch the latest tree and leaf_idx (asset.nonce) for the asset
             // and use them to fetch transactions
-            let asset_leaf = storage
+            match storage
                 .asset_leaf_data
                 .get(asset_id)?
-                .ok_or_else(|| StorageError::Common("Leaf ID does not exist".to_string()))?; // Not found error
-            (
-                asset_leaf.tree_id,
-                asset_leaf.nonce.ok_or_else(|| {
-                    StorageError::Common("Leaf nonce does not exist".to_string())
-                    // Not found error
-                })?,
-            )
+                .and_then(|asset_leaf| asset_leaf.nonce.map(|nonce| (asset_leaf.tree_id, nonce)))
+            {
+                Some(leaf) => leaf,
+                // non-compressed assets have their signatures recorded per asset account
+                None => {
+                    return Ok(get_asset_account_signatures(
+                        storage.as_ref(),
+                        asset_id,
+                        page,
+                        before,
+                        after,
+                        limit,
+                        sort_direction,
+                    ))
+                }
+            }
         }
         _ => {
             // If neither set of parameters is provided, return an error
@@ -350,6 +376,18 @@ pub async fn get_asset_signatures(
         }
     };
 
+    let before_sequence = before.as_ref().and_then(|b| b.parse::<u64>().ok());
+    let after_sequence = after.as_ref().and_then(|a| a.parse::<u64>().ok());
+    if let (Some(before_sequence), Some(after_sequence)) = (before_sequence, after_sequence) {
+        let invalid_range = match sort_direction {
+            AssetSortDirection::Asc => before_sequence <= after_sequence,
+            AssetSortDirection::Desc => before_sequence >= after_sequence,
+        };
+        if invalid_range {
+            return Ok(AssetSignatureWithPagination::default());
+        }
+    }
+
     Ok(storage.get_asset_signatures(
         tree_id,
         leaf_idx,
@@ -360,3 +398,30 @@ pub async fn get_asset_signatures(
         limit,
     ))
 }
+
+fn get_asset_account_signatures(
+    storage: &Storage,
+    asset_id: Pubkey,
+    page: Option<u64>,
+    before: &Option<String>,
+    after: &Option<String>,
+    limit: u64,
+    sort_direction: AssetSortDirection,
+) -> AssetSignatureWithPagination {
+    let before = before
+        .as_deref()
+        .and_then(decode_asset_account_signature_cursor);
+    let after = after
+        .as_deref()
+        .and_then(decode_asset_account_signature_cursor);
+    if let (Some(before), Some(after)) = (before, after) {
+        let invalid_range = match sort_direction {
+            AssetSortDirection::Asc => before <= after,
+            AssetSortDirection::Desc => before >= after,
+        };
+        if invalid_range {
+            return AssetSignatureWithPagination::default();
+        }
+    }
+    storage.get_asset_account_signatures(asset_id, before, after, page, sort_direction, limit)
+}
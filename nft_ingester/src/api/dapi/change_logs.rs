@@ -3,7 +3,8 @@ use std::{collections::HashMap, str::FromStr};
 
 use interface::proofs::ProofChecker;
 use metrics_utils::ApiMetricsConfig;
-use rocks_db::cl_items::{ClItemKey, ClLeafKey};
+use rocks_db::cl_items::{ClItem, ClItemKey, ClLeafKey};
+use rocks_db::cl_items_history::ClItemsVersion;
 use rocks_db::errors::StorageError;
 use solana_sdk::pubkey::Pubkey;
 use tracing::{debug, warn};
@@ -31,6 +32,7 @@ pub async fn get_proof_for_assets(
     asset_ids: Vec<Pubkey>,
     proof_checker: Option<Arc<impl ProofChecker + Sync + Send + 'static>>,
     metrics: Arc<ApiMetricsConfig>,
+    version: Option<ClItemsVersion>,
 ) -> Result<HashMap<String, Option<AssetProof>>, StorageError> {
     if !rocks_db.can_process_assets(asset_ids.as_slice()).await {
         return Err(StorageError::CannotServiceRequest);
@@ -62,7 +64,7 @@ pub async fn get_proof_for_assets(
             cl_leaf.map(|leaf| ClItemKey::new(leaf.cli_node_idx, leaf.cli_tree_key))
         })
         .collect::<Vec<_>>();
-    let cl_items_first_leaf = rocks_db.cl_items.batch_get(keys.clone()).await?;
+    let cl_items_first_leaf = get_cl_items(&rocks_db, keys.clone(), version).await?;
 
     if cl_items_first_leaf.is_empty() {
         return Ok(HashMap::new());
@@ -101,9 +103,7 @@ pub async fn get_proof_for_assets(
                 .map(move |node| ClItemKey::new(node as u64, tree_id))
         })
         .collect();
-    let all_nodes = rocks_db
-        .cl_items
-        .batch_get(all_req_keys)
+    let all_nodes = get_cl_items(&rocks_db, all_req_keys, version)
         .await?
         .into_iter()
         .flatten()
@@ -130,6 +130,17 @@ pub async fn get_proof_for_assets(
     Ok(results)
 }
 
+async fn get_cl_items(
+    rocks_db: &Storage,
+    keys: Vec<ClItemKey>,
+    version: Option<ClItemsVersion>,
+) -> Result<Vec<Option<ClItem>>, StorageError> {
+    match version {
+        Some(version) => rocks_db.get_cl_items_at_version(keys, version).await,
+        None => rocks_db.cl_items.batch_get(keys).await,
+    }
+}
+
 fn get_asset_proof(
     asset_id: &Pubkey,
     nodes: &[SimpleChangeLog],
@@ -179,6 +190,13 @@ fn get_asset_proof(
         );
     }
 
+    // every update changes the root, so the latest seq of the path is the seq of the whole tree
+    let seq = final_node_list
+        .iter()
+        .map(|node| node.cli_seq)
+        .max()
+        .unwrap_or_default()
+        .max(leaf.seq);
     let root = bs58::encode(final_node_list.pop().unwrap().cli_hash).into_string();
     let proof: Vec<Vec<u8>> = final_node_list
         .iter()
@@ -234,6 +252,7 @@ fn get_asset_proof(
         proof,
         node_index: leaf.node_idx,
         tree_id: tree_id.to_string(),
+        seq,
     })
 }
 
@@ -1,12 +1,14 @@
 use crate::api::dapi::asset::COLLECTION_GROUP_KEY;
-use entities::api_req_params::SearchConditionType;
 use entities::api_req_params::{
     GetAssetsByAuthority, GetAssetsByCreator, GetAssetsByGroup, GetAssetsByOwner, SearchAssets,
 };
+use entities::api_req_params::{NameMatchType, SearchConditionType};
 use entities::enums::{
     OwnerType, RoyaltyTargetType, SpecificationAssetClass, SpecificationVersions, TokenType,
 };
 use interface::error::UsecaseError;
+use postgre_client::model::AssetAttributes;
+use rocks_db::asset::core_plugin_type;
 use thiserror::Error;
 use usecase::validation::{validate_opt_pubkey_vec, validate_pubkey};
 
@@ -46,6 +48,11 @@ pub struct SearchAssetsQuery {
     pub burnt: Option<bool>,
     pub json_uri: Option<String>,
     pub token_type: Option<TokenType>,
+    pub name: Option<AssetName>,
+    pub symbol: Option<String>,
+    pub attributes: Option<Vec<(String, String)>>,
+    pub core_plugins: Option<Vec<String>>,
+    pub core_attributes: Option<Vec<(String, String)>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -54,6 +61,13 @@ pub enum AssetSupply {
     Equal(u64),
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetName {
+    Exact(String),
+    Prefix(String),
+    Fuzzy(String),
+}
+
 impl TryFrom<SearchAssets> for SearchAssetsQuery {
     type Error = UsecaseError;
     fn try_from(search_assets: SearchAssets) -> Result<Self, Self::Error> {
@@ -92,6 +106,48 @@ impl TryFrom<SearchAssets> for SearchAssetsQuery {
                 .map(|s| s.into())
                 .filter(|v| v != &SpecificationAssetClass::Unknown),
             token_type: search_assets.token_type,
+            name: search_assets
+                .name
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .map(|name| match search_assets.name_match.unwrap_or_default() {
+                    NameMatchType::Exact => AssetName::Exact(name),
+                    NameMatchType::Prefix => AssetName::Prefix(name),
+                    NameMatchType::Fuzzy => AssetName::Fuzzy(name),
+                }),
+            symbol: search_assets
+                .symbol
+                .map(|symbol| symbol.trim().to_string())
+                .filter(|symbol| !symbol.is_empty()),
+            attributes: search_assets
+                .attributes
+                .map(|attributes| {
+                    attributes
+                        .into_iter()
+                        .map(|a| (a.trait_type.trim().to_string(), a.value.trim().to_string()))
+                        .collect::<Vec<_>>()
+                })
+                .filter(|attributes| !attributes.is_empty()),
+            // plugin types are indexed in snake_case, so both "FreezeDelegate" and "freeze_delegate" match
+            core_plugins: search_assets
+                .core_plugins
+                .map(|plugins| {
+                    plugins
+                        .into_iter()
+                        .map(|plugin| core_plugin_type(&plugin))
+                        .filter(|plugin| !plugin.is_empty())
+                        .collect::<Vec<_>>()
+                })
+                .filter(|plugins| !plugins.is_empty()),
+            core_attributes: search_assets
+                .core_attributes
+                .map(|attributes| {
+                    attributes
+                        .into_iter()
+                        .map(|a| (a.key, a.value))
+                        .collect::<Vec<_>>()
+                })
+                .filter(|attributes| !attributes.is_empty()),
         })
     }
 }
@@ -201,6 +257,22 @@ impl TryFrom<SearchAssetsQuery> for postgre_client::model::SearchAssetsFilter {
             burnt: query.burnt,
             json_uri: query.json_uri,
             token_type: query.token_type,
+            name: query.name.map(|n| n.into()),
+            symbol: query.symbol,
+            attributes: query
+                .attributes
+                .map(|attributes| match query.condition_type {
+                    Some(ConditionType::Any) => AssetAttributes::Any(attributes),
+                    _ => AssetAttributes::All(attributes),
+                }),
+            core_plugins: query.core_plugins,
+            core_attributes: query
+                .core_attributes
+                .map(|attributes| match query.condition_type {
+                    Some(ConditionType::Any) => AssetAttributes::Any(attributes),
+                    _ => AssetAttributes::All(attributes),
+                }),
+            owner_at_slot: None,
         })
     }
 }
@@ -214,6 +286,16 @@ impl From<AssetSupply> for postgre_client::model::AssetSupply {
     }
 }
 
+impl From<AssetName> for postgre_client::model::AssetName {
+    fn from(name: AssetName) -> Self {
+        match name {
+            AssetName::Exact(n) => Self::Exact(n),
+            AssetName::Prefix(n) => Self::Prefix(n),
+            AssetName::Fuzzy(n) => Self::Fuzzy(n),
+        }
+    }
+}
+
 impl From<SearchConditionType> for ConditionType {
     fn from(search_condition_type: SearchConditionType) -> Self {
         match search_condition_type {
@@ -225,7 +307,104 @@ impl From<SearchConditionType> for ConditionType {
 
 #[cfg(test)]
 mod tests {
-    use crate::api::dapi::converters::SearchAssetsQuery;
+    use crate::api::dapi::converters::{AssetName, SearchAssetsQuery};
+    use entities::api_req_params::{
+        NameMatchType, SearchAssetAttribute, SearchAssets, SearchConditionType, SearchCoreAttribute,
+    };
+    use postgre_client::model::AssetAttributes;
+
+    #[test]
+    fn test_search_assets_query_name_match_conversion() {
+        let query = SearchAssetsQuery::try_from(SearchAssets {
+            name: Some(" Mad Lads ".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(query.name, Some(AssetName::Exact("Mad Lads".to_string())));
+
+        let query = SearchAssetsQuery::try_from(SearchAssets {
+            name: Some("Mad".to_string()),
+            name_match: Some(NameMatchType::Prefix),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(query.name, Some(AssetName::Prefix("Mad".to_string())));
+
+        let query = SearchAssetsQuery::try_from(SearchAssets {
+            name: Some("  ".to_string()),
+            name_match: Some(NameMatchType::Fuzzy),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(query.name, None);
+    }
+
+    #[test]
+    fn test_search_assets_filter_attributes_condition_type() {
+        let attributes = vec![SearchAssetAttribute {
+            trait_type: "Background".to_string(),
+            value: " Blue ".to_string(),
+        }];
+        let query = SearchAssetsQuery::try_from(SearchAssets {
+            attributes: Some(attributes.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+        let filter = postgre_client::model::SearchAssetsFilter::try_from(query).unwrap();
+        assert!(matches!(
+            filter.attributes,
+            Some(AssetAttributes::All(a)) if a == vec![("Background".to_string(), "Blue".to_string())]
+        ));
+
+        let query = SearchAssetsQuery::try_from(SearchAssets {
+            attributes: Some(attributes),
+            condition_type: Some(SearchConditionType::Any),
+            ..Default::default()
+        })
+        .unwrap();
+        let filter = postgre_client::model::SearchAssetsFilter::try_from(query).unwrap();
+        assert!(matches!(filter.attributes, Some(AssetAttributes::Any(_))));
+    }
+
+    #[test]
+    fn test_search_assets_filter_core_plugins() {
+        let query = SearchAssetsQuery::try_from(SearchAssets {
+            core_plugins: Some(vec![
+                "FreezeDelegate".to_string(),
+                " oracle ".to_string(),
+                "LifecycleHook".to_string(),
+            ]),
+            core_attributes: Some(vec![SearchCoreAttribute {
+                key: "level".to_string(),
+                value: "10".to_string(),
+            }]),
+            condition_type: Some(SearchConditionType::Any),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            query.core_plugins,
+            Some(vec![
+                "freeze_delegate".to_string(),
+                "oracle".to_string(),
+                "lifecycle_hook".to_string()
+            ])
+        );
+        let filter = postgre_client::model::SearchAssetsFilter::try_from(query).unwrap();
+        assert!(matches!(
+            filter.core_attributes,
+            Some(AssetAttributes::Any(a)) if a == vec![("level".to_string(), "10".to_string())]
+        ));
+
+        let query = SearchAssetsQuery::try_from(SearchAssets {
+            core_plugins: Some(vec![]),
+            core_attributes: Some(vec![]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(query.core_plugins, None);
+        assert_eq!(query.core_attributes, None);
+    }
 
     #[test]
     fn test_search_assets_filter_from_search_assets_query_conversion_error() {
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use postgre_client::PgClient;
+use rocks_db::dead_letters::{DeadLetter, DeadLetterStream};
+use rocks_db::errors::StorageError;
+use rocks_db::Storage;
+
+use crate::api::dapi::response::{DeadLetterItem, DeadLettersList, DeadLettersRedrive};
+
+pub async fn get_dead_letters(
+    rocks_db: Arc<Storage>,
+    limit: u64,
+    after: Option<u64>,
+) -> Result<DeadLettersList, StorageError> {
+    let dead_letters =
+        tokio::task::spawn_blocking(move || rocks_db.get_dead_letters(after, limit as usize))
+            .await
+            .map_err(|e| StorageError::Common(e.to_string()))?;
+
+    let cursor = dead_letters.last().map(|(id, _)| id.to_string());
+    let items = dead_letters
+        .into_iter()
+        .map(|(id, dead_letter)| dead_letter_item(id, dead_letter, false))
+        .collect::<Vec<_>>();
+
+    Ok(DeadLettersList {
+        total: items.len() as u64,
+        limit,
+        cursor,
+        items,
+    })
+}
+
+pub async fn get_dead_letter(
+    rocks_db: Arc<Storage>,
+    id: u64,
+) -> Result<Option<DeadLetterItem>, StorageError> {
+    Ok(rocks_db
+        .dead_letters
+        .get(id)?
+        .map(|dead_letter| dead_letter_item(id, dead_letter, true)))
+}
+
+/// Requests the ingester to feed the dead letters back into the account and transaction processors
+pub async fn redrive_dead_letters(
+    rocks_db: Arc<Storage>,
+    pg_client: Arc<PgClient>,
+    ids: Vec<u64>,
+) -> Result<DeadLettersRedrive, StorageError> {
+    let dead_letters = rocks_db.dead_letters.batch_get(ids.clone()).await?;
+
+    let mut response = DeadLettersRedrive::default();
+    let mut found_ids = Vec::new();
+    for (id, dead_letter) in ids.into_iter().zip(dead_letters) {
+        if dead_letter.is_some() {
+            found_ids.push(id);
+            response.queued.push(id.to_string());
+        } else {
+            response.not_found.push(id.to_string());
+        }
+    }
+
+    pg_client
+        .request_dead_letters_redrive(&found_ids)
+        .await
+        .map_err(|e| StorageError::Common(e.to_string()))?;
+
+    Ok(response)
+}
+
+fn dead_letter_item(id: u64, dead_letter: DeadLetter, with_data: bool) -> DeadLetterItem {
+    let stream = match dead_letter.stream {
+        DeadLetterStream::Accounts => "accounts",
+        DeadLetterStream::Transactions => "transactions",
+    };
+
+    DeadLetterItem {
+        id: id.to_string(),
+        stream: stream.to_string(),
+        pubkey: dead_letter.pubkey.map(|pubkey| pubkey.to_string()),
+        error: dead_letter.error,
+        failed_at: dead_letter.failed_at,
+        data_size: dead_letter.data.len() as u64,
+        data: with_data.then(|| general_purpose::STANDARD.encode(&dead_letter.data)),
+    }
+}
@@ -11,7 +11,7 @@ use tokio::{
     task::{JoinError, JoinSet},
 };
 
-use crate::api::dapi::asset;
+use crate::api::dapi::asset::{self, RequestedSlot};
 use crate::api::dapi::rpc_asset_convertors::asset_to_rpc;
 use crate::api::dapi::rpc_asset_models::Asset;
 
@@ -29,6 +29,7 @@ pub async fn get_asset<TPF: TokenPriceFetcher>(
     storage_service_base_path: Option<String>,
     token_price_fetcher: Arc<TPF>,
     metrics: Arc<ApiMetricsConfig>,
+    at_slot: Option<RequestedSlot>,
 ) -> Result<Option<Asset>, StorageError> {
     let assets = asset::get_by_ids(
         rocks_db.clone(),
@@ -41,6 +42,7 @@ pub async fn get_asset<TPF: TokenPriceFetcher>(
         &None,
         token_price_fetcher,
         metrics,
+        at_slot,
     )
     .await?;
 
@@ -10,7 +10,7 @@ use tokio::{
     task::{JoinError, JoinSet},
 };
 
-use crate::api::dapi::asset;
+use crate::api::dapi::asset::{self, RequestedSlot};
 use crate::api::dapi::rpc_asset_convertors::asset_to_rpc;
 use crate::api::dapi::rpc_asset_models::Asset;
 
@@ -28,6 +28,7 @@ pub async fn get_asset_batch<TPF: TokenPriceFetcher>(
     storage_service_base_path: Option<String>,
     token_price_fetcher: Arc<TPF>,
     metrics: Arc<ApiMetricsConfig>,
+    at_slot: Option<RequestedSlot>,
 ) -> Result<Vec<Option<Asset>>, StorageError> {
     let assets = asset::get_by_ids(
         rocks_db.clone(),
@@ -40,6 +41,7 @@ pub async fn get_asset_batch<TPF: TokenPriceFetcher>(
         &None,
         token_price_fetcher,
         metrics,
+        at_slot,
     )
     .await?;
 
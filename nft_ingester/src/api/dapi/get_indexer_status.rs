@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use postgre_client::storage_traits::AssetIndexStorage;
+use postgre_client::PgClient;
+use rocks_db::errors::StorageError;
+use rocks_db::key_encoders::decode_u64x2_pubkey;
+use rocks_db::parameters::Parameter;
+use rocks_db::storage_traits::AssetUpdateIndexStorage;
+use rocks_db::Storage;
+
+use crate::api::dapi::response::IndexerStatus;
+
+pub async fn get_indexer_status(
+    rocks_db: Arc<Storage>,
+    pg_client: Arc<PgClient>,
+) -> Result<IndexerStatus, StorageError> {
+    // taken before the last known key is read, as reading it catches up with the primary
+    let secondary_catch_up_age_sec = rocks_db
+        .last_catch_up_at()
+        .map(|timestamp| chrono::Utc::now().timestamp() - timestamp);
+
+    let (last_ingested_slot, last_confirmed_slot, last_finalized_slot) = tokio::try_join!(
+        rocks_db.get_parameter::<u64>(Parameter::TopSeenSlot),
+        rocks_db.get_parameter::<u64>(Parameter::LastConfirmedSlot),
+        rocks_db.get_parameter::<u64>(Parameter::LastFinalizedSlot),
+    )?;
+    let (last_backfilled_slot, last_fetched_slot) = tokio::try_join!(
+        rocks_db.get_parameter::<u64>(Parameter::LastBackfilledSlot),
+        rocks_db.get_parameter::<u64>(Parameter::LastFetchedSlot),
+    )?;
+
+    let rocks = rocks_db.clone();
+    let (last_known_key, trees_with_gaps) = tokio::task::spawn_blocking(move || {
+        rocks
+            .last_known_asset_updated_key()
+            .map(|key| (key, rocks.trees_gaps.iter_start().count() as u64))
+    })
+    .await
+    .map_err(|e| StorageError::Common(e.to_string()))??;
+
+    let last_synced_key = pg_client
+        .fetch_last_synced_id()
+        .await
+        .map_err(|e| StorageError::Common(e.to_string()))?
+        .map(decode_u64x2_pubkey)
+        .transpose()?;
+    let json_tasks_pending = pg_client
+        .count_pending_tasks()
+        .await
+        .map_err(|e| StorageError::Common(e.to_string()))?;
+
+    let last_known_update_seq = last_known_key.map(|key| key.seq);
+    let last_synced_update_seq = last_synced_key.map(|key| key.seq);
+
+    Ok(IndexerStatus {
+        last_ingested_slot,
+        last_confirmed_slot,
+        last_finalized_slot,
+        ingestion_slot_lag: last_confirmed_slot
+            .map(|confirmed| confirmed.saturating_sub(last_ingested_slot.unwrap_or_default())),
+        last_known_update_seq,
+        last_synced_update_seq,
+        postgres_sync_lag: last_known_update_seq
+            .map(|known| known.saturating_sub(last_synced_update_seq.unwrap_or_default())),
+        trees_with_gaps,
+        last_backfilled_slot,
+        last_fetched_slot,
+        json_tasks_pending,
+        secondary_catch_up_age_sec,
+    })
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use entities::enums::TokenMetadataEdition;
+use mpl_token_metadata::accounts::MasterEdition;
+use rocks_db::errors::StorageError;
+use rocks_db::Storage;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::api::dapi::response::{NftEdition, NftEditionsList};
+
+#[allow(clippy::too_many_arguments)]
+pub async fn get_nft_editions(
+    rocks_db: Arc<Storage>,
+    mint: Pubkey,
+    limit: u64,
+    page: Option<u64>,
+    before: Option<u64>,
+    after: Option<u64>,
+    cursor: Option<u64>,
+) -> Result<Option<NftEditionsList>, StorageError> {
+    let master_edition_address = MasterEdition::find_pda(&mint).0;
+    let Some(TokenMetadataEdition::MasterEdition(master_edition)) = rocks_db
+        .token_metadata_edition_cbor
+        .get_cbor_encoded(master_edition_address)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let cursor_enabled = before.is_none() && after.is_none() && page.is_none();
+    // if cursor is passed use it as 'after' parameter
+    let after = if cursor_enabled { cursor } else { after };
+
+    let editions =
+        rocks_db.get_editions_by_parent(master_edition_address, after, before, page, limit);
+    let mints = rocks_db
+        .edition_mint_map
+        .batch_get(editions.iter().map(|(_, edition)| *edition).collect())
+        .await?;
+
+    let first_edition = editions.first().map(|(number, _)| number.to_string());
+    let last_edition = editions.last().map(|(number, _)| number.to_string());
+    let (before, after, cursor, page) = if cursor_enabled {
+        (None, None, last_edition, None)
+    } else if let Some(page) = page {
+        (None, None, None, Some(page as u32))
+    } else {
+        (first_edition, last_edition, None, None)
+    };
+
+    Ok(Some(NftEditionsList {
+        total: editions.len() as u32,
+        limit: limit as u32,
+        page,
+        before,
+        after,
+        cursor,
+        master_edition_address: master_edition_address.to_string(),
+        supply: master_edition.supply,
+        max_supply: master_edition.max_supply,
+        editions: editions
+            .into_iter()
+            .zip(mints)
+            .map(|((edition, edition_address), mint)| NftEdition {
+                mint: mint.map(|m| m.mint_key.to_string()),
+                edition_address: edition_address.to_string(),
+                edition,
+            })
+            .collect(),
+    }))
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use futures::{stream, Stream, StreamExt};
+use postgre_client::model::{OwnerAssetsCount, OwnersSnapshotFilter};
+use postgre_client::PgClient;
+use rocks_db::dump_client::csv_writer;
+use rocks_db::errors::StorageError;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use usecase::validation::validate_opt_pubkey_vec;
+
+use crate::api::api_keys::PageCharger;
+use crate::api::dapi::asset::COLLECTION_GROUP_KEY;
+use crate::api::dapi::response::{OwnersSnapshot, SnapshotOwner};
+use crate::api::error::DasApiError;
+
+const CSV_HEADER: &str = "owner,asset_count\n";
+
+#[derive(Serialize)]
+struct OwnerRecord {
+    owner: String,
+    asset_count: i64,
+}
+
+pub fn owners_snapshot_filter(
+    grouping: Option<(String, String)>,
+    creator_address: Option<String>,
+    creator_verified: Option<bool>,
+    show_unverified_collections: bool,
+) -> Result<OwnersSnapshotFilter, DasApiError> {
+    let collection = match grouping {
+        Some((key, _)) if key != COLLECTION_GROUP_KEY => {
+            return Err(DasApiError::InvalidGroupingKey(key))
+        }
+        Some((_, value)) => validate_opt_pubkey_vec(&Some(value))?,
+        None => None,
+    };
+    let creator_address = validate_opt_pubkey_vec(&creator_address)?;
+    if collection.is_none() && creator_address.is_none() {
+        return Err(DasApiError::Validation(
+            "Either grouping or creatorAddress is required".to_string(),
+        ));
+    }
+
+    Ok(OwnersSnapshotFilter {
+        collection,
+        creator_address,
+        creator_verified,
+        show_unverified_collections,
+    })
+}
+
+fn owner_key_to_string(owner: &[u8]) -> String {
+    Pubkey::try_from(owner)
+        .map(|owner| owner.to_string())
+        .unwrap_or_default()
+}
+
+pub async fn get_owners_snapshot(
+    pg_client: Arc<PgClient>,
+    filter: OwnersSnapshotFilter,
+    limit: u64,
+    after: Option<Pubkey>,
+) -> Result<OwnersSnapshot, StorageError> {
+    let owners = pg_client
+        .get_owners_snapshot(&filter, limit, after.map(|key| key.to_bytes().to_vec()))
+        .await
+        .map_err(|e| StorageError::Common(e.to_string()))?;
+
+    let owners = owners
+        .into_iter()
+        .map(|OwnerAssetsCount { owner, asset_count }| SnapshotOwner {
+            owner: owner_key_to_string(&owner),
+            asset_count: asset_count as u64,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(OwnersSnapshot {
+        total: owners.len() as u64,
+        limit,
+        after: after.map(|key| key.to_string()),
+        cursor: owners.last().map(|owner| owner.owner.clone()),
+        owners,
+    })
+}
+
+/// Streams the whole snapshot as CSV, fetching the owners page by page.
+/// The pages following the first one are charged from the quota of the API key, if any
+pub fn owners_snapshot_csv_stream(
+    pg_client: Arc<PgClient>,
+    filter: OwnersSnapshotFilter,
+    page_size: u64,
+    charger: Option<PageCharger>,
+) -> impl Stream<Item = Result<Vec<u8>, String>> + Send {
+    let header = stream::once(async { Ok(CSV_HEADER.as_bytes().to_vec()) });
+    let rows = stream::try_unfold(
+        (pg_client, filter, charger, None::<Vec<u8>>, false),
+        move |(pg_client, filter, charger, after, is_finished)| async move {
+            if is_finished {
+                return Ok(None);
+            }
+            if let (Some(charger), Some(_)) = (&charger, &after) {
+                charger.charge_page().await.map_err(|e| e.to_string())?;
+            }
+            let owners = pg_client
+                .get_owners_snapshot(&filter, page_size, after)
+                .await
+                .map_err(|e| e.to_string())?;
+            let is_finished = (owners.len() as u64) < page_size;
+            let after = owners.last().map(|owner| owner.owner.clone());
+
+            let mut writer = csv_writer(Vec::new());
+            for OwnerAssetsCount { owner, asset_count } in owners {
+                writer
+                    .serialize(OwnerRecord {
+                        owner: owner_key_to_string(&owner),
+                        asset_count,
+                    })
+                    .map_err(|e| e.to_string())?;
+            }
+            let chunk = writer.into_inner().map_err(|e| e.to_string())?;
+
+            Ok(Some((
+                chunk,
+                (pg_client, filter, charger, after, is_finished),
+            )))
+        },
+    );
+
+    header.chain(rows)
+}
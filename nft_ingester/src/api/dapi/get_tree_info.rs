@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use rocks_db::errors::StorageError;
+use rocks_db::Storage;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::api::dapi::response::TreeInfo;
+
+pub async fn get_tree_info(
+    rocks_db: Arc<Storage>,
+    tree: Pubkey,
+) -> Result<Option<TreeInfo>, StorageError> {
+    let tree_info = rocks_db.tree_info.get(tree)?;
+    let latest_seq = rocks_db.get_latest_tree_seq(tree)?;
+    if tree_info.is_none() && latest_seq.is_none() {
+        return Ok(None);
+    }
+    let has_gaps = rocks_db.trees_gaps.has_key(tree).await?;
+
+    let config = tree_info.as_ref().and_then(|info| info.config.clone());
+    Ok(Some(TreeInfo {
+        tree: tree.to_string(),
+        max_depth: config.as_ref().map(|c| c.max_depth),
+        max_buffer_size: config.as_ref().map(|c| c.max_buffer_size),
+        canopy_depth: config.as_ref().and_then(|c| c.canopy_depth),
+        tree_creator: config.as_ref().map(|c| c.tree_creator.to_string()),
+        tree_delegate: config.as_ref().map(|c| c.tree_delegate.to_string()),
+        is_public: config.as_ref().map(|c| c.is_public),
+        created_at_slot: config.as_ref().map(|c| c.created_at_slot),
+        total_mint_capacity: config.as_ref().and_then(|c| 1u64.checked_shl(c.max_depth)),
+        num_minted: tree_info.map(|info| info.num_minted).unwrap_or_default(),
+        seq: latest_seq.as_ref().map(|s| s.seq),
+        seq_slot: latest_seq.as_ref().map(|s| s.slot),
+        is_consistent: !has_gaps,
+    }))
+}
@@ -2,16 +2,24 @@ mod asset;
 pub mod asset_preview;
 mod change_logs;
 pub mod converters;
+pub mod dead_letters;
 mod get_asset;
 mod get_asset_batch;
 pub mod get_asset_signatures;
 pub mod get_core_fees;
+pub mod get_indexer_status;
+pub mod get_nft_editions;
+pub mod get_owners_snapshot;
 pub mod get_token_accounts;
+pub mod get_tree_info;
 mod model;
+pub mod refresh_asset_metadata;
 pub mod response;
 pub mod rpc_asset_convertors;
 pub mod rpc_asset_models;
 mod search_assets;
+pub mod tree_gap_repairs;
+pub mod webhooks;
 
 pub use change_logs::*;
 pub use get_asset::*;
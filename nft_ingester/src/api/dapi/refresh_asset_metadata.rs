@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use postgre_client::PgClient;
+use rocks_db::errors::StorageError;
+use rocks_db::Storage;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::api::dapi::response::MetadataRefresh;
+
+/// Enqueues the metadata urls of the assets to be downloaded by the JSON downloader right away
+pub async fn refresh_asset_metadata(
+    rocks_db: Arc<Storage>,
+    pg_client: Arc<PgClient>,
+    ids: Vec<Pubkey>,
+) -> Result<MetadataRefresh, StorageError> {
+    let dynamic_details = rocks_db.asset_dynamic_data.batch_get(ids.clone()).await?;
+
+    let mut response = MetadataRefresh::default();
+    let mut metadata_urls = Vec::new();
+    for (id, details) in ids.into_iter().zip(dynamic_details) {
+        match details
+            .map(|details| details.url.value)
+            .filter(|url| !url.trim().is_empty())
+        {
+            Some(url) => {
+                metadata_urls.push(url);
+                response.queued.push(id.to_string());
+            }
+            None => response.not_found.push(id.to_string()),
+        }
+    }
+
+    pg_client
+        .enqueue_metadata_refresh(&metadata_urls)
+        .await
+        .map_err(|e| StorageError::Common(e.to_string()))?;
+
+    Ok(response)
+}
@@ -136,6 +136,187 @@ pub struct TokenAccountsList {
     pub token_accounts: Vec<ResponseTokenAccount>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct NftEdition {
+    pub mint: Option<String>,
+    pub edition_address: String,
+    pub edition: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct TreeInfo {
+    pub tree: String,
+    // tree configuration is unknown if the CreateTree instruction was not indexed
+    pub max_depth: Option<u32>,
+    pub max_buffer_size: Option<u32>,
+    pub canopy_depth: Option<u32>,
+    pub tree_creator: Option<String>,
+    pub tree_delegate: Option<String>,
+    pub is_public: Option<bool>,
+    pub created_at_slot: Option<u64>,
+    pub total_mint_capacity: Option<u64>,
+    pub num_minted: u64,
+    // the latest sequence seen for the tree and the slot it was seen at
+    pub seq: Option<u64>,
+    pub seq_slot: Option<u64>,
+    // false if the tree has gaps in its sequence
+    pub is_consistent: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct NftEditionsList {
+    // number of the editions in the page, as in the other lists;
+    // the supply of the master edition is the number of all the prints
+    pub total: u32,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub cursor: Option<String>,
+    pub master_edition_address: String,
+    pub supply: u64,
+    pub max_supply: Option<u64>,
+    pub editions: Vec<NftEdition>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct SnapshotOwner {
+    pub owner: String,
+    pub asset_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct OwnersSnapshot {
+    pub total: u64,
+    pub limit: u64,
+    pub after: Option<String>,
+    pub cursor: Option<String>,
+    pub owners: Vec<SnapshotOwner>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct MetadataRefresh {
+    // assets whose metadata urls were enqueued
+    pub queued: Vec<String>,
+    // assets which are unknown or have no metadata url
+    pub not_found: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct DeadLetterItem {
+    pub id: String,
+    pub stream: String,
+    pub pubkey: Option<String>,
+    pub error: String,
+    pub failed_at: i64,
+    pub data_size: u64,
+    // base64 encoded raw message, returned only when a single dead letter is requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct DeadLettersList {
+    pub total: u64,
+    pub limit: u64,
+    pub cursor: Option<String>,
+    pub items: Vec<DeadLetterItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct DeadLettersRedrive {
+    // dead letters requested to be redriven by the ingester
+    pub queued: Vec<String>,
+    // dead letters which are unknown or were redriven already
+    pub not_found: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct WebhookItem {
+    pub id: String,
+    pub url: String,
+    pub owners: Vec<String>,
+    pub collections: Vec<String>,
+    pub creators: Vec<String>,
+    pub trees: Vec<String>,
+    pub asset_ids: Vec<String>,
+    pub event_types: Vec<String>,
+    pub created_at: i64,
+    // returned only once the webhook is registered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct WebhooksList {
+    pub total: u64,
+    pub limit: u64,
+    pub cursor: Option<String>,
+    pub items: Vec<WebhookItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct WebhookDeletion {
+    // false if there was no such webhook
+    pub deleted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct TreeGapRepairItem {
+    pub tree: String,
+    // pending, repaired or unrepairable
+    pub state: String,
+    pub attempts: u32,
+    // gaps left after the last attempt
+    pub gaps: u64,
+    pub updated_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct TreeGapRepairsList {
+    pub total: u64,
+    pub limit: u64,
+    pub cursor: Option<String>,
+    pub items: Vec<TreeGapRepairItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+#[serde(default)]
+pub struct IndexerStatus {
+    // top slot seen by the ingester in the processed transactions
+    pub last_ingested_slot: Option<u64>,
+    pub last_confirmed_slot: Option<u64>,
+    pub last_finalized_slot: Option<u64>,
+    // slots the ingester is behind the confirmed commitment
+    pub ingestion_slot_lag: Option<u64>,
+    pub last_known_update_seq: Option<u64>,
+    pub last_synced_update_seq: Option<u64>,
+    // asset updates stored in RocksDB but not synchronized to Postgres yet
+    pub postgres_sync_lag: Option<u64>,
+    pub trees_with_gaps: u64,
+    pub last_backfilled_slot: Option<u64>,
+    pub last_fetched_slot: Option<u64>,
+    pub json_tasks_pending: u64,
+    // seconds since the RocksDB secondary instance caught up with the primary one,
+    // absent if the instance is not a secondary one or did not catch up yet
+    pub secondary_catch_up_age_sec: Option<i64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
 #[serde(default)]
 pub struct CoreFeesAccountsList {
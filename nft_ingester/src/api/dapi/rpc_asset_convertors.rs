@@ -558,8 +558,8 @@ pub fn build_transaction_signatures_response(
         total: items.len() as u32,
         limit: limit as u32,
         page: page.map(|x| x as u32),
-        before: signatures.before.map(|before| before.to_string()),
-        after: signatures.after.map(|after| after.to_string()),
+        before: signatures.before,
+        after: signatures.after,
         items,
     }
 }
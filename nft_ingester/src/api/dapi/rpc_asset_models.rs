@@ -21,6 +21,8 @@ pub struct AssetProof {
     pub node_index: i64,
     pub leaf: String,
     pub tree_id: String,
+    // seq of the tree state the proof was built at
+    pub seq: i64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
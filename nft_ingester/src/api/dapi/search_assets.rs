@@ -1,4 +1,4 @@
-use crate::api::dapi::asset;
+use crate::api::dapi::asset::{self, RequestedSlot};
 use crate::api::dapi::converters::{ConversionError, SearchAssetsQuery};
 use crate::api::dapi::response::{AssetList, NativeBalance};
 use crate::api::dapi::rpc_asset_convertors::asset_list_to_rpc;
@@ -8,6 +8,7 @@ use interface::account_balance::AccountBalanceGetter;
 use interface::json::{JsonDownloader, JsonPersister};
 use interface::price_fetcher::TokenPriceFetcher;
 use metrics_utils::ApiMetricsConfig;
+use postgre_client::model::OwnerAtSlot;
 use rocks_db::errors::StorageError;
 use rocks_db::Storage;
 use solana_sdk::pubkey::Pubkey;
@@ -38,6 +39,7 @@ pub async fn search_assets<TPF: TokenPriceFetcher>(
     storage_service_base_path: Option<String>,
     token_price_fetcher: Arc<TPF>,
     metrics: Arc<ApiMetricsConfig>,
+    at_slot: Option<RequestedSlot>,
 ) -> Result<AssetList, StorageError> {
     if options.show_fungible {
         filter.token_type = Some(TokenType::All)
@@ -61,6 +63,7 @@ pub async fn search_assets<TPF: TokenPriceFetcher>(
             tasks,
             token_price_fetcher.clone(),
             metrics,
+            at_slot,
         ),
         fetch_native_balance(
             show_native_balance,
@@ -101,10 +104,11 @@ async fn fetch_assets<TPF: TokenPriceFetcher>(
     tasks: Arc<Mutex<JoinSet<Result<(), JoinError>>>>,
     token_price_fetcher: Arc<TPF>,
     metrics: Arc<ApiMetricsConfig>,
+    at_slot: Option<RequestedSlot>,
 ) -> Result<AssetList, StorageError> {
-    let filter_result: &Result<postgre_client::model::SearchAssetsFilter, ConversionError> =
-        &filter.try_into();
-    if let Err(ConversionError::IncompatibleGroupingKey(_)) = filter_result {
+    let filter_result: Result<postgre_client::model::SearchAssetsFilter, ConversionError> =
+        filter.try_into();
+    if let Err(ConversionError::IncompatibleGroupingKey(_)) = &filter_result {
         // If the error is IncompatibleGroupingKey, return an empty response
         return Ok(AssetList {
             total: 0,
@@ -112,9 +116,29 @@ async fn fetch_assets<TPF: TokenPriceFetcher>(
             ..AssetList::default()
         });
     }
-    let filter = filter_result
-        .as_ref()
-        .map_err(|e| StorageError::Common(e.to_string()))?; // TODO: change error
+    // TODO: change error
+    let mut filter = filter_result.map_err(|e| StorageError::Common(e.to_string()))?;
+    // the owners index holds the latest owners, the assets moved since the commitment slot
+    // are corrected by the versions replaced since then
+    if let (Some(RequestedSlot::Commitment(slot)), Some(owner_address)) =
+        (at_slot, filter.owner_address.as_ref())
+    {
+        let owner = Pubkey::try_from(owner_address.clone())
+            .map_err(|pk| StorageError::Common(format!("Cannot convert public key: {:?}", pk)))?;
+        let changes = rocks_db.owner_changes_after_slot(owner, slot).await?;
+        filter.owner_at_slot = Some(OwnerAtSlot {
+            owned: changes
+                .owned
+                .iter()
+                .map(|k| k.to_bytes().to_vec())
+                .collect(),
+            not_owned: changes
+                .not_owned
+                .iter()
+                .map(|k| k.to_bytes().to_vec())
+                .collect(),
+        });
+    }
 
     let cursor_enabled = before.is_none() && after.is_none() && page.is_none();
 
@@ -129,7 +153,7 @@ async fn fetch_assets<TPF: TokenPriceFetcher>(
 
     let keys = index_client
         .get_asset_pubkeys_filtered(
-            filter,
+            &filter,
             &sort_by.into(),
             limit,
             page,
@@ -168,6 +192,7 @@ async fn fetch_assets<TPF: TokenPriceFetcher>(
         &owner_address,
         token_price_fetcher,
         metrics,
+        at_slot,
     )
     .await?;
     let assets = assets.into_iter().flatten().collect::<Vec<_>>();
@@ -190,7 +215,7 @@ async fn fetch_assets<TPF: TokenPriceFetcher>(
     if options.show_grand_total {
         grand_total = Some(
             index_client
-                .get_grand_total(filter, &options)
+                .get_grand_total(&filter, &options)
                 .await
                 .map_err(|e| StorageError::Common(e.to_string()))?,
         )
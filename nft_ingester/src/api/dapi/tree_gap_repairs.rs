@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use rocks_db::errors::StorageError;
+use rocks_db::Storage;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::api::dapi::response::{TreeGapRepairItem, TreeGapRepairsList};
+
+pub async fn get_tree_gap_repairs(
+    rocks_db: Arc<Storage>,
+    limit: u64,
+    after: Option<Pubkey>,
+) -> Result<TreeGapRepairsList, StorageError> {
+    let repairs =
+        tokio::task::spawn_blocking(move || rocks_db.get_tree_gap_repairs(after, limit as usize))
+            .await
+            .map_err(|e| StorageError::Common(e.to_string()))?;
+
+    let cursor = repairs.last().map(|(tree, _)| tree.to_string());
+    let items = repairs
+        .into_iter()
+        .map(|(tree, repair)| TreeGapRepairItem {
+            tree: tree.to_string(),
+            state: repair.state.as_str().to_string(),
+            attempts: repair.attempts,
+            gaps: repair.gaps,
+            updated_at: repair.updated_at,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(TreeGapRepairsList {
+        total: items.len() as u64,
+        limit,
+        cursor,
+        items,
+    })
+}
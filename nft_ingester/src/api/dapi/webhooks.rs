@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use entities::models::{Webhook, WebhookFilter};
+use postgre_client::PgClient;
+use rocks_db::errors::StorageError;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::api::dapi::response::{WebhookDeletion, WebhookItem, WebhooksList};
+
+/// Registers the webhook of the API key in Postgres, where the ingester takes it from,
+/// as the API may only have a read-only RocksDB replica.
+/// Returns None if the key already has `max_webhooks` webhooks
+pub async fn register_webhook(
+    pg_client: Arc<PgClient>,
+    key_name: String,
+    max_webhooks: u64,
+    url: String,
+    secret: String,
+    filter: WebhookFilter,
+) -> Result<Option<WebhookItem>, StorageError> {
+    let webhook = pg_client
+        .insert_webhook(&url, &secret, &filter, &key_name, max_webhooks)
+        .await
+        .map_err(|e| StorageError::Common(e.to_string()))?;
+
+    Ok(webhook.map(|webhook| webhook_item(webhook, true)))
+}
+
+pub async fn get_webhooks(
+    pg_client: Arc<PgClient>,
+    key_name: String,
+    limit: u64,
+    after: Option<u64>,
+) -> Result<WebhooksList, StorageError> {
+    let webhooks = pg_client
+        .get_webhooks(Some(&key_name), after, limit)
+        .await
+        .map_err(|e| StorageError::Common(e.to_string()))?;
+
+    let cursor = webhooks.last().map(|webhook| webhook.id.to_string());
+    let items = webhooks
+        .into_iter()
+        .map(|webhook| webhook_item(webhook, false))
+        .collect::<Vec<_>>();
+
+    Ok(WebhooksList {
+        total: items.len() as u64,
+        limit,
+        cursor,
+        items,
+    })
+}
+
+/// Deletes the webhook of the API key, its notifications which are not sent yet
+/// are dropped by the ingester
+pub async fn delete_webhook(
+    pg_client: Arc<PgClient>,
+    key_name: String,
+    id: u64,
+) -> Result<WebhookDeletion, StorageError> {
+    let deleted = pg_client
+        .delete_webhook(id, &key_name)
+        .await
+        .map_err(|e| StorageError::Common(e.to_string()))?;
+
+    Ok(WebhookDeletion { deleted })
+}
+
+fn keys_to_strings(keys: &[Pubkey]) -> Vec<String> {
+    keys.iter().map(|key| key.to_string()).collect()
+}
+
+fn webhook_item(webhook: Webhook, with_secret: bool) -> WebhookItem {
+    let filter = &webhook.filter;
+
+    WebhookItem {
+        id: webhook.id.to_string(),
+        owners: keys_to_strings(&filter.owners),
+        collections: keys_to_strings(&filter.collections),
+        creators: keys_to_strings(&filter.creators),
+        trees: keys_to_strings(&filter.trees),
+        asset_ids: keys_to_strings(&filter.asset_ids),
+        event_types: filter
+            .event_types
+            .iter()
+            .map(|event_type| event_type.as_str().to_string())
+            .collect(),
+        created_at: webhook.created_at,
+        secret: with_secret.then_some(webhook.secret),
+        url: webhook.url,
+    }
+}
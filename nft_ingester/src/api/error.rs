@@ -5,8 +5,13 @@ use tracing::error;
 use interface::error::UsecaseError;
 use rocks_db::errors::StorageError;
 
-const STANDARD_ERROR_CODE: i64 = -32000;
+pub const STANDARD_ERROR_CODE: i64 = -32000;
 pub const CANNOT_SERVICE_REQUEST_ERROR_CODE: i64 = -32050;
+// the same code the Solana RPC returns for the requests with minContextSlot
+pub const MIN_CONTEXT_SLOT_NOT_REACHED_ERROR_CODE: i64 = -32016;
+pub const UNAUTHORIZED_ERROR_CODE: i64 = -32051;
+pub const RATE_LIMITED_ERROR_CODE: i64 = -32052;
+pub const FORBIDDEN_ERROR_CODE: i64 = -32053;
 
 #[derive(Error, Debug)]
 pub enum DasApiError {
@@ -44,6 +49,16 @@ pub enum DasApiError {
     CannotServiceRequest,
     #[error("MissingOwnerAddress")]
     MissingOwnerAddress,
+    #[error("Minimum context slot has not been reached, context slot: {0}")]
+    MinContextSlotNotReached(u64),
+    #[error("Unauthorized")]
+    Unauthorized,
+    #[error("RateLimited")]
+    RateLimited,
+    #[error("Forbidden")]
+    Forbidden,
+    #[error("Asset history is unavailable for slot {0}")]
+    HistoryUnavailable(u64),
 }
 
 impl From<DasApiError> for jsonrpc_core::Error {
@@ -97,6 +112,31 @@ impl From<DasApiError> for jsonrpc_core::Error {
                 data: None,
             },
             DasApiError::CannotServiceRequest => cannot_service_request_error(),
+            DasApiError::MinContextSlotNotReached(context_slot) => jsonrpc_core::Error {
+                code: ErrorCode::ServerError(MIN_CONTEXT_SLOT_NOT_REACHED_ERROR_CODE),
+                message: "Minimum context slot has not been reached".to_string(),
+                data: Some(serde_json::json!({ "contextSlot": context_slot })),
+            },
+            DasApiError::Unauthorized => jsonrpc_core::Error {
+                code: ErrorCode::ServerError(UNAUTHORIZED_ERROR_CODE),
+                message: "Missing or unknown API key".to_string(),
+                data: None,
+            },
+            DasApiError::RateLimited => jsonrpc_core::Error {
+                code: ErrorCode::ServerError(RATE_LIMITED_ERROR_CODE),
+                message: "Request rate quota of the API key is exceeded".to_string(),
+                data: None,
+            },
+            DasApiError::Forbidden => jsonrpc_core::Error {
+                code: ErrorCode::ServerError(FORBIDDEN_ERROR_CODE),
+                message: "API key lacks the scope of the method".to_string(),
+                data: None,
+            },
+            DasApiError::HistoryUnavailable(slot) => jsonrpc_core::Error {
+                code: ErrorCode::ServerError(STANDARD_ERROR_CODE),
+                message: format!("Asset history is unavailable for slot {slot}"),
+                data: None,
+            },
             _ => jsonrpc_core::Error::new(ErrorCode::InternalError),
         }
     }
@@ -116,6 +156,7 @@ impl From<StorageError> for DasApiError {
     fn from(value: StorageError) -> Self {
         match value {
             StorageError::CannotServiceRequest => Self::CannotServiceRequest,
+            StorageError::HistoryUnavailable(slot) => Self::HistoryUnavailable(slot),
             e => Self::RocksError(e.to_string()),
         }
     }
@@ -2,20 +2,71 @@ use futures::future::Either;
 use interface::consistency_check::ConsistencyChecker;
 use jsonrpc_core::futures_util::future;
 use jsonrpc_core::middleware::{NoopCallFuture, NoopFuture};
-use jsonrpc_core::{Call, Failure, Metadata, Middleware, Output, Version};
+use jsonrpc_core::{Call, Failure, Metadata, Middleware, Output, Params, Version};
+use postgre_client::storage_traits::AssetIndexStorage;
+use postgre_client::PgClient;
+use rocks_db::key_encoders::decode_u64x2_pubkey;
+use rocks_db::storage_traits::AssetUpdateIndexStorage;
+use rocks_db::Storage;
+use serde_json::json;
 use std::future::Future;
 use std::sync::Arc;
+use tracing::error;
+
+use crate::api::api_keys::ApiKeys;
+use crate::api::error::DasApiError;
+
+// mirrors the parameter of the Solana RPC, accepted by every method either as a named param
+// or in the object passed as the last positional param
+pub(crate) const MIN_CONTEXT_SLOT_PARAM: &str = "minContextSlot";
+// the methods selecting the assets from Postgres, their state is as old as the synced one
+const PG_BACKED_METHODS: &[&str] = &[
+    "get_assets_by_owner",
+    "getAssetsByOwner",
+    "get_assets_by_creator",
+    "getAssetsByCreator",
+    "get_assets_by_authority",
+    "getAssetsByAuthority",
+    "get_assets_by_group",
+    "getAssetsByGroup",
+    "search_assets",
+    "searchAssets",
+    "get_grouping",
+    "getGrouping",
+    "get_owners_snapshot",
+    "getOwnersSnapshot",
+    "get_core_fees",
+    "getCoreFees",
+];
+
+/// Extracted from the HTTP request of the call
+#[derive(Default, Clone, Debug)]
+pub struct RequestMetadata {
+    pub api_key: Option<String>,
+}
+impl Metadata for RequestMetadata {}
 
 #[derive(Default, Clone)]
 pub struct RpcMetaMiddleware {
     consistency_checkers: Vec<Arc<dyn ConsistencyChecker>>,
+    rocks_db: Option<Arc<Storage>>,
+    pg_client: Option<Arc<PgClient>>,
+    // the requests aren't authenticated if not set
+    api_keys: Option<Arc<ApiKeys>>,
 }
-impl Metadata for RpcMetaMiddleware {}
 
 impl RpcMetaMiddleware {
-    pub(crate) fn new(consistency_checkers: Vec<Arc<dyn ConsistencyChecker>>) -> Self {
+    pub(crate) fn new(
+        consistency_checkers: Vec<Arc<dyn ConsistencyChecker>>,
+        rocks_db: Arc<Storage>,
+        pg_client: Arc<PgClient>,
+        api_keys: Option<Arc<ApiKeys>>,
+    ) -> Self {
         Self {
             consistency_checkers,
+            rocks_db: Some(rocks_db),
+            pg_client: Some(pg_client),
+            api_keys,
         }
     }
 
@@ -26,15 +77,113 @@ impl RpcMetaMiddleware {
             id: jsonrpc_core::types::id::Id::Null,
         }))
     }
+
+    fn check_api_key(&self, call: &Call, meta: &RequestMetadata) -> Result<(), DasApiError> {
+        let Some(api_keys) = self.api_keys.as_ref() else {
+            return Ok(());
+        };
+        let (method, params) = match call {
+            Call::MethodCall(method_call) => (&method_call.method, &method_call.params),
+            Call::Notification(notification) => (&notification.method, &notification.params),
+            Call::Invalid { .. } => return Ok(()),
+        };
+        api_keys.check(meta.api_key.as_deref(), method, Some(params))
+    }
+
+    /// Removes the min context slot from the call params, so the methods
+    /// don't have to know about it
+    fn take_min_context_slot(call: &mut Call) -> Result<Option<u64>, DasApiError> {
+        let Call::MethodCall(method_call) = call else {
+            return Ok(None);
+        };
+        let min_context_slot = match method_call.params {
+            Params::Map(ref mut params) => params.remove(MIN_CONTEXT_SLOT_PARAM),
+            Params::Array(ref mut params) => match params.last_mut() {
+                Some(serde_json::Value::Object(config)) => {
+                    let min_context_slot = config.remove(MIN_CONTEXT_SLOT_PARAM);
+                    // the object passed only for the min context slot isn't a param of the method
+                    if min_context_slot.is_some() && config.is_empty() {
+                        params.pop();
+                    }
+                    min_context_slot
+                }
+                _ => None,
+            },
+            Params::None => None,
+        };
+        min_context_slot
+            .filter(|value| !value.is_null())
+            .map(|value| {
+                value.as_u64().ok_or_else(|| {
+                    DasApiError::Validation(format!(
+                        "{} should be a slot number",
+                        MIN_CONTEXT_SLOT_PARAM
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// Returns the slot of the latest update applied to the storage serving the method,
+    /// as it is visible to this instance. Unlike the top seen slot, the update index
+    /// is written along with the updates, so the state of the slot is already stored.
+    async fn context_slot(
+        rocks_db: Option<Arc<Storage>>,
+        pg_client: Option<Arc<PgClient>>,
+        method: String,
+    ) -> Result<u64, DasApiError> {
+        let Some(rocks_db) = rocks_db else {
+            return Err(DasApiError::CannotServiceRequest);
+        };
+        let applied_slot =
+            tokio::task::spawn_blocking(move || rocks_db.last_known_asset_updated_key())
+                .await
+                .map_err(|e| DasApiError::RocksError(e.to_string()))??
+                .map(|key| key.slot)
+                .unwrap_or_default();
+        if !PG_BACKED_METHODS.contains(&method.as_str()) {
+            return Ok(applied_slot);
+        }
+
+        let Some(pg_client) = pg_client else {
+            return Err(DasApiError::CannotServiceRequest);
+        };
+        let synced_slot = pg_client
+            .fetch_last_synced_id()
+            .await
+            .map_err(|e| DasApiError::DatabaseErrorOther(e.to_string()))?
+            .map(decode_u64x2_pubkey)
+            .transpose()?
+            .map(|key| key.slot)
+            .unwrap_or_default();
+        // the selected assets are read from RocksDB, so their state is the older of the two
+        Ok(applied_slot.min(synced_slot))
+    }
+
+    fn failure(call: &Call, error: DasApiError) -> Option<Output> {
+        let Call::MethodCall(method_call) = call else {
+            return None;
+        };
+        Some(Output::Failure(Failure {
+            jsonrpc: method_call.jsonrpc,
+            error: error.into(),
+            id: method_call.id.clone(),
+        }))
+    }
 }
 
-impl<M: Metadata> Middleware<M> for RpcMetaMiddleware {
+impl Middleware<RequestMetadata> for RpcMetaMiddleware {
     type Future = NoopFuture;
     type CallFuture = NoopCallFuture;
 
-    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    fn on_call<F, X>(
+        &self,
+        mut call: Call,
+        meta: RequestMetadata,
+        next: F,
+    ) -> Either<Self::CallFuture, X>
     where
-        F: Fn(Call, M) -> X + Send + Sync,
+        F: Fn(Call, RequestMetadata) -> X + Send + Sync,
         X: Future<Output = Option<Output>> + Send + 'static,
     {
         if self
@@ -44,6 +193,210 @@ impl<M: Metadata> Middleware<M> for RpcMetaMiddleware {
         {
             return Either::Left(Box::pin(future::ready(Self::cannot_service_request())));
         }
-        Either::Right(next(call, meta))
+        // taken first, the quotas of the key apply to the params the method parses
+        let min_context_slot = Self::take_min_context_slot(&mut call);
+        if let Err(e) = self.check_api_key(&call, &meta) {
+            return Either::Left(Box::pin(future::ready(Self::failure(&call, e))));
+        }
+
+        let min_context_slot = match min_context_slot {
+            Ok(Some(min_context_slot)) => min_context_slot,
+            Ok(None) => return Either::Right(next(call, meta)),
+            Err(e) => return Either::Left(Box::pin(future::ready(Self::failure(&call, e)))),
+        };
+
+        let rocks_db = self.rocks_db.clone();
+        let pg_client = self.pg_client.clone();
+        let method = match &call {
+            Call::MethodCall(method_call) => method_call.method.clone(),
+            _ => String::new(),
+        };
+        let failed_call = call.clone();
+        let response = next(call, meta);
+        // the context slot is taken before the request is served,
+        // so the response reflects at least the state of that slot
+        Either::Left(Box::pin(async move {
+            let context_slot = match Self::context_slot(rocks_db, pg_client, method).await {
+                Ok(context_slot) => context_slot,
+                Err(e) => {
+                    error!("Get context slot: {}", e);
+                    return Self::failure(&failed_call, e);
+                }
+            };
+            if context_slot < min_context_slot {
+                return Self::failure(
+                    &failed_call,
+                    DasApiError::MinContextSlotNotReached(context_slot),
+                );
+            }
+
+            response.await.map(|output| match output {
+                Output::Success(mut success) => {
+                    success.result = json!({
+                        "context": { "slot": context_slot },
+                        "value": success.result,
+                    });
+                    Output::Success(success)
+                }
+                failure => failure,
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::error::{MIN_CONTEXT_SLOT_NOT_REACHED_ERROR_CODE, UNAUTHORIZED_ERROR_CODE};
+    use jsonrpc_core::{MetaIoHandler, Value};
+    use metrics_utils::ApiMetricsConfig;
+    use setup::rocks::RocksTestEnvironment;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn handler(rocks_db: Arc<Storage>) -> MetaIoHandler<RequestMetadata, RpcMetaMiddleware> {
+        let mut handler = MetaIoHandler::<RequestMetadata, RpcMetaMiddleware>::new(
+            Default::default(),
+            RpcMetaMiddleware {
+                rocks_db: Some(rocks_db),
+                ..Default::default()
+            },
+        );
+        // returns the params it received, the min context slot should not be among them
+        handler.add_method("echo", |params: Params| async move {
+            match params {
+                Params::Array(params) => Ok(Value::Array(params)),
+                params => params
+                    .parse::<serde_json::Map<String, Value>>()
+                    .map(Value::Object),
+            }
+        });
+        // served from Postgres, which isn't available here
+        handler.add_method(
+            "searchAssets",
+            |_params: Params| async move { Ok(Value::Null) },
+        );
+        handler
+    }
+
+    async fn call(
+        handler: &MetaIoHandler<RequestMetadata, RpcMetaMiddleware>,
+        params: Value,
+    ) -> Value {
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "echo", "params": params});
+        let response = handler
+            .handle_request(&request.to_string(), RequestMetadata::default())
+            .await
+            .unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_min_context_slot() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+        // the seen slot is ahead of the applied updates
+        storage
+            .merge_top_parameter(rocks_db::parameters::Parameter::TopSeenSlot, 200u64)
+            .await
+            .unwrap();
+        storage.asset_updated(100, Pubkey::new_unique()).unwrap();
+        let handler = handler(storage);
+
+        let response = call(&handler, json!({"id": "a"})).await;
+        assert_eq!(response["result"], json!({"id": "a"}));
+
+        let response = call(&handler, json!({"id": "a", "minContextSlot": 100})).await;
+        assert_eq!(
+            response["result"],
+            json!({"context": {"slot": 100}, "value": {"id": "a"}})
+        );
+
+        let response = call(&handler, json!({"id": "a", "minContextSlot": 101})).await;
+        assert_eq!(
+            response["error"]["code"],
+            MIN_CONTEXT_SLOT_NOT_REACHED_ERROR_CODE
+        );
+        assert_eq!(response["error"]["data"]["contextSlot"], 100);
+        assert_eq!(response["id"], 1);
+
+        let response = call(&handler, json!({"id": "a", "minContextSlot": "latest"})).await;
+        assert!(response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("minContextSlot"));
+
+        // the positional params take it in the trailing object
+        let response = call(&handler, json!(["a", {"minContextSlot": 100}])).await;
+        assert_eq!(
+            response["result"],
+            json!({"context": {"slot": 100}, "value": ["a"]})
+        );
+        let response = call(
+            &handler,
+            json!(["a", {"sortBy": "created", "minContextSlot": 100}]),
+        )
+        .await;
+        assert_eq!(
+            response["result"],
+            json!({"context": {"slot": 100}, "value": ["a", {"sortBy": "created"}]})
+        );
+        let response = call(&handler, json!(["a", {"minContextSlot": 101}])).await;
+        assert_eq!(response["error"]["data"]["contextSlot"], 100);
+    }
+
+    #[tokio::test]
+    async fn test_pg_backed_context_slot() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+        storage.asset_updated(100, Pubkey::new_unique()).unwrap();
+        let handler = handler(storage);
+
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "searchAssets", "params": {"minContextSlot": 1}});
+        let response = handler
+            .handle_request(&request.to_string(), RequestMetadata::default())
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(
+            response["error"]["code"],
+            crate::api::error::CANNOT_SERVICE_REQUEST_ERROR_CODE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_key() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            file.path(),
+            json!({"keys": [{"key": "secret", "name": "partner", "requests_per_second": 10}]})
+                .to_string(),
+        )
+        .unwrap();
+        let api_keys = ApiKeys::from_file(file.path(), Arc::new(ApiMetricsConfig::new())).unwrap();
+        let mut handler = MetaIoHandler::<RequestMetadata, RpcMetaMiddleware>::new(
+            Default::default(),
+            RpcMetaMiddleware {
+                api_keys: Some(Arc::new(api_keys)),
+                ..Default::default()
+            },
+        );
+        handler.add_method("echo", |_params: Params| async move { Ok(Value::Null) });
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "echo", "params": {}});
+
+        let response = handler
+            .handle_request(&request.to_string(), RequestMetadata::default())
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["error"]["code"], UNAUTHORIZED_ERROR_CODE);
+
+        let meta = RequestMetadata {
+            api_key: Some("secret".to_string()),
+        };
+        let response = handler
+            .handle_request(&request.to_string(), meta)
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["result"], Value::Null);
+        assert!(response.get("error").is_none());
     }
 }
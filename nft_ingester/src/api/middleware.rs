@@ -1,9 +1,17 @@
 use jsonrpc_core::Output::Failure;
 use jsonrpc_core::{ErrorCode, Response};
+use postgre_client::PgClient;
+use std::collections::HashMap;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::api::error::CANNOT_SERVICE_REQUEST_ERROR_CODE;
+use crate::api::api_keys::{request_api_key, ApiKeys};
+use crate::api::dapi::get_owners_snapshot::{owners_snapshot_csv_stream, owners_snapshot_filter};
+use crate::api::error::{
+    DasApiError, CANNOT_SERVICE_REQUEST_ERROR_CODE, FORBIDDEN_ERROR_CODE, RATE_LIMITED_ERROR_CODE,
+    UNAUTHORIZED_ERROR_CODE,
+};
 use jsonrpc_http_server::hyper::header::HeaderValue;
 use jsonrpc_http_server::hyper::StatusCode;
 use jsonrpc_http_server::jsonrpc_core::futures::TryStreamExt;
@@ -14,19 +22,58 @@ use tokio_util::codec::{BytesCodec, FramedRead};
 use tracing::info;
 
 const FULL_BACKUP_REQUEST_PATH: &str = "/snapshot";
+const OWNERS_SNAPSHOT_CSV_REQUEST_PATH: &str = "/owners_snapshot.csv";
+const OWNERS_SNAPSHOT_CSV_PAGE_SIZE: u64 = 10_000;
 
 #[derive(Default, Clone)]
 pub struct RpcRequestMiddleware {
     pub archives_dir: String,
+    pub pg_client: Option<Arc<PgClient>>,
+    pub api_keys: Option<Arc<ApiKeys>>,
 }
 
 impl RpcRequestMiddleware {
-    pub fn new(archives_dir: &str) -> Self {
+    pub fn new(
+        archives_dir: &str,
+        pg_client: Option<Arc<PgClient>>,
+        api_keys: Option<Arc<ApiKeys>>,
+    ) -> Self {
         Self {
             archives_dir: archives_dir.to_string(),
+            pg_client,
+            api_keys,
         }
     }
 
+    /// The file requests are checked the same way as the methods, by their paths
+    fn check_api_key(
+        &self,
+        request: &hyper::Request<hyper::Body>,
+    ) -> Option<RequestMiddlewareAction> {
+        let api_keys = self.api_keys.as_ref()?;
+        let status = match api_keys.check(
+            request_api_key(request).as_deref(),
+            request.uri().path(),
+            None,
+        ) {
+            Ok(()) => return None,
+            Err(DasApiError::Unauthorized) => hyper::StatusCode::UNAUTHORIZED,
+            Err(DasApiError::RateLimited) => hyper::StatusCode::TOO_MANY_REQUESTS,
+            Err(DasApiError::Forbidden) => hyper::StatusCode::FORBIDDEN,
+            Err(_) => hyper::StatusCode::BAD_REQUEST,
+        };
+
+        Some(RequestMiddlewareAction::Respond {
+            should_validate_hosts: true,
+            response: Box::pin(async move {
+                Ok(hyper::Response::builder()
+                    .status(status)
+                    .body(hyper::Body::empty())
+                    .unwrap())
+            }),
+        })
+    }
+
     fn not_found() -> hyper::Response<hyper::Body> {
         hyper::Response::builder()
             .status(hyper::StatusCode::NOT_FOUND)
@@ -53,6 +100,77 @@ impl RpcRequestMiddleware {
             .await
     }
 
+    fn bad_request(message: String) -> hyper::Response<hyper::Body> {
+        hyper::Response::builder()
+            .status(hyper::StatusCode::BAD_REQUEST)
+            .body(hyper::Body::from(message))
+            .unwrap()
+    }
+
+    /// CSV variant of getOwnersSnapshot, takes the same parameters as a query string:
+    /// `groupKey`, `groupValue`, `creatorAddress`, `creatorVerified`, `showUnverifiedCollections`
+    fn process_owners_snapshot_csv_get(
+        &self,
+        api_key: Option<String>,
+        query: Option<&str>,
+    ) -> RequestMiddlewareAction {
+        let Some(pg_client) = self.pg_client.clone() else {
+            return RequestMiddlewareAction::Respond {
+                should_validate_hosts: true,
+                response: Box::pin(async move { Ok(Self::not_found()) }),
+            };
+        };
+        let params: HashMap<String, String> =
+            url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+                .into_owned()
+                .collect();
+        let grouping = params
+            .get("groupKey")
+            .cloned()
+            .zip(params.get("groupValue").cloned());
+        let filter = owners_snapshot_filter(
+            grouping,
+            params.get("creatorAddress").cloned(),
+            params.get("creatorVerified").map(|v| v == "true"),
+            params
+                .get("showUnverifiedCollections")
+                .map_or(true, |v| v == "true"),
+        );
+        // every page is charged and capped like a page of getOwnersSnapshot
+        let (page_size, charger) = self
+            .api_keys
+            .as_ref()
+            .and_then(|api_keys| {
+                api_keys.page_charger(
+                    api_key.as_deref(),
+                    OWNERS_SNAPSHOT_CSV_REQUEST_PATH,
+                    OWNERS_SNAPSHOT_CSV_PAGE_SIZE,
+                )
+            })
+            .map_or(
+                (OWNERS_SNAPSHOT_CSV_PAGE_SIZE, None),
+                |(page_size, charger)| (page_size, Some(charger)),
+            );
+
+        RequestMiddlewareAction::Respond {
+            should_validate_hosts: true,
+            response: Box::pin(async move {
+                let filter = match filter {
+                    Ok(filter) => filter,
+                    Err(e) => return Ok(Self::bad_request(e.to_string())),
+                };
+                let body = hyper::Body::wrap_stream(owners_snapshot_csv_stream(
+                    pg_client, filter, page_size, charger,
+                ));
+
+                Ok(hyper::Response::builder()
+                    .header(hyper::header::CONTENT_TYPE, "text/csv")
+                    .body(body)
+                    .unwrap())
+            }),
+        }
+    }
+
     fn get_last_backup(&self) -> io::Result<Option<PathBuf>> {
         let entries = std::fs::read_dir(self.archives_dir.clone())?;
         let mut last_created_time = std::time::SystemTime::UNIX_EPOCH;
@@ -128,8 +246,20 @@ impl RpcRequestMiddleware {
 impl RequestMiddleware for RpcRequestMiddleware {
     fn on_request(&self, request: hyper::Request<hyper::Body>) -> RequestMiddlewareAction {
         if request.uri().path() == FULL_BACKUP_REQUEST_PATH {
+            if let Some(rejection) = self.check_api_key(&request) {
+                return rejection;
+            }
             return self.process_file_get(request.uri().path());
         }
+        if request.method() == hyper::Method::GET
+            && request.uri().path() == OWNERS_SNAPSHOT_CSV_REQUEST_PATH
+        {
+            if let Some(rejection) = self.check_api_key(&request) {
+                return rejection;
+            }
+            return self
+                .process_owners_snapshot_csv_get(request_api_key(&request), request.uri().query());
+        }
 
         request.into()
     }
@@ -140,14 +270,27 @@ pub struct RpcResponseMiddleware {}
 
 impl ResponseMiddleware for RpcResponseMiddleware {
     fn on_response(&self, response: Response) -> jsonrpc_http_server::Response {
-        fn is_cannot_service_request_error(response: &Response) -> bool {
+        fn has_error(response: &Response, code: i64) -> bool {
             match response {
-                Response::Single(ref o) => matches!(o, Failure(ref f) if f.error.code == ErrorCode::ServerError(CANNOT_SERVICE_REQUEST_ERROR_CODE)),
-                Response::Batch(ref os) => os.iter().any(|o| matches!(o, Failure(ref f) if f.error.code == ErrorCode::ServerError(CANNOT_SERVICE_REQUEST_ERROR_CODE))),
+                Response::Single(ref o) => {
+                    matches!(o, Failure(ref f) if f.error.code == ErrorCode::ServerError(code))
+                }
+                Response::Batch(ref os) => os.iter().any(
+                    |o| matches!(o, Failure(ref f) if f.error.code == ErrorCode::ServerError(code)),
+                ),
             }
         }
-        if is_cannot_service_request_error(&response) {
-            return Self::i_am_teapot(response);
+        if has_error(&response, CANNOT_SERVICE_REQUEST_ERROR_CODE) {
+            return Self::with_status(StatusCode::IM_A_TEAPOT, response);
+        }
+        if has_error(&response, UNAUTHORIZED_ERROR_CODE) {
+            return Self::with_status(StatusCode::UNAUTHORIZED, response);
+        }
+        if has_error(&response, RATE_LIMITED_ERROR_CODE) {
+            return Self::with_status(StatusCode::TOO_MANY_REQUESTS, response);
+        }
+        if has_error(&response, FORBIDDEN_ERROR_CODE) {
+            return Self::with_status(StatusCode::FORBIDDEN, response);
         }
 
         jsonrpc_http_server::Response::ok(format!("{}\n", json!(response)))
@@ -155,9 +298,9 @@ impl ResponseMiddleware for RpcResponseMiddleware {
 }
 
 impl RpcResponseMiddleware {
-    fn i_am_teapot(response: Response) -> jsonrpc_http_server::Response {
+    fn with_status(code: StatusCode, response: Response) -> jsonrpc_http_server::Response {
         jsonrpc_http_server::Response {
-            code: StatusCode::IM_A_TEAPOT,
+            code,
             content_type: HeaderValue::from_static("application/json; charset=utf-8"),
             content: format!("{}", json!(response)),
         }
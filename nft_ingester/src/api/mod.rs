@@ -2,12 +2,16 @@ pub use api_impl::*;
 
 pub mod account_balance;
 pub mod api_impl;
+pub mod api_keys;
+pub mod asset_cache;
 pub mod backfilling_state_consistency;
 pub mod builder;
 pub mod dapi;
 pub mod error;
 pub mod meta_middleware;
 pub mod middleware;
+pub mod openrpc;
 pub mod service;
+pub mod subscriptions;
 pub mod synchronization_state_consistency;
 pub mod util;
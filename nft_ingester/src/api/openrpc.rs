@@ -0,0 +1,512 @@
+use std::collections::HashMap;
+
+use entities::api_req_params::{
+    DeleteWebhook, GetAsset, GetAssetBatch, GetAssetProof, GetAssetProofBatch, GetAssetSignatures,
+    GetAssetsByAuthority, GetAssetsByCreator, GetAssetsByGroup, GetAssetsByOwner, GetCoreFees,
+    GetDeadLetter, GetDeadLetters, GetGrouping, GetNftEditions, GetOwnersSnapshot,
+    GetTokenAccounts, GetTreeGapRepairs, GetTreeInfo, GetWebhooks, RedriveDeadLetters,
+    RefreshAssetMetadata, RegisterWebhook, SearchAssets,
+};
+use jsonrpc_core::ErrorCode;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::Schema;
+use schemars::{JsonSchema, Map};
+use serde::Serialize;
+
+use crate::api::dapi::response::{
+    AssetList, CoreFeesAccountsList, DeadLetterItem, DeadLettersList, DeadLettersRedrive,
+    GetGroupingResponse, IndexerStatus, MetadataRefresh, NftEditionsList, OwnersSnapshot,
+    TokenAccountsList, TransactionSignatureList, TransactionSignatureListDeprecated,
+    TreeGapRepairsList, TreeInfo, WebhookDeletion, WebhookItem, WebhooksList,
+};
+use crate::api::dapi::rpc_asset_models::{Asset, AssetProof};
+use crate::api::error::{cannot_service_request_error, DasApiError, STANDARD_ERROR_CODE};
+use crate::api::meta_middleware::MIN_CONTEXT_SLOT_PARAM;
+
+pub const DISCOVER_METHOD: &str = "rpc.discover";
+const OPENRPC_VERSION: &str = "1.2.6";
+const SCHEMAS_PATH: &str = "#/components/schemas/";
+
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+struct MethodSpec {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    summary: &'static str,
+    // the fields of the params struct become the named params of the method
+    params: Option<SchemaFn>,
+    result: SchemaFn,
+}
+
+/// Methods registered by the `RpcApiBuilder`, with the aliases they are registered under
+const METHODS: &[MethodSpec] = &[
+    MethodSpec {
+        name: "health",
+        aliases: &["getAuraHealth"],
+        summary: "Checks the API can reach its database",
+        params: None,
+        result: SchemaGenerator::subschema_for::<String>,
+    },
+    MethodSpec {
+        name: "get_indexer_status",
+        aliases: &["getIndexerStatus"],
+        summary: "Returns the ingestion, synchronization and backfilling progress of the indexer",
+        params: None,
+        result: SchemaGenerator::subschema_for::<IndexerStatus>,
+    },
+    MethodSpec {
+        name: "get_asset_proof",
+        aliases: &["getAssetProof"],
+        summary: "Returns the merkle proof of a compressed asset",
+        params: Some(GetAssetProof::json_schema),
+        result: SchemaGenerator::subschema_for::<AssetProof>,
+    },
+    MethodSpec {
+        name: "get_asset",
+        aliases: &["getAsset"],
+        summary: "Returns an asset by its id",
+        params: Some(GetAsset::json_schema),
+        result: SchemaGenerator::subschema_for::<Asset>,
+    },
+    MethodSpec {
+        name: "get_assets_by_owner",
+        aliases: &["getAssetsByOwner"],
+        summary: "Returns the assets of an owner",
+        params: Some(GetAssetsByOwner::json_schema),
+        result: SchemaGenerator::subschema_for::<AssetList>,
+    },
+    MethodSpec {
+        name: "get_assets_by_creator",
+        aliases: &["getAssetsByCreator"],
+        summary: "Returns the assets of a creator",
+        params: Some(GetAssetsByCreator::json_schema),
+        result: SchemaGenerator::subschema_for::<AssetList>,
+    },
+    MethodSpec {
+        name: "get_assets_by_authority",
+        aliases: &["getAssetsByAuthority"],
+        summary: "Returns the assets of an update authority",
+        params: Some(GetAssetsByAuthority::json_schema),
+        result: SchemaGenerator::subschema_for::<AssetList>,
+    },
+    MethodSpec {
+        name: "get_assets_by_group",
+        aliases: &["getAssetsByGroup"],
+        summary: "Returns the assets of a group, e.g. of a collection",
+        params: Some(GetAssetsByGroup::json_schema),
+        result: SchemaGenerator::subschema_for::<AssetList>,
+    },
+    MethodSpec {
+        name: "get_asset_batch",
+        aliases: &["getAssetBatch", "getAssets", "get_assets"],
+        summary: "Returns the assets by their ids, null for the ones not found",
+        params: Some(GetAssetBatch::json_schema),
+        result: SchemaGenerator::subschema_for::<Vec<Option<Asset>>>,
+    },
+    MethodSpec {
+        name: "get_asset_proof_batch",
+        aliases: &["getAssetProofBatch", "getAssetProofs", "get_asset_proofs"],
+        summary: "Returns the merkle proofs of compressed assets by the asset ids",
+        params: Some(GetAssetProofBatch::json_schema),
+        result: SchemaGenerator::subschema_for::<HashMap<String, Option<AssetProof>>>,
+    },
+    MethodSpec {
+        name: "get_grouping",
+        aliases: &["getGrouping"],
+        summary: "Returns the size of a group",
+        params: Some(GetGrouping::json_schema),
+        result: SchemaGenerator::subschema_for::<GetGroupingResponse>,
+    },
+    MethodSpec {
+        name: "search_assets",
+        aliases: &["searchAssets"],
+        summary: "Returns the assets matching the conditions",
+        params: Some(SearchAssets::json_schema),
+        result: SchemaGenerator::subschema_for::<AssetList>,
+    },
+    MethodSpec {
+        name: "get_signatures_for_asset",
+        aliases: &[
+            "getSignaturesForAsset",
+            "getAssetSignatures",
+            "get_asset_signatures",
+        ],
+        summary: "Returns the transactions of a compressed asset as signature and type pairs",
+        params: Some(GetAssetSignatures::json_schema),
+        result: SchemaGenerator::subschema_for::<TransactionSignatureListDeprecated>,
+    },
+    MethodSpec {
+        name: "get_signatures_for_asset_v2",
+        aliases: &[
+            "getSignaturesForAssetV2",
+            "getAssetSignaturesV2",
+            "get_asset_signatures_v2",
+        ],
+        summary: "Returns the transactions of a compressed asset",
+        params: Some(GetAssetSignatures::json_schema),
+        result: SchemaGenerator::subschema_for::<TransactionSignatureList>,
+    },
+    MethodSpec {
+        name: "get_token_accounts",
+        aliases: &["getTokenAccounts"],
+        summary: "Returns the token accounts of an owner or a mint",
+        params: Some(GetTokenAccounts::json_schema),
+        result: SchemaGenerator::subschema_for::<TokenAccountsList>,
+    },
+    MethodSpec {
+        name: "get_nft_editions",
+        aliases: &["getNftEditions"],
+        summary: "Returns a page of the prints of a master edition, total is the page size",
+        params: Some(GetNftEditions::json_schema),
+        result: SchemaGenerator::subschema_for::<NftEditionsList>,
+    },
+    MethodSpec {
+        name: "get_tree_info",
+        aliases: &["getTreeInfo"],
+        summary: "Returns the state of a merkle tree",
+        params: Some(GetTreeInfo::json_schema),
+        result: SchemaGenerator::subschema_for::<TreeInfo>,
+    },
+    MethodSpec {
+        name: "get_owners_snapshot",
+        aliases: &["getOwnersSnapshot"],
+        summary: "Returns the owners of the assets of a collection or a creator",
+        params: Some(GetOwnersSnapshot::json_schema),
+        result: SchemaGenerator::subschema_for::<OwnersSnapshot>,
+    },
+    MethodSpec {
+        name: "refresh_asset_metadata",
+        aliases: &["refreshAssetMetadata"],
+        summary:
+            "Schedules the off-chain metadata to be downloaded again, requires the admin scope",
+        params: Some(RefreshAssetMetadata::json_schema),
+        result: SchemaGenerator::subschema_for::<MetadataRefresh>,
+    },
+    MethodSpec {
+        name: "register_webhook",
+        aliases: &["registerWebhook"],
+        summary: "Registers a webhook of the API key notified of the asset events matching \
+            its filter, requires the webhooks scope",
+        params: Some(RegisterWebhook::json_schema),
+        result: SchemaGenerator::subschema_for::<WebhookItem>,
+    },
+    MethodSpec {
+        name: "get_webhooks",
+        aliases: &["getWebhooks"],
+        summary: "Returns the webhooks of the API key, requires the webhooks scope",
+        params: Some(GetWebhooks::json_schema),
+        result: SchemaGenerator::subschema_for::<WebhooksList>,
+    },
+    MethodSpec {
+        name: "delete_webhook",
+        aliases: &["deleteWebhook"],
+        summary: "Deletes a webhook of the API key, requires the webhooks scope",
+        params: Some(DeleteWebhook::json_schema),
+        result: SchemaGenerator::subschema_for::<WebhookDeletion>,
+    },
+    MethodSpec {
+        name: "get_dead_letters",
+        aliases: &["getDeadLetters"],
+        summary: "Returns the updates the ingester failed to process, requires the admin scope",
+        params: Some(GetDeadLetters::json_schema),
+        result: SchemaGenerator::subschema_for::<DeadLettersList>,
+    },
+    MethodSpec {
+        name: "get_dead_letter",
+        aliases: &["getDeadLetter"],
+        summary:
+            "Returns an update the ingester failed to process by its id, requires the admin scope",
+        params: Some(GetDeadLetter::json_schema),
+        result: SchemaGenerator::subschema_for::<DeadLetterItem>,
+    },
+    MethodSpec {
+        name: "redrive_dead_letters",
+        aliases: &["redriveDeadLetters"],
+        summary: "Schedules the failed updates to be processed again, requires the admin scope",
+        params: Some(RedriveDeadLetters::json_schema),
+        result: SchemaGenerator::subschema_for::<DeadLettersRedrive>,
+    },
+    MethodSpec {
+        name: "get_tree_gap_repairs",
+        aliases: &["getTreeGapRepairs"],
+        summary:
+            "Returns the repair state of the trees with sequence gaps, requires the admin scope",
+        params: Some(GetTreeGapRepairs::json_schema),
+        result: SchemaGenerator::subschema_for::<TreeGapRepairsList>,
+    },
+    MethodSpec {
+        name: "get_core_fees",
+        aliases: &["getCoreFees"],
+        summary: "Returns the MPL Core accounts with the fees to collect",
+        params: Some(GetCoreFees::json_schema),
+        result: SchemaGenerator::subschema_for::<CoreFeesAccountsList>,
+    },
+    MethodSpec {
+        name: DISCOVER_METHOD,
+        aliases: &[],
+        summary: "Returns the OpenRPC document of the API",
+        params: None,
+        result: SchemaGenerator::subschema_for::<serde_json::Value>,
+    },
+];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct OpenRpcDocument {
+    pub openrpc: String,
+    pub info: Info,
+    pub methods: Vec<Method>,
+    pub components: Components,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Info {
+    pub title: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Method {
+    pub name: String,
+    /// The other names the method may be called with, not a part of the OpenRPC spec
+    #[serde(rename = "x-aliases")]
+    pub aliases: Vec<String>,
+    pub summary: String,
+    pub param_structure: String,
+    pub params: Vec<ContentDescriptor>,
+    pub result: ContentDescriptor,
+    pub errors: Vec<ErrorObject>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ContentDescriptor {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub required: bool,
+    pub schema: Schema,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ErrorObject {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Components {
+    pub schemas: Map<String, Schema>,
+}
+
+/// Builds the OpenRPC document served by the `rpc.discover` method
+pub fn discover_document() -> OpenRpcDocument {
+    let mut gen = SchemaSettings::draft07()
+        .with(|settings| settings.definitions_path = SCHEMAS_PATH.to_string())
+        .into_generator();
+    let errors = errors();
+
+    let methods = METHODS
+        .iter()
+        .map(|method| {
+            let mut params = method
+                .params
+                .map(|params| named_params(params(&mut gen)))
+                .unwrap_or_default();
+            if method.params.is_some() {
+                params.push(ContentDescriptor {
+                    name: MIN_CONTEXT_SLOT_PARAM.to_string(),
+                    description: Some(
+                        "Fails the request if the indexer is behind the slot, \
+                        otherwise wraps the result into the context slot envelope"
+                            .to_string(),
+                    ),
+                    required: false,
+                    schema: gen.subschema_for::<u64>(),
+                });
+            }
+
+            Method {
+                name: method.name.to_string(),
+                aliases: method
+                    .aliases
+                    .iter()
+                    .map(|alias| alias.to_string())
+                    .collect(),
+                summary: method.summary.to_string(),
+                param_structure: "by-name".to_string(),
+                params,
+                result: ContentDescriptor {
+                    name: "result".to_string(),
+                    description: None,
+                    required: true,
+                    schema: (method.result)(&mut gen),
+                },
+                errors: errors.clone(),
+            }
+        })
+        .collect();
+
+    OpenRpcDocument {
+        openrpc: OPENRPC_VERSION.to_string(),
+        info: Info {
+            title: "Aura DAS API".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+        methods,
+        components: Components {
+            schemas: gen.take_definitions(),
+        },
+    }
+}
+
+/// Resolves an alias to the name the method is registered under
+pub(crate) fn canonical_method_name(method: &str) -> &str {
+    METHODS
+        .iter()
+        .find(|spec| spec.name == method || spec.aliases.contains(&method))
+        .map_or(method, |spec| spec.name)
+}
+
+fn named_params(schema: Schema) -> Vec<ContentDescriptor> {
+    let Some(object) = schema.into_object().object else {
+        return Vec::new();
+    };
+    object
+        .properties
+        .into_iter()
+        .map(|(name, schema)| ContentDescriptor {
+            required: object.required.contains(&name),
+            description: match &schema {
+                Schema::Object(object) => object
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.description.clone()),
+                Schema::Bool(_) => None,
+            },
+            name,
+            schema,
+        })
+        .collect()
+}
+
+/// Error codes the methods answer with, see the conversion of the `DasApiError`
+fn errors() -> Vec<ErrorObject> {
+    let min_context_slot_error: jsonrpc_core::Error =
+        DasApiError::MinContextSlotNotReached(0).into();
+    let cannot_service_request_error = cannot_service_request_error();
+    let unauthorized_error: jsonrpc_core::Error = DasApiError::Unauthorized.into();
+    let rate_limited_error: jsonrpc_core::Error = DasApiError::RateLimited.into();
+    let forbidden_error: jsonrpc_core::Error = DasApiError::Forbidden.into();
+    vec![
+        ErrorObject {
+            code: ErrorCode::InvalidParams.code(),
+            message: ErrorCode::InvalidParams.description(),
+        },
+        ErrorObject {
+            code: ErrorCode::InternalError.code(),
+            message: ErrorCode::InternalError.description(),
+        },
+        ErrorObject {
+            code: STANDARD_ERROR_CODE,
+            message: "Validation, pagination or not found error, described by the message"
+                .to_string(),
+        },
+        ErrorObject {
+            code: min_context_slot_error.code.code(),
+            message: min_context_slot_error.message,
+        },
+        ErrorObject {
+            code: cannot_service_request_error.code.code(),
+            message: cannot_service_request_error.message,
+        },
+        ErrorObject {
+            code: unauthorized_error.code.code(),
+            message: unauthorized_error.message,
+        },
+        ErrorObject {
+            code: rate_limited_error.code.code(),
+            message: rate_limited_error.message,
+        },
+        ErrorObject {
+            code: forbidden_error.code.code(),
+            message: forbidden_error.message,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // regenerated with UPDATE_OPENRPC_DOCUMENT=1 cargo test test_discover_document
+    const GOLDEN_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/artifacts/openrpc.json");
+
+    // names of the methods and aliases the RpcApiBuilder registers by a string literal,
+    // the discover method is registered by its constant
+    fn registered_methods() -> HashSet<String> {
+        let builder = include_str!("builder.rs");
+        ["add_method(", "add_method_with_meta(", "add_alias("]
+            .iter()
+            .flat_map(|call| builder.split(call).skip(1))
+            .filter_map(|args| args.trim_start().strip_prefix('"')?.split('"').next())
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_discover_document() {
+        let document = discover_document();
+
+        let mut names = HashSet::new();
+        for method in document.methods.iter() {
+            assert!(
+                names.insert(method.name.clone()),
+                "{} is duplicated",
+                method.name
+            );
+            for alias in method.aliases.iter() {
+                assert!(names.insert(alias.clone()), "{} is duplicated", alias);
+            }
+        }
+        assert!(names.contains(DISCOVER_METHOD));
+        assert!(names.contains("getAssets"));
+        let get_asset = document
+            .methods
+            .iter()
+            .find(|method| method.name == "get_asset")
+            .unwrap();
+        assert!(get_asset
+            .params
+            .iter()
+            .any(|param| param.name == "id" && param.required));
+        assert!(document.components.schemas.contains_key("Asset"));
+
+        let registered = registered_methods();
+        for method in registered.iter() {
+            assert!(
+                names.contains(method),
+                "{} is registered but not described",
+                method
+            );
+        }
+        for name in names.iter().filter(|name| *name != DISCOVER_METHOD) {
+            assert!(
+                registered.contains(name),
+                "{} is described but not registered",
+                name
+            );
+        }
+
+        let document = serde_json::to_string_pretty(&document).unwrap() + "\n";
+        if std::env::var("UPDATE_OPENRPC_DOCUMENT").is_ok() {
+            std::fs::write(GOLDEN_FILE, &document).unwrap();
+        }
+        assert_eq!(
+            document,
+            std::fs::read_to_string(GOLDEN_FILE).unwrap(),
+            "The OpenRPC document has changed, regenerate {} if it is intended",
+            GOLDEN_FILE
+        );
+    }
+}
@@ -4,6 +4,7 @@ use jsonrpc_http_server::hyper::service::{make_service_fn, service_fn};
 use multer::Multipart;
 use postgre_client::PgClient;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::broadcast::Receiver;
@@ -20,6 +21,8 @@ use metrics_utils::ApiMetricsConfig;
 use rocks_db::Storage;
 
 use crate::api::account_balance::AccountBalanceGetterImpl;
+use crate::api::api_keys::{request_api_key, run_api_keys_reloader, ApiKeys};
+use crate::api::asset_cache::{run_asset_cache_invalidator, AssetCache};
 use crate::api::error::DasApiError;
 use {crate::api::DasApi, std::net::SocketAddr};
 use {
@@ -28,7 +31,9 @@ use {
 };
 
 use crate::api::builder::RpcApiBuilder;
+use crate::api::meta_middleware::RequestMetadata;
 use crate::api::middleware::{RpcRequestMiddleware, RpcResponseMiddleware};
+use crate::api::subscriptions::run_subscriptions_service;
 use crate::api::synchronization_state_consistency::SynchronizationStateConsistencyChecker;
 use crate::config::JsonMiddlewareConfig;
 use crate::json_worker::JsonWorker;
@@ -45,6 +50,7 @@ pub(crate) struct MiddlewaresData {
     response_middleware: RpcResponseMiddleware,
     request_middleware: RpcRequestMiddleware,
     pub(crate) consistency_checkers: Vec<Arc<dyn ConsistencyChecker>>,
+    pub(crate) api_keys: Option<Arc<ApiKeys>>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -67,9 +73,28 @@ pub async fn start_api(
     file_storage_path: &str,
     account_balance_getter: Arc<AccountBalanceGetterImpl>,
     storage_service_base_url: Option<String>,
+    websocket_port: Option<u16>,
+    websocket_max_connections_per_client: usize,
+    api_keys_file: Option<String>,
+    api_keys_reload_interval_sec: u64,
+    asset_cache_size: u64,
+    asset_cache_ttl_sec: u64,
 ) -> Result<(), DasApiError> {
+    let api_keys = match api_keys_file {
+        Some(api_keys_file) => {
+            let api_keys = Arc::new(ApiKeys::from_file(api_keys_file, metrics.clone())?);
+            tasks.lock().await.spawn(run_api_keys_reloader(
+                api_keys.clone(),
+                Duration::from_secs(api_keys_reload_interval_sec),
+                rx.resubscribe(),
+            ));
+            Some(api_keys)
+        }
+        None => None,
+    };
     let response_middleware = RpcResponseMiddleware {};
-    let request_middleware = RpcRequestMiddleware::new(archives_dir);
+    let request_middleware =
+        RpcRequestMiddleware::new(archives_dir, Some(pg_client.clone()), api_keys.clone());
 
     let mut consistency_checkers: Vec<Arc<dyn ConsistencyChecker>> = vec![];
 
@@ -105,10 +130,10 @@ pub async fn start_api(
     }
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    let api = DasApi::new(
+    let mut api = DasApi::new(
         pg_client.clone(),
-        rocks_db,
-        metrics,
+        rocks_db.clone(),
+        metrics.clone(),
         proof_checker,
         max_page_limit,
         json_downloader,
@@ -118,6 +143,19 @@ pub async fn start_api(
         storage_service_base_url,
         Arc::new(RaydiumTokenPriceFetcher::default()),
     );
+    if asset_cache_size > 0 {
+        let asset_cache = Arc::new(AssetCache::new(
+            asset_cache_size,
+            Duration::from_secs(asset_cache_ttl_sec),
+            metrics,
+        ));
+        tasks.lock().await.spawn(run_asset_cache_invalidator(
+            asset_cache.clone(),
+            rocks_db,
+            rx.resubscribe(),
+        ));
+        api = api.with_asset_cache(asset_cache);
+    }
 
     run_api(
         api,
@@ -125,6 +163,7 @@ pub async fn start_api(
             response_middleware,
             request_middleware,
             consistency_checkers,
+            api_keys,
         }),
         addr,
         tasks,
@@ -132,6 +171,8 @@ pub async fn start_api(
         file_storage_path,
         pg_client,
         rx,
+        websocket_port,
+        websocket_max_connections_per_client,
     )
     .await
 }
@@ -152,13 +193,28 @@ async fn run_api(
     file_storage_path: &str,
     pg_client: Arc<PgClient>,
     shutdown_rx: Receiver<()>,
+    websocket_port: Option<u16>,
+    websocket_max_connections_per_client: usize,
 ) -> Result<(), DasApiError> {
+    let api = Arc::new(api);
+    let api_keys = middlewares_data.clone().and_then(|m| m.api_keys);
+    if let Some(port) = websocket_port {
+        tasks.lock().await.spawn(run_subscriptions_service(
+            api.clone(),
+            tasks.clone(),
+            port,
+            websocket_max_connections_per_client,
+            api_keys.clone(),
+            shutdown_rx.resubscribe(),
+        ));
+    }
     let rpc = RpcApiBuilder::build(
         api,
         middlewares_data
             .clone()
             .map(|m| m.consistency_checkers)
             .unwrap_or_default(),
+        api_keys,
         tasks,
     )?;
     let runtime = tokio::runtime::Builder::new_multi_thread()
@@ -176,6 +232,9 @@ async fn run_api(
         .cors_allow_headers(AccessControlAllowHeaders::Any)
         .cors_max_age(Some(MAX_CORS_AGE))
         .max_request_body_size(MAX_REQUEST_BODY_SIZE)
+        .meta_extractor(|request: &hyper::Request<Body>| RequestMetadata {
+            api_key: request_api_key(request),
+        })
         .health_api(("/health", "health"));
     if let Some(mw) = middlewares_data.clone() {
         builder = builder.request_middleware(mw.request_middleware);
@@ -0,0 +1,846 @@
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use entities::api_req_params::{
+    AssetSubscribe, GetAssetBatch, GroupSubscribe, Options, OwnerSubscribe, Unsubscribe,
+};
+use futures::{SinkExt, StreamExt};
+use metrics_utils::ApiMetricsConfig;
+use rocks_db::errors::StorageError;
+use rocks_db::storage_traits::{AssetUpdateIndexStorage, AssetUpdatedKey};
+use rocks_db::Storage;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{channel, Receiver as MessageReceiver, Sender};
+use tokio::sync::Mutex;
+use tokio::task::{JoinError, JoinSet};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+use usecase::proofs::MaybeProofChecker;
+use usecase::validation::validate_pubkey;
+
+use crate::api::account_balance::AccountBalanceGetterImpl;
+use crate::api::api_keys::{api_key_from, ApiKeys, API_KEY_HEADER};
+use crate::api::error::DasApiError;
+use crate::api::DasApi;
+use crate::json_worker::JsonWorker;
+use crate::raydium_price_fetcher::RaydiumTokenPriceFetcher;
+
+const UPDATES_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// the max number of the assets loaded at once, the same as for getAssetBatch
+const UPDATED_ASSETS_BATCH_SIZE: usize = 1000;
+const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 100;
+// the messages waiting to be written to a connection, it's closed once they don't fit
+const CONNECTION_BUFFER_SIZE: usize = 1000;
+const SUBSCRIPTION_METHODS: [&str; 6] = [
+    "assetSubscribe",
+    "ownerSubscribe",
+    "groupSubscribe",
+    "assetUnsubscribe",
+    "ownerUnsubscribe",
+    "groupUnsubscribe",
+];
+
+type Api = DasApi<
+    MaybeProofChecker,
+    JsonWorker,
+    JsonWorker,
+    AccountBalanceGetterImpl,
+    RaydiumTokenPriceFetcher,
+>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SubscriptionFilter {
+    Asset(String),
+    Owner(String),
+    Collection(String),
+}
+
+impl SubscriptionFilter {
+    fn notification_method(&self) -> &'static str {
+        match self {
+            SubscriptionFilter::Asset(_) => "assetNotification",
+            SubscriptionFilter::Owner(_) => "ownerNotification",
+            SubscriptionFilter::Collection(_) => "groupNotification",
+        }
+    }
+
+    // works on the serialized rpc_asset_models::Asset, the same the clients receive,
+    // the owner is notified about the asset which left the wallet as well
+    fn matches(&self, asset: &Value, previous_owner: Option<&str>) -> bool {
+        match self {
+            SubscriptionFilter::Asset(id) => asset["id"] == *id,
+            SubscriptionFilter::Owner(owner) => {
+                asset["ownership"]["owner"] == *owner || previous_owner == Some(owner.as_str())
+            }
+            SubscriptionFilter::Collection(collection) => asset["grouping"]
+                .as_array()
+                .map(|groups| {
+                    groups.iter().any(|group| {
+                        group["group_key"] == "collection" && group["group_value"] == *collection
+                    })
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+struct Subscription {
+    filter: SubscriptionFilter,
+    sender: ConnectionSender,
+}
+
+/// Queues the messages to be written to the connection. A client which doesn't keep up
+/// with its notifications gets disconnected instead of having them buffered without a bound
+#[derive(Clone)]
+struct ConnectionSender {
+    messages: Sender<Message>,
+    // cancelled once the buffer of the connection overflows
+    slow_consumer: CancellationToken,
+}
+
+impl ConnectionSender {
+    fn channel(buffer_size: usize) -> (Self, MessageReceiver<Message>) {
+        let (messages, receiver) = channel(buffer_size);
+        let sender = Self {
+            messages,
+            slow_consumer: CancellationToken::new(),
+        };
+        (sender, receiver)
+    }
+
+    /// Returns false if the connection is closed or is going to be closed
+    fn send(&self, message: Message) -> bool {
+        match self.messages.try_send(message) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                self.slow_consumer.cancel();
+                false
+            }
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+}
+
+/// Counts the open connections of every client, by the IP address and by the API key,
+/// so a single client can't take all the connections the service is able to hold
+struct ConnectionLimiter {
+    max_per_client: usize,
+    connections: StdMutex<HashMap<String, usize>>,
+}
+
+impl ConnectionLimiter {
+    fn new(max_per_client: usize) -> Self {
+        Self {
+            max_per_client,
+            connections: Default::default(),
+        }
+    }
+
+    /// Returns None if the client has the max number of connections open already
+    fn acquire(self: &Arc<Self>, client: String) -> Option<ConnectionPermit> {
+        let mut connections = self.connections.lock().unwrap();
+        let count = connections.entry(client.clone()).or_default();
+        if *count >= self.max_per_client {
+            return None;
+        }
+        *count += 1;
+
+        Some(ConnectionPermit {
+            limiter: self.clone(),
+            client,
+        })
+    }
+}
+
+/// Frees the connection of the client once dropped
+struct ConnectionPermit {
+    limiter: Arc<ConnectionLimiter>,
+    client: String,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        let mut connections = self.limiter.connections.lock().unwrap();
+        if let Some(count) = connections.get_mut(&self.client) {
+            *count -= 1;
+            if *count == 0 {
+                connections.remove(&self.client);
+            }
+        }
+    }
+}
+
+/// Keys the subscriptions are made for, the updated assets matching none of them aren't loaded
+#[derive(Debug, Default, PartialEq)]
+struct SubscribedKeys {
+    assets: HashSet<String>,
+    owners: HashSet<String>,
+    collections: HashSet<String>,
+}
+
+/// Subscriptions of all the connections, notified about the assets the ingester changes
+#[derive(Default)]
+pub struct Subscriptions {
+    last_id: AtomicU64,
+    subscriptions: StdMutex<HashMap<u64, Subscription>>,
+}
+
+impl Subscriptions {
+    fn subscribe(&self, filter: SubscriptionFilter, sender: ConnectionSender) -> u64 {
+        let id = self.last_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .insert(id, Subscription { filter, sender });
+        id
+    }
+
+    fn unsubscribe(&self, id: u64) {
+        self.subscriptions.lock().unwrap().remove(&id);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.subscriptions.lock().unwrap().is_empty()
+    }
+
+    fn subscribed_keys(&self) -> SubscribedKeys {
+        let mut keys = SubscribedKeys::default();
+        for subscription in self.subscriptions.lock().unwrap().values() {
+            match &subscription.filter {
+                SubscriptionFilter::Asset(id) => keys.assets.insert(id.clone()),
+                SubscriptionFilter::Owner(owner) => keys.owners.insert(owner.clone()),
+                SubscriptionFilter::Collection(collection) => {
+                    keys.collections.insert(collection.clone())
+                }
+            };
+        }
+        keys
+    }
+
+    /// Notifies about the assets, the previous owners are the ones of the assets
+    /// which left the wallets, by the asset ids
+    fn notify(&self, assets: &[Value], previous_owners: &HashMap<String, Option<String>>) {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        for asset in assets {
+            let previous_owner = asset["id"]
+                .as_str()
+                .and_then(|id| previous_owners.get(id))
+                .and_then(Option::as_deref);
+            for (id, subscription) in subscriptions.iter() {
+                if !subscription.filter.matches(asset, previous_owner) {
+                    continue;
+                }
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": subscription.filter.notification_method(),
+                    "params": {
+                        "subscription": id,
+                        "result": asset,
+                    },
+                });
+                // the connection is closing, its subscriptions are removed on close
+                subscription
+                    .sender
+                    .send(Message::Text(notification.to_string()));
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SubscriptionRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Subscriptions made through the single connection
+struct Connection {
+    subscriptions: Arc<Subscriptions>,
+    metrics: Arc<ApiMetricsConfig>,
+    sender: ConnectionSender,
+    ids: HashSet<u64>,
+    // the key the connection was opened with, checked for every request
+    api_key: Option<String>,
+    api_keys: Option<Arc<ApiKeys>>,
+}
+
+impl Connection {
+    fn handle_request(&mut self, request: &str) -> Value {
+        let request = match serde_json::from_str::<SubscriptionRequest>(request) {
+            Ok(request) => request,
+            Err(_) => {
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": Value::Null,
+                    "error": jsonrpc_core::Error::parse_error(),
+                })
+            }
+        };
+        if !SUBSCRIPTION_METHODS.contains(&request.method.as_str()) {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "error": jsonrpc_core::Error::method_not_found(),
+            });
+        }
+        self.metrics.inc_requests(&request.method);
+        if let Some(api_keys) = self.api_keys.as_ref() {
+            // the subscriptions have no pages, so only the rate of the key applies to them
+            if let Err(e) = api_keys.check(self.api_key.as_deref(), &request.method, None) {
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": request.id,
+                    "error": jsonrpc_core::Error::from(e),
+                });
+            }
+        }
+
+        let result = match request.method.as_str() {
+            "assetSubscribe" => parse_params::<AssetSubscribe>(request.params)
+                .and_then(|params| validated(params.id))
+                .map(SubscriptionFilter::Asset)
+                .and_then(|filter| self.subscribe(filter)),
+            "ownerSubscribe" => parse_params::<OwnerSubscribe>(request.params)
+                .and_then(|params| validated(params.owner_address))
+                .map(SubscriptionFilter::Owner)
+                .and_then(|filter| self.subscribe(filter)),
+            "groupSubscribe" => parse_params::<GroupSubscribe>(request.params)
+                .and_then(|params| {
+                    if params.group_key != "collection" {
+                        return Err(DasApiError::Validation(
+                            "Only the collection grouping is supported".to_string(),
+                        ));
+                    }
+                    validated(params.group_value)
+                })
+                .map(SubscriptionFilter::Collection)
+                .and_then(|filter| self.subscribe(filter)),
+            _ => parse_params::<Unsubscribe>(request.params)
+                .map(|params| json!(self.unsubscribe(params.subscription))),
+        };
+
+        match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": request.id, "result": result}),
+            Err(e) => json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "error": jsonrpc_core::Error::from(e),
+            }),
+        }
+    }
+
+    fn subscribe(&mut self, filter: SubscriptionFilter) -> Result<Value, DasApiError> {
+        if self.ids.len() >= MAX_SUBSCRIPTIONS_PER_CONNECTION {
+            return Err(DasApiError::Validation(format!(
+                "Up to {} subscriptions are allowed per connection",
+                MAX_SUBSCRIPTIONS_PER_CONNECTION
+            )));
+        }
+        let id = self.subscriptions.subscribe(filter, self.sender.clone());
+        self.ids.insert(id);
+        Ok(json!(id))
+    }
+
+    fn unsubscribe(&mut self, id: u64) -> bool {
+        // the subscriptions of the other connections can't be removed
+        if !self.ids.remove(&id) {
+            return false;
+        }
+        self.subscriptions.unsubscribe(id);
+        true
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        for id in self.ids.drain() {
+            self.subscriptions.unsubscribe(id);
+        }
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, DasApiError> {
+    serde_json::from_value(params).map_err(|e| DasApiError::Validation(e.to_string()))
+}
+
+fn validated(pubkey: String) -> Result<String, DasApiError> {
+    Ok(validate_pubkey(pubkey)?.to_string())
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    subscriptions: Arc<Subscriptions>,
+    metrics: Arc<ApiMetricsConfig>,
+    api_keys: Option<Arc<ApiKeys>>,
+    limiter: Arc<ConnectionLimiter>,
+    _ip_permit: ConnectionPermit,
+    mut shutdown_rx: Receiver<()>,
+) {
+    let mut api_key = None;
+    let mut key_permit = None;
+    let take_api_key = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        api_key = api_key_from(
+            request
+                .headers()
+                .get(API_KEY_HEADER)
+                .and_then(|value| value.to_str().ok()),
+            request.uri().query(),
+        );
+        // the unknown keys are limited by the IP address only, their requests are refused anyway
+        let key_name = api_keys
+            .as_ref()
+            .and_then(|api_keys| api_keys.key_name(api_key.as_deref()));
+        if let Some(key_name) = key_name {
+            key_permit = Some(
+                limiter
+                    .acquire(format!("key:{}", key_name))
+                    .ok_or_else(too_many_connections)?,
+            );
+        }
+        Ok(response)
+    };
+    let ws_stream = match tokio_tungstenite::accept_hdr_async(stream, take_api_key).await {
+        Ok(ws_stream) => ws_stream,
+        Err(e) => {
+            error!("Accept websocket connection: {}", e);
+            return;
+        }
+    };
+    let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+    let (sender, mut receiver) = ConnectionSender::channel(CONNECTION_BUFFER_SIZE);
+    let slow_consumer = sender.slow_consumer.clone();
+    // the writer stops once the connection and all its subscriptions are dropped,
+    // or right away if the client doesn't keep up, as the writes to it may hang
+    let writer_slow_consumer = slow_consumer.clone();
+    tokio::spawn(async move {
+        loop {
+            let message = tokio::select! {
+                message = receiver.recv() => message,
+                _ = writer_slow_consumer.cancelled() => None,
+            };
+            let Some(message) = message else {
+                break;
+            };
+            if ws_sender.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut connection = Connection {
+        subscriptions,
+        metrics,
+        sender,
+        ids: HashSet::new(),
+        api_key,
+        api_keys,
+    };
+    loop {
+        let message = tokio::select! {
+            message = ws_receiver.next() => message,
+            _ = slow_consumer.cancelled() => {
+                info!("Closing subscriptions connection which doesn't keep up with its messages");
+                break;
+            }
+            _ = shutdown_rx.recv() => break,
+        };
+        match message {
+            Some(Ok(Message::Text(request))) => {
+                let response = connection.handle_request(&request);
+                if !connection.sender.send(Message::Text(response.to_string())) {
+                    break;
+                }
+            }
+            Some(Ok(Message::Close(_))) | None => break,
+            // pings are answered by the websocket stream itself
+            Some(Ok(_)) => {}
+            Some(Err(e)) => {
+                error!("Read websocket message: {}", e);
+                break;
+            }
+        }
+    }
+    // the connection of the key is counted until it's closed
+    drop(key_permit);
+}
+
+fn too_many_connections() -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some("Too many connections".to_string()));
+    *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+    response
+}
+
+pub(crate) async fn fetch_updated_keys(
+    rocks_db: Arc<Storage>,
+    from: Option<AssetUpdatedKey>,
+) -> Result<(HashSet<Pubkey>, Option<AssetUpdatedKey>), StorageError> {
+    tokio::task::spawn_blocking(move || {
+        rocks_db.fetch_asset_updated_keys(from, None, UPDATED_ASSETS_BATCH_SIZE, None)
+    })
+    .await
+    .map_err(|e| StorageError::Common(e.to_string()))?
+}
+
+pub(crate) async fn last_known_key(
+    rocks_db: Arc<Storage>,
+) -> Result<Option<AssetUpdatedKey>, StorageError> {
+    tokio::task::spawn_blocking(move || rocks_db.last_known_asset_updated_key())
+        .await
+        .map_err(|e| StorageError::Common(e.to_string()))?
+}
+
+/// Selects the updated assets matching the subscriptions by their ids, owners and collections,
+/// so only those are loaded. Returns them by the ids along with the owners they left
+/// since the slot of the previously tailed update.
+async fn subscribed_updates(
+    rocks_db: Arc<Storage>,
+    pubkeys: HashSet<Pubkey>,
+    from_slot: Option<u64>,
+    subscribed: SubscribedKeys,
+) -> Result<HashMap<String, Option<String>>, StorageError> {
+    let pubkeys = pubkeys.into_iter().collect::<Vec<_>>();
+    let owners = rocks_db.asset_owner_data.batch_get(pubkeys.clone()).await?;
+    let collections = rocks_db
+        .asset_collection_data
+        .batch_get(pubkeys.clone())
+        .await?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut updates = HashMap::new();
+        for ((pubkey, owner), collection) in pubkeys.into_iter().zip(owners).zip(collections) {
+            let id = pubkey.to_string();
+            let owner = owner
+                .and_then(|owner| owner.owner.value)
+                .map(|owner| owner.to_string());
+            // the versions replaced in the slot of the previous update are taken as well,
+            // as the updates of the same slot may follow it
+            let previous_owner = match from_slot {
+                Some(slot) if !subscribed.owners.is_empty() => rocks_db
+                    .get_previous_versions_after_slot(pubkey, slot.saturating_sub(1))?
+                    .owner
+                    .flatten()
+                    .and_then(|previous| previous.owner.value)
+                    .map(|previous| previous.to_string())
+                    .filter(|previous| Some(previous) != owner.as_ref()),
+                _ => None,
+            };
+            let collection = collection.map(|collection| collection.collection.value.to_string());
+
+            if subscribed.assets.contains(&id)
+                || [&owner, &previous_owner]
+                    .into_iter()
+                    .flatten()
+                    .any(|owner| subscribed.owners.contains(owner))
+                || collection.is_some_and(|collection| subscribed.collections.contains(&collection))
+            {
+                updates.insert(id, previous_owner);
+            }
+        }
+        Ok::<_, StorageError>(updates)
+    })
+    .await
+    .map_err(|e| StorageError::Common(e.to_string()))?
+}
+
+/// Tails the assets updates index, which is caught up on the API replicas as well,
+/// and notifies the subscriptions about the changed assets
+async fn run_updates_tailer(
+    api: Arc<Api>,
+    subscriptions: Arc<Subscriptions>,
+    tasks: Arc<Mutex<JoinSet<Result<(), JoinError>>>>,
+    mut shutdown_rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    let rocks_db = api.rocks_db.clone();
+    // only the changes made after the start are sent
+    let mut last_key = last_known_key(rocks_db.clone()).await.unwrap_or_else(|e| {
+        error!("Get last known asset updated key: {}", e);
+        None
+    });
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(UPDATES_POLL_INTERVAL) => {},
+            _ = shutdown_rx.recv() => {
+                info!("Received stop signal, stopping subscriptions updates tailer...");
+                return Ok(());
+            }
+        }
+        if subscriptions.is_empty() {
+            match last_known_key(rocks_db.clone()).await {
+                Ok(key) => last_key = key.or(last_key),
+                Err(e) => error!("Get last known asset updated key: {}", e),
+            }
+            continue;
+        }
+
+        while shutdown_rx.is_empty() {
+            let (pubkeys, key) = match fetch_updated_keys(rocks_db.clone(), last_key.clone()).await
+            {
+                Ok(updated_keys) => updated_keys,
+                Err(e) => {
+                    error!("Fetch asset updated keys: {}", e);
+                    break;
+                }
+            };
+            if pubkeys.is_empty() {
+                last_key = key;
+                break;
+            }
+            // the notifications must not be built from the responses cached before the change
+            if let Some(asset_cache) = &api.asset_cache {
+                asset_cache.invalidate(pubkeys.iter().copied()).await;
+            }
+
+            // the key is advanced only once the updates are notified, the failed ones are retried
+            let updates = match subscribed_updates(
+                rocks_db.clone(),
+                pubkeys,
+                last_key.as_ref().map(|key| key.slot),
+                subscriptions.subscribed_keys(),
+            )
+            .await
+            {
+                Ok(updates) => updates,
+                Err(e) => {
+                    error!("Select subscribed asset updates: {}", e);
+                    break;
+                }
+            };
+            if updates.is_empty() {
+                last_key = key;
+                continue;
+            }
+            let assets = match api
+                .get_asset_batch(
+                    GetAssetBatch {
+                        ids: updates.keys().cloned().collect(),
+                        options: Some(Options {
+                            show_unverified_collections: true,
+                            ..Default::default()
+                        }),
+                        at_slot: None,
+                    },
+                    tasks.clone(),
+                )
+                .await
+            {
+                Ok(assets) => assets,
+                Err(e) => {
+                    error!("Get updated assets: {}", e);
+                    break;
+                }
+            };
+            if let Value::Array(assets) = assets {
+                subscriptions.notify(&assets, &updates);
+            }
+            last_key = key;
+        }
+    }
+}
+
+pub(crate) async fn run_subscriptions_service(
+    api: Arc<Api>,
+    tasks: Arc<Mutex<JoinSet<Result<(), JoinError>>>>,
+    port: u16,
+    max_connections_per_client: usize,
+    api_keys: Option<Arc<ApiKeys>>,
+    mut shutdown_rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Bind subscriptions service: {}", e);
+            return Ok(());
+        }
+    };
+    let subscriptions = Arc::new(Subscriptions::default());
+    let limiter = Arc::new(ConnectionLimiter::new(max_connections_per_client));
+    tasks.lock().await.spawn(run_updates_tailer(
+        api.clone(),
+        subscriptions.clone(),
+        tasks.clone(),
+        shutdown_rx.resubscribe(),
+    ));
+    info!("Subscriptions service started on {}", addr);
+
+    loop {
+        tokio::select! {
+            connection = listener.accept() => match connection {
+                Ok((stream, peer_addr)) => match limiter.acquire(ip_client(peer_addr.ip())) {
+                    Some(ip_permit) => {
+                        tokio::spawn(handle_connection(
+                            stream,
+                            subscriptions.clone(),
+                            api.metrics.clone(),
+                            api_keys.clone(),
+                            limiter.clone(),
+                            ip_permit,
+                            shutdown_rx.resubscribe(),
+                        ));
+                    }
+                    // dropping the stream closes the connection
+                    None => info!("Too many subscriptions connections from {}", peer_addr.ip()),
+                },
+                Err(e) => error!("Accept subscriptions connection: {}", e),
+            },
+            _ = shutdown_rx.recv() => {
+                info!("Received stop signal, stopping subscriptions service...");
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn ip_client(ip: IpAddr) -> String {
+    format!("ip:{}", ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset() -> Value {
+        json!({
+            "id": "asset",
+            "ownership": {"owner": "owner"},
+            "grouping": [{"group_key": "collection", "group_value": "collection"}],
+        })
+    }
+
+    #[test]
+    fn test_filters() {
+        let asset = asset();
+        assert!(SubscriptionFilter::Asset("asset".to_string()).matches(&asset, None));
+        assert!(!SubscriptionFilter::Asset("owner".to_string()).matches(&asset, None));
+        assert!(SubscriptionFilter::Owner("owner".to_string()).matches(&asset, None));
+        assert!(!SubscriptionFilter::Owner("sender".to_string()).matches(&asset, None));
+        // the asset left the wallet
+        assert!(SubscriptionFilter::Owner("sender".to_string()).matches(&asset, Some("sender")));
+        assert!(SubscriptionFilter::Collection("collection".to_string()).matches(&asset, None));
+        assert!(!SubscriptionFilter::Collection("asset".to_string()).matches(&asset, None));
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions() {
+        let subscriptions = Arc::new(Subscriptions::default());
+        let (sender, mut receiver) = ConnectionSender::channel(CONNECTION_BUFFER_SIZE);
+        let mut connection = Connection {
+            subscriptions: subscriptions.clone(),
+            metrics: Arc::new(ApiMetricsConfig::new()),
+            sender,
+            ids: HashSet::new(),
+            api_key: None,
+            api_keys: None,
+        };
+        let owner = solana_sdk::pubkey::Pubkey::new_unique().to_string();
+
+        let response = connection.handle_request(
+            &json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "ownerSubscribe",
+                "params": {"ownerAddress": owner},
+            })
+            .to_string(),
+        );
+        let subscription = response["result"].as_u64().unwrap();
+        assert_eq!(
+            subscriptions.subscribed_keys(),
+            SubscribedKeys {
+                owners: HashSet::from([owner.clone()]),
+                ..Default::default()
+            }
+        );
+
+        let response = connection.handle_request(
+            &json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "groupSubscribe",
+                "params": {"groupKey": "creator", "groupValue": owner},
+            })
+            .to_string(),
+        );
+        assert!(response["error"].is_object());
+
+        subscriptions.notify(
+            &[json!({"id": "asset", "ownership": {"owner": owner}})],
+            &HashMap::new(),
+        );
+        let Message::Text(notification) = receiver.recv().await.unwrap() else {
+            panic!("text notification is expected");
+        };
+        let notification: Value = serde_json::from_str(&notification).unwrap();
+        assert_eq!(notification["method"], "ownerNotification");
+        assert_eq!(notification["params"]["subscription"], subscription);
+        assert_eq!(notification["params"]["result"]["id"], "asset");
+
+        // the asset which left the wallet is notified to its previous owner
+        subscriptions.notify(
+            &[json!({"id": "sent", "ownership": {"owner": "receiver"}})],
+            &HashMap::from([("sent".to_string(), Some(owner.clone()))]),
+        );
+        let Message::Text(notification) = receiver.recv().await.unwrap() else {
+            panic!("text notification is expected");
+        };
+        let notification: Value = serde_json::from_str(&notification).unwrap();
+        assert_eq!(notification["params"]["result"]["id"], "sent");
+
+        let response = connection.handle_request(
+            &json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "ownerUnsubscribe",
+                "params": {"subscription": subscription},
+            })
+            .to_string(),
+        );
+        assert_eq!(response["result"], true);
+        assert!(subscriptions.is_empty());
+    }
+
+    #[test]
+    fn test_slow_consumer() {
+        let subscriptions = Subscriptions::default();
+        let (sender, _receiver) = ConnectionSender::channel(2);
+        subscriptions.subscribe(
+            SubscriptionFilter::Asset("asset".to_string()),
+            sender.clone(),
+        );
+
+        subscriptions.notify(&[asset(), asset()], &HashMap::new());
+        assert!(!sender.slow_consumer.is_cancelled());
+        subscriptions.notify(&[asset()], &HashMap::new());
+        assert!(sender.slow_consumer.is_cancelled());
+    }
+
+    #[test]
+    fn test_connection_limiter() {
+        let limiter = Arc::new(ConnectionLimiter::new(2));
+        let ip = ip_client(IpAddr::from([1, 2, 3, 4]));
+
+        let first = limiter.acquire(ip.clone()).unwrap();
+        let _second = limiter.acquire(ip.clone()).unwrap();
+        assert!(limiter.acquire(ip.clone()).is_none());
+        assert!(limiter.acquire("key:partner".to_string()).is_some());
+
+        drop(first);
+        assert!(limiter.acquire(ip).is_some());
+    }
+}
@@ -1,16 +1,19 @@
 use entities::api_req_params::{
-    AssetSorting, GetAssetsByAuthority, GetAssetsByCreator, GetAssetsByGroup, GetAssetsByOwner,
-    GetByMethodsOptions, Pagination, SearchAssets,
+    AssetSorting, Commitment, GetAssetsByAuthority, GetAssetsByCreator, GetAssetsByGroup,
+    GetAssetsByOwner, GetByMethodsOptions, Pagination, SearchAssets,
 };
 
 pub trait ApiRequest {
     fn get_all_pagination_parameters(&self) -> Pagination;
     fn get_sort_parameter(&self) -> Option<AssetSorting>;
     fn get_options(&self) -> Option<GetByMethodsOptions>;
+    fn get_commitment(&self) -> Option<Commitment> {
+        None
+    }
 }
 
 macro_rules! impl_request_with_pagination {
-    ($struct_name:ident) => {
+    ($struct_name:ident $(, $commitment:ident)?) => {
         impl ApiRequest for $struct_name {
             fn get_all_pagination_parameters(&self) -> Pagination {
                 Pagination {
@@ -29,12 +32,18 @@ macro_rules! impl_request_with_pagination {
             fn get_options(&self) -> Option<GetByMethodsOptions> {
                 self.options.clone().map(Into::into)
             }
+
+            $(
+                fn get_commitment(&self) -> Option<Commitment> {
+                    self.$commitment
+                }
+            )?
         }
     };
 }
 
-impl_request_with_pagination!(GetAssetsByOwner);
+impl_request_with_pagination!(GetAssetsByOwner, commitment);
 impl_request_with_pagination!(GetAssetsByGroup);
 impl_request_with_pagination!(GetAssetsByCreator);
 impl_request_with_pagination!(GetAssetsByAuthority);
-impl_request_with_pagination!(SearchAssets);
+impl_request_with_pagination!(SearchAssets, commitment);
@@ -0,0 +1,210 @@
+use blockbuster::program_handler::ProgramParser;
+use blockbuster::programs::mpl_core_program::MplCoreParser;
+use blockbuster::programs::token_metadata::token_metadata_id;
+use solana_sdk::pubkey::Pubkey;
+
+const UNKNOWN_INSTRUCTION: &str = "Unknown";
+
+// Token Metadata and MPL Core instructions are Borsh enums, so the first byte is the variant index
+const TOKEN_METADATA_INSTRUCTIONS: &[&str] = &[
+    "CreateMetadataAccount",
+    "UpdateMetadataAccount",
+    "DeprecatedCreateMasterEdition",
+    "DeprecatedMintNewEditionFromMasterEditionViaPrintingToken",
+    "UpdatePrimarySaleHappenedViaToken",
+    "DeprecatedSetReservationList",
+    "DeprecatedCreateReservationList",
+    "SignMetadata",
+    "DeprecatedMintPrintingTokensViaToken",
+    "DeprecatedMintPrintingTokens",
+    "CreateMasterEdition",
+    "MintNewEditionFromMasterEditionViaToken",
+    "ConvertMasterEditionV1ToV2",
+    "MintNewEditionFromMasterEditionViaVaultProxy",
+    "PuffMetadata",
+    "UpdateMetadataAccountV2",
+    "CreateMetadataAccountV2",
+    "CreateMasterEditionV3",
+    "VerifyCollection",
+    "Utilize",
+    "ApproveUseAuthority",
+    "RevokeUseAuthority",
+    "UnverifyCollection",
+    "ApproveCollectionAuthority",
+    "RevokeCollectionAuthority",
+    "SetAndVerifyCollection",
+    "FreezeDelegatedAccount",
+    "ThawDelegatedAccount",
+    "RemoveCreatorVerification",
+    "BurnNft",
+    "VerifySizedCollectionItem",
+    "UnverifySizedCollectionItem",
+    "SetAndVerifySizedCollectionItem",
+    "CreateMetadataAccountV3",
+    "SetCollectionSize",
+    "SetTokenStandard",
+    "BubblegumSetCollectionSize",
+    "BurnEditionNft",
+    "CreateEscrowAccount",
+    "CloseEscrowAccount",
+    "TransferOutOfEscrow",
+    "Burn",
+    "Create",
+    "Mint",
+    "Delegate",
+    "Revoke",
+    "Lock",
+    "Unlock",
+    "Migrate",
+    "Transfer",
+    "Update",
+    "Use",
+    "Verify",
+    "Unverify",
+    "Collect",
+    "Print",
+    "Resize",
+    "CloseAccounts",
+];
+
+const MPL_CORE_INSTRUCTIONS: &[&str] = &[
+    "CreateV1",
+    "CreateCollectionV1",
+    "AddPluginV1",
+    "AddCollectionPluginV1",
+    "RemovePluginV1",
+    "RemoveCollectionPluginV1",
+    "UpdatePluginV1",
+    "UpdateCollectionPluginV1",
+    "ApprovePluginAuthorityV1",
+    "ApproveCollectionPluginAuthorityV1",
+    "RevokePluginAuthorityV1",
+    "RevokeCollectionPluginAuthorityV1",
+    "BurnV1",
+    "BurnCollectionV1",
+    "TransferV1",
+    "UpdateV1",
+    "UpdateCollectionV1",
+    "CompressV1",
+    "DecompressV1",
+    "Collect",
+    "CreateV2",
+    "CreateCollectionV2",
+    "AddExternalPluginAdapterV1",
+    "AddCollectionExternalPluginAdapterV1",
+    "RemoveExternalPluginAdapterV1",
+    "RemoveCollectionExternalPluginAdapterV1",
+    "UpdateExternalPluginAdapterV1",
+    "UpdateCollectionExternalPluginAdapterV1",
+    "WriteExternalPluginAdapterDataV1",
+    "WriteCollectionExternalPluginAdapterDataV1",
+];
+
+/// Programs owning the accounts of non-compressed assets,
+/// the transactions invoking them are recorded in the asset signatures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetAccountProgram {
+    TokenMetadata,
+    MplCore,
+    Token,
+    Token2022,
+}
+
+impl AssetAccountProgram {
+    pub const ALL: [AssetAccountProgram; 4] = [
+        AssetAccountProgram::TokenMetadata,
+        AssetAccountProgram::MplCore,
+        AssetAccountProgram::Token,
+        AssetAccountProgram::Token2022,
+    ];
+
+    pub fn id(&self) -> Pubkey {
+        match self {
+            AssetAccountProgram::TokenMetadata => token_metadata_id(),
+            AssetAccountProgram::MplCore => MplCoreParser {}.key(),
+            AssetAccountProgram::Token => spl_token::id(),
+            AssetAccountProgram::Token2022 => spl_token_2022::id(),
+        }
+    }
+
+    pub fn from_id(program: &Pubkey) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|asset_program| asset_program.id() == *program)
+    }
+
+    /// Name of the instruction encoded in the instruction data,
+    /// "Unknown" if the data doesn't match any instruction of the program.
+    pub fn instruction_name(&self, data: &[u8]) -> String {
+        let name = match self {
+            AssetAccountProgram::TokenMetadata => data
+                .first()
+                .and_then(|discriminator| TOKEN_METADATA_INSTRUCTIONS.get(*discriminator as usize))
+                .map(|name| name.to_string()),
+            AssetAccountProgram::MplCore => data
+                .first()
+                .and_then(|discriminator| MPL_CORE_INSTRUCTIONS.get(*discriminator as usize))
+                .map(|name| name.to_string()),
+            AssetAccountProgram::Token => spl_token::instruction::TokenInstruction::unpack(data)
+                .ok()
+                .map(|ix| variant_name(format!("{:?}", ix))),
+            AssetAccountProgram::Token2022 => {
+                spl_token_2022::instruction::TokenInstruction::unpack(data)
+                    .ok()
+                    .map(|ix| variant_name(format!("{:?}", ix)))
+            }
+        };
+        name.unwrap_or_else(|| UNKNOWN_INSTRUCTION.to_string())
+    }
+}
+
+// the Debug output of the SPL instructions starts with the variant name followed by its fields
+fn variant_name(debug: String) -> String {
+    debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or(UNKNOWN_INSTRUCTION)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instruction_name() {
+        assert_eq!(
+            AssetAccountProgram::TokenMetadata.instruction_name(&[33, 1, 2]),
+            "CreateMetadataAccountV3"
+        );
+        assert_eq!(
+            AssetAccountProgram::TokenMetadata.instruction_name(&[49, 0]),
+            "Transfer"
+        );
+        assert_eq!(
+            AssetAccountProgram::MplCore.instruction_name(&[14]),
+            "TransferV1"
+        );
+        assert_eq!(
+            AssetAccountProgram::Token.instruction_name(
+                &spl_token::instruction::TokenInstruction::Transfer { amount: 1 }.pack()
+            ),
+            "Transfer"
+        );
+        assert_eq!(
+            AssetAccountProgram::Token2022.instruction_name(
+                &spl_token_2022::instruction::TokenInstruction::SyncNative.pack()
+            ),
+            "SyncNative"
+        );
+        assert_eq!(
+            AssetAccountProgram::MplCore.instruction_name(&[200]),
+            "Unknown"
+        );
+        assert_eq!(AssetAccountProgram::Token.instruction_name(&[]), "Unknown");
+        assert_eq!(
+            AssetAccountProgram::from_id(&spl_token_2022::id()),
+            Some(AssetAccountProgram::Token2022)
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use rocks_db::parameters::Parameter;
+use rocks_db::Storage;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::Receiver;
+use tokio::task::JoinError;
+use tokio::time::sleep as tokio_sleep;
+use tracing::{error, info};
+
+const ASSET_HISTORY_CLEANING_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Keeps the asset history within the retention window behind the latest confirmed slot.
+/// The versions older than the window are folded into one entry per asset,
+/// and the reads at the slots before the window are refused from then on.
+pub async fn run_asset_history_cleaner(
+    storage: Arc<Storage>,
+    retention_slots: u64,
+    mut rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    info!("Start cleaning asset history...");
+    while rx.is_empty() {
+        match storage
+            .get_parameter::<u64>(Parameter::LastConfirmedSlot)
+            .await
+        {
+            Ok(Some(last_slot)) if last_slot > retention_slots => {
+                let cutoff_slot = last_slot - retention_slots;
+                let cloned_storage = storage.clone();
+                match tokio::task::spawn_blocking(move || {
+                    cloned_storage.compact_asset_history(cutoff_slot)
+                })
+                .await?
+                {
+                    Ok(deleted) => info!(
+                        "Compacted asset history up to slot {}, deleted {} entries",
+                        cutoff_slot, deleted
+                    ),
+                    Err(e) => error!("Compact asset history: {}", e),
+                }
+            }
+            Ok(_) => {}
+            Err(e) => error!("Get {:?}: {}", Parameter::LastConfirmedSlot, e),
+        }
+
+        tokio::select! {
+            _ = tokio_sleep(ASSET_HISTORY_CLEANING_INTERVAL) => {}
+            _ = rx.recv() => {
+                info!("Received stop signal, stopping cleaning asset history!");
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
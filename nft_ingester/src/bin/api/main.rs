@@ -153,6 +153,12 @@ pub async fn main() -> Result<(), IngesterError> {
             config.file_storage_path_container.as_str(),
             account_balance_getter,
             config.storage_service_base_url,
+            config.websocket_port,
+            config.websocket_max_connections_per_client,
+            config.api_keys_file,
+            config.api_keys_reload_interval_sec,
+            config.asset_cache_size,
+            config.asset_cache_ttl_sec,
         )
         .await
         {
@@ -201,7 +207,7 @@ pub async fn main() -> Result<(), IngesterError> {
     let dur = tokio::time::Duration::from_secs(config.rocks_sync_interval_seconds);
     mutexed_tasks.lock().await.spawn(async move {
         while cloned_rx.is_empty() {
-            if let Err(e) = cloned_rocks_storage.db.try_catch_up_with_primary() {
+            if let Err(e) = cloned_rocks_storage.catch_up_with_primary() {
                 error!("Sync rocksdb error: {}", e);
             }
             tokio::time::sleep(dur).await;
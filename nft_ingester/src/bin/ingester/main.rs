@@ -32,6 +32,7 @@ use nft_ingester::accounts_processor::run_accounts_processor;
 use nft_ingester::ack::create_ack_channel;
 use nft_ingester::api::account_balance::AccountBalanceGetterImpl;
 use nft_ingester::api::service::start_api;
+use nft_ingester::asset_history_cleaner::run_asset_history_cleaner;
 use nft_ingester::backfiller::{
     run_perpetual_slot_collection, run_perpetual_slot_processing, run_slot_force_persister, BackfillSource, Backfiller,
     DirectBlockParser, ForceReingestableSlotGetter, TransactionsParser,
@@ -39,20 +40,30 @@ use nft_ingester::backfiller::{
 use nft_ingester::batch_mint::batch_mint_processor::{process_batch_mints, BatchMintProcessor, NoopBatchMintTxSender};
 use nft_ingester::bubblegum_updates_processor::BubblegumTxProcessor;
 use nft_ingester::buffer::{debug_buffer, Buffer};
+use nft_ingester::commitment_slots::run_commitment_slots_tracker;
 use nft_ingester::config::{
     setup_config, ApiConfig, BackfillerConfig, BackfillerMode, IngesterConfig, MessageSource, INGESTER_CONFIG_PREFIX,
 };
+use nft_ingester::dead_letters::{
+    run_dead_letters_depth_monitor, run_dead_letters_redrive, DeadLetterQueue, DeadLetterReceiver,
+};
+use nft_ingester::file_replay_receiver::{replay_recorded_messages, FileReplayReceiver};
 use nft_ingester::fork_cleaner::{run_fork_cleaner, ForkCleaner};
+use nft_ingester::forked_accounts::ForkedAccountsReceiver;
+use nft_ingester::gap_repairer::{run_gap_repairer, GapRepairer};
 use nft_ingester::gapfiller::{process_asset_details_stream_wrapper, run_sequence_consistent_gapfiller};
+use nft_ingester::grpc_geyser_receiver::{connect_to_grpc_geyser, GrpcGeyserReceiver};
 use nft_ingester::index_syncronizer::Synchronizer;
 use nft_ingester::init::{graceful_stop, init_index_storage_with_migration, init_primary_storage};
 use nft_ingester::json_worker::JsonWorker;
 use nft_ingester::message_handler::MessageHandlerIngester;
+use nft_ingester::message_recorder::{run_message_recorder, MessageRecorder};
 use nft_ingester::redis_receiver::RedisReceiver;
 use nft_ingester::rocks_db::{perform_backup, receive_last_saved_slot, restore_rocksdb};
 use nft_ingester::tcp_receiver::{connect_to_geyser, connect_to_snapshot_receiver, TcpReceiver};
 use nft_ingester::transaction_ingester::BackfillTransactionIngester;
 use nft_ingester::transaction_processor::run_transaction_processor;
+use nft_ingester::webhooks::{run_webhooks_sender, WebhookSender};
 use nft_ingester::{config::init_logger, error::IngesterError};
 use rocks_db::backup_service;
 use rocks_db::backup_service::BackupService;
@@ -118,6 +129,33 @@ pub async fn main() -> Result<(), IngesterError> {
         .await?,
     );
 
+    let dead_letters =
+        Arc::new(DeadLetterQueue::new(primary_rocks_storage.clone(), metrics_state.ingester_metrics.clone()));
+    let cloned_rx = shutdown_rx.resubscribe();
+    mutexed_tasks.lock().await.spawn(run_dead_letters_depth_monitor(
+        primary_rocks_storage.clone(),
+        metrics_state.ingester_metrics.clone(),
+        Duration::from_secs(config.dead_letters_retention_sec),
+        cloned_rx,
+    ));
+
+    if config.run_webhooks {
+        let webhook_sender = WebhookSender::new(
+            primary_rocks_storage.clone(),
+            index_pg_storage.clone(),
+            metrics_state.ingester_metrics.clone(),
+            config.webhook_max_attempts,
+            config.webhook_retry_base_sec,
+            Duration::from_secs(config.webhook_request_timeout_sec),
+            config.webhook_allow_private_hosts,
+        )?;
+        let cloned_rx = shutdown_rx.resubscribe();
+        mutexed_tasks
+            .lock()
+            .await
+            .spawn(run_webhooks_sender(webhook_sender, cloned_rx));
+    }
+
     let synchronizer = Synchronizer::new(
         primary_rocks_storage.clone(),
         index_pg_storage.clone(),
@@ -134,15 +172,30 @@ pub async fn main() -> Result<(), IngesterError> {
         synchronizer.full_syncronize(&shutdown_rx.resubscribe()).await?;
     }
 
+    let message_recorder = if let Some(message_recorder_config) = config.message_recorder_config.clone() {
+        let (recorder, writer) = MessageRecorder::new(message_recorder_config);
+        let cloned_rx = shutdown_rx.resubscribe();
+        mutexed_tasks
+            .lock()
+            .await
+            .spawn(run_message_recorder(writer, cloned_rx));
+        Some(recorder)
+    } else {
+        None
+    };
+
     // setup receiver
-    let message_handler = Arc::new(MessageHandlerIngester::new(buffer.clone()));
+    let message_handler =
+        Arc::new(MessageHandlerIngester::new(buffer.clone(), message_recorder.clone(), dead_letters.clone()));
     let (geyser_tcp_receiver, geyser_addr) = (
         TcpReceiver::new(message_handler.clone(), config.tcp_config.get_tcp_receiver_reconnect_interval()?),
         config.tcp_config.get_tcp_receiver_addr_ingester()?,
     );
     // For now there is no snapshot mechanism via Redis, so we use snapshot_tcp_receiver for this purpose
+    // snapshots are not recorded
+    let snapshot_message_handler = Arc::new(MessageHandlerIngester::new(buffer.clone(), None, dead_letters.clone()));
     let (snapshot_tcp_receiver, snapshot_addr) = (
-        TcpReceiver::new(message_handler.clone(), config.tcp_config.get_tcp_receiver_reconnect_interval()? * 2),
+        TcpReceiver::new(snapshot_message_handler, config.tcp_config.get_tcp_receiver_reconnect_interval()? * 2),
         config.tcp_config.get_snapshot_addr_ingester()?,
     );
 
@@ -163,6 +216,42 @@ pub async fn main() -> Result<(), IngesterError> {
         .await
         .spawn(connect_to_snapshot_receiver(snapshot_tcp_receiver, snapshot_addr, cloned_rx));
 
+    let grpc_geyser_receiver = if config.message_source == MessageSource::Grpc {
+        let grpc_geyser_config = config
+            .grpc_geyser_config
+            .clone()
+            .ok_or(IngesterError::ConfigurationError {
+                msg: "grpc_geyser_config is required for the Grpc message source".to_string(),
+            })?;
+        let (receiver, subscriber) = GrpcGeyserReceiver::new(grpc_geyser_config)?;
+        let cloned_rx = shutdown_rx.resubscribe();
+        mutexed_tasks
+            .lock()
+            .await
+            .spawn(connect_to_grpc_geyser(subscriber, cloned_rx));
+        Some(Arc::new(receiver))
+    } else {
+        None
+    };
+
+    let file_replay_receiver = if config.message_source == MessageSource::File {
+        let file_replay_config = config
+            .file_replay_config
+            .clone()
+            .ok_or(IngesterError::ConfigurationError {
+                msg: "file_replay_config is required for the File message source".to_string(),
+            })?;
+        let (receiver, replayer) = FileReplayReceiver::new(file_replay_config);
+        let cloned_rx = shutdown_rx.resubscribe();
+        mutexed_tasks
+            .lock()
+            .await
+            .spawn(replay_recorded_messages(replayer, cloned_rx));
+        Some(Arc::new(receiver))
+    } else {
+        None
+    };
+
     let cloned_buffer = buffer.clone();
     let cloned_rx = shutdown_rx.resubscribe();
     let cloned_metrics = metrics_state.ingester_metrics.clone();
@@ -185,6 +274,33 @@ pub async fn main() -> Result<(), IngesterError> {
     }
 
     let rpc_client = Arc::new(RpcClient::new(config.rpc_host.clone()));
+    let (dead_letter_receiver, dead_letter_redriver) = DeadLetterReceiver::new(
+        primary_rocks_storage.clone(),
+        index_pg_storage.clone(),
+        rpc_client.clone(),
+        dead_letters.clone(),
+    );
+    let dead_letter_receiver = Arc::new(dead_letter_receiver);
+    let cloned_rx = shutdown_rx.resubscribe();
+    mutexed_tasks
+        .lock()
+        .await
+        .spawn(run_dead_letters_redrive(dead_letter_redriver, cloned_rx));
+    // the redriven dead letters are processed by the dedicated workers
+    run_accounts_processor(
+        shutdown_rx.resubscribe(),
+        mutexed_tasks.clone(),
+        dead_letter_receiver.clone(),
+        primary_rocks_storage.clone(),
+        config.accounts_buffer_size,
+        config.mpl_core_fees_buffer_size,
+        metrics_state.ingester_metrics.clone(),
+        index_pg_storage.clone(),
+        rpc_client.clone(),
+        mutexed_tasks.clone(),
+        dead_letters.clone(),
+    )
+    .await;
     for _ in 0..config.accounts_parsing_workers {
         match config.message_source {
             MessageSource::Redis => {
@@ -193,6 +309,8 @@ pub async fn main() -> Result<(), IngesterError> {
                         config.redis_messenger_config.clone(),
                         ConsumptionType::All,
                         ack_channel.clone(),
+                        message_recorder.clone(),
+                        dead_letters.clone(),
                     )
                     .await?,
                 );
@@ -207,9 +325,46 @@ pub async fn main() -> Result<(), IngesterError> {
                     index_pg_storage.clone(),
                     rpc_client.clone(),
                     mutexed_tasks.clone(),
+                    dead_letters.clone(),
                 )
                 .await;
             }
+            MessageSource::Grpc => {
+                if let Some(grpc_geyser_receiver) = grpc_geyser_receiver.clone() {
+                    run_accounts_processor(
+                        shutdown_rx.resubscribe(),
+                        mutexed_tasks.clone(),
+                        grpc_geyser_receiver,
+                        primary_rocks_storage.clone(),
+                        config.accounts_buffer_size,
+                        config.mpl_core_fees_buffer_size,
+                        metrics_state.ingester_metrics.clone(),
+                        index_pg_storage.clone(),
+                        rpc_client.clone(),
+                        mutexed_tasks.clone(),
+                        dead_letters.clone(),
+                    )
+                    .await;
+                }
+            }
+            MessageSource::File => {
+                if let Some(file_replay_receiver) = file_replay_receiver.clone() {
+                    run_accounts_processor(
+                        shutdown_rx.resubscribe(),
+                        mutexed_tasks.clone(),
+                        file_replay_receiver,
+                        primary_rocks_storage.clone(),
+                        config.accounts_buffer_size,
+                        config.mpl_core_fees_buffer_size,
+                        metrics_state.ingester_metrics.clone(),
+                        index_pg_storage.clone(),
+                        rpc_client.clone(),
+                        mutexed_tasks.clone(),
+                        dead_letters.clone(),
+                    )
+                    .await;
+                }
+            }
             MessageSource::TCP => {
                 run_accounts_processor(
                     shutdown_rx.resubscribe(),
@@ -222,6 +377,7 @@ pub async fn main() -> Result<(), IngesterError> {
                     index_pg_storage.clone(),
                     rpc_client.clone(),
                     mutexed_tasks.clone(),
+                    dead_letters.clone(),
                 )
                 .await;
             }
@@ -241,6 +397,7 @@ pub async fn main() -> Result<(), IngesterError> {
             index_pg_storage.clone(),
             rpc_client.clone(),
             mutexed_tasks.clone(),
+            dead_letters.clone(),
         )
         .await;
     }
@@ -348,6 +505,12 @@ pub async fn main() -> Result<(), IngesterError> {
             api_config.file_storage_path_container.as_str(),
             account_balance_getter,
             api_config.storage_service_base_url,
+            api_config.websocket_port,
+            api_config.websocket_max_connections_per_client,
+            api_config.api_keys_file,
+            api_config.api_keys_reload_interval_sec,
+            api_config.asset_cache_size,
+            api_config.asset_cache_ttl_sec,
         )
         .await
         {
@@ -365,6 +528,15 @@ pub async fn main() -> Result<(), IngesterError> {
         buffer.json_tasks.clone(),
     ));
 
+    run_transaction_processor(
+        shutdown_rx.resubscribe(),
+        mutexed_tasks.clone(),
+        dead_letter_receiver,
+        geyser_bubblegum_updates_processor.clone(),
+        dead_letters.clone(),
+    )
+    .await;
+
     for _ in 0..config.transactions_parsing_workers {
         match config.message_source {
             MessageSource::Redis => {
@@ -373,6 +545,8 @@ pub async fn main() -> Result<(), IngesterError> {
                         config.redis_messenger_config.clone(),
                         ConsumptionType::All,
                         ack_channel.clone(),
+                        message_recorder.clone(),
+                        dead_letters.clone(),
                     )
                     .await?,
                 );
@@ -381,15 +555,41 @@ pub async fn main() -> Result<(), IngesterError> {
                     mutexed_tasks.clone(),
                     redis_receiver,
                     geyser_bubblegum_updates_processor.clone(),
+                    dead_letters.clone(),
                 )
                 .await;
             }
+            MessageSource::Grpc => {
+                if let Some(grpc_geyser_receiver) = grpc_geyser_receiver.clone() {
+                    run_transaction_processor(
+                        shutdown_rx.resubscribe(),
+                        mutexed_tasks.clone(),
+                        grpc_geyser_receiver,
+                        geyser_bubblegum_updates_processor.clone(),
+                        dead_letters.clone(),
+                    )
+                    .await;
+                }
+            }
+            MessageSource::File => {
+                if let Some(file_replay_receiver) = file_replay_receiver.clone() {
+                    run_transaction_processor(
+                        shutdown_rx.resubscribe(),
+                        mutexed_tasks.clone(),
+                        file_replay_receiver,
+                        geyser_bubblegum_updates_processor.clone(),
+                        dead_letters.clone(),
+                    )
+                    .await;
+                }
+            }
             MessageSource::TCP => {
                 run_transaction_processor(
                     shutdown_rx.resubscribe(),
                     mutexed_tasks.clone(),
                     buffer.clone(),
                     geyser_bubblegum_updates_processor.clone(),
+                    dead_letters.clone(),
                 )
                 .await;
             }
@@ -422,6 +622,20 @@ pub async fn main() -> Result<(), IngesterError> {
         Arc::new(Backfiller::new(primary_rocks_storage.clone(), backfiller_source.clone(), backfiller_config.clone()));
 
     let rpc_backfiller = Arc::new(BackfillRPC::connect(config.backfill_rpc_address.clone()));
+    let cloned_rx = shutdown_rx.resubscribe();
+    mutexed_tasks.lock().await.spawn(run_commitment_slots_tracker(
+        primary_rocks_storage.clone(),
+        rpc_backfiller.clone(),
+        cloned_rx,
+    ));
+    if config.store_asset_history {
+        let cloned_rx = shutdown_rx.resubscribe();
+        mutexed_tasks.lock().await.spawn(run_asset_history_cleaner(
+            primary_rocks_storage.clone(),
+            config.asset_history_retention_slots,
+            cloned_rx,
+        ));
+    }
     if config.run_bubblegum_backfiller {
         if backfiller_config.should_reingest {
             warn!("'Reingest' flag is set, deleting last fetched slot.");
@@ -589,7 +803,12 @@ pub async fn main() -> Result<(), IngesterError> {
         Ok(())
     });
 
-    Scheduler::run_in_background(Scheduler::new(primary_rocks_storage.clone())).await;
+    Scheduler::run_in_background(Scheduler::new(
+        primary_rocks_storage.clone(),
+        index_pg_storage.clone(),
+        config.metadata_refresh_interval_sec,
+    ))
+    .await;
 
     let rocks_clone = primary_rocks_storage.clone();
     let signature_fetcher = SignatureFetcher::new(
@@ -685,9 +904,43 @@ pub async fn main() -> Result<(), IngesterError> {
                 .spawn(run_slot_force_persister(force_reingestable_transactions_parser, rx));
         }
 
+        let gap_repairer = GapRepairer::new(
+            primary_rocks_storage.clone(),
+            backfiller_source.clone(),
+            backfiller_source.clone(),
+            force_reingestable_slot_processor.clone(),
+            rpc_backfiller.clone(),
+            metrics_state.backfiller_metrics.clone(),
+            metrics_state.sequence_consistent_gapfill_metrics.clone(),
+            config.gap_repair_max_attempts,
+        );
+        let rx = shutdown_rx.resubscribe();
+        mutexed_tasks.lock().await.spawn(run_gap_repairer(
+            gap_repairer,
+            rx,
+            config.sequence_consistent_checker_wait_period_sec,
+        ));
+
+        let (forked_accounts_receiver, forked_accounts_rollbacker) = ForkedAccountsReceiver::new(rpc_client.clone());
+        run_accounts_processor(
+            shutdown_rx.resubscribe(),
+            mutexed_tasks.clone(),
+            Arc::new(forked_accounts_receiver),
+            primary_rocks_storage.clone(),
+            config.accounts_buffer_size,
+            config.mpl_core_fees_buffer_size,
+            metrics_state.ingester_metrics.clone(),
+            index_pg_storage.clone(),
+            rpc_client.clone(),
+            mutexed_tasks.clone(),
+            dead_letters.clone(),
+        )
+        .await;
         let fork_cleaner = ForkCleaner::new(
             primary_rocks_storage.clone(),
             primary_rocks_storage.clone(),
+            primary_rocks_storage.clone(),
+            Arc::new(forked_accounts_rollbacker),
             metrics_state.fork_cleaner_metrics.clone(),
         );
         let rx = shutdown_rx.resubscribe();
@@ -121,7 +121,7 @@ pub async fn main() -> Result<(), IngesterError> {
         config.run_temp_sync_during_dump,
     );
 
-    if let Err(e) = rocks_storage.db.try_catch_up_with_primary() {
+    if let Err(e) = rocks_storage.catch_up_with_primary() {
         tracing::error!("Sync rocksdb error: {}", e);
     }
     synchronizer
@@ -129,7 +129,7 @@ pub async fn main() -> Result<(), IngesterError> {
         .await;
 
     while shutdown_rx.is_empty() {
-        if let Err(e) = rocks_storage.db.try_catch_up_with_primary() {
+        if let Err(e) = rocks_storage.catch_up_with_primary() {
             tracing::error!("Sync rocksdb error: {}", e);
         }
         let res = synchronizer
@@ -1,3 +1,4 @@
+use crate::asset_account_instructions::AssetAccountProgram;
 use crate::error::IngesterError;
 use crate::flatbuffer_mapper::FlatbufferMapper;
 use crate::plerkle;
@@ -11,7 +12,7 @@ use blockbuster::{
 use bubblegum_batch_sdk::model::BatchMint;
 use chrono::Utc;
 use entities::enums::{
-    ChainMutability, OwnerType, PersistingBatchMintState, RoyaltyTargetType,
+    AssetEventType, ChainMutability, OwnerType, PersistingBatchMintState, RoyaltyTargetType,
     SpecificationAssetClass, TokenStandard, UseMethod,
 };
 use entities::models::{
@@ -32,11 +33,15 @@ use rocks_db::transaction::{
     AssetDynamicUpdate, AssetUpdate, AssetUpdateEvent, InstructionResult, TransactionResult,
     TreeUpdate,
 };
+use rocks_db::tree_info::{TreeConfig, TreeInfo};
 use rocks_db::Storage;
 use serde_json::json;
 use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use spl_account_compression::state::CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1;
 use std::collections::{HashSet, VecDeque};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -47,6 +52,8 @@ use usecase::save_metrics::result_to_metrics;
 
 pub const BUFFER_PROCESSING_COUNTER: i32 = 10;
 const BATCH_MINT_BATCH_FLUSH_SIZE: usize = 10_000;
+// anchor discriminator of the SetTreeDelegate instruction, sha256("global:set_tree_delegate")[..8]
+const SET_TREE_DELEGATE_DISCRIMINATOR: [u8; 8] = [253, 118, 66, 37, 190, 49, 154, 102];
 lazy_static! {
     static ref KEY_SET: HashSet<Pubkey> = {
         let mut m = HashSet::new();
@@ -91,11 +98,55 @@ impl BubblegumTxProcessor {
         )
     }
 
+    /// Programs of the transactions the processor handles: Bubblegum for the compressed assets
+    /// and the programs owning the non-compressed assets for their signature history
+    pub fn supported_transaction_programs() -> Vec<Pubkey> {
+        std::iter::once(mpl_bubblegum::programs::MPL_BUBBLEGUM_ID)
+            .chain(AssetAccountProgram::ALL.iter().map(|program| program.id()))
+            .collect()
+    }
+
+    /// Collects the accounts of the outer and inner instructions of the programs
+    /// owning non-compressed assets together with the name of the first instruction touching them,
+    /// so their signature history can be recorded.
+    pub fn get_asset_accounts(tx_info: &plerkle::TransactionInfo) -> Vec<(Pubkey, String)> {
+        let mut seen = HashSet::new();
+        let mut asset_accounts = Vec::new();
+        let inner_instructions = tx_info
+            .meta_inner_instructions
+            .iter()
+            .flat_map(|ixs| ixs.instructions.iter().map(|ix| &ix.instruction));
+        for ix in tx_info
+            .message_instructions
+            .iter()
+            .chain(inner_instructions)
+        {
+            let Some(program) = tx_info
+                .account_keys
+                .get(ix.program_id_index as usize)
+                .and_then(AssetAccountProgram::from_id)
+            else {
+                continue;
+            };
+            let instruction_name = program.instruction_name(&ix.data);
+            for account in ix
+                .accounts
+                .iter()
+                .filter_map(|a| tx_info.account_keys.get(*a as usize))
+            {
+                if seen.insert(*account) {
+                    asset_accounts.push((*account, instruction_name.clone()));
+                }
+            }
+        }
+        asset_accounts
+    }
+
     pub async fn process_transaction(
         &self,
-        data: BufferedTransaction,
+        data: &BufferedTransaction,
     ) -> Result<(), IngesterError> {
-        if data == BufferedTransaction::default() {
+        if *data == BufferedTransaction::default() {
             return Ok(());
         }
         let begin_processing = Instant::now();
@@ -122,27 +173,28 @@ impl BubblegumTxProcessor {
     }
 
     pub fn get_process_transaction_results(
-        data: BufferedTransaction,
+        data: &BufferedTransaction,
         instruction_parser: Arc<BubblegumParser>,
         transaction_parser: Arc<FlatbufferMapper>,
         metrics: Arc<IngesterMetricsConfig>,
     ) -> Result<TransactionResult, IngesterError> {
         let seen_at = Utc::now();
 
-        let mut transaction_info_bytes = data.transaction.clone();
+        let mapped_transaction_info_bytes;
+        let mut transaction_info_bytes = data.transaction.as_slice();
 
         if data.map_flatbuffer {
             let tx_update =
                     utils::flatbuffer::transaction_info_generated::transaction_info::root_as_transaction_info(
                         &data.transaction,
                     ).unwrap();
-            transaction_info_bytes = transaction_parser
+            mapped_transaction_info_bytes = transaction_parser
                 .map_tx_fb_bytes(tx_update, seen_at)
                 .unwrap();
+            transaction_info_bytes = mapped_transaction_info_bytes.as_slice();
         }
         let transaction_info =
-            plerkle_serialization::root_as_transaction_info(transaction_info_bytes.as_slice())
-                .unwrap();
+            plerkle_serialization::root_as_transaction_info(transaction_info_bytes).unwrap();
         let transaction_info: plerkle::TransactionInfo =
             PlerkleTransactionInfo(transaction_info).try_into()?;
 
@@ -195,12 +247,24 @@ impl BubblegumTxProcessor {
         let instructions = Self::break_transaction(&tx);
         let slot = tx.slot;
         let signature = tx.signature;
+        // the transactions of the asset account programs are received for their signature history only,
+        // so they are not indexed as Bubblegum signatures
+        let signature_program = if tx
+            .account_keys
+            .contains(&mpl_bubblegum::programs::MPL_BUBBLEGUM_ID)
+        {
+            mpl_bubblegum::programs::MPL_BUBBLEGUM_ID
+        } else {
+            tx.account_keys
+                .iter()
+                .find_map(AssetAccountProgram::from_id)
+                .map(|program| program.id())
+                .unwrap_or(mpl_bubblegum::programs::MPL_BUBBLEGUM_ID)
+        };
         let mut transaction_result = TransactionResult {
             instruction_results: vec![],
-            transaction_signature: Some((
-                mpl_bubblegum::programs::MPL_BUBBLEGUM_ID,
-                SignatureWithSlot { signature, slot },
-            )),
+            transaction_signature: Some((signature_program, SignatureWithSlot { signature, slot })),
+            asset_accounts: Self::get_asset_accounts(&tx),
         };
 
         for (outer_ix, inner_ix) in instructions {
@@ -235,6 +299,15 @@ impl BubblegumTxProcessor {
                 slot,
             };
 
+            // SetTreeDelegate isn't known to the parser
+            if let Some(tree_info) = Self::get_set_tree_delegate_update(&ix) {
+                metrics.inc_instructions("SetTreeDelegate");
+                transaction_result
+                    .instruction_results
+                    .push(tree_info.into());
+                continue;
+            }
+
             let result = instruction_parser.handle_instruction(&ix)?;
             if let ProgramParseResult::Bubblegum(parsing_result) = result.result_type() {
                 metrics.inc_instructions(Self::instruction_name_to_string(
@@ -261,6 +334,22 @@ impl BubblegumTxProcessor {
             }
         }
 
+        for tree_info in transaction_result
+            .instruction_results
+            .iter_mut()
+            .filter_map(|ix_result| ix_result.tree_info.as_mut())
+        {
+            let tree = tree_info.tree;
+            if let Some(config) = tree_info.config.as_mut() {
+                config.canopy_depth = Self::get_tree_canopy_depth(
+                    &tx,
+                    &tree,
+                    config.max_depth,
+                    config.max_buffer_size,
+                );
+            }
+        }
+
         metrics.set_last_processed_slot("transaction", slot as i64);
 
         Ok(transaction_result)
@@ -327,19 +416,118 @@ impl BubblegumTxProcessor {
                     .map(From::from)
                     .map(Ok)?
             }
+            InstructionName::CreateTree => Self::get_create_tree_update(bundle)
+                .map(From::from)
+                .map(Ok)?,
             _ => {
                 debug!("Bubblegum: Not Implemented Instruction");
                 Ok(InstructionResult::default())
             } // InstructionName::Unknown => todo!(),
               // InstructionName::Compress => todo!(),
-              // InstructionName::CreateTree => todo!(),
               // InstructionName::SetDecompressibleState => todo!(),
         };
         let mut instruction = instruction?;
+        if let (InstructionName::MintV1 | InstructionName::MintToCollectionV1, Some(cl)) =
+            (ix_type, &parsing_result.tree_update)
+        {
+            // leaves are minted in order, so the index of the minted leaf tells how many were minted
+            instruction.tree_info = Some(TreeInfo {
+                tree: cl.id,
+                config: None,
+                num_minted: cl.index as u64 + 1,
+                delegate: None,
+            });
+        }
         instruction.tree_update = tree_update;
+        instruction.event_type = AssetEventType::from_instruction(ix_type);
         Ok(instruction)
     }
 
+    pub fn get_create_tree_update(bundle: &InstructionBundle) -> Result<TreeInfo, IngesterError> {
+        // CreateTreeConfig accounts: tree_config, merkle_tree, payer, tree_creator, ...
+        if let (Some(instruction), Some(tree), Some(tree_creator)) =
+            (bundle.instruction, bundle.keys.get(1), bundle.keys.get(3))
+        {
+            // 8 bytes discriminator followed by max_depth: u32, max_buffer_size: u32, public: Option<bool>
+            let data = instruction.data.as_slice();
+            if let (Some(max_depth), Some(max_buffer_size)) = (
+                data.get(8..12)
+                    .and_then(|b| b.try_into().ok())
+                    .map(u32::from_le_bytes),
+                data.get(12..16)
+                    .and_then(|b| b.try_into().ok())
+                    .map(u32::from_le_bytes),
+            ) {
+                let is_public = matches!(data.get(16..18), Some([1, 1]));
+                return Ok(TreeInfo {
+                    tree: *tree,
+                    config: Some(TreeConfig {
+                        max_depth,
+                        max_buffer_size,
+                        canopy_depth: None,
+                        tree_creator: *tree_creator,
+                        tree_delegate: *tree_creator,
+                        is_public,
+                        created_at_slot: bundle.slot,
+                    }),
+                    num_minted: 0,
+                    delegate: None,
+                });
+            }
+        }
+
+        Err(IngesterError::ParsingError(
+            "Ix not parsed correctly".to_string(),
+        ))
+    }
+
+    pub fn get_set_tree_delegate_update(bundle: &InstructionBundle) -> Option<TreeInfo> {
+        // SetTreeDelegate accounts: tree_config, tree_creator, new_tree_delegate, merkle_tree, ...
+        let instruction = bundle.instruction?;
+        if !instruction
+            .data
+            .starts_with(&SET_TREE_DELEGATE_DISCRIMINATOR)
+        {
+            return None;
+        }
+        let (Some(new_tree_delegate), Some(tree)) = (bundle.keys.get(2), bundle.keys.get(3)) else {
+            return None;
+        };
+
+        Some(TreeInfo {
+            tree: *tree,
+            config: None,
+            num_minted: 0,
+            delegate: Some(Updated::new(bundle.slot, None, *new_tree_delegate)),
+        })
+    }
+
+    /// Canopy depth is not a part of the CreateTree instruction, it's derived from the size
+    /// of the tree account if the account is created within the same transaction.
+    fn get_tree_canopy_depth(
+        tx: &plerkle::TransactionInfo,
+        tree: &Pubkey,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Option<u32> {
+        let space = tx.message_instructions.iter().find_map(|ix| {
+            if tx.account_keys.get(ix.program_id_index as usize) != Some(&system_program::id())
+                || ix
+                    .accounts
+                    .get(1)
+                    .and_then(|a| tx.account_keys.get(*a as usize))
+                    != Some(tree)
+            {
+                return None;
+            }
+            match bincode::deserialize::<SystemInstruction>(&ix.data) {
+                Ok(SystemInstruction::CreateAccount { space, .. }) => Some(space),
+                _ => None,
+            }
+        })?;
+        canopy_depth_from_account_size(max_depth, max_buffer_size, space)
+    }
+
     pub fn get_create_tree_with_root_update(
         parsing_result: &BubblegumInstruction,
         bundle: &InstructionBundle,
@@ -1122,6 +1310,7 @@ impl BubblegumTxProcessor {
                 mpl_bubblegum::programs::MPL_BUBBLEGUM_ID,
                 SignatureWithSlot { signature, slot },
             )),
+            asset_accounts: vec![],
         };
         for batched_mint in batch_mint.batch_mints.iter() {
             let seq = batched_mint.tree_update.seq;
@@ -1190,3 +1379,44 @@ fn get_delegate(delegate: Pubkey, owner: Pubkey, slot: u64, seq: u64) -> Updated
 
     Updated::new(slot, Some(UpdateVersion::Sequence(seq)), delegate)
 }
+
+// mirrors the layout of spl_account_compression: header, ConcurrentMerkleTree<max_depth, max_buffer_size>, canopy
+fn canopy_depth_from_account_size(
+    max_depth: u32,
+    max_buffer_size: u32,
+    account_size: u64,
+) -> Option<u32> {
+    const NODE_SIZE: u64 = 32;
+    let max_depth = max_depth as u64;
+    // root, path and index with padding
+    let change_log_size = NODE_SIZE + max_depth * NODE_SIZE + 8;
+    // proof, leaf and index with padding
+    let path_size = max_depth * NODE_SIZE + NODE_SIZE + 8;
+    // sequence number, active index and buffer size followed by the change logs and the rightmost proof
+    let tree_size = 24 + max_buffer_size as u64 * change_log_size + path_size;
+    let canopy_size = account_size
+        .checked_sub(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 as u64)?
+        .checked_sub(tree_size)?;
+    if canopy_size % NODE_SIZE != 0 {
+        return None;
+    }
+    // the canopy of depth N stores 2^(N+1) - 2 nodes
+    let nodes = canopy_size / NODE_SIZE + 2;
+    if !nodes.is_power_of_two() {
+        return None;
+    }
+    Some(nodes.trailing_zeros() - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canopy_depth_from_account_size() {
+        assert_eq!(canopy_depth_from_account_size(14, 64, 31_800), Some(0));
+        assert_eq!(canopy_depth_from_account_size(14, 64, 97_272), Some(10));
+        assert_eq!(canopy_depth_from_account_size(14, 64, 31_801), None);
+        assert_eq!(canopy_depth_from_account_size(14, 64, 1_000), None);
+    }
+}
@@ -0,0 +1,92 @@
+use interface::slot_getter::{ConfirmedSlotGetter, FinalizedSlotGetter};
+use rocks_db::parameters::Parameter;
+use rocks_db::Storage;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::Receiver;
+use tokio::task::JoinError;
+use tokio::time::sleep as tokio_sleep;
+use tracing::{error, info};
+
+const COMMITMENT_SLOTS_TRACKING_INTERVAL: Duration = Duration::from_secs(1);
+const PREVIOUS_VERSIONS_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+// the replaced versions are kept for this many slots behind the finalized one,
+// so the readers of the slot tracked a moment ago still find them
+const PREVIOUS_VERSIONS_RETENTION_SLOTS: u64 = 1_000;
+
+/// Keeps the latest confirmed and finalized slots in the storage,
+/// so the API can return the asset data at the commitment a client asks for,
+/// and drops the replaced asset versions once they are finalized
+pub async fn run_commitment_slots_tracker(
+    storage: Arc<Storage>,
+    slot_getter: Arc<impl FinalizedSlotGetter + ConfirmedSlotGetter>,
+    mut rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    info!("Start tracking commitment slots...");
+    let mut last_cleanup: Option<Instant> = None;
+    while rx.is_empty() {
+        let (confirmed_slot, finalized_slot) = tokio::join!(
+            slot_getter.get_confirmed_slot(),
+            slot_getter.get_finalized_slot()
+        );
+        if let Ok(confirmed_slot) = confirmed_slot.as_ref() {
+            // the slots processed before the versions were recorded for the first time
+            // are confirmed by now
+            if let Err(e) = storage
+                .init_previous_versions_start_slot(*confirmed_slot)
+                .await
+            {
+                error!("Init previous versions start slot: {}", e);
+            }
+        }
+        if let Ok(finalized_slot) = finalized_slot.as_ref() {
+            if last_cleanup.map_or(true, |at| {
+                at.elapsed() >= PREVIOUS_VERSIONS_CLEANUP_INTERVAL
+            }) {
+                last_cleanup = Some(Instant::now());
+                let cutoff_slot = finalized_slot.saturating_sub(PREVIOUS_VERSIONS_RETENTION_SLOTS);
+                let cloned_storage = storage.clone();
+                match tokio::task::spawn_blocking(move || {
+                    cloned_storage.delete_previous_versions(cutoff_slot)
+                })
+                .await
+                {
+                    Ok(Ok(deleted)) => {
+                        info!(
+                            "Deleted {} previous asset versions up to slot {}",
+                            deleted, cutoff_slot
+                        )
+                    }
+                    Ok(Err(e)) => error!("Delete previous asset versions: {}", e),
+                    Err(e) => error!("Delete previous asset versions task: {}", e),
+                }
+            }
+        }
+        for (parameter, slot) in [
+            (Parameter::LastConfirmedSlot, confirmed_slot),
+            (Parameter::LastFinalizedSlot, finalized_slot),
+        ] {
+            let slot = match slot {
+                Ok(slot) => slot,
+                Err(e) => {
+                    error!("Get {:?}: {}", parameter, e);
+                    continue;
+                }
+            };
+            // slots never go back, even if the RPC node lags behind the previous one
+            if let Err(e) = storage.merge_top_parameter(parameter.clone(), slot).await {
+                error!("Put {:?}: {}", parameter, e);
+            }
+        }
+
+        tokio::select! {
+            _ = tokio_sleep(COMMITMENT_SLOTS_TRACKING_INTERVAL) => {}
+            _ = rx.recv() => {
+                info!("Received stop signal, stopping tracking commitment slots!");
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use core::time;
+use std::collections::HashMap;
 use std::net::{SocketAddr, ToSocketAddrs};
 
 use figment::{providers::Env, Figment};
@@ -113,6 +114,8 @@ pub enum MessageSource {
     #[default]
     Redis,
     TCP,
+    Grpc,
+    File,
 }
 
 #[derive(Deserialize, PartialEq, Debug, Clone)]
@@ -134,6 +137,12 @@ pub struct IngesterConfig {
     pub tcp_config: TcpConfig,
     pub redis_messenger_config: MessengerConfig,
     pub message_source: MessageSource,
+    // required if the message source is Grpc
+    pub grpc_geyser_config: Option<GrpcGeyserConfig>,
+    // required if the message source is File
+    pub file_replay_config: Option<FileReplayConfig>,
+    // the messages received from Redis or TCP are recorded if set
+    pub message_recorder_config: Option<MessageRecorderConfig>,
     pub accounts_buffer_size: usize,
     #[serde(default = "default_snapshot_parsing_workers")]
     pub snapshot_parsing_workers: u32,
@@ -177,6 +186,9 @@ pub struct IngesterConfig {
     pub run_sequence_consistent_checker: bool,
     #[serde(default = "default_sequence_consistent_checker_wait_period_sec")]
     pub sequence_consistent_checker_wait_period_sec: u64,
+    // attempts to reingest the slots of a tree with sequence gaps before it is marked unrepairable
+    #[serde(default = "default_gap_repair_max_attempts")]
+    pub gap_repair_max_attempts: u32,
     pub rpc_host: String,
     #[serde(default)]
     pub check_proofs: bool,
@@ -198,12 +210,179 @@ pub struct IngesterConfig {
     pub migration_storage_path: String,
     #[serde(default = "default_price_monitoring_interval_sec")]
     pub price_monitoring_interval_sec: u64,
+    // records every asset update into the history column to serve point-in-time reads
+    #[serde(default)]
+    pub store_asset_history: bool,
+    // the history older than this many slots behind the confirmed slot is folded and dropped
+    #[serde(default = "default_asset_history_retention_slots")]
+    pub asset_history_retention_slots: u64,
+    // keeps the latest versions of every tree node to build proofs at a prior seq or slot
+    #[serde(default)]
+    pub store_cl_items_history: bool,
+    // gateways to download ipfs:// and ar:// metadata from, in the fallback order
+    #[serde(default = "default_ipfs_gateways")]
+    pub ipfs_gateways: Vec<String>,
+    #[serde(default = "default_arweave_gateways")]
+    pub arweave_gateways: Vec<String>,
+    // downloaded metadata is re-fetched with this interval to catch the updates of mutable JSONs
+    pub metadata_refresh_interval_sec: Option<u64>,
+    #[serde(default = "default_json_download_timeout_sec")]
+    pub json_download_timeout_sec: u64,
+    #[serde(default)]
+    pub json_download_limits: JsonDownloadLimitsConfig,
+    // the dead letters which weren't redriven are dropped once they are this old
+    #[serde(default = "default_dead_letters_retention_sec")]
+    pub dead_letters_retention_sec: u64,
+    // records the asset events and sends them to the webhooks registered via the API
+    #[serde(default)]
+    pub run_webhooks: bool,
+    // a notification is dropped once it fails this many times
+    #[serde(default = "default_webhook_max_attempts")]
+    pub webhook_max_attempts: u32,
+    // delay before the first retry, doubled with every next one
+    #[serde(default = "default_webhook_retry_base_sec")]
+    pub webhook_retry_base_sec: u64,
+    #[serde(default = "default_webhook_request_timeout_sec")]
+    pub webhook_request_timeout_sec: u64,
+    // lets the webhooks point to the loopback and private addresses, e.g. in a closed deployment
+    #[serde(default)]
+    pub webhook_allow_private_hosts: bool,
 }
 
 const fn default_parallel_json_downloaders() -> i32 {
     100
 }
 
+const fn default_json_download_timeout_sec() -> u64 {
+    5
+}
+
+/// Limits of the requests the JSON downloader makes to a single host
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct JsonDownloadLimitsConfig {
+    pub max_concurrent_requests_per_host: usize,
+    // zero disables the rate limit
+    pub requests_per_sec_per_host: f64,
+    // throttled, failed with 5xx or unreachable requests in a row
+    pub failures_to_open_circuit: u32,
+    // the backoff is doubled every time the host fails again after it
+    pub circuit_open_sec: u64,
+    pub max_circuit_open_sec: u64,
+    // overrides of the limits for specific hosts
+    pub hosts: HashMap<String, HostLimits>,
+}
+
+impl Default for JsonDownloadLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests_per_host: 20,
+            requests_per_sec_per_host: 50.0,
+            failures_to_open_circuit: 5,
+            circuit_open_sec: 10,
+            max_circuit_open_sec: 600,
+            hosts: HashMap::new(),
+        }
+    }
+}
+
+/// Yellowstone (Dragon's Mouth) gRPC Geyser endpoint the account and transaction updates are streamed from
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub struct GrpcGeyserConfig {
+    pub endpoint: String,
+    #[serde(default)]
+    pub x_token: Option<String>,
+    #[serde(default = "default_grpc_geyser_commitment")]
+    pub commitment: CommitmentLevel,
+    #[serde(default = "default_grpc_geyser_connect_timeout_sec")]
+    pub connect_timeout_sec: u64,
+    #[serde(default = "default_grpc_geyser_reconnect_interval_sec")]
+    pub reconnect_interval_sec: u64,
+    // number of received but not yet processed updates of each kind
+    #[serde(default = "default_grpc_geyser_buffer_size")]
+    pub buffer_size: usize,
+    // programs the subscribed transactions have to mention,
+    // all the programs the transaction processor handles if not set
+    #[serde(default)]
+    pub transaction_programs: Option<Vec<String>>,
+}
+
+const fn default_grpc_geyser_commitment() -> CommitmentLevel {
+    CommitmentLevel::Confirmed
+}
+
+const fn default_grpc_geyser_connect_timeout_sec() -> u64 {
+    10
+}
+
+const fn default_grpc_geyser_reconnect_interval_sec() -> u64 {
+    5
+}
+
+const fn default_grpc_geyser_buffer_size() -> usize {
+    10_000
+}
+
+/// Where and how the received messages are recorded for the later replay
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub struct MessageRecorderConfig {
+    // directory the record files are written into
+    pub path: String,
+    #[serde(default = "default_message_recorder_max_file_size_mb")]
+    pub max_file_size_mb: u64,
+    // the oldest files are removed once there are more, all the files are kept if not set
+    #[serde(default)]
+    pub max_files: Option<usize>,
+    // number of received but not yet written messages, the messages over it are not recorded
+    #[serde(default = "default_message_recorder_buffer_size")]
+    pub buffer_size: usize,
+}
+
+const fn default_message_recorder_max_file_size_mb() -> u64 {
+    256
+}
+
+const fn default_message_recorder_buffer_size() -> usize {
+    10_000
+}
+
+/// Record files the File message source replays
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub struct FileReplayConfig {
+    // record file or directory with the record files
+    pub path: String,
+    // 1.0 keeps the recorded pace, 2.0 replays twice as fast, 0 replays as fast as possible
+    #[serde(default = "default_file_replay_speed")]
+    pub speed: f64,
+    #[serde(default = "default_file_replay_buffer_size")]
+    pub buffer_size: usize,
+}
+
+const fn default_file_replay_speed() -> f64 {
+    1.0
+}
+
+const fn default_file_replay_buffer_size() -> usize {
+    10_000
+}
+
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub struct HostLimits {
+    pub max_concurrent_requests: usize,
+    pub requests_per_sec: f64,
+}
+
+fn default_ipfs_gateways() -> Vec<String> {
+    vec![
+        "https://ipfs.io/ipfs/".to_string(),
+        "https://cloudflare-ipfs.com/ipfs/".to_string(),
+    ]
+}
+
+fn default_arweave_gateways() -> Vec<String> {
+    vec!["https://arweave.net/".to_string()]
+}
+
 const fn default_synchronizer_parallel_tasks() -> usize {
     20
 }
@@ -223,6 +402,32 @@ const fn default_sequence_consistent_checker_wait_period_sec() -> u64 {
     60
 }
 
+// about a week of slots
+const fn default_asset_history_retention_slots() -> u64 {
+    1_500_000
+}
+
+// two weeks
+const fn default_dead_letters_retention_sec() -> u64 {
+    1_209_600
+}
+
+const fn default_gap_repair_max_attempts() -> u32 {
+    5
+}
+
+const fn default_webhook_max_attempts() -> u32 {
+    10
+}
+
+const fn default_webhook_retry_base_sec() -> u64 {
+    5
+}
+
+const fn default_webhook_request_timeout_sec() -> u64 {
+    10
+}
+
 #[derive(Deserialize, PartialEq, Debug, Clone)]
 pub struct JsonMigratorConfig {
     pub log_level: Option<String>,
@@ -287,6 +492,11 @@ pub struct ApiConfig {
     pub metrics_port: Option<u16>,
     pub server_port: u16,
     pub batch_mint_service_port: Option<u16>,
+    // port of the websocket subscriptions to the asset changes, disabled if not set
+    pub websocket_port: Option<u16>,
+    // connections the websocket subscriptions accept from an IP address, and from an API key
+    #[serde(default = "default_websocket_max_connections_per_client")]
+    pub websocket_max_connections_per_client: usize,
     pub file_storage_path_container: String,
     pub log_level: Option<String>,
     pub peer_grpc_port: u16,
@@ -310,6 +520,27 @@ pub struct ApiConfig {
     pub heap_path: String,
     pub consistence_backfilling_slots_threshold: Option<u64>,
     pub storage_service_base_url: Option<String>,
+    // JSON file with the API keys and their quotas, the requests aren't authenticated if not set
+    pub api_keys_file: Option<String>,
+    #[serde(default = "default_api_keys_reload_interval_sec")]
+    pub api_keys_reload_interval_sec: u64,
+    // number of the getAsset responses cached in memory, the cache is disabled if 0
+    #[serde(default)]
+    pub asset_cache_size: u64,
+    #[serde(default = "default_asset_cache_ttl_sec")]
+    pub asset_cache_ttl_sec: u64,
+}
+
+const fn default_api_keys_reload_interval_sec() -> u64 {
+    10
+}
+
+const fn default_asset_cache_ttl_sec() -> u64 {
+    60
+}
+
+const fn default_websocket_max_connections_per_client() -> usize {
+    100
 }
 
 fn default_heap_path() -> String {
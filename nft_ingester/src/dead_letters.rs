@@ -0,0 +1,296 @@
+use crate::error::IngesterError;
+use crate::grpc_geyser_receiver::recv_batch;
+use crate::message_parser::{MessageParser, UnprocessedAccountWithMetadata};
+use async_trait::async_trait;
+use chrono::Utc;
+use entities::models::{BufferedTransaction, BufferedTxWithID, UnprocessedAccountMessage};
+use interface::error::UsecaseError;
+use interface::signature_persistence::UnprocessedTransactionsGetter;
+use interface::unprocessed_data_getter::UnprocessedAccountsGetter;
+use metrics_utils::IngesterMetricsConfig;
+use postgre_client::PgClient;
+use rocks_db::dead_letters::{DeadLetter, DeadLetterStream};
+use rocks_db::Storage;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinError;
+use tokio::time::sleep as tokio_sleep;
+use tracing::{error, info};
+
+const DEAD_LETTERS_DEPTH_INTERVAL: Duration = Duration::from_secs(60);
+const REDRIVE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const REDRIVE_BATCH_SIZE: i64 = 100;
+const TXS_BATCH_SIZE: usize = 100;
+
+/// Stores the messages the ingester failed to parse or to process,
+/// so an asset isn't left stale silently and the message can be redriven once the cause is fixed
+pub struct DeadLetterQueue {
+    storage: Arc<Storage>,
+    metrics: Arc<IngesterMetricsConfig>,
+}
+
+impl DeadLetterQueue {
+    pub fn new(storage: Arc<Storage>, metrics: Arc<IngesterMetricsConfig>) -> Self {
+        Self { storage, metrics }
+    }
+
+    /// Stores the raw message which failed, returns false if it couldn't be stored
+    pub fn push_message(
+        &self,
+        stream: DeadLetterStream,
+        data: Vec<u8>,
+        map_flatbuffer: bool,
+        error: String,
+    ) -> bool {
+        self.push(DeadLetter::message(stream, data, map_flatbuffer, error))
+    }
+
+    /// Stores the account whose parsed update failed processing
+    pub fn push_account(&self, pubkey: Pubkey, write_version: u64, error: String) -> bool {
+        self.push(DeadLetter::account(pubkey, write_version, error))
+    }
+
+    fn push(&self, dead_letter: DeadLetter) -> bool {
+        let label = match dead_letter.stream {
+            DeadLetterStream::Accounts => "accounts",
+            DeadLetterStream::Transactions => "transactions",
+        };
+        match self.storage.put_dead_letter(dead_letter) {
+            Ok(_) => {
+                self.metrics.inc_dead_letters(label);
+                true
+            }
+            Err(e) => {
+                error!("Storing dead letter: {}", e);
+                false
+            }
+        }
+    }
+}
+
+/// Drops the dead letters older than the retention and reports the depth of the rest
+pub async fn run_dead_letters_depth_monitor(
+    storage: Arc<Storage>,
+    metrics: Arc<IngesterMetricsConfig>,
+    retention: Duration,
+    mut rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    while rx.is_empty() {
+        let stored_before = Utc::now().timestamp() - retention.as_secs() as i64;
+        if let Err(e) = storage.purge_dead_letters(stored_before).await {
+            error!("Purging dead letters: {}", e);
+        }
+        let cloned_storage = storage.clone();
+        match tokio::task::spawn_blocking(move || cloned_storage.dead_letters_count()).await {
+            Ok(depth) => {
+                metrics.set_dead_letters_depth(depth as i64);
+            }
+            Err(e) => error!("Counting dead letters: {}", e),
+        }
+        tokio::select! {
+            _ = tokio_sleep(DEAD_LETTERS_DEPTH_INTERVAL) => {}
+            _ = rx.recv() => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Feeds the dead letters requested to be redriven back into the account and transaction processors.
+/// A redriven dead letter is removed, if it fails again it is stored as a new one.
+pub struct DeadLetterReceiver {
+    accounts: Mutex<mpsc::Receiver<UnprocessedAccountMessage>>,
+    transactions: Mutex<mpsc::Receiver<BufferedTransaction>>,
+}
+
+/// Sending half of the [DeadLetterReceiver] which takes the redrive requests
+pub struct DeadLetterRedriver {
+    storage: Arc<Storage>,
+    pg_client: Arc<PgClient>,
+    rpc_client: Arc<RpcClient>,
+    dead_letters: Arc<DeadLetterQueue>,
+    message_parser: MessageParser,
+    accounts: mpsc::Sender<UnprocessedAccountMessage>,
+    transactions: mpsc::Sender<BufferedTransaction>,
+}
+
+impl DeadLetterReceiver {
+    pub fn new(
+        storage: Arc<Storage>,
+        pg_client: Arc<PgClient>,
+        rpc_client: Arc<RpcClient>,
+        dead_letters: Arc<DeadLetterQueue>,
+    ) -> (Self, DeadLetterRedriver) {
+        let (accounts_tx, accounts_rx) = mpsc::channel(REDRIVE_BATCH_SIZE as usize);
+        let (transactions_tx, transactions_rx) = mpsc::channel(REDRIVE_BATCH_SIZE as usize);
+
+        (
+            Self {
+                accounts: Mutex::new(accounts_rx),
+                transactions: Mutex::new(transactions_rx),
+            },
+            DeadLetterRedriver {
+                storage,
+                pg_client,
+                rpc_client,
+                dead_letters,
+                message_parser: MessageParser::new(),
+                accounts: accounts_tx,
+                transactions: transactions_tx,
+            },
+        )
+    }
+}
+
+pub async fn run_dead_letters_redrive(
+    redriver: DeadLetterRedriver,
+    rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    while rx.is_empty() {
+        let mut cl_rx = rx.resubscribe();
+        tokio::select! {
+            result = redriver.redrive() => {
+                match result {
+                    Ok(0) => tokio_sleep(REDRIVE_POLL_INTERVAL).await,
+                    Ok(redriven) => info!("Redriven {} dead letters", redriven),
+                    Err(e) => {
+                        error!("Redriving dead letters: {}", e);
+                        tokio_sleep(REDRIVE_POLL_INTERVAL).await;
+                    }
+                }
+            }
+            _ = cl_rx.recv() => {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl DeadLetterRedriver {
+    async fn redrive(&self) -> Result<usize, IngesterError> {
+        let ids = self
+            .pg_client
+            .take_dead_letters_redrive(REDRIVE_BATCH_SIZE)
+            .await?;
+        let dead_letters = self.storage.dead_letters.batch_get(ids.clone()).await?;
+
+        let mut redriven = 0;
+        for (id, dead_letter) in ids.into_iter().zip(dead_letters) {
+            // the dead letter was redriven already
+            let Some(dead_letter) = dead_letter else {
+                continue;
+            };
+            // the dead letter is kept until it is handed over, so it isn't lost if the ingester stops
+            match dead_letter.stream {
+                DeadLetterStream::Accounts => {
+                    for account in self.redrive_account(dead_letter).await {
+                        if self.accounts.send(account).await.is_err() {
+                            return Ok(redriven);
+                        }
+                    }
+                }
+                DeadLetterStream::Transactions => {
+                    let transaction = BufferedTransaction {
+                        transaction: dead_letter.data,
+                        map_flatbuffer: dead_letter.map_flatbuffer,
+                    };
+                    if self.transactions.send(transaction).await.is_err() {
+                        return Ok(redriven);
+                    }
+                }
+            }
+            self.storage.dead_letters.delete(id)?;
+            redriven += 1;
+        }
+
+        Ok(redriven)
+    }
+
+    // parses the raw message again or fetches the latest state of the account
+    async fn redrive_account(&self, dead_letter: DeadLetter) -> Vec<UnprocessedAccountMessage> {
+        let accounts = match dead_letter.pubkey {
+            Some(pubkey) => self.fetch_account(pubkey, dead_letter.write_version).await,
+            None => self
+                .message_parser
+                .parse_account(&dead_letter.data, dead_letter.map_flatbuffer),
+        };
+
+        match accounts {
+            Ok(accounts) => accounts
+                .into_iter()
+                .map(|account| UnprocessedAccountMessage {
+                    account: account.unprocessed_account,
+                    key: account.pubkey,
+                    id: String::new(),
+                })
+                .collect(),
+            Err(e) => {
+                self.dead_letters.push(DeadLetter {
+                    error: e.to_string(),
+                    failed_at: Utc::now().timestamp(),
+                    ..dead_letter
+                });
+                Vec::new()
+            }
+        }
+    }
+
+    async fn fetch_account(
+        &self,
+        pubkey: Pubkey,
+        write_version: u64,
+    ) -> Result<Vec<UnprocessedAccountWithMetadata>, IngesterError> {
+        let response = self
+            .rpc_client
+            .get_account_with_commitment(&pubkey, CommitmentConfig::confirmed())
+            .await?;
+        let Some(account) = response.value else {
+            return Err(IngesterError::AccountParsingError(format!(
+                "Account {} not found",
+                pubkey
+            )));
+        };
+
+        Ok(self.message_parser.parse_rpc_account(
+            pubkey,
+            response.context.slot,
+            account,
+            write_version,
+        ))
+    }
+}
+
+#[async_trait]
+impl UnprocessedAccountsGetter for DeadLetterReceiver {
+    async fn next_accounts(
+        &self,
+        batch_size: usize,
+    ) -> Result<Vec<UnprocessedAccountMessage>, UsecaseError> {
+        Ok(recv_batch(&self.accounts, batch_size).await)
+    }
+
+    fn ack(&self, _ids: Vec<String>) {}
+}
+
+#[async_trait]
+impl UnprocessedTransactionsGetter for DeadLetterReceiver {
+    async fn next_transactions(&self) -> Result<Vec<BufferedTxWithID>, UsecaseError> {
+        Ok(recv_batch(&self.transactions, TXS_BATCH_SIZE)
+            .await
+            .into_iter()
+            .map(|tx| BufferedTxWithID {
+                tx,
+                id: String::new(),
+            })
+            .collect())
+    }
+
+    fn ack(&self, _id: String) {}
+}
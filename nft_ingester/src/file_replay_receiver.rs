@@ -0,0 +1,176 @@
+use crate::config::FileReplayConfig;
+use crate::error::IngesterError;
+use crate::grpc_geyser_receiver::recv_batch;
+use crate::message_parser::MessageParser;
+use crate::message_recorder::{
+    list_record_files, open_record_file, RecordedMessage, RecordedMessageKind,
+};
+use async_trait::async_trait;
+use entities::models::{BufferedTxWithID, UnprocessedAccountMessage};
+use interface::error::UsecaseError;
+use interface::signature_persistence::UnprocessedTransactionsGetter;
+use interface::unprocessed_data_getter::UnprocessedAccountsGetter;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinError;
+use tracing::{error, info};
+
+const TXS_BATCH_SIZE: usize = 100;
+
+/// Replays the messages written by the [crate::message_recorder::MessageRecorder].
+/// The replay runs in [replay_recorded_messages] and puts the messages into bounded channels,
+/// so the replay is paced by the processors if they can't keep up with the configured speed.
+pub struct FileReplayReceiver {
+    message_parser: Arc<MessageParser>,
+    accounts: Mutex<mpsc::Receiver<RecordedMessage>>,
+    transactions: Mutex<mpsc::Receiver<RecordedMessage>>,
+}
+
+/// Sending half of the [FileReplayReceiver] which reads the record files
+pub struct FileReplayer {
+    config: FileReplayConfig,
+    accounts: mpsc::Sender<RecordedMessage>,
+    transactions: mpsc::Sender<RecordedMessage>,
+}
+
+impl FileReplayReceiver {
+    pub fn new(config: FileReplayConfig) -> (Self, FileReplayer) {
+        let (accounts_tx, accounts_rx) = mpsc::channel(config.buffer_size.max(1));
+        let (transactions_tx, transactions_rx) = mpsc::channel(config.buffer_size.max(1));
+
+        (
+            Self {
+                message_parser: Arc::new(MessageParser::new()),
+                accounts: Mutex::new(accounts_rx),
+                transactions: Mutex::new(transactions_rx),
+            },
+            FileReplayer {
+                config,
+                accounts: accounts_tx,
+                transactions: transactions_tx,
+            },
+        )
+    }
+}
+
+pub async fn replay_recorded_messages(
+    replayer: FileReplayer,
+    rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    let mut cl_rx = rx.resubscribe();
+    tokio::select! {
+        result = replayer.replay() => {
+            match result {
+                Ok(replayed) => info!("Replayed {} recorded messages from {}", replayed, replayer.config.path),
+                Err(e) => error!("Replaying recorded messages from {}: {}", replayer.config.path, e),
+            }
+        }
+        _ = cl_rx.recv() => {
+            info!("Replay of the recorded messages stopped");
+        }
+    }
+
+    Ok(())
+}
+
+impl FileReplayer {
+    async fn replay(&self) -> Result<u64, IngesterError> {
+        let files = list_record_files(Path::new(&self.config.path)).await?;
+        info!(
+            "Replaying {} record files from {} at speed {}",
+            files.len(),
+            self.config.path,
+            self.config.speed
+        );
+
+        let started_at = Instant::now();
+        let mut first_recorded_at = None;
+        let mut replayed = 0;
+        for file in files {
+            let mut reader = open_record_file(&file).await?;
+            while let Some(message) = RecordedMessage::read(&mut reader).await? {
+                // speed 0 replays as fast as the processors consume the messages
+                if self.config.speed > 0.0 {
+                    let first_recorded_at = *first_recorded_at.get_or_insert(message.recorded_at);
+                    let offset = Duration::from_millis(
+                        message.recorded_at.saturating_sub(first_recorded_at),
+                    )
+                    .div_f64(self.config.speed);
+                    tokio::time::sleep_until((started_at + offset).into()).await;
+                }
+
+                let sender = match message.kind {
+                    RecordedMessageKind::Account => &self.accounts,
+                    RecordedMessageKind::Transaction => &self.transactions,
+                };
+                // the receiver is dropped only when the ingester stops
+                if sender.send(message).await.is_err() {
+                    return Ok(replayed);
+                }
+                replayed += 1;
+            }
+        }
+
+        Ok(replayed)
+    }
+}
+
+#[async_trait]
+impl UnprocessedAccountsGetter for FileReplayReceiver {
+    async fn next_accounts(
+        &self,
+        batch_size: usize,
+    ) -> Result<Vec<UnprocessedAccountMessage>, UsecaseError> {
+        let mut result = Vec::new();
+        for message in recv_batch(&self.accounts, batch_size).await {
+            match self
+                .message_parser
+                .parse_account(&message.data, message.map_flatbuffer)
+            {
+                Ok(accounts) => {
+                    result.extend(
+                        accounts
+                            .into_iter()
+                            .map(|account| UnprocessedAccountMessage {
+                                account: account.unprocessed_account,
+                                key: account.pubkey,
+                                id: String::new(),
+                            }),
+                    );
+                }
+                Err(err) => {
+                    error!("Parsing recorded account: {}", err)
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn ack(&self, _ids: Vec<String>) {}
+}
+
+#[async_trait]
+impl UnprocessedTransactionsGetter for FileReplayReceiver {
+    async fn next_transactions(&self) -> Result<Vec<BufferedTxWithID>, UsecaseError> {
+        let mut result = Vec::new();
+        for message in recv_batch(&self.transactions, TXS_BATCH_SIZE).await {
+            if let Some(tx) = self
+                .message_parser
+                .parse_transaction(message.data, message.map_flatbuffer)
+            {
+                result.push(BufferedTxWithID {
+                    tx,
+                    id: String::new(),
+                })
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn ack(&self, _id: String) {}
+}
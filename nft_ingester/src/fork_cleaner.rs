@@ -1,9 +1,13 @@
-use entities::models::ForkedItem;
-use interface::fork_cleaner::{CompressedTreeChangesManager, ForkChecker};
+use crate::forked_accounts::ForkedAccountsRollbacker;
+use entities::models::{ClItem, ForkedItem, SlotAccountUpdate};
+use interface::fork_cleaner::{
+    AccountChangesManager, CompressedTreeChangesManager, ForkChecker, ForkedAccountsRollback,
+};
 use metrics_utils::ForkCleanerMetricsConfig;
 use rocks_db::Storage;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast::Receiver;
@@ -14,9 +18,14 @@ use tracing::info;
 
 const CI_ITEMS_DELETE_BATCH_SIZE: usize = 100;
 const SLOT_CHECK_OFFSET: u64 = 1500;
+const ACCOUNT_UPDATES_CHECK_BATCH_SIZE: usize = 1000;
+const ASSET_SIGNATURES_DELETE_BATCH_SIZE: usize = 1000;
+// account updates the fork check didn't reach this far behind the latest one are dropped unchecked,
+// so the column stays bounded while the raw blocks the check relies on lag behind
+const ACCOUNT_UPDATES_RETENTION_SLOTS: u64 = 100 * SLOT_CHECK_OFFSET;
 
 pub async fn run_fork_cleaner(
-    fork_cleaner: ForkCleaner<Storage, Storage>,
+    fork_cleaner: ForkCleaner<Storage, Storage, Storage, ForkedAccountsRollbacker>,
     metrics: Arc<ForkCleanerMetricsConfig>,
     mut rx: Receiver<()>,
     sequence_consistent_checker_wait_period_sec: u64,
@@ -39,29 +48,39 @@ pub async fn run_fork_cleaner(
     Ok(())
 }
 
-pub struct ForkCleaner<CM, FC>
+pub struct ForkCleaner<CM, FC, AM, AR>
 where
     CM: CompressedTreeChangesManager,
     FC: ForkChecker,
+    AM: AccountChangesManager,
+    AR: ForkedAccountsRollback,
 {
     cl_items_manager: Arc<CM>,
     fork_checker: Arc<FC>,
+    account_changes_manager: Arc<AM>,
+    accounts_rollback: Arc<AR>,
     metrics: Arc<ForkCleanerMetricsConfig>,
 }
 
-impl<CM, FC> ForkCleaner<CM, FC>
+impl<CM, FC, AM, AR> ForkCleaner<CM, FC, AM, AR>
 where
     CM: CompressedTreeChangesManager,
     FC: ForkChecker,
+    AM: AccountChangesManager,
+    AR: ForkedAccountsRollback,
 {
     pub fn new(
         cl_items_manager: Arc<CM>,
         fork_checker: Arc<FC>,
+        account_changes_manager: Arc<AM>,
+        accounts_rollback: Arc<AR>,
         metrics: Arc<ForkCleanerMetricsConfig>,
     ) -> Self {
         Self {
             cl_items_manager,
             fork_checker,
+            account_changes_manager,
+            accounts_rollback,
             metrics,
         }
     }
@@ -184,13 +203,133 @@ where
             self.delete_cl_items(&mut delete_items).await;
         }
 
+        // the previous versions of the nodes kept for the proofs at a prior root
+        // are dropped by slot as well
+        let mut forked_versions = Vec::new();
+        for cl_item in self.cl_items_manager.cl_items_history_iter() {
+            if !rx.is_empty() {
+                info!("Stop iteration over cl items history iterator...");
+                return;
+            }
+
+            if cl_item.slot_updated <= last_slot_for_check
+                && !all_non_forked_slots.contains(&cl_item.slot_updated)
+            {
+                forked_versions.push(cl_item);
+            }
+
+            if forked_versions.len() >= CI_ITEMS_DELETE_BATCH_SIZE {
+                self.delete_cl_items_history(&mut forked_versions).await;
+            }
+        }
+
+        if !forked_versions.is_empty() {
+            self.delete_cl_items_history(&mut forked_versions).await;
+        }
+
         if !signatures_to_drop.is_empty() {
             self.delete_leaf_signatures(signatures_to_drop).await;
         }
 
+        if let Some(last_update_slot) = self.account_changes_manager.last_account_update_slot() {
+            let retention_slot = last_update_slot.saturating_sub(ACCOUNT_UPDATES_RETENTION_SLOTS);
+            if retention_slot > 0 {
+                self.account_changes_manager
+                    .delete_account_updates_before(retention_slot)
+                    .await;
+            }
+        }
+
+        // account based updates are not merged by sequence, so instead of dropping them
+        // the accounts updated in forked slots are restored to their confirmed state
+        let mut checked_updates = Vec::new();
+        let mut forked_updates = Vec::new();
+        for account_update in self.account_changes_manager.account_updates_iter() {
+            if !rx.is_empty() {
+                info!("Stop iteration over account update slots iterator...");
+                return;
+            }
+
+            // updates are sorted by slot, so the rest of them are too fresh to be checked
+            if account_update.slot > last_slot_for_check {
+                break;
+            }
+
+            checked_updates.push((account_update.slot, account_update.pubkey));
+            if !all_non_forked_slots.contains(&account_update.slot) {
+                forked_updates.push(account_update);
+            }
+
+            if checked_updates.len() >= ACCOUNT_UPDATES_CHECK_BATCH_SIZE {
+                self.rollback_accounts(&mut checked_updates, &mut forked_updates)
+                    .await;
+            }
+        }
+
+        if !checked_updates.is_empty() {
+            self.rollback_accounts(&mut checked_updates, &mut forked_updates)
+                .await;
+        }
+
+        // signatures of the non-compressed assets are recorded for every transaction touching them,
+        // the ones of the transactions from the forked slots are dropped
+        let mut forked_signatures = Vec::new();
+        for (asset, slot, signature) in self.account_changes_manager.asset_account_signatures_iter()
+        {
+            if !rx.is_empty() {
+                info!("Stop iteration over asset account signatures iterator...");
+                return;
+            }
+
+            if slot <= last_slot_for_check && !all_non_forked_slots.contains(&slot) {
+                forked_signatures.push((asset, slot, signature));
+            }
+
+            if forked_signatures.len() >= ASSET_SIGNATURES_DELETE_BATCH_SIZE {
+                self.delete_asset_account_signatures(&mut forked_signatures)
+                    .await;
+            }
+        }
+
+        if !forked_signatures.is_empty() {
+            self.delete_asset_account_signatures(&mut forked_signatures)
+                .await;
+        }
+
         self.metrics.set_forks_detected(forked_slots as i64);
     }
 
+    // the updates are dropped only once the accounts are handed over for the rollback
+    async fn rollback_accounts(
+        &self,
+        checked_updates: &mut Vec<(u64, Pubkey)>,
+        forked_updates: &mut Vec<SlotAccountUpdate>,
+    ) {
+        if !forked_updates.is_empty() {
+            // the account is restored once, with the write version of its latest forked update
+            let mut forked_accounts = HashMap::<Pubkey, SlotAccountUpdate>::new();
+            for update in std::mem::take(forked_updates) {
+                match forked_accounts.get(&update.pubkey) {
+                    Some(current) if current.write_version >= update.write_version => {}
+                    _ => {
+                        forked_accounts.insert(update.pubkey, update);
+                    }
+                }
+            }
+            let rolled_back = self
+                .accounts_rollback
+                .rollback_accounts(forked_accounts.into_values().collect())
+                .await;
+            self.metrics.inc_by_rolled_back_accounts(rolled_back as u64);
+        }
+
+        self.metrics
+            .inc_by_deleted_items(checked_updates.len() as u64);
+        self.account_changes_manager
+            .delete_account_updates(std::mem::take(checked_updates))
+            .await;
+    }
+
     async fn delete_tree_seq_idx(&self, delete_items: &mut Vec<ForkedItem>) {
         self.metrics.inc_by_deleted_items(delete_items.len() as u64);
         self.cl_items_manager
@@ -205,6 +344,25 @@ where
             .await;
     }
 
+    async fn delete_asset_account_signatures(
+        &self,
+        forked_signatures: &mut Vec<(Pubkey, u64, Signature)>,
+    ) {
+        self.metrics
+            .inc_by_deleted_items(forked_signatures.len() as u64);
+        self.account_changes_manager
+            .delete_asset_account_signatures(std::mem::take(forked_signatures))
+            .await;
+    }
+
+    async fn delete_cl_items_history(&self, forked_versions: &mut Vec<ClItem>) {
+        self.metrics
+            .inc_by_deleted_items(forked_versions.len() as u64);
+        self.cl_items_manager
+            .delete_cl_items_history(std::mem::take(forked_versions))
+            .await;
+    }
+
     async fn delete_leaf_signatures(&self, keys: Vec<(Signature, Pubkey, u64)>) {
         self.metrics.inc_by_deleted_items(keys.len() as u64);
         self.cl_items_manager.delete_signatures(keys).await;
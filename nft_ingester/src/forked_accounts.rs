@@ -0,0 +1,167 @@
+use crate::grpc_geyser_receiver::recv_batch;
+use crate::message_parser::MessageParser;
+use async_trait::async_trait;
+use entities::models::{SlotAccountUpdate, UnprocessedAccountMessage};
+use interface::error::UsecaseError;
+use interface::fork_cleaner::ForkedAccountsRollback;
+use interface::unprocessed_data_getter::UnprocessedAccountsGetter;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{error, info, warn};
+
+// max number of accounts RPC returns in a single getMultipleAccounts request
+const RPC_ACCOUNTS_BATCH_SIZE: usize = 100;
+const ACCOUNTS_BUFFER_SIZE: usize = 1000;
+
+/// Feeds the confirmed state of the accounts updated in forked slots into the account processor
+pub struct ForkedAccountsReceiver {
+    accounts: Mutex<mpsc::Receiver<UnprocessedAccountMessage>>,
+}
+
+/// Sending half of the [ForkedAccountsReceiver] the fork cleaner hands the forked accounts to
+pub struct ForkedAccountsRollbacker {
+    rpc_client: Arc<RpcClient>,
+    message_parser: MessageParser,
+    accounts: mpsc::Sender<UnprocessedAccountMessage>,
+}
+
+impl ForkedAccountsReceiver {
+    pub fn new(rpc_client: Arc<RpcClient>) -> (Self, ForkedAccountsRollbacker) {
+        let (accounts_tx, accounts_rx) = mpsc::channel(ACCOUNTS_BUFFER_SIZE);
+
+        (
+            Self {
+                accounts: Mutex::new(accounts_rx),
+            },
+            ForkedAccountsRollbacker {
+                rpc_client,
+                message_parser: MessageParser::new(),
+                accounts: accounts_tx,
+            },
+        )
+    }
+}
+
+impl ForkedAccountsRollbacker {
+    // the fork checker knows only the slots of the saved blocks,
+    // so the slots missing there are checked against the confirmed blocks before the rollback
+    async fn confirmed_slots(&self, accounts: &[SlotAccountUpdate]) -> HashSet<u64> {
+        let (Some(first_slot), Some(last_slot)) = (
+            accounts.iter().map(|account| account.slot).min(),
+            accounts.iter().map(|account| account.slot).max(),
+        ) else {
+            return HashSet::new();
+        };
+
+        match self
+            .rpc_client
+            .get_blocks_with_commitment(first_slot, Some(last_slot), CommitmentConfig::confirmed())
+            .await
+        {
+            Ok(slots) => slots.into_iter().collect(),
+            Err(e) => {
+                // restoring an account which wasn't forked only costs an extra request
+                error!(
+                    "Get confirmed blocks from {} to {}: {}",
+                    first_slot, last_slot, e
+                );
+                HashSet::new()
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ForkedAccountsRollback for ForkedAccountsRollbacker {
+    async fn rollback_accounts(&self, accounts: Vec<SlotAccountUpdate>) -> usize {
+        let confirmed_slots = self.confirmed_slots(&accounts).await;
+        let forked_accounts = accounts
+            .into_iter()
+            .filter(|account| !confirmed_slots.contains(&account.slot))
+            .collect::<Vec<_>>();
+
+        let mut rolled_back = 0;
+        for chunk in forked_accounts.chunks(RPC_ACCOUNTS_BATCH_SIZE) {
+            let pubkeys = chunk
+                .iter()
+                .map(|account| account.pubkey)
+                .collect::<Vec<_>>();
+            let response = match self
+                .rpc_client
+                .get_multiple_accounts_with_commitment(&pubkeys, CommitmentConfig::confirmed())
+                .await
+            {
+                Ok(response) => response,
+                Err(e) => {
+                    error!("Get forked accounts: {}", e);
+                    continue;
+                }
+            };
+
+            for (forked, account) in chunk.iter().zip(response.value) {
+                // the restored state has to outrank the update from the forked slot
+                let write_version = forked.write_version.saturating_add(1);
+                let restored = match account {
+                    Some(account) => self
+                        .message_parser
+                        .parse_rpc_account(
+                            forked.pubkey,
+                            response.context.slot,
+                            account,
+                            write_version,
+                        )
+                        .into_iter()
+                        .map(|parsed| parsed.unprocessed_account)
+                        .collect::<Vec<_>>(),
+                    // the account created or kept alive only in the fork is closed
+                    None => match forked
+                        .tombstone
+                        .to_unprocessed_account(response.context.slot, write_version)
+                    {
+                        Some(tombstone) => vec![tombstone],
+                        None => {
+                            warn!(
+                                "Account {} updated in forked slot {} doesn't exist",
+                                forked.pubkey, forked.slot
+                            );
+                            continue;
+                        }
+                    },
+                };
+                for account in restored {
+                    let message = UnprocessedAccountMessage {
+                        account,
+                        key: forked.pubkey,
+                        id: String::new(),
+                    };
+                    // the receiver is dropped only when the ingester stops
+                    if self.accounts.send(message).await.is_err() {
+                        return rolled_back;
+                    }
+                }
+                info!(
+                    "Rolling back account {} updated in forked slot {}",
+                    forked.pubkey, forked.slot
+                );
+                rolled_back += 1;
+            }
+        }
+
+        rolled_back
+    }
+}
+
+#[async_trait]
+impl UnprocessedAccountsGetter for ForkedAccountsReceiver {
+    async fn next_accounts(
+        &self,
+        batch_size: usize,
+    ) -> Result<Vec<UnprocessedAccountMessage>, UsecaseError> {
+        Ok(recv_batch(&self.accounts, batch_size).await)
+    }
+
+    fn ack(&self, _ids: Vec<String>) {}
+}
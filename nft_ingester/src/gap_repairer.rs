@@ -0,0 +1,244 @@
+use async_trait::async_trait;
+use interface::signature_persistence::{BlockConsumer, BlockProducer};
+use interface::slot_getter::FinalizedSlotGetter;
+use interface::slots_dumper::SlotsDumper;
+use metrics_utils::{
+    BackfillerMetricsConfig, MetricStatus, SequenceConsistentGapfillMetricsConfig,
+};
+use rocks_db::tree_seq::{GapRepairState, TreeGapRepair};
+use rocks_db::Storage;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::Mutex;
+use tokio::task::JoinError;
+use tokio::time::sleep as tokio_sleep;
+use tracing::{error, info, warn};
+use usecase::slots_collector::{SlotsCollector, SlotsGetter};
+
+pub async fn run_gap_repairer<R, P, C, F>(
+    gap_repairer: GapRepairer<R, P, C, F>,
+    mut rx: Receiver<()>,
+    sequence_consistent_checker_wait_period_sec: u64,
+) -> Result<(), JoinError>
+where
+    R: SlotsGetter + Sync + Send + 'static,
+    P: BlockProducer,
+    C: BlockConsumer,
+    F: FinalizedSlotGetter,
+{
+    info!("Start repairing trees gaps...");
+    loop {
+        gap_repairer.repair_gaps(&rx).await;
+        tokio::select! {
+            _ = tokio_sleep(Duration::from_secs(sequence_consistent_checker_wait_period_sec)) => {},
+            _ = rx.recv() => {
+                info!("Received stop signal, stopping repairing trees gaps!");
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects slots found by the slots collector instead of dumping them into the storage
+#[derive(Default)]
+struct CollectedSlots {
+    slots: Mutex<BTreeSet<u64>>,
+}
+
+#[async_trait]
+impl SlotsDumper for CollectedSlots {
+    async fn dump_slots(&self, slots: &[u64]) {
+        self.slots.lock().await.extend(slots);
+    }
+}
+
+/// Takes the trees the sequence consistent checker found gaps in, reingests the slots
+/// of the gaps and checks the sequences again
+pub struct GapRepairer<R, P, C, F>
+where
+    R: SlotsGetter + Sync + Send + 'static,
+    P: BlockProducer,
+    C: BlockConsumer,
+    F: FinalizedSlotGetter,
+{
+    storage: Arc<Storage>,
+    slots_getter: Arc<R>,
+    block_producer: Arc<P>,
+    block_consumer: Arc<C>,
+    finalized_slot_getter: Arc<F>,
+    backfiller_metrics: Arc<BackfillerMetricsConfig>,
+    metrics: Arc<SequenceConsistentGapfillMetricsConfig>,
+    max_attempts: u32,
+}
+
+impl<R, P, C, F> GapRepairer<R, P, C, F>
+where
+    R: SlotsGetter + Sync + Send + 'static,
+    P: BlockProducer,
+    C: BlockConsumer,
+    F: FinalizedSlotGetter,
+{
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        storage: Arc<Storage>,
+        slots_getter: Arc<R>,
+        block_producer: Arc<P>,
+        block_consumer: Arc<C>,
+        finalized_slot_getter: Arc<F>,
+        backfiller_metrics: Arc<BackfillerMetricsConfig>,
+        metrics: Arc<SequenceConsistentGapfillMetricsConfig>,
+        max_attempts: u32,
+    ) -> Self {
+        Self {
+            storage,
+            slots_getter,
+            block_producer,
+            block_consumer,
+            finalized_slot_getter,
+            backfiller_metrics,
+            metrics,
+            max_attempts,
+        }
+    }
+
+    pub async fn repair_gaps(&self, rx: &Receiver<()>) {
+        let last_slot = self
+            .finalized_slot_getter
+            .get_finalized_slot_no_error()
+            .await;
+        let trees = self
+            .storage
+            .trees_gaps
+            .iter_start()
+            .filter_map(Result::ok)
+            .filter_map(|(key, _)| self.storage.trees_gaps.decode_key(key.to_vec()).ok())
+            .collect::<Vec<_>>();
+
+        for tree in trees {
+            if !rx.is_empty() {
+                info!("Stop repairing trees gaps...");
+                return;
+            }
+            let repair = match self.storage.tree_gap_repairs.get(tree) {
+                // the tree got new gaps after it was repaired
+                Ok(Some(repair)) if repair.state == GapRepairState::Repaired => {
+                    TreeGapRepair::default()
+                }
+                Ok(Some(repair)) => repair,
+                Ok(None) => TreeGapRepair::default(),
+                Err(e) => {
+                    error!("Get tree {} gap repair: {}", tree, e);
+                    continue;
+                }
+            };
+            if repair.state == GapRepairState::Unrepairable {
+                continue;
+            }
+
+            let repair = self.repair_tree(tree, repair, last_slot, rx).await;
+            if repair.state == GapRepairState::Repaired {
+                if let Err(e) = self.storage.trees_gaps.delete(tree) {
+                    error!("Delete tree {} gap: {}", tree, e);
+                }
+            }
+            if let Err(e) = self.storage.tree_gap_repairs.put(tree, repair) {
+                error!("Put tree {} gap repair: {}", tree, e);
+            }
+        }
+
+        self.report_repair_states();
+    }
+
+    async fn repair_tree(
+        &self,
+        tree: Pubkey,
+        mut repair: TreeGapRepair,
+        last_slot: u64,
+        rx: &Receiver<()>,
+    ) -> TreeGapRepair {
+        for (prev_state, current_state) in self.storage.tree_sequence_gaps(tree, last_slot) {
+            let collected_slots = Arc::new(CollectedSlots::default());
+            SlotsCollector::new(
+                collected_slots.clone(),
+                self.slots_getter.clone(),
+                self.backfiller_metrics.clone(),
+            )
+            .collect_slots(&tree, current_state.slot, prev_state.slot, rx)
+            .await;
+
+            let slots = collected_slots.slots.lock().await.clone();
+            for slot in slots.range(prev_state.slot..=current_state.slot) {
+                if !rx.is_empty() {
+                    return repair;
+                }
+                self.reingest_slot(*slot).await;
+            }
+        }
+
+        // the reingested transactions fill the tree sequences, so it is enough to check them again
+        let gaps = self.storage.tree_sequence_gaps(tree, last_slot).len() as u64;
+        repair.attempts += 1;
+        repair.gaps = gaps;
+        repair.updated_at = chrono::Utc::now().timestamp();
+        repair.state = if gaps == 0 {
+            info!("Gaps of {} tree are repaired", tree);
+            self.metrics.inc_gap_repair_attempts(MetricStatus::SUCCESS);
+            GapRepairState::Repaired
+        } else {
+            self.metrics.inc_gap_repair_attempts(MetricStatus::FAILURE);
+            if repair.attempts >= self.max_attempts {
+                warn!(
+                    "Gaps of {} tree are left after {} attempts: {}",
+                    tree, repair.attempts, gaps
+                );
+                GapRepairState::Unrepairable
+            } else {
+                GapRepairState::Pending
+            }
+        };
+
+        repair
+    }
+
+    async fn reingest_slot(&self, slot: u64) {
+        let block = match self.block_producer.get_block(slot, None::<Arc<P>>).await {
+            Ok(block) => block,
+            Err(e) => {
+                error!("Get block {} to repair gaps: {}", slot, e);
+                return;
+            }
+        };
+        if let Err(e) = self.block_consumer.consume_block(slot, block).await {
+            error!("Consume block {} to repair gaps: {}", slot, e);
+        }
+    }
+
+    fn report_repair_states(&self) {
+        let mut states_count = HashMap::new();
+        for (_, repair) in self.storage.tree_gap_repairs.get_from_start(usize::MAX) {
+            *states_count.entry(repair.state).or_insert(0) += 1;
+        }
+        for state in [
+            GapRepairState::Pending,
+            GapRepairState::Repaired,
+            GapRepairState::Unrepairable,
+        ] {
+            self.metrics.set_trees_by_repair_state(
+                state.as_str(),
+                states_count.get(&state).copied().unwrap_or_default(),
+            );
+        }
+        self.metrics.set_total_tree_with_gaps(
+            self.storage
+                .trees_gaps
+                .iter_start()
+                .filter(|item: &Result<_, _>| item.is_ok())
+                .count() as i64,
+        );
+    }
+}
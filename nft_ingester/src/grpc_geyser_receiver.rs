@@ -0,0 +1,311 @@
+use crate::bubblegum_updates_processor::BubblegumTxProcessor;
+use crate::config::GrpcGeyserConfig;
+use crate::error::IngesterError;
+use crate::message_parser::MessageParser;
+use crate::plerkle;
+use async_trait::async_trait;
+use entities::models::{BufferedTransaction, BufferedTxWithID, UnprocessedAccountMessage};
+use flatbuffers::FlatBufferBuilder;
+use futures::{SinkExt, StreamExt};
+use interface::error::UsecaseError;
+use interface::signature_persistence::UnprocessedTransactionsGetter;
+use interface::unprocessed_data_getter::UnprocessedAccountsGetter;
+use plerkle_serialization::serializer::seralize_encoded_transaction_with_status;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinError;
+use tokio::time::sleep as tokio_sleep;
+use tracing::{error, info, warn};
+use yellowstone_grpc_client::GeyserGrpcClient;
+use yellowstone_grpc_proto::convert_from::create_tx_with_meta;
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, CommitmentLevel as GrpcCommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterTransactions, SubscribeRequestPing,
+    SubscribeUpdateAccount, SubscribeUpdateTransaction,
+};
+
+const ACCOUNTS_FILTER: &str = "accounts";
+const TRANSACTIONS_FILTER: &str = "transactions";
+// next_* calls return an empty batch after this timeout, so the workers can check the shutdown signal
+const RECV_IDLE_TIMEOUT: Duration = Duration::from_millis(500);
+const TXS_BATCH_SIZE: usize = 100;
+
+/// Streams account and transaction updates from a Yellowstone (Dragon's Mouth) gRPC Geyser endpoint.
+/// The subscription runs in [connect_to_grpc_geyser] and puts the updates into bounded channels,
+/// the workers read them through [UnprocessedAccountsGetter] and [UnprocessedTransactionsGetter].
+/// gRPC streams have no acknowledgements, so `ack` is a no-op.
+pub struct GrpcGeyserReceiver {
+    message_parser: Arc<MessageParser>,
+    accounts: Mutex<mpsc::Receiver<SubscribeUpdateAccount>>,
+    transactions: Mutex<mpsc::Receiver<SubscribeUpdateTransaction>>,
+}
+
+/// Sending half of the [GrpcGeyserReceiver] which owns the connection to the endpoint
+pub struct GrpcGeyserSubscriber {
+    config: GrpcGeyserConfig,
+    request: SubscribeRequest,
+    accounts: mpsc::Sender<SubscribeUpdateAccount>,
+    transactions: mpsc::Sender<SubscribeUpdateTransaction>,
+}
+
+impl GrpcGeyserReceiver {
+    pub fn new(config: GrpcGeyserConfig) -> Result<(Self, GrpcGeyserSubscriber), IngesterError> {
+        let message_parser = Arc::new(MessageParser::new());
+        let transaction_programs = match &config.transaction_programs {
+            Some(programs) => programs
+                .iter()
+                .map(|program| {
+                    Pubkey::from_str(program).map_err(|e| IngesterError::ConfigurationError {
+                        msg: format!("Invalid transaction program {}: {}", program, e),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => BubblegumTxProcessor::supported_transaction_programs(),
+        };
+        let (accounts_tx, accounts_rx) = mpsc::channel(config.buffer_size.max(1));
+        let (transactions_tx, transactions_rx) = mpsc::channel(config.buffer_size.max(1));
+        let request = subscribe_request(
+            &message_parser.supported_account_owners(),
+            &transaction_programs,
+            config.commitment,
+        );
+
+        Ok((
+            Self {
+                message_parser,
+                accounts: Mutex::new(accounts_rx),
+                transactions: Mutex::new(transactions_rx),
+            },
+            GrpcGeyserSubscriber {
+                config,
+                request,
+                accounts: accounts_tx,
+                transactions: transactions_tx,
+            },
+        ))
+    }
+}
+
+/// Subscribes to the accounts owned by the programs the [MessageParser] can parse
+/// and to the successful transactions mentioning the transaction programs
+pub fn subscribe_request(
+    account_owners: &[Pubkey],
+    transaction_programs: &[Pubkey],
+    commitment: CommitmentLevel,
+) -> SubscribeRequest {
+    let commitment = match commitment {
+        CommitmentLevel::Processed => GrpcCommitmentLevel::Processed,
+        CommitmentLevel::Finalized => GrpcCommitmentLevel::Finalized,
+        _ => GrpcCommitmentLevel::Confirmed,
+    };
+
+    SubscribeRequest {
+        accounts: HashMap::from([(
+            ACCOUNTS_FILTER.to_string(),
+            SubscribeRequestFilterAccounts {
+                owner: account_owners.iter().map(|key| key.to_string()).collect(),
+                ..Default::default()
+            },
+        )]),
+        transactions: HashMap::from([(
+            TRANSACTIONS_FILTER.to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                account_include: transaction_programs
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect(),
+                ..Default::default()
+            },
+        )]),
+        commitment: Some(commitment as i32),
+        ..Default::default()
+    }
+}
+
+pub async fn connect_to_grpc_geyser(
+    subscriber: GrpcGeyserSubscriber,
+    cloned_rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    let reconnect_interval = Duration::from_secs(subscriber.config.reconnect_interval_sec);
+    while cloned_rx.is_empty() {
+        let mut cl_rx = cloned_rx.resubscribe();
+        tokio::select! {
+            result = subscriber.subscribe() => {
+                match result {
+                    Ok(()) => warn!("gRPC Geyser stream ended, reconnecting"),
+                    Err(e) => error!("gRPC Geyser subscription: {}", e),
+                }
+            }
+            _ = cl_rx.recv() => {
+                info!("gRPC Geyser subscription stopped");
+                break;
+            }
+        }
+        tokio_sleep(reconnect_interval).await;
+    }
+
+    Ok(())
+}
+
+impl GrpcGeyserSubscriber {
+    async fn subscribe(&self) -> Result<(), IngesterError> {
+        let mut client = GeyserGrpcClient::build_from_shared(self.config.endpoint.clone())
+            .and_then(|builder| builder.x_token(self.config.x_token.clone()))
+            .map_err(|e| IngesterError::GeyserError(e.to_string()))?
+            .connect_timeout(Duration::from_secs(self.config.connect_timeout_sec))
+            .connect()
+            .await
+            .map_err(|e| IngesterError::GeyserError(e.to_string()))?;
+        let (mut subscribe_tx, mut stream) = client
+            .subscribe_with_request(Some(self.request.clone()))
+            .await
+            .map_err(|e| IngesterError::GeyserError(e.to_string()))?;
+        info!("Subscribed to gRPC Geyser {}", self.config.endpoint);
+
+        while let Some(update) = stream.next().await {
+            let update = update.map_err(|e| IngesterError::GeyserError(e.to_string()))?;
+            match update.update_oneof {
+                Some(UpdateOneof::Account(account)) => self
+                    .accounts
+                    .send(account)
+                    .await
+                    .map_err(|e| IngesterError::GeyserError(e.to_string()))?,
+                Some(UpdateOneof::Transaction(transaction)) => self
+                    .transactions
+                    .send(transaction)
+                    .await
+                    .map_err(|e| IngesterError::GeyserError(e.to_string()))?,
+                // load balancers close idle streams, so the server pings are answered
+                Some(UpdateOneof::Ping(_)) => subscribe_tx
+                    .send(SubscribeRequest {
+                        ping: Some(SubscribeRequestPing { id: 1 }),
+                        ..Default::default()
+                    })
+                    .await
+                    .map_err(|e| IngesterError::GeyserError(e.to_string()))?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn map_account_update(update: SubscribeUpdateAccount) -> Result<plerkle::AccountInfo, String> {
+    let account = update.account.ok_or("missing account info")?;
+    Ok(plerkle::AccountInfo {
+        slot: update.slot,
+        pubkey: Pubkey::try_from(account.pubkey.as_slice()).map_err(|e| e.to_string())?,
+        owner: Pubkey::try_from(account.owner.as_slice()).map_err(|e| e.to_string())?,
+        lamports: account.lamports,
+        rent_epoch: account.rent_epoch,
+        executable: account.executable,
+        write_version: account.write_version,
+        data: account.data,
+    })
+}
+
+/// Serializes the transaction into the plerkle flatbuffer the transaction processors consume
+fn map_transaction_update(
+    update: SubscribeUpdateTransaction,
+) -> Result<BufferedTransaction, IngesterError> {
+    let transaction = update
+        .transaction
+        .ok_or(IngesterError::TransactionParsingError(
+            "missing transaction info".to_string(),
+        ))?;
+    let transaction = create_tx_with_meta(transaction)
+        .map_err(|e| IngesterError::TransactionParsingError(e.to_string()))?
+        .encode(UiTransactionEncoding::Base64, Some(0), false)?;
+    let builder = seralize_encoded_transaction_with_status(
+        FlatBufferBuilder::new(),
+        EncodedConfirmedTransactionWithStatusMeta {
+            slot: update.slot,
+            transaction,
+            block_time: None,
+        },
+    )?;
+
+    Ok(BufferedTransaction {
+        transaction: builder.finished_data().to_vec(),
+        map_flatbuffer: false,
+    })
+}
+
+// waits for the first update and then takes everything already received, up to the limit
+pub(crate) async fn recv_batch<T>(receiver: &Mutex<mpsc::Receiver<T>>, limit: usize) -> Vec<T> {
+    let mut receiver = receiver.lock().await;
+    let mut result = Vec::new();
+    if let Ok(Some(first)) = tokio::time::timeout(RECV_IDLE_TIMEOUT, receiver.recv()).await {
+        result.push(first);
+    }
+    while result.len() < limit.max(1) {
+        match receiver.try_recv() {
+            Ok(update) => result.push(update),
+            Err(_) => break,
+        }
+    }
+    result
+}
+
+#[async_trait]
+impl UnprocessedAccountsGetter for GrpcGeyserReceiver {
+    async fn next_accounts(
+        &self,
+        batch_size: usize,
+    ) -> Result<Vec<UnprocessedAccountMessage>, UsecaseError> {
+        let mut result = Vec::new();
+        for update in recv_batch(&self.accounts, batch_size).await {
+            let account_info = match map_account_update(update) {
+                Ok(account_info) => account_info,
+                Err(err) => {
+                    error!("Mapping gRPC account update: {}", err);
+                    continue;
+                }
+            };
+            result.extend(
+                self.message_parser
+                    .parse_account_info(&account_info)
+                    .into_iter()
+                    .map(|account| UnprocessedAccountMessage {
+                        account: account.unprocessed_account,
+                        key: account.pubkey,
+                        id: String::new(),
+                    }),
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn ack(&self, _ids: Vec<String>) {}
+}
+
+#[async_trait]
+impl UnprocessedTransactionsGetter for GrpcGeyserReceiver {
+    async fn next_transactions(&self) -> Result<Vec<BufferedTxWithID>, UsecaseError> {
+        let mut result = Vec::new();
+        for update in recv_batch(&self.transactions, TXS_BATCH_SIZE).await {
+            match map_transaction_update(update) {
+                Ok(tx) => result.push(BufferedTxWithID {
+                    tx,
+                    id: String::new(),
+                }),
+                Err(err) => error!("Mapping gRPC transaction update: {}", err),
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn ack(&self, _id: String) {}
+}
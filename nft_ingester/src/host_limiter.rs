@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use metrics_utils::{JsonDownloaderMetricsConfig, MetricStatus};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{Duration, Instant};
+
+use crate::config::{HostLimits, JsonDownloadLimitsConfig};
+
+/// Coordinates the requests made to the same host by all the JSON downloader workers.
+/// Every host gets its own concurrency cap, token bucket and circuit breaker,
+/// so a slow or rate limiting host doesn't ban the ingester and doesn't block downloads from the others.
+pub struct HostLimiter {
+    config: JsonDownloadLimitsConfig,
+    hosts: Mutex<HashMap<String, Arc<HostState>>>,
+    metrics: Arc<JsonDownloaderMetricsConfig>,
+}
+
+struct HostState {
+    host: String,
+    semaphore: Arc<Semaphore>,
+    bucket: Mutex<TokenBucket>,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+/// Request slot of a host, the outcome of the request must be reported back through it
+pub struct HostPermit {
+    state: Arc<HostState>,
+    started_at: Instant,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl HostLimiter {
+    pub fn new(
+        config: JsonDownloadLimitsConfig,
+        metrics: Arc<JsonDownloaderMetricsConfig>,
+    ) -> Self {
+        Self {
+            config,
+            hosts: Mutex::new(HashMap::new()),
+            metrics,
+        }
+    }
+
+    fn host_state(&self, host: &str) -> Arc<HostState> {
+        let mut hosts = self.hosts.lock().unwrap();
+        hosts
+            .entry(host.to_string())
+            .or_insert_with(|| {
+                let limits = self.config.hosts.get(host).cloned().unwrap_or(HostLimits {
+                    max_concurrent_requests: self.config.max_concurrent_requests_per_host,
+                    requests_per_sec: self.config.requests_per_sec_per_host,
+                });
+                Arc::new(HostState {
+                    host: host.to_string(),
+                    semaphore: Arc::new(Semaphore::new(limits.max_concurrent_requests.max(1))),
+                    bucket: Mutex::new(TokenBucket::new(limits.requests_per_sec, Instant::now())),
+                    breaker: Mutex::new(CircuitBreaker::default()),
+                })
+            })
+            .clone()
+    }
+
+    /// Waits for a request slot of the host. Returns the time left until the host
+    /// is requested again if it's backed off after the recent failures.
+    pub async fn acquire(&self, host: &str) -> Result<HostPermit, Duration> {
+        let state = self.host_state(host);
+        if let Err(backoff_left) = state.breaker.lock().unwrap().check(Instant::now()) {
+            self.metrics.inc_domain_rejected_requests(host);
+            return Err(backoff_left);
+        }
+
+        let permit = state
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("host semaphore is never closed");
+        loop {
+            let wait = state.bucket.lock().unwrap().try_take(Instant::now());
+            match wait {
+                Ok(()) => break,
+                Err(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+
+        Ok(HostPermit {
+            state,
+            started_at: Instant::now(),
+            _permit: permit,
+        })
+    }
+
+    /// Releases the request slot. Failures of the host (throttling, server errors and
+    /// unreachable host) trip the circuit breaker after the configured number in a row.
+    pub fn report(&self, permit: HostPermit, is_host_failure: bool) {
+        let state = &permit.state;
+        self.metrics
+            .set_domain_latency(&state.host, permit.started_at.elapsed().as_millis() as f64);
+        self.metrics.inc_domain_requests(
+            &state.host,
+            if is_host_failure {
+                MetricStatus::FAILURE
+            } else {
+                MetricStatus::SUCCESS
+            },
+        );
+
+        let mut breaker = state.breaker.lock().unwrap();
+        if is_host_failure {
+            breaker.on_failure(Instant::now(), &self.config);
+        } else {
+            breaker.on_success();
+        }
+        self.metrics
+            .set_domain_circuit_open(&state.host, breaker.open_until.is_some());
+    }
+}
+
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(requests_per_sec: f64, now: Instant) -> Self {
+        // a bucket holds up to one second of requests, so short bursts are allowed
+        let capacity = requests_per_sec.max(1.0);
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: requests_per_sec,
+            refilled_at: now,
+        }
+    }
+
+    /// Takes a token or returns how long to wait for the next one
+    fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        // zero or negative rate means the host is not rate limited
+        if self.refill_per_sec <= 0.0 {
+            return Ok(());
+        }
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+}
+
+#[derive(Default)]
+struct CircuitBreaker {
+    consecutive_failures: u32,
+    // number of times the circuit was opened since the last successful request
+    trips: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn check(&mut self, now: Instant) -> Result<(), Duration> {
+        match self.open_until {
+            Some(open_until) if open_until > now => Err(open_until - now),
+            Some(_) => {
+                // half open: the requests are let through, but the first failure opens the circuit again
+                self.open_until = None;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn on_success(&mut self) {
+        *self = Self::default();
+    }
+
+    fn on_failure(&mut self, now: Instant, config: &JsonDownloadLimitsConfig) {
+        // requests started before the circuit was opened don't prolong the backoff
+        if self.open_until.map_or(false, |open_until| open_until > now) {
+            return;
+        }
+        self.consecutive_failures += 1;
+        if self.consecutive_failures < config.failures_to_open_circuit && self.trips == 0 {
+            return;
+        }
+        self.trips += 1;
+        self.consecutive_failures = 0;
+        let backoff = config
+            .circuit_open_sec
+            .saturating_mul(1 << (self.trips - 1).min(16))
+            .min(config.max_circuit_open_sec);
+        self.open_until = Some(now + Duration::from_secs(backoff));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, now);
+        assert!(bucket.try_take(now).is_ok());
+        assert!(bucket.try_take(now).is_ok());
+        assert_eq!(bucket.try_take(now), Err(Duration::from_millis(500)));
+        assert!(bucket.try_take(now + Duration::from_millis(500)).is_ok());
+
+        let mut unlimited = TokenBucket::new(0.0, now);
+        for _ in 0..10 {
+            assert!(unlimited.try_take(now).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_circuit_breaker_backoff() {
+        let config = JsonDownloadLimitsConfig {
+            failures_to_open_circuit: 2,
+            circuit_open_sec: 10,
+            max_circuit_open_sec: 15,
+            ..Default::default()
+        };
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::default();
+
+        breaker.on_failure(now, &config);
+        assert!(breaker.check(now).is_ok());
+        breaker.on_failure(now, &config);
+        assert_eq!(breaker.check(now), Err(Duration::from_secs(10)));
+
+        // the first failure after the backoff opens the circuit again for longer
+        let now = now + Duration::from_secs(10);
+        assert!(breaker.check(now).is_ok());
+        breaker.on_failure(now, &config);
+        assert_eq!(breaker.check(now), Err(Duration::from_secs(15)));
+
+        let now = now + Duration::from_secs(15);
+        assert!(breaker.check(now).is_ok());
+        breaker.on_success();
+        breaker.on_failure(now, &config);
+        assert!(breaker.check(now).is_ok());
+    }
+}
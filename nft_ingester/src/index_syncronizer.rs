@@ -408,6 +408,9 @@ mod tests {
             update_authority: None,
             slot_updated: 123456,
             fungible_tokens: vec![],
+            name: None,
+            core_plugins: vec![],
+            core_attributes: vec![],
         }
     }
 
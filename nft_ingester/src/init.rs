@@ -83,12 +83,20 @@ pub async fn init_primary_storage(
     )
     .await?;
 
-    Ok(Storage::open(
+    let storage = Storage::open(
         db_path,
         mutexed_tasks.clone(),
         metrics_state.red_metrics.clone(),
         MigrationState::Last,
-    )?)
+    )?;
+    storage.set_asset_history_enabled(config.store_asset_history);
+    storage.set_cl_items_history_enabled(config.store_cl_items_history);
+    storage.init_cl_items_history_start_slot().await?;
+    // the fork cleaner runs together with the sequence consistent checker
+    storage.set_account_update_slots_enabled(config.run_sequence_consistent_checker);
+    storage.set_asset_events_enabled(config.run_webhooks);
+
+    Ok(storage)
 }
 
 pub async fn graceful_stop(
@@ -1,14 +1,20 @@
 use crate::api::dapi::rpc_asset_convertors::parse_files;
 use crate::config::{setup_config, IngesterConfig, INGESTER_CONFIG_PREFIX};
+use crate::host_limiter::HostLimiter;
 use async_trait::async_trait;
+use base64::engine::general_purpose;
+use base64::Engine;
 use entities::enums::TaskStatus;
-use entities::models::{JsonDownloadTask, OffChainData};
+use entities::models::{DownloadedJson, JsonDownloadTask, OffChainData};
 use interface::error::JsonDownloaderError;
 use interface::json::{JsonDownloader, JsonPersister};
 use metrics_utils::{JsonDownloaderMetricsConfig, MetricStatus};
-use postgre_client::tasks::UpdatedTask;
+use percent_encoding::percent_decode_str;
+use postgre_client::offchain_traits::OffchainTraits;
+use postgre_client::tasks::{TaskContent, UpdatedTask};
 use postgre_client::PgClient;
-use reqwest::{Client, ClientBuilder};
+use reqwest::header::{self, HeaderName};
+use reqwest::{Client, ClientBuilder, Response};
 use rocks_db::asset_previews::UrlToDownload;
 use rocks_db::Storage;
 use std::collections::HashMap;
@@ -17,20 +23,34 @@ use tokio::sync::broadcast::Receiver;
 use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::{mpsc, Mutex};
 use tokio::task::JoinSet;
-use tokio::time::{self, Duration, Instant};
+use tokio::time::{Duration, Instant};
 use tracing::{debug, error};
+use url::Url;
 
 pub const JSON_CONTENT_TYPE: &str = "application/json";
 pub const JSON_BATCH: usize = 300;
 pub const WIPE_PERIOD_SEC: u64 = 60;
 pub const SLEEP_TIME: u64 = 1;
-pub const CLIENT_TIMEOUT: u64 = 5;
+pub const MAX_INDEXED_ATTRIBUTES: usize = 100;
+pub const MAX_INDEXED_TRAIT_LEN: usize = 256;
+pub const DIRECT_GATEWAY: &str = "direct";
+pub const INLINE_GATEWAY: &str = "inline";
+const IPFS_SCHEME: &str = "ipfs://";
+const ARWEAVE_SCHEME: &str = "ar://";
+const DATA_SCHEME: &str = "data:";
+const DEFAULT_DATA_MEDIA_TYPE: &str = "text/plain";
 
 pub struct JsonWorker {
     pub db_client: Arc<PgClient>,
     pub rocks_db: Arc<Storage>,
     pub num_of_parallel_workers: i32,
     pub metrics: Arc<JsonDownloaderMetricsConfig>,
+    pub uri_resolver: Arc<dyn UriResolver>,
+    // None disables the periodic re-download of the files
+    pub metadata_refresh_interval: Option<Duration>,
+    // shared by all the workers to reuse the connections
+    pub http_client: Client,
+    pub host_limiter: Arc<HostLimiter>,
 }
 
 impl JsonWorker {
@@ -40,12 +60,29 @@ impl JsonWorker {
         metrics: Arc<JsonDownloaderMetricsConfig>,
     ) -> Self {
         let config: IngesterConfig = setup_config(INGESTER_CONFIG_PREFIX);
+        let http_client = ClientBuilder::new()
+            .timeout(Duration::from_secs(config.json_download_timeout_sec))
+            .build()
+            .expect("Failed to create JSON downloader HTTP client");
+        let host_limiter = Arc::new(HostLimiter::new(
+            config.json_download_limits,
+            metrics.clone(),
+        ));
 
         Self {
             db_client,
             num_of_parallel_workers: config.parallel_json_downloaders,
             metrics,
             rocks_db,
+            uri_resolver: Arc::new(GatewayUriResolver::new(
+                config.ipfs_gateways,
+                config.arweave_gateways,
+            )),
+            metadata_refresh_interval: config
+                .metadata_refresh_interval_sec
+                .map(Duration::from_secs),
+            http_client,
+            host_limiter,
         }
     }
 }
@@ -107,13 +144,17 @@ impl TasksStreamer {
 
 pub struct TasksPersister<T: JsonPersister + Send + Sync + 'static> {
     pub persister: Arc<T>,
-    pub receiver: tokio::sync::mpsc::Receiver<(String, Result<String, JsonDownloaderError>)>,
+    pub receiver:
+        tokio::sync::mpsc::Receiver<(String, Result<DownloadedJson, JsonDownloaderError>)>,
 }
 
 impl<T: JsonPersister + Send + Sync + 'static> TasksPersister<T> {
     pub fn new(
         persister: Arc<T>,
-        receiver: tokio::sync::mpsc::Receiver<(String, Result<String, JsonDownloaderError>)>,
+        receiver: tokio::sync::mpsc::Receiver<(
+            String,
+            Result<DownloadedJson, JsonDownloaderError>,
+        )>,
     ) -> Self {
         Self {
             persister,
@@ -219,9 +260,7 @@ pub async fn run(json_downloader: Arc<JsonWorker>, rx: Receiver<()>) {
 
                         let begin_processing = Instant::now();
 
-                        let response = json_downloader
-                            .download_file(task.metadata_url.clone())
-                            .await;
+                        let response = json_downloader.download_task(&task).await;
 
                         json_downloader.metrics.set_latency_task_executed(
                             "json_downloader",
@@ -255,86 +294,391 @@ pub async fn run(json_downloader: Arc<JsonWorker>, rx: Receiver<()>) {
     while (workers_pool.join_next().await).is_some() {}
 }
 
+impl JsonWorker {
+    /// Downloads the file of the task. If it was downloaded before, the validators
+    /// of that download are sent along so the unchanged file is not transferred again.
+    pub async fn download_task(
+        &self,
+        task: &JsonDownloadTask,
+    ) -> Result<DownloadedJson, JsonDownloaderError> {
+        self.download(
+            &task.metadata_url,
+            task.etag.as_deref(),
+            task.last_modified.as_deref(),
+        )
+        .await
+    }
+
+    async fn download(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> Result<DownloadedJson, JsonDownloaderError> {
+        let gateway_urls = match self.uri_resolver.resolve(url)? {
+            ResolvedUri::Inline {
+                media_type,
+                content,
+            } => {
+                self.metrics
+                    .inc_gateway_requests(INLINE_GATEWAY, MetricStatus::SUCCESS);
+                if !media_type.contains(JSON_CONTENT_TYPE) {
+                    return Err(JsonDownloaderError::GotNotJsonFile);
+                }
+                return String::from_utf8(content)
+                    .map(|metadata| DownloadedJson {
+                        metadata: metadata.trim().replace('\0', ""),
+                        gateway: Some(INLINE_GATEWAY.to_string()),
+                        ..Default::default()
+                    })
+                    .map_err(|_| JsonDownloaderError::CouldNotDeserialize);
+            }
+            ResolvedUri::Remote(gateway_urls) => gateway_urls,
+        };
+
+        let mut last_error =
+            JsonDownloaderError::ErrorDownloading(format!("No gateways to download {} from", url));
+        // the shortest backoff of the skipped hosts, used if none of the gateways was requested
+        let mut backed_off: Option<Duration> = None;
+        let mut requested = false;
+        for GatewayUrl { gateway, url } in gateway_urls {
+            let host = Url::parse(&url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_ascii_lowercase));
+            let permit = match &host {
+                Some(host) => match self.host_limiter.acquire(host).await {
+                    Ok(permit) => Some(permit),
+                    Err(backoff_left) => {
+                        debug!("Skipping {} as {} is backed off", url, host);
+                        backed_off = Some(
+                            backed_off.map_or(backoff_left, |backoff| backoff.min(backoff_left)),
+                        );
+                        continue;
+                    }
+                },
+                None => None,
+            };
+
+            requested = true;
+            let result = fetch_metadata(&self.http_client, &url, etag, last_modified).await;
+            if let Some(permit) = permit {
+                self.host_limiter.report(permit, is_host_failure(&result));
+            }
+
+            match result {
+                // the gateway is unavailable or doesn't have the file, the next one may have it
+                Err(
+                    err @ (JsonDownloaderError::ErrorDownloading(_)
+                    | JsonDownloaderError::ErrorStatusCode(_)),
+                ) => {
+                    debug!("Could not download {} through {}: {:?}", url, gateway, err);
+                    self.metrics
+                        .inc_gateway_requests(&gateway, MetricStatus::FAILURE);
+                    last_error = err;
+                }
+                result => {
+                    debug!("Downloaded {} through {}", url, gateway);
+                    self.metrics
+                        .inc_gateway_requests(&gateway, MetricStatus::SUCCESS);
+                    return result.map(|json| DownloadedJson {
+                        gateway: Some(gateway),
+                        ..json
+                    });
+                }
+            }
+        }
+
+        match backed_off {
+            // the task is not attempted, it's postponed until the hosts are requested again
+            Some(backoff) if !requested => Err(JsonDownloaderError::HostBackedOff(backoff)),
+            _ => Err(last_error),
+        }
+    }
+}
+
 #[async_trait]
 impl JsonDownloader for JsonWorker {
     async fn download_file(&self, url: String) -> Result<String, JsonDownloaderError> {
-        let client = ClientBuilder::new()
-            .timeout(time::Duration::from_secs(CLIENT_TIMEOUT))
-            .build()
-            .map_err(|e| {
-                JsonDownloaderError::ErrorDownloading(format!("Failed to create client: {:?}", e))
-            })?;
-        let response = Client::get(&client, url)
-            .send()
+        self.download(&url, None, None)
             .await
-            .map_err(|e| format!("Failed to make request: {:?}", e));
-
-        match response {
-            Ok(response) => {
-                if let Some(content_header) = response.headers().get("Content-Type") {
-                    match content_header.to_str() {
-                        Ok(header) => {
-                            if !header.contains(JSON_CONTENT_TYPE) {
-                                return Err(JsonDownloaderError::GotNotJsonFile);
-                            }
-                        }
-                        Err(_) => {
-                            return Err(JsonDownloaderError::CouldNotReadHeader);
+            .map(|json| json.metadata)
+    }
+}
+
+async fn fetch_metadata(
+    client: &Client,
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> Result<DownloadedJson, JsonDownloaderError> {
+    let mut request = Client::get(client, url);
+    if let Some(etag) = etag {
+        request = request.header(header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = last_modified {
+        request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to make request: {:?}", e));
+
+    match response {
+        Ok(response) => {
+            if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+                return Err(JsonDownloaderError::NotModified);
+            }
+            // error pages are usually HTML, so the status goes first,
+            // otherwise a failed gateway would look like a media file
+            if response.status() != reqwest::StatusCode::OK {
+                return Err(JsonDownloaderError::ErrorStatusCode(
+                    response.status().as_str().to_string(),
+                ));
+            }
+            if let Some(content_header) = response.headers().get("Content-Type") {
+                match content_header.to_str() {
+                    Ok(header) => {
+                        if !header.contains(JSON_CONTENT_TYPE) {
+                            return Err(JsonDownloaderError::GotNotJsonFile);
                         }
                     }
-                }
-
-                if response.status() != reqwest::StatusCode::OK {
-                    return Err(JsonDownloaderError::ErrorStatusCode(
-                        response.status().as_str().to_string(),
-                    ));
-                } else {
-                    let metadata_body = response.text().await;
-                    if let Ok(metadata) = metadata_body {
-                        return Ok(metadata.trim().replace('\0', ""));
-                    } else {
-                        Err(JsonDownloaderError::CouldNotDeserialize)
+                    Err(_) => {
+                        return Err(JsonDownloaderError::CouldNotReadHeader);
                     }
                 }
             }
-            Err(e) => Err(JsonDownloaderError::ErrorDownloading(e.to_string())),
+
+            let etag = header_value(&response, header::ETAG);
+            let last_modified = header_value(&response, header::LAST_MODIFIED);
+            let metadata_body = response.text().await;
+            if let Ok(metadata) = metadata_body {
+                Ok(DownloadedJson {
+                    metadata: metadata.trim().replace('\0', ""),
+                    etag,
+                    last_modified,
+                })
+            } else {
+                Err(JsonDownloaderError::CouldNotDeserialize)
+            }
+        }
+        Err(e) => Err(JsonDownloaderError::ErrorDownloading(e.to_string())),
+    }
+}
+
+/// Whether the host itself is unhealthy or throttles the requests, rather than the file is bad
+fn is_host_failure(result: &Result<DownloadedJson, JsonDownloaderError>) -> bool {
+    match result {
+        Err(JsonDownloaderError::ErrorDownloading(_)) => true,
+        Err(JsonDownloaderError::ErrorStatusCode(status)) => status
+            .parse::<u16>()
+            .map_or(false, |status| status == 429 || status >= 500),
+        _ => false,
+    }
+}
+
+fn header_value(response: &Response, name: HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// HTTP URL of the file along with the gateway it's served by
+#[derive(Debug, Clone, PartialEq)]
+pub struct GatewayUrl {
+    pub gateway: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolvedUri {
+    /// URLs to try one by one until some of them responds
+    Remote(Vec<GatewayUrl>),
+    /// The file embedded into the URI itself
+    Inline {
+        media_type: String,
+        content: Vec<u8>,
+    },
+}
+
+/// Turns a metadata URI into the locations the file can be downloaded from
+pub trait UriResolver: Send + Sync {
+    fn resolve(&self, uri: &str) -> Result<ResolvedUri, JsonDownloaderError>;
+}
+
+/// Rewrites `ipfs://` and `ar://` URIs to the configured HTTP gateways
+/// and decodes `data:` URIs. Any other URI is requested as is.
+pub struct GatewayUriResolver {
+    ipfs_gateways: Vec<String>,
+    arweave_gateways: Vec<String>,
+}
+
+impl GatewayUriResolver {
+    pub fn new(ipfs_gateways: Vec<String>, arweave_gateways: Vec<String>) -> Self {
+        Self {
+            ipfs_gateways,
+            arweave_gateways,
+        }
+    }
+
+    fn through_gateways(gateways: &[String], path: &str) -> ResolvedUri {
+        ResolvedUri::Remote(
+            gateways
+                .iter()
+                .map(|gateway| GatewayUrl {
+                    gateway: gateway.clone(),
+                    url: format!(
+                        "{}/{}",
+                        gateway.trim_end_matches('/'),
+                        path.trim_start_matches('/')
+                    ),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl UriResolver for GatewayUriResolver {
+    fn resolve(&self, uri: &str) -> Result<ResolvedUri, JsonDownloaderError> {
+        let uri = uri.trim();
+        if let Some(path) = strip_scheme(uri, IPFS_SCHEME) {
+            // some URIs repeat the namespace, e.g. ipfs://ipfs/CID
+            let path = path.strip_prefix("ipfs/").unwrap_or(path);
+            return Ok(Self::through_gateways(&self.ipfs_gateways, path));
+        }
+        if let Some(path) = strip_scheme(uri, ARWEAVE_SCHEME) {
+            return Ok(Self::through_gateways(&self.arweave_gateways, path));
         }
+        if let Some(data) = strip_scheme(uri, DATA_SCHEME) {
+            return decode_data_uri(data);
+        }
+
+        Ok(ResolvedUri::Remote(vec![GatewayUrl {
+            gateway: DIRECT_GATEWAY.to_string(),
+            url: uri.to_string(),
+        }]))
     }
 }
 
+fn strip_scheme<'a>(uri: &'a str, scheme: &str) -> Option<&'a str> {
+    uri.get(..scheme.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(scheme))
+        .map(|_| &uri[scheme.len()..])
+}
+
+/// Decodes `data:[<media type>][;base64],<data>` URIs, the media type defaults to text/plain
+fn decode_data_uri(data: &str) -> Result<ResolvedUri, JsonDownloaderError> {
+    let (header, payload) = data
+        .split_once(',')
+        .ok_or(JsonDownloaderError::CouldNotDeserialize)?;
+    let mut params = header.split(';');
+    let media_type = params
+        .next()
+        .filter(|media_type| !media_type.is_empty())
+        .unwrap_or(DEFAULT_DATA_MEDIA_TYPE)
+        .to_ascii_lowercase();
+    let content = if params.any(|param| param.eq_ignore_ascii_case("base64")) {
+        general_purpose::STANDARD
+            .decode(payload.trim())
+            .map_err(|_| JsonDownloaderError::CouldNotDeserialize)?
+    } else {
+        percent_decode_str(payload).collect()
+    };
+
+    Ok(ResolvedUri::Inline {
+        media_type,
+        content,
+    })
+}
+
 #[async_trait]
 impl JsonPersister for JsonWorker {
     async fn persist_response(
         &self,
-        results: Vec<(String, Result<String, JsonDownloaderError>)>,
+        results: Vec<(String, Result<DownloadedJson, JsonDownloaderError>)>,
     ) -> Result<(), JsonDownloaderError> {
         let mut pg_updates = Vec::new();
         let mut rocks_updates = HashMap::new();
+        let mut offchain_traits = Vec::new();
+        let next_refresh_at = self.metadata_refresh_interval.and_then(|interval| {
+            chrono::Duration::from_std(interval)
+                .ok()
+                .map(|interval| chrono::Utc::now() + interval)
+        });
+
+        let downloaded_urls = results
+            .iter()
+            .filter(|(_, result)| result.is_ok())
+            .map(|(metadata_url, _)| metadata_url.clone())
+            .collect::<Vec<_>>();
+        let previous_hashes = if downloaded_urls.is_empty() {
+            HashMap::new()
+        } else {
+            self.db_client
+                .get_tasks_content_hashes(&downloaded_urls)
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Unable to select content hashes of the tasks: {e}");
+                    HashMap::new()
+                })
+        };
 
         for (metadata_url, result) in results.iter() {
             match &result {
                 Ok(json_file) => {
-                    rocks_updates.insert(
-                        metadata_url.clone(),
-                        OffChainData {
-                            url: metadata_url.clone(),
-                            metadata: json_file.clone(),
-                        },
-                    );
+                    let hash = json_file.content_hash();
+                    // the refreshed file is the same, so there is nothing to update in the storages
+                    if previous_hashes.get(metadata_url) != Some(&hash) {
+                        rocks_updates.insert(
+                            metadata_url.clone(),
+                            OffChainData {
+                                url: metadata_url.clone(),
+                                metadata: json_file.metadata.clone(),
+                            },
+                        );
+                        offchain_traits
+                            .push(parse_offchain_traits(metadata_url, &json_file.metadata));
+                    }
                     pg_updates.push(UpdatedTask {
                         status: TaskStatus::Success,
                         metadata_url: metadata_url.clone(),
                         error: "".to_string(),
+                        content: Some(TaskContent {
+                            etag: json_file.etag.clone(),
+                            last_modified: json_file.last_modified.clone(),
+                            hash,
+                            gateway: json_file.gateway.clone(),
+                        }),
+                        next_refresh_at,
+                        deferred_until: None,
                     });
 
                     self.metrics.inc_tasks("json", MetricStatus::SUCCESS);
                 }
                 Err(json_err) => match json_err {
+                    JsonDownloaderError::NotModified => {
+                        pg_updates.push(UpdatedTask {
+                            status: TaskStatus::Success,
+                            metadata_url: metadata_url.clone(),
+                            error: "".to_string(),
+                            content: None,
+                            next_refresh_at,
+                            deferred_until: None,
+                        });
+
+                        self.metrics
+                            .inc_tasks("not_modified", MetricStatus::SUCCESS);
+                    }
                     JsonDownloaderError::GotNotJsonFile => {
+                        // media files are not refreshed
                         pg_updates.push(UpdatedTask {
                             status: TaskStatus::Success,
                             metadata_url: metadata_url.clone(),
                             error: "".to_string(),
+                            content: None,
+                            next_refresh_at: None,
+                            deferred_until: None,
                         });
                         rocks_updates.insert(
                             metadata_url.clone(),
@@ -343,6 +687,11 @@ impl JsonPersister for JsonWorker {
                                 metadata: "".to_string(),
                             },
                         );
+                        // media files have no traits, wipe the ones indexed for a previous JSON
+                        offchain_traits.push(OffchainTraits {
+                            metadata_url: metadata_url.clone(),
+                            ..Default::default()
+                        });
 
                         self.metrics.inc_tasks("media", MetricStatus::SUCCESS);
                     }
@@ -351,6 +700,9 @@ impl JsonPersister for JsonWorker {
                             status: TaskStatus::Failed,
                             metadata_url: metadata_url.clone(),
                             error: "Failed to deserialize metadata body".to_string(),
+                            content: None,
+                            next_refresh_at,
+                            deferred_until: None,
                         });
                         self.metrics.inc_tasks("json", MetricStatus::FAILURE);
                     }
@@ -359,6 +711,9 @@ impl JsonPersister for JsonWorker {
                             status: TaskStatus::Failed,
                             metadata_url: metadata_url.clone(),
                             error: "Failed to read header".to_string(),
+                            content: None,
+                            next_refresh_at,
+                            deferred_until: None,
                         });
                         self.metrics.inc_tasks("unknown", MetricStatus::FAILURE);
                     }
@@ -367,6 +722,10 @@ impl JsonPersister for JsonWorker {
                             status: TaskStatus::Failed,
                             metadata_url: metadata_url.clone(),
                             error: err.clone(),
+                            content: None,
+                            // e.g. the file of an unrevealed asset may appear later
+                            next_refresh_at,
+                            deferred_until: None,
                         });
 
                         self.metrics.inc_tasks("json", MetricStatus::FAILURE);
@@ -379,6 +738,24 @@ impl JsonPersister for JsonWorker {
                             status: TaskStatus::Pending,
                             metadata_url: metadata_url.clone(),
                             error: err.clone(),
+                            content: None,
+                            next_refresh_at: None,
+                            deferred_until: None,
+                        });
+                    }
+                    JsonDownloaderError::HostBackedOff(backoff) => {
+                        self.metrics.inc_tasks("backed_off", MetricStatus::FAILURE);
+                        // nothing was requested, so it's not an attempt,
+                        // the task is locked until the host may be requested again
+                        pg_updates.push(UpdatedTask {
+                            status: TaskStatus::Pending,
+                            metadata_url: metadata_url.clone(),
+                            error: format!("Hosts are backed off for {:?}", backoff),
+                            content: None,
+                            next_refresh_at: None,
+                            deferred_until: chrono::Duration::from_std(*backoff)
+                                .ok()
+                                .map(|backoff| chrono::Utc::now() + backoff),
                         });
                     }
                     _ => {} // intentionally empty because nothing to process
@@ -386,13 +763,6 @@ impl JsonPersister for JsonWorker {
             }
         }
 
-        if !pg_updates.is_empty() {
-            self.db_client
-                .update_tasks_and_attempts(pg_updates)
-                .await
-                .map_err(|e| JsonDownloaderError::IndexStorageError(e.to_string()))?;
-        }
-
         if !rocks_updates.is_empty() {
             let urls_to_download = rocks_updates
                 .values()
@@ -403,11 +773,22 @@ impl JsonPersister for JsonWorker {
                 .map(|uri| (uri, UrlToDownload::default()))
                 .collect::<HashMap<_, _>>();
 
+            let updated_urls = rocks_updates.keys().cloned().collect::<Vec<_>>();
             self.rocks_db
                 .asset_offchain_data
                 .put_batch(rocks_updates)
                 .await
                 .map_err(|e| JsonDownloaderError::MainStorageError(e.to_string()))?;
+            // the index of the assets takes the name and the download status
+            // from the off-chain data, so the assets are synced to Postgres again
+            let updated_assets = self
+                .db_client
+                .get_assets_by_metadata_urls(&updated_urls)
+                .await
+                .map_err(|e| JsonDownloaderError::IndexStorageError(e.to_string()))?;
+            self.rocks_db
+                .asset_updated_batch(updated_assets)
+                .map_err(|e| JsonDownloaderError::MainStorageError(e.to_string()))?;
 
             if let Err(e) = self
                 .rocks_db
@@ -419,6 +800,207 @@ impl JsonPersister for JsonWorker {
             };
         }
 
+        self.db_client
+            .update_offchain_traits(offchain_traits)
+            .await
+            .map_err(|e| JsonDownloaderError::IndexStorageError(e.to_string()))?;
+
+        // the tasks are completed only once their results are stored, otherwise they stay locked
+        // and are downloaded again when the lock expires
+        if !pg_updates.is_empty() {
+            self.db_client
+                .update_tasks_and_attempts(pg_updates)
+                .await
+                .map_err(|e| JsonDownloaderError::IndexStorageError(e.to_string()))?;
+        }
+
         Ok(())
     }
 }
+
+/// Extracts the searchable `symbol` and `attributes` from an offchain JSON.
+/// Non-string attribute values are indexed by their JSON representation.
+/// NUL characters are dropped, Postgres text can't hold them.
+pub(crate) fn parse_offchain_traits(metadata_url: &str, metadata: &str) -> OffchainTraits {
+    let mut traits = OffchainTraits {
+        metadata_url: metadata_url.to_string(),
+        ..Default::default()
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(metadata) else {
+        return traits;
+    };
+    traits.symbol = json
+        .get("symbol")
+        .and_then(|s| s.as_str())
+        .map(|s| s.replace('\0', "").trim().to_string())
+        .filter(|s| !s.is_empty() && s.len() <= MAX_INDEXED_TRAIT_LEN);
+    traits.attributes = json
+        .get("attributes")
+        .and_then(|a| a.as_array())
+        .map(|attributes| {
+            attributes
+                .iter()
+                .filter_map(|attribute| {
+                    let trait_type = attribute
+                        .get("trait_type")?
+                        .as_str()?
+                        .replace('\0', "")
+                        .trim()
+                        .to_string();
+                    let value = match attribute.get("value")? {
+                        serde_json::Value::String(value) => {
+                            value.replace('\0', "").trim().to_string()
+                        }
+                        serde_json::Value::Number(value) => value.to_string(),
+                        serde_json::Value::Bool(value) => value.to_string(),
+                        _ => return None,
+                    };
+                    (!trait_type.is_empty()
+                        && trait_type.len() <= MAX_INDEXED_TRAIT_LEN
+                        && value.len() <= MAX_INDEXED_TRAIT_LEN)
+                        .then_some((trait_type, value))
+                })
+                .take(MAX_INDEXED_ATTRIBUTES)
+                .collect()
+        })
+        .unwrap_or_default();
+    traits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offchain_traits() {
+        let metadata = r#"{
+            "name": "Mad Lad #1",
+            "symbol": " MAD ",
+            "attributes": [
+                {"trait_type": "Background", "value": "Blue"},
+                {"trait_type": "Level", "value": 7},
+                {"trait_type": "Shiny", "value": true},
+                {"value": "no trait type"},
+                {"trait_type": "Nested", "value": {"a": 1}}
+            ]
+        }"#;
+        let traits = parse_offchain_traits("https://example.com/1.json", metadata);
+        assert_eq!(traits.symbol, Some("MAD".to_string()));
+        assert_eq!(
+            traits.attributes,
+            vec![
+                ("Background".to_string(), "Blue".to_string()),
+                ("Level".to_string(), "7".to_string()),
+                ("Shiny".to_string(), "true".to_string()),
+            ]
+        );
+
+        let traits = parse_offchain_traits(
+            "https://example.com/3.json",
+            r#"{
+                "symbol": "M\u0000AD\u0000",
+                "attributes": [{"trait_type": "Back\u0000ground", "value": "Bl\u0000ue"}]
+            }"#,
+        );
+        assert_eq!(traits.symbol, Some("MAD".to_string()));
+        assert_eq!(
+            traits.attributes,
+            vec![("Background".to_string(), "Blue".to_string())]
+        );
+
+        let traits = parse_offchain_traits("https://example.com/2.json", "not a json");
+        assert_eq!(traits.symbol, None);
+        assert!(traits.attributes.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_uris() {
+        let resolver = GatewayUriResolver::new(
+            vec![
+                "https://ipfs.io/ipfs/".to_string(),
+                "https://gateway.pinata.cloud/ipfs".to_string(),
+            ],
+            vec!["https://arweave.net".to_string()],
+        );
+
+        let expected = ResolvedUri::Remote(vec![
+            GatewayUrl {
+                gateway: "https://ipfs.io/ipfs/".to_string(),
+                url: "https://ipfs.io/ipfs/bafybeic/1.json".to_string(),
+            },
+            GatewayUrl {
+                gateway: "https://gateway.pinata.cloud/ipfs".to_string(),
+                url: "https://gateway.pinata.cloud/ipfs/bafybeic/1.json".to_string(),
+            },
+        ]);
+        assert_eq!(
+            resolver.resolve("ipfs://bafybeic/1.json").unwrap(),
+            expected
+        );
+        assert_eq!(
+            resolver.resolve("IPFS://ipfs/bafybeic/1.json").unwrap(),
+            expected
+        );
+
+        assert_eq!(
+            resolver.resolve("ar://Uw5h1Yd0").unwrap(),
+            ResolvedUri::Remote(vec![GatewayUrl {
+                gateway: "https://arweave.net".to_string(),
+                url: "https://arweave.net/Uw5h1Yd0".to_string(),
+            }])
+        );
+
+        assert_eq!(
+            resolver.resolve(" https://example.com/1.json ").unwrap(),
+            ResolvedUri::Remote(vec![GatewayUrl {
+                gateway: DIRECT_GATEWAY.to_string(),
+                url: "https://example.com/1.json".to_string(),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_resolve_data_uris() {
+        let resolver = GatewayUriResolver::new(vec![], vec![]);
+        let metadata = r#"{"name":"Inline #1"}"#;
+
+        let uri = format!(
+            "data:application/json;base64,{}",
+            general_purpose::STANDARD.encode(metadata)
+        );
+        assert_eq!(
+            resolver.resolve(&uri).unwrap(),
+            ResolvedUri::Inline {
+                media_type: JSON_CONTENT_TYPE.to_string(),
+                content: metadata.as_bytes().to_vec(),
+            }
+        );
+
+        assert_eq!(
+            resolver
+                .resolve("data:application/json,%7B%22name%22%3A%22Inline%20%231%22%7D")
+                .unwrap(),
+            ResolvedUri::Inline {
+                media_type: JSON_CONTENT_TYPE.to_string(),
+                content: r#"{"name":"Inline #1"}"#.as_bytes().to_vec(),
+            }
+        );
+
+        assert_eq!(
+            resolver.resolve("data:,hello").unwrap(),
+            ResolvedUri::Inline {
+                media_type: DEFAULT_DATA_MEDIA_TYPE.to_string(),
+                content: b"hello".to_vec(),
+            }
+        );
+
+        assert!(matches!(
+            resolver.resolve("data:application/json;base64"),
+            Err(JsonDownloaderError::CouldNotDeserialize)
+        ));
+        assert!(matches!(
+            resolver.resolve("data:application/json;base64,not base64!"),
+            Err(JsonDownloaderError::CouldNotDeserialize)
+        ));
+    }
+}
@@ -1,15 +1,24 @@
 pub mod accounts_processor;
 pub mod ack;
 pub mod api;
+pub mod asset_account_instructions;
+pub mod asset_history_cleaner;
 pub mod backfiller;
 pub mod batch_mint;
 pub mod bubblegum_updates_processor;
 pub mod buffer;
+pub mod commitment_slots;
 pub mod config;
+pub mod dead_letters;
 pub mod error;
+pub mod file_replay_receiver;
 pub mod flatbuffer_mapper;
 pub mod fork_cleaner;
+pub mod forked_accounts;
+pub mod gap_repairer;
 pub mod gapfiller;
+pub mod grpc_geyser_receiver;
+pub mod host_limiter;
 pub mod index_syncronizer;
 pub mod init;
 pub mod inscription_raw_parsing;
@@ -17,6 +26,7 @@ pub mod inscriptions_processor;
 pub mod json_worker;
 pub mod message_handler;
 pub mod message_parser;
+pub mod message_recorder;
 pub mod mpl_core_fee_indexing_processor;
 pub mod mpl_core_processor;
 pub mod mplx_updates_processor;
@@ -31,3 +41,4 @@ pub mod tcp_receiver;
 pub mod token_updates_processor;
 pub mod transaction_ingester;
 pub mod transaction_processor;
+pub mod webhooks;
@@ -8,8 +8,11 @@ use tokio::sync::Mutex;
 use tracing::error;
 
 use crate::buffer::Buffer;
+use crate::dead_letters::DeadLetterQueue;
 use crate::message_parser::MessageParser;
+use crate::message_recorder::{MessageRecorder, RecordedMessageKind};
 use interface::message_handler::MessageHandler;
+use rocks_db::dead_letters::DeadLetterStream;
 
 const BYTE_PREFIX_TX_SIMPLE_FINALIZED: u8 = 22;
 const BYTE_PREFIX_TX_FINALIZED: u8 = 12;
@@ -87,6 +90,8 @@ async fn update_or_insert_account(
 pub struct MessageHandlerIngester {
     buffer: Arc<Buffer>,
     message_parser: Arc<MessageParser>,
+    recorder: Option<MessageRecorder>,
+    dead_letters: Arc<DeadLetterQueue>,
 }
 
 #[async_trait]
@@ -97,7 +102,10 @@ impl MessageHandler for MessageHandlerIngester {
 
         match prefix {
             BYTE_PREFIX_ACCOUNT_FINALIZED | BYTE_PREFIX_ACCOUNT_PROCESSED => {
-                match self.message_parser.parse_account(data, true) {
+                if let Some(recorder) = &self.recorder {
+                    recorder.record(RecordedMessageKind::Account, true, &data);
+                }
+                match self.message_parser.parse_account(&data, true) {
                     Ok(accounts) => {
                         for account in accounts {
                             update_or_insert_account(
@@ -110,13 +118,22 @@ impl MessageHandler for MessageHandlerIngester {
                         }
                     }
                     Err(err) => {
-                        error!("parse_account: {:?}", err)
+                        error!("parse_account: {:?}", err);
+                        self.dead_letters.push_message(
+                            DeadLetterStream::Accounts,
+                            data,
+                            true,
+                            err.to_string(),
+                        );
                     }
                 }
             }
             BYTE_PREFIX_TX_SIMPLE_FINALIZED
             | BYTE_PREFIX_TX_FINALIZED
             | BYTE_PREFIX_TX_PROCESSED => {
+                if let Some(recorder) = &self.recorder {
+                    recorder.record(RecordedMessageKind::Transaction, true, &data);
+                }
                 let Some(tx) = self.message_parser.parse_transaction(data, true) else {
                     return;
                 };
@@ -129,12 +146,18 @@ impl MessageHandler for MessageHandlerIngester {
 }
 
 impl MessageHandlerIngester {
-    pub fn new(buffer: Arc<Buffer>) -> Self {
+    pub fn new(
+        buffer: Arc<Buffer>,
+        recorder: Option<MessageRecorder>,
+        dead_letters: Arc<DeadLetterQueue>,
+    ) -> Self {
         let message_parser = Arc::new(MessageParser::new());
 
         Self {
             buffer,
             message_parser,
+            recorder,
+            dead_letters,
         }
     }
 }
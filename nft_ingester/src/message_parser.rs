@@ -21,6 +21,7 @@ use flatbuffers::FlatBufferBuilder;
 use itertools::Itertools;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
 use std::fmt::Debug;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -86,58 +87,99 @@ impl MessageParser {
 
     pub fn parse_account(
         &self,
-        data: Vec<u8>,
+        data: &[u8],
         map_flatbuffer: bool,
     ) -> Result<Vec<UnprocessedAccountWithMetadata>, IngesterError> {
+        let mapped_bytes;
         let account_info_bytes = if map_flatbuffer {
             let account_update =
                 utils::flatbuffer::account_info_generated::account_info::root_as_account_info(
-                    &data,
+                    data,
                 )?;
-            map_account_info_fb_bytes(account_update)?
+            mapped_bytes = map_account_info_fb_bytes(account_update)?;
+            mapped_bytes.as_slice()
         } else {
             data
         };
 
-        let account_info = plerkle_serialization::root_as_account_info(account_info_bytes.as_ref())
-            .map_err(|e| IngesterError::AccountParsingError(e.to_string()))
-            .unwrap();
+        let account_info = plerkle_serialization::root_as_account_info(account_info_bytes)
+            .map_err(|e| IngesterError::AccountParsingError(e.to_string()))?;
 
         let account_info: plerkle::AccountInfo = PlerkleAccountInfo(account_info).try_into()?;
+
+        Ok(self.parse_account_info(&account_info))
+    }
+
+    /// Owners of the accounts [MessageParser::parse_account_info] knows how to parse
+    pub fn supported_account_owners(&self) -> Vec<Pubkey> {
+        vec![
+            spl_token::id(),
+            blockbuster::programs::token_metadata::token_metadata_id(),
+            self.mpl_core_parser.key(),
+            libreplex_inscriptions::id(),
+            spl_token_2022::id(),
+        ]
+    }
+
+    /// Parses the account state fetched from RPC. The write version has to be newer than the one
+    /// of the stored updates of the account, otherwise the state doesn't overwrite them.
+    pub fn parse_rpc_account(
+        &self,
+        pubkey: Pubkey,
+        slot: u64,
+        account: Account,
+        write_version: u64,
+    ) -> Vec<UnprocessedAccountWithMetadata> {
+        self.parse_account_info(&plerkle::AccountInfo {
+            slot,
+            pubkey,
+            owner: account.owner,
+            lamports: account.lamports,
+            rent_epoch: account.rent_epoch,
+            executable: account.executable,
+            write_version,
+            data: account.data,
+        })
+    }
+
+    pub fn parse_account_info(
+        &self,
+        account_info: &plerkle::AccountInfo,
+    ) -> Vec<UnprocessedAccountWithMetadata> {
         let account_owner = account_info.owner;
 
         // do not use match expression
         // because match cases cannot contain function calls like spl_token::id()
         let accounts = if account_owner == spl_token::id() {
-            self.handle_spl_token_account(&account_info)
+            self.handle_spl_token_account(account_info)
                 .into_iter()
                 .collect_vec()
         } else if account_owner == blockbuster::programs::token_metadata::token_metadata_id() {
-            self.handle_token_metadata_account(&account_info)
+            self.handle_token_metadata_account(account_info)
                 .into_iter()
                 .collect_vec()
         } else if account_owner == self.mpl_core_parser.key() {
-            self.handle_mpl_core_account(&account_info)
+            self.handle_mpl_core_account(account_info)
         } else if account_owner == libreplex_inscriptions::id() {
-            self.handle_inscription_account(&account_info)
+            self.handle_inscription_account(account_info)
                 .into_iter()
                 .collect_vec()
         } else if account_owner == spl_token_2022::id() {
-            self.parse_spl_2022_accounts(&account_info)
+            self.parse_spl_2022_accounts(account_info)
                 .into_iter()
                 .collect_vec()
         } else {
             Vec::new()
         };
 
-        Ok(accounts
+        accounts
             .into_iter()
             .map(|account| UnprocessedAccountWithMetadata {
                 unprocessed_account: account,
                 pubkey: account_info.pubkey,
                 write_version: account_info.write_version,
             })
-            .collect())
+            .collect()
     }
 
     fn handle_spl_token_account(
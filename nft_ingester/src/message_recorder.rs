@@ -0,0 +1,298 @@
+//! Records the raw account and transaction messages the ingester receives, so they can be
+//! replayed later through the [crate::file_replay_receiver::FileReplayReceiver].
+//!
+//! Messages are appended to files named `messages-<unix millis>.bin` which are rotated once they
+//! grow over the configured size. Every record is length-prefixed:
+//!
+//! | kind: u8 | map_flatbuffer: u8 | recorded_at: u64 LE, unix millis | length: u32 LE | data |
+
+use crate::config::MessageRecorderConfig;
+use crate::error::IngesterError;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::fs::{self, File};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::task::JoinError;
+use tracing::{error, info, warn};
+
+const RECORD_FILE_PREFIX: &str = "messages-";
+const RECORD_FILE_EXTENSION: &str = "bin";
+const RECORD_HEADER_SIZE: usize = 14;
+const BYTES_IN_MB: u64 = 1024 * 1024;
+// how often the number of dropped messages is reported
+const DROPPED_MESSAGES_LOG_INTERVAL: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordedMessageKind {
+    Account = 0,
+    Transaction = 1,
+}
+
+impl TryFrom<u8> for RecordedMessageKind {
+    type Error = IngesterError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Account),
+            1 => Ok(Self::Transaction),
+            _ => Err(IngesterError::DeserializationError(format!(
+                "Unknown recorded message kind: {}",
+                value
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedMessage {
+    pub kind: RecordedMessageKind,
+    // whether the data has to be mapped into the plerkle flatbuffer before parsing, as TCP messages do
+    pub map_flatbuffer: bool,
+    pub recorded_at: u64,
+    pub data: Vec<u8>,
+}
+
+impl RecordedMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(RECORD_HEADER_SIZE + self.data.len());
+        result.push(self.kind as u8);
+        result.push(self.map_flatbuffer as u8);
+        result.extend_from_slice(&self.recorded_at.to_le_bytes());
+        result.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        result.extend_from_slice(&self.data);
+        result
+    }
+
+    /// Reads the next record, returns `None` at the end of the file
+    pub async fn read<R: AsyncReadExt + Unpin>(
+        reader: &mut R,
+    ) -> Result<Option<Self>, IngesterError> {
+        let mut header = [0; RECORD_HEADER_SIZE];
+        match reader.read_exact(&mut header).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let kind = RecordedMessageKind::try_from(header[0])?;
+        let map_flatbuffer = header[1] != 0;
+        let recorded_at = u64::from_le_bytes(header[2..10].try_into().unwrap_or_default());
+        let length = u32::from_le_bytes(header[10..14].try_into().unwrap_or_default());
+        let mut data = vec![0; length as usize];
+        reader.read_exact(&mut data).await?;
+
+        Ok(Some(Self {
+            kind,
+            map_flatbuffer,
+            recorded_at,
+            data,
+        }))
+    }
+}
+
+/// Sending half of the recorder, cloned into the receivers the messages are recorded from.
+/// Recording never blocks the ingestion, messages are dropped if the writer falls behind.
+#[derive(Clone)]
+pub struct MessageRecorder {
+    sender: mpsc::Sender<RecordedMessage>,
+    dropped: std::sync::Arc<AtomicU64>,
+}
+
+/// Writes the recorded messages into the rotating files
+pub struct MessageRecordWriter {
+    config: MessageRecorderConfig,
+    receiver: mpsc::Receiver<RecordedMessage>,
+}
+
+impl MessageRecorder {
+    pub fn new(config: MessageRecorderConfig) -> (Self, MessageRecordWriter) {
+        let (sender, receiver) = mpsc::channel(config.buffer_size.max(1));
+        (
+            Self {
+                sender,
+                dropped: Default::default(),
+            },
+            MessageRecordWriter { config, receiver },
+        )
+    }
+
+    pub fn record(&self, kind: RecordedMessageKind, map_flatbuffer: bool, data: &[u8]) {
+        let message = RecordedMessage {
+            kind,
+            map_flatbuffer,
+            recorded_at: Utc::now().timestamp_millis() as u64,
+            data: data.to_vec(),
+        };
+        match self.sender.try_send(message) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed);
+                if dropped % DROPPED_MESSAGES_LOG_INTERVAL == 0 {
+                    warn!(
+                        "Message recorder is falling behind, dropped {} messages",
+                        dropped + 1
+                    );
+                }
+            }
+            Err(TrySendError::Closed(_)) => {}
+        }
+    }
+}
+
+pub async fn run_message_recorder(
+    writer: MessageRecordWriter,
+    rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    let path = writer.config.path.clone();
+    if let Err(e) = writer.run(rx).await {
+        error!("Message recorder {}: {}", path, e);
+    }
+
+    Ok(())
+}
+
+impl MessageRecordWriter {
+    async fn run(mut self, mut rx: Receiver<()>) -> Result<(), IngesterError> {
+        fs::create_dir_all(&self.config.path).await?;
+        let max_file_size = self.config.max_file_size_mb * BYTES_IN_MB;
+        let mut file: Option<(BufWriter<File>, u64)> = None;
+        info!("Recording received messages into {}", self.config.path);
+
+        loop {
+            let message = tokio::select! {
+                message = self.receiver.recv() => message,
+                _ = rx.recv() => None,
+            };
+            let Some(message) = message else {
+                break;
+            };
+
+            let mut messages = vec![message];
+            while let Ok(message) = self.receiver.try_recv() {
+                messages.push(message);
+            }
+            for message in messages {
+                if file
+                    .as_ref()
+                    .map_or(true, |(_, size)| *size >= max_file_size)
+                {
+                    if let Some((mut writer, _)) = file.take() {
+                        writer.flush().await?;
+                    }
+                    file = Some((self.open_next_file().await?, 0));
+                }
+                if let Some((writer, size)) = file.as_mut() {
+                    let record = message.encode();
+                    writer.write_all(&record).await?;
+                    *size += record.len() as u64;
+                }
+            }
+            if let Some((writer, _)) = file.as_mut() {
+                writer.flush().await?;
+            }
+        }
+
+        if let Some((mut writer, _)) = file.take() {
+            writer.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn open_next_file(&self) -> Result<BufWriter<File>, IngesterError> {
+        if let Some(max_files) = self.config.max_files {
+            let files = list_record_files(Path::new(&self.config.path)).await?;
+            // keep room for the file which is about to be created
+            let excess = (files.len() + 1).saturating_sub(max_files.max(1));
+            for old_file in files.into_iter().take(excess) {
+                fs::remove_file(&old_file).await?;
+            }
+        }
+
+        let file_path = Path::new(&self.config.path).join(format!(
+            "{}{:020}.{}",
+            RECORD_FILE_PREFIX,
+            Utc::now().timestamp_millis(),
+            RECORD_FILE_EXTENSION
+        ));
+        info!("Recording messages into {:?}", file_path);
+
+        Ok(BufWriter::new(
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(file_path)
+                .await?,
+        ))
+    }
+}
+
+/// Returns the record files in the order they were written.
+/// The path may point either to a single file or to the directory the recorder writes into.
+pub async fn list_record_files(path: &Path) -> Result<Vec<PathBuf>, IngesterError> {
+    if fs::metadata(path).await?.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    let mut entries = fs::read_dir(path).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let file_path = entry.path();
+        let is_record_file = file_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| name.starts_with(RECORD_FILE_PREFIX))
+            && file_path
+                .extension()
+                .map_or(false, |extension| extension == RECORD_FILE_EXTENSION);
+        if is_record_file {
+            files.push(file_path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+/// Opens a record file for [RecordedMessage::read]
+pub async fn open_record_file(path: &Path) -> Result<BufReader<File>, IngesterError> {
+    Ok(BufReader::new(File::open(path).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recorded_message_encoding() {
+        let messages = vec![
+            RecordedMessage {
+                kind: RecordedMessageKind::Account,
+                map_flatbuffer: false,
+                recorded_at: 1_700_000_000_000,
+                data: vec![1, 2, 3],
+            },
+            RecordedMessage {
+                kind: RecordedMessageKind::Transaction,
+                map_flatbuffer: true,
+                recorded_at: 1_700_000_000_001,
+                data: vec![],
+            },
+        ];
+        let bytes = messages
+            .iter()
+            .flat_map(|message| message.encode())
+            .collect::<Vec<_>>();
+
+        let mut reader = bytes.as_slice();
+        let mut decoded = Vec::new();
+        while let Some(message) = RecordedMessage::read(&mut reader).await.unwrap() {
+            decoded.push(message);
+        }
+
+        assert_eq!(decoded, messages);
+    }
+}
@@ -16,6 +16,7 @@ use rocks_db::asset::{
     AssetAuthority, AssetCollection, AssetDynamicDetails, AssetStaticDetails, MetadataMintMap,
 };
 use rocks_db::batch_savers::{BatchSaveStorage, MetadataModels};
+use rocks_db::editions::EditionMintMap;
 use rocks_db::errors::StorageError;
 use usecase::save_metrics::result_to_metrics;
 
@@ -35,7 +36,10 @@ impl MplxAccountsProcessor {
         burnt_metadata_slot: &BurntMetadataSlot,
     ) -> Result<(), StorageError> {
         let begin_processing = Instant::now();
-        let res = self.mark_metadata_as_burnt(storage, key, burnt_metadata_slot);
+        // the closed account is either the metadata or the edition of the burnt asset
+        let res = self
+            .mark_metadata_as_burnt(storage, key, burnt_metadata_slot)
+            .and_then(|_| storage.delete_burnt_print(key));
 
         result_to_metrics(self.metrics.clone(), &res, "burn_metadata_merge_with_batch");
         self.metrics.set_latency(
@@ -74,7 +78,17 @@ impl MplxAccountsProcessor {
         edition: &TokenMetadataEdition,
     ) -> Result<(), StorageError> {
         let begin_processing = Instant::now();
-        let res = storage.store_edition(key, edition);
+        let res = storage
+            .store_edition(key, edition)
+            .and_then(|_| match edition {
+                TokenMetadataEdition::EditionV1(edition) => storage.store_edition_parent_idx(
+                    key,
+                    edition.parent,
+                    edition.edition,
+                    edition.write_version,
+                ),
+                TokenMetadataEdition::MasterEdition(_) => Ok(()),
+            });
 
         result_to_metrics(self.metrics.clone(), &res, "editions_merge_with_batch");
         self.metrics.set_latency(
@@ -115,12 +129,25 @@ impl MplxAccountsProcessor {
             _ => SpecificationAssetClass::Unknown,
         };
 
+        let edition_address = MasterEdition::find_pda(&mint).0;
+        // prints are listed by the master edition, so the mint has to be resolvable from the edition
+        if matches!(
+            metadata.token_standard,
+            None | Some(TokenStandard::NonFungibleEdition)
+                | Some(TokenStandard::ProgrammableNonFungibleEdition)
+        ) {
+            models.edition_mint = Some(EditionMintMap {
+                pubkey: edition_address,
+                mint_key: mint,
+            });
+        }
+
         models.asset_static = Some(AssetStaticDetails {
             pubkey: mint,
             specification_asset_class: class,
             royalty_target_type: RoyaltyTargetType::Creators,
             created_at: metadata_info.slot_updated as i64,
-            edition_address: Some(MasterEdition::find_pda(&mint).0),
+            edition_address: Some(edition_address),
         });
 
         let mut chain_data = ChainDataV1 {
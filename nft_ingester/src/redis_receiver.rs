@@ -1,5 +1,7 @@
+use crate::dead_letters::DeadLetterQueue;
 use crate::error::IngesterError;
 use crate::message_parser::MessageParser;
+use crate::message_recorder::{MessageRecorder, RecordedMessageKind};
 use async_trait::async_trait;
 use entities::models::{BufferedTxWithID, UnprocessedAccountMessage};
 use interface::error::UsecaseError;
@@ -10,6 +12,7 @@ use plerkle_messenger::redis_messenger::RedisMessenger;
 use plerkle_messenger::{
     ConsumptionType, Messenger, MessengerConfig, ACCOUNT_STREAM, TRANSACTION_STREAM,
 };
+use rocks_db::dead_letters::DeadLetterStream;
 use std::sync::Arc;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::Mutex;
@@ -20,6 +23,8 @@ pub struct RedisReceiver {
     message_parser: Arc<MessageParser>,
     messanger: Mutex<RedisMessenger>,
     ack_channel: UnboundedSender<(&'static str, String)>,
+    recorder: Option<MessageRecorder>,
+    dead_letters: Arc<DeadLetterQueue>,
 }
 
 impl RedisReceiver {
@@ -27,6 +32,8 @@ impl RedisReceiver {
         config: MessengerConfig,
         consumption_type: ConsumptionType,
         ack_channel: UnboundedSender<(&'static str, String)>,
+        recorder: Option<MessageRecorder>,
+        dead_letters: Arc<DeadLetterQueue>,
     ) -> Result<Self, IngesterError> {
         let message_parser = Arc::new(MessageParser::new());
         let messanger = Mutex::new(RedisMessenger::new(config).await?);
@@ -35,6 +42,8 @@ impl RedisReceiver {
             consumption_type,
             message_parser,
             ack_channel,
+            recorder,
+            dead_letters,
         })
     }
 }
@@ -51,6 +60,9 @@ impl UnprocessedTransactionsGetter for RedisReceiver {
             .map_err(|e| UsecaseError::Messenger(e.to_string()))?;
         let mut result = Vec::new();
         for item in recv_data {
+            if let Some(recorder) = &self.recorder {
+                recorder.record(RecordedMessageKind::Transaction, false, &item.data);
+            }
             if let Some(tx) = self.message_parser.parse_transaction(item.data, false) {
                 result.push(BufferedTxWithID { tx, id: item.id })
             }
@@ -80,15 +92,18 @@ impl UnprocessedAccountsGetter for RedisReceiver {
             .await
             .map_err(|e| UsecaseError::Messenger(e.to_string()))?;
         let mut result = Vec::new();
-        let mut unknown_account_types_ids = Vec::new();
+        let mut not_processed_ids = Vec::new();
         for item in recv_data {
-            match self.message_parser.parse_account(item.data, false) {
+            if let Some(recorder) = &self.recorder {
+                recorder.record(RecordedMessageKind::Account, false, &item.data);
+            }
+            match self.message_parser.parse_account(&item.data, false) {
                 Ok(accounts) => {
                     // We can receive empty UnprocessedAccountWithMetadata array if there no
                     // known account data types in item
                     // If so we need to ack item as received one but do not process it
                     if accounts.is_empty() {
-                        unknown_account_types_ids.push(item.id);
+                        not_processed_ids.push(item.id);
                         continue;
                     }
                     for (i, account) in accounts.into_iter().enumerate() {
@@ -107,12 +122,21 @@ impl UnprocessedAccountsGetter for RedisReceiver {
                     }
                 }
                 Err(err) => {
-                    error!("Parsing account: {}", err)
+                    error!("Parsing account: {}", err);
+                    // the message is acked only if it is kept in the dead letters
+                    if self.dead_letters.push_message(
+                        DeadLetterStream::Accounts,
+                        item.data,
+                        false,
+                        err.to_string(),
+                    ) {
+                        not_processed_ids.push(item.id);
+                    }
                 }
             }
         }
 
-        UnprocessedAccountsGetter::ack(self, unknown_account_types_ids);
+        UnprocessedAccountsGetter::ack(self, not_processed_ids);
         Ok(result)
     }
 
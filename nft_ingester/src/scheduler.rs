@@ -2,10 +2,13 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
-use entities::models::OffChainData;
+use entities::models::{OffChainData, PubkeyWithSlot};
+use rocks_db::asset::AssetDynamicDetails;
 use rocks_db::asset_previews::UrlToDownload;
+use solana_sdk::pubkey::Pubkey;
 use tracing::log::error;
 
+use postgre_client::PgClient;
 use rocks_db::Storage;
 
 use async_trait::async_trait;
@@ -14,6 +17,12 @@ use entities::schedule::ScheduledJob;
 use interface::schedules::SchedulesStore;
 
 use crate::api::dapi::rpc_asset_convertors::parse_files;
+use crate::json_worker::parse_offchain_traits;
+
+/// How often the tasks are checked for the metadata to refresh
+pub const METADATA_REFRESH_CHECK_INTERVAL_SEC: u64 = 60;
+/// How long the offchain traits indexing waits for the index storage after a failure
+const OFFCHAIN_TRAITS_RETRY_INTERVAL_SEC: u64 = 10;
 
 /// Represents a functionality for running background jobs according
 /// to a confgurations stored in DB.
@@ -32,13 +41,53 @@ pub struct Scheduler {
 }
 
 impl Scheduler {
-    pub fn new(storage: Arc<Storage>) -> Scheduler {
+    pub fn new(
+        storage: Arc<Storage>,
+        index_storage: Arc<PgClient>,
+        metadata_refresh_interval_sec: Option<u64>,
+    ) -> Scheduler {
         // Here we defined all "scheduled" jobs
-        let jobs: Vec<Box<dyn Job + Send>> = vec![Box::new(InitUrlsToDownloadJob {
-            storage: storage.clone(),
-            batch_size: 1000,
-            last_key: None,
-        })];
+        let mut jobs: Vec<Box<dyn Job + Send>> = vec![
+            Box::new(InitUrlsToDownloadJob {
+                storage: storage.clone(),
+                batch_size: 1000,
+                last_key: None,
+            }),
+            Box::new(IndexOffchainTraitsJob {
+                storage: storage.clone(),
+                index_storage: index_storage.clone(),
+                batch_size: 1000,
+                last_key: None,
+            }),
+            Box::new(ResyncAssetsJob {
+                id: "resync_core_plugins",
+                storage: storage.clone(),
+                filter: |dynamic_details| {
+                    dynamic_details.mpl_core_plugins.is_some()
+                        || dynamic_details.mpl_core_external_plugins.is_some()
+                },
+                batch_size: 1000,
+                last_key: None,
+            }),
+            // the name is taken from the on-chain metadata, the raw name or the off-chain JSON
+            Box::new(ResyncAssetsJob {
+                id: "resync_asset_names",
+                storage: storage.clone(),
+                filter: |dynamic_details| {
+                    dynamic_details.onchain_data.is_some()
+                        || dynamic_details.raw_name.is_some()
+                        || !dynamic_details.url.value.trim().is_empty()
+                },
+                batch_size: 1000,
+                last_key: None,
+            }),
+        ];
+        if metadata_refresh_interval_sec.is_some() {
+            jobs.push(Box::new(RefreshMetadataJob {
+                index_storage,
+                batch_size: 1000,
+            }));
+        }
 
         Scheduler { storage, jobs }
     }
@@ -67,6 +116,7 @@ impl Scheduler {
                 if sched.wont_run_again() {
                     to_remove.push(job.id());
                 } else if sched.need_to_run_now() {
+                    job.init_with_state(sched.state.clone());
                     self.storage.mark_started(&mut sched);
                     loop {
                         match job.run().await {
@@ -74,11 +124,6 @@ impl Scheduler {
                                 self.storage.mark_finished(&mut sched, state);
                                 if sched.wont_run_again() {
                                     to_remove.push(job.id());
-                                } else if let Some(run_interval_sec) = sched.run_interval_sec {
-                                    sleep_to_next_run = std::cmp::min(
-                                        sleep_to_next_run,
-                                        sched.last_run_epoch_time + run_interval_sec,
-                                    );
                                 }
                                 break;
                             }
@@ -93,8 +138,13 @@ impl Scheduler {
                         };
                     }
                 }
+                if !to_remove.contains(&job.id()) {
+                    if let Some(seconds_to_next_run) = sched.seconds_to_next_run() {
+                        sleep_to_next_run = std::cmp::min(sleep_to_next_run, seconds_to_next_run);
+                    }
+                }
             }
-            self.jobs.retain(|j| to_remove.contains(&j.id()));
+            self.jobs.retain(|j| !to_remove.contains(&j.id()));
             if self.jobs.is_empty() || sleep_to_next_run == u64::max_value() {
                 break;
             }
@@ -239,3 +289,193 @@ impl Job for InitUrlsToDownloadJob {
         )
     }
 }
+
+/// One-shot job that indexes the searchable symbol and attributes of the offchain JSONs
+/// downloaded before the traits were indexed. The JSONs downloaded since then are indexed
+/// by the JSON downloader when they are persisted.
+pub struct IndexOffchainTraitsJob {
+    storage: Arc<Storage>,
+    index_storage: Arc<PgClient>,
+    /// how much offchain data records are indexed in a single run
+    batch_size: usize,
+    /// a key of the last offchain data record processed in the previous run
+    last_key: Option<String>,
+}
+
+#[async_trait]
+impl Job for IndexOffchainTraitsJob {
+    fn id(&self) -> String {
+        "index_offchain_traits".to_string()
+    }
+
+    fn initial_config(&self) -> ScheduledJob {
+        ScheduledJob {
+            job_id: self.id(),
+            run_interval_sec: None, // one-time job
+            last_run_epoch_time: 0,
+            last_run_status: JobRunState::NotRun,
+            state: None,
+        }
+    }
+
+    fn init_with_state(&mut self, prev_state: Option<Vec<u8>>) {
+        self.last_key = prev_state.and_then(|bytes| bincode::deserialize::<String>(&bytes).ok());
+    }
+
+    async fn run(&mut self) -> JobRunResult {
+        let data = match &self.last_key {
+            Some(v) => self
+                .storage
+                .asset_offchain_data
+                .get_after(v.clone(), self.batch_size),
+            None => self
+                .storage
+                .asset_offchain_data
+                .get_from_start(self.batch_size),
+        };
+
+        if data.is_empty() {
+            return JobRunResult::Finished(None);
+        }
+
+        let last_key = data.last().unwrap().0.clone(); // .unwrap() won't ever fail
+
+        // media files have no traits
+        let traits = data
+            .into_iter()
+            .filter(|(_, OffChainData { metadata, .. })| !metadata.is_empty())
+            .map(|(_, OffChainData { url, metadata })| parse_offchain_traits(&url, &metadata))
+            .collect::<Vec<_>>();
+
+        match self.index_storage.update_offchain_traits(traits).await {
+            Ok(()) => self.last_key = Some(last_key),
+            Err(e) => {
+                error!("Error indexing offchain traits: {e}");
+                // the batch is retried from the same checkpoint
+                tokio::time::sleep(Duration::from_secs(OFFCHAIN_TRAITS_RETRY_INTERVAL_SEC)).await;
+            }
+        }
+
+        JobRunResult::NotFinished(
+            self.last_key
+                .clone()
+                .map(|s| bincode::serialize(&s).unwrap()),
+        )
+    }
+}
+
+/// One-shot job that marks the assets as updated, so the synchronizer indexes them in Postgres again.
+/// It backfills the index columns added after the assets were synced, the assets changed
+/// since then are indexed with these columns already.
+pub struct ResyncAssetsJob {
+    id: &'static str,
+    storage: Arc<Storage>,
+    /// selects the assets whose index has to be backfilled
+    filter: fn(&AssetDynamicDetails) -> bool,
+    /// how much dynamic details records are checked in a single run
+    batch_size: usize,
+    /// a key of the last dynamic details record processed in the previous run
+    last_key: Option<Pubkey>,
+}
+
+#[async_trait]
+impl Job for ResyncAssetsJob {
+    fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn initial_config(&self) -> ScheduledJob {
+        ScheduledJob {
+            job_id: self.id(),
+            run_interval_sec: None, // one-time job
+            last_run_epoch_time: 0,
+            last_run_status: JobRunState::NotRun,
+            state: None,
+        }
+    }
+
+    fn init_with_state(&mut self, prev_state: Option<Vec<u8>>) {
+        self.last_key = prev_state.and_then(|bytes| bincode::deserialize::<Pubkey>(&bytes).ok());
+    }
+
+    async fn run(&mut self) -> JobRunResult {
+        let data = match self.last_key {
+            Some(v) => self
+                .storage
+                .asset_dynamic_data
+                .get_after(v, self.batch_size),
+            None => self
+                .storage
+                .asset_dynamic_data
+                .get_from_start(self.batch_size),
+        };
+
+        if data.is_empty() {
+            return JobRunResult::Finished(None);
+        }
+
+        self.last_key = Some(data.last().unwrap().0); // .unwrap() won't ever fail
+
+        let updated_assets = data
+            .into_iter()
+            .filter(|(_, dynamic_details)| (self.filter)(dynamic_details))
+            .map(|(pubkey, dynamic_details)| PubkeyWithSlot {
+                pubkey,
+                slot: dynamic_details.get_slot_updated(),
+            })
+            .collect::<Vec<_>>();
+
+        if let Err(e) = self.storage.asset_updated_batch(updated_assets) {
+            error!("Error marking assets updated: {e}");
+            return JobRunResult::Error(None);
+        }
+
+        JobRunResult::NotFinished(self.last_key.map(|key| bincode::serialize(&key).unwrap()))
+    }
+}
+
+/// Periodic job that re-enqueues the downloaded offchain JSONs whose refresh time has come,
+/// so the updates of mutable metadata (e.g. reveals) are picked up by the JSON downloader.
+/// The refresh time of a file is set by the downloader when it's persisted.
+pub struct RefreshMetadataJob {
+    index_storage: Arc<PgClient>,
+    /// how much tasks are re-enqueued in a single run
+    batch_size: i64,
+}
+
+#[async_trait]
+impl Job for RefreshMetadataJob {
+    fn id(&self) -> String {
+        "refresh_metadata".to_string()
+    }
+
+    fn initial_config(&self) -> ScheduledJob {
+        ScheduledJob {
+            job_id: self.id(),
+            run_interval_sec: Some(METADATA_REFRESH_CHECK_INTERVAL_SEC),
+            last_run_epoch_time: 0,
+            last_run_status: JobRunState::NotRun,
+            state: None,
+        }
+    }
+
+    fn init_with_state(&mut self, _prev_state: Option<Vec<u8>>) {}
+
+    async fn run(&mut self) -> JobRunResult {
+        match self
+            .index_storage
+            .schedule_metadata_refresh(self.batch_size)
+            .await
+        {
+            Ok(rescheduled) if rescheduled as i64 == self.batch_size => {
+                JobRunResult::NotFinished(None)
+            }
+            Ok(_) => JobRunResult::Finished(None),
+            Err(e) => {
+                error!("Error scheduling metadata refresh: {e}");
+                // the refresh is retried on the next run
+                JobRunResult::Finished(None)
+            }
+        }
+    }
+}
@@ -18,7 +18,7 @@ impl BackfillTransactionIngester {
 impl TransactionIngester for BackfillTransactionIngester {
     async fn ingest_transaction(&self, tx: BufferedTransaction) -> Result<(), StorageError> {
         self.tx_processor
-            .process_transaction(tx)
+            .process_transaction(&tx)
             .await
             .map_err(|e| StorageError::Common(e.to_string()))
     }
@@ -31,7 +31,7 @@ impl TransactionProcessor for BackfillTransactionIngester {
         tx: BufferedTransaction,
     ) -> Result<TransactionResult, StorageError> {
         BubblegumTxProcessor::get_process_transaction_results(
-            tx,
+            &tx,
             self.tx_processor.instruction_parser.clone(),
             self.tx_processor.transaction_parser.clone(),
             self.tx_processor.metrics.clone(),
@@ -1,6 +1,8 @@
 use crate::bubblegum_updates_processor::BubblegumTxProcessor;
+use crate::dead_letters::DeadLetterQueue;
 use crate::error::IngesterError;
 use interface::signature_persistence::UnprocessedTransactionsGetter;
+use rocks_db::dead_letters::DeadLetterStream;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast::Receiver;
@@ -16,6 +18,7 @@ pub async fn run_transaction_processor<TG>(
     mutexed_tasks: Arc<Mutex<JoinSet<Result<(), JoinError>>>>,
     unprocessed_transactions_getter: Arc<TG>,
     geyser_bubblegum_updates_processor: Arc<BubblegumTxProcessor>,
+    dead_letters: Arc<DeadLetterQueue>,
 ) where
     TG: UnprocessedTransactionsGetter + Send + Sync + 'static,
 {
@@ -35,13 +38,22 @@ pub async fn run_transaction_processor<TG>(
 
             for tx in txs {
                 match geyser_bubblegum_updates_processor
-                    .process_transaction(tx.tx)
+                    .process_transaction(&tx.tx)
                     .await
                 {
                     Ok(_) => unprocessed_transactions_getter.ack(tx.id),
                     Err(IngesterError::NotImplemented) => {}
                     Err(err) => {
                         error!("Background saver could not process received data: {}", err);
+                        // the message is acked only if the transaction is kept in the dead letters
+                        if dead_letters.push_message(
+                            DeadLetterStream::Transactions,
+                            tx.tx.transaction,
+                            tx.tx.map_flatbuffer,
+                            err.to_string(),
+                        ) {
+                            unprocessed_transactions_getter.ack(tx.id);
+                        }
                     }
                 }
             }
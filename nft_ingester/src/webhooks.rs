@@ -0,0 +1,597 @@
+use chrono::Utc;
+use entities::enums::AssetEventType;
+use entities::models::{Webhook, WebhookFilter};
+use futures::future::join_all;
+use hmac::{Hmac, Mac};
+use metrics_utils::IngesterMetricsConfig;
+use postgre_client::PgClient;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::redirect::Policy;
+use rocks_db::webhooks::{AssetEvent, WebhookDelivery};
+use rocks_db::Storage;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::Mutex;
+use tokio::task::JoinError;
+use tokio::time::sleep as tokio_sleep;
+use tracing::{error, info, warn};
+use url::{Host, Url};
+
+use crate::error::IngesterError;
+
+const WEBHOOKS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// the registered and deleted webhooks are picked up with this delay
+const WEBHOOKS_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+const EVENTS_BATCH_SIZE: usize = 1000;
+const DELIVERIES_BATCH_SIZE: usize = 100;
+// the exponential backoff stops growing at an hour
+const MAX_RETRY_INTERVAL_SEC: u64 = 3600;
+
+/// Hex encoded HMAC-SHA256 of `<timestamp>.<request body>` keyed by the webhook secret
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+/// Unix time the request was signed at, the receivers should refuse the stale ones
+/// so a captured request can't be replayed
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const WEBHOOK_ID_HEADER: &str = "X-Webhook-Id";
+/// Id of the notification, the same for every retry of it
+pub const DELIVERY_ID_HEADER: &str = "X-Webhook-Delivery-Id";
+
+/// Body of the request sent to the webhooks
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetEventNotification {
+    pub event_id: String,
+    pub event_type: AssetEventType,
+    pub asset_id: String,
+    pub slot: u64,
+    pub signature: Option<String>,
+    pub tree: Option<String>,
+    pub owner: Option<String>,
+    pub collection: Option<String>,
+    pub creators: Vec<String>,
+}
+
+/// Asset fields the webhook filters are matched against
+#[derive(Debug, Default)]
+struct EventSubject {
+    owner: Option<Pubkey>,
+    collection: Option<Pubkey>,
+    creators: Vec<Pubkey>,
+    tree: Option<Pubkey>,
+}
+
+pub async fn run_webhooks_sender(
+    sender: WebhookSender,
+    mut rx: Receiver<()>,
+) -> Result<(), JoinError> {
+    info!("Start sending webhooks...");
+    loop {
+        // the full batches are followed by the next ones right away, so a burst of events
+        // doesn't wait for the poll interval batch by batch
+        while rx.is_empty() && sender.dispatch_events().await >= EVENTS_BATCH_SIZE {}
+        while rx.is_empty() && sender.send_deliveries(&rx).await >= DELIVERIES_BATCH_SIZE {}
+        tokio::select! {
+            _ = tokio_sleep(WEBHOOKS_POLL_INTERVAL) => {},
+            _ = rx.recv() => {
+                info!("Received stop signal, stopping sending webhooks!");
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Matches the asset events recorded by the processors against the registered webhooks
+/// and sends the signed notifications, retrying the failed ones with an exponential backoff
+pub struct WebhookSender {
+    storage: Arc<Storage>,
+    pg_client: Arc<PgClient>,
+    client: reqwest::Client,
+    metrics: Arc<IngesterMetricsConfig>,
+    max_attempts: u32,
+    retry_base_sec: u64,
+    // the webhooks are registered by the API clients, so by default they can't make
+    // the ingester call the services of its own network
+    allow_private_hosts: bool,
+    webhooks: Mutex<Option<CachedWebhooks>>,
+    // number of the notifications in the outbox, counted once on the start
+    outbox_depth: AtomicI64,
+}
+
+struct CachedWebhooks {
+    webhooks: Arc<HashMap<u64, Webhook>>,
+    loaded_at: Instant,
+}
+
+impl WebhookSender {
+    pub fn new(
+        storage: Arc<Storage>,
+        pg_client: Arc<PgClient>,
+        metrics: Arc<IngesterMetricsConfig>,
+        max_attempts: u32,
+        retry_base_sec: u64,
+        request_timeout: Duration,
+        allow_private_hosts: bool,
+    ) -> Result<Self, IngesterError> {
+        // the target of a redirect isn't checked like the url of the webhook
+        let mut client_builder = reqwest::Client::builder()
+            .timeout(request_timeout)
+            .redirect(Policy::none());
+        if !allow_private_hosts {
+            client_builder = client_builder.dns_resolver(Arc::new(PublicAddressResolver));
+        }
+        let client = client_builder.build()?;
+        let outbox_depth = storage.index_webhook_deliveries()? as i64;
+        metrics.set_webhook_outbox_depth(outbox_depth);
+
+        Ok(Self {
+            storage,
+            pg_client,
+            client,
+            metrics,
+            max_attempts,
+            retry_base_sec,
+            allow_private_hosts,
+            webhooks: Mutex::new(None),
+            outbox_depth: AtomicI64::new(outbox_depth),
+        })
+    }
+
+    /// Moves the recorded asset events into the outbox as the notifications
+    /// of the webhooks they match. Returns the number of the dispatched events
+    pub async fn dispatch_events(&self) -> usize {
+        let events = self.storage.get_asset_events(EVENTS_BATCH_SIZE);
+        if events.is_empty() {
+            return 0;
+        }
+        let Some(webhooks) = self.webhooks().await else {
+            return 0;
+        };
+
+        let now = Utc::now().timestamp();
+        let mut deliveries = Vec::new();
+        for (id, event) in events.iter() {
+            if webhooks.is_empty() {
+                break;
+            }
+            let subject = self.event_subject(event);
+            let matched_webhooks = webhooks
+                .values()
+                .filter(|webhook| matches(&webhook.filter, event, &subject))
+                .map(|webhook| webhook.id)
+                .collect::<Vec<_>>();
+            if matched_webhooks.is_empty() {
+                continue;
+            }
+            let payload = match serde_json::to_vec(&notification(*id, event, subject)) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    error!("Serialize asset event {}: {}", id, e);
+                    continue;
+                }
+            };
+            deliveries.extend(
+                matched_webhooks
+                    .into_iter()
+                    .map(|webhook_id| WebhookDelivery {
+                        webhook_id,
+                        payload: payload.clone(),
+                        attempts: 0,
+                        next_attempt_at: now,
+                        last_error: None,
+                    }),
+            );
+        }
+
+        let event_ids = events.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        if let Err(e) = self
+            .storage
+            .dispatch_asset_events(&event_ids, &deliveries)
+            .await
+        {
+            error!("Dispatch asset events: {}", e);
+            return 0;
+        }
+        self.change_outbox_depth(deliveries.len() as i64);
+
+        event_ids.len()
+    }
+
+    /// Sends the notifications whose attempt time has come. The notifications of a webhook
+    /// are sent one by one in their order, while the webhooks are sent to concurrently,
+    /// so a slow webhook only delays its own notifications. Returns the number of the sent ones
+    pub async fn send_deliveries(&self, rx: &Receiver<()>) -> usize {
+        let deliveries = match self
+            .storage
+            .get_due_webhook_deliveries(Utc::now().timestamp(), DELIVERIES_BATCH_SIZE)
+        {
+            Ok(deliveries) => deliveries,
+            Err(e) => {
+                error!("Get due webhook deliveries: {}", e);
+                return 0;
+            }
+        };
+        if deliveries.is_empty() || !rx.is_empty() {
+            return 0;
+        }
+        let Some(webhooks) = self.webhooks().await else {
+            return 0;
+        };
+
+        let sent = deliveries.len();
+        let mut webhook_deliveries = HashMap::<u64, Vec<(u64, WebhookDelivery)>>::new();
+        for (id, delivery) in deliveries {
+            webhook_deliveries
+                .entry(delivery.webhook_id)
+                .or_default()
+                .push((id, delivery));
+        }
+        join_all(webhook_deliveries.into_values().map(|deliveries| {
+            let webhooks = webhooks.clone();
+            async move {
+                for (id, delivery) in deliveries {
+                    self.send_delivery(&webhooks, id, delivery).await;
+                }
+            }
+        }))
+        .await;
+
+        sent
+    }
+
+    async fn send_delivery(
+        &self,
+        webhooks: &HashMap<u64, Webhook>,
+        id: u64,
+        mut delivery: WebhookDelivery,
+    ) {
+        let Some(webhook) = webhooks.get(&delivery.webhook_id) else {
+            // the webhook was deleted after the event was dispatched
+            self.delete_delivery(id, &delivery).await;
+            return;
+        };
+        let error = match self.send(webhook, id, &delivery.payload).await {
+            Ok(()) => {
+                self.metrics.inc_webhook_deliveries("delivered");
+                self.delete_delivery(id, &delivery).await;
+                return;
+            }
+            Err(e) => e,
+        };
+
+        delivery.attempts += 1;
+        if delivery.attempts >= self.max_attempts {
+            warn!(
+                "Dropping notification {} of webhook {} after {} attempts: {}",
+                id, webhook.id, delivery.attempts, error
+            );
+            self.metrics.inc_webhook_deliveries("dropped");
+            self.delete_delivery(id, &delivery).await;
+            return;
+        }
+        self.metrics.inc_webhook_deliveries("retried");
+        let previous_attempt_at = delivery.next_attempt_at;
+        delivery.next_attempt_at =
+            Utc::now().timestamp() + retry_delay_sec(self.retry_base_sec, delivery.attempts) as i64;
+        delivery.last_error = Some(error);
+        if let Err(e) = self
+            .storage
+            .reschedule_webhook_delivery(id, previous_attempt_at, &delivery)
+            .await
+        {
+            error!("Put webhook delivery {}: {}", id, e);
+        }
+    }
+
+    async fn send(&self, webhook: &Webhook, id: u64, payload: &[u8]) -> Result<(), String> {
+        // the resolver only covers the hosts given by the name
+        if !self.allow_private_hosts {
+            let url = Url::parse(&webhook.url).map_err(|e| e.to_string())?;
+            validate_webhook_url(&url)?;
+        }
+        let timestamp = Utc::now().timestamp();
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_ID_HEADER, webhook.id.to_string())
+            .header(DELIVERY_ID_HEADER, id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, sign(&webhook.secret, timestamp, payload))
+            .body(payload.to_vec())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Response status: {}", response.status()));
+        }
+
+        Ok(())
+    }
+
+    async fn delete_delivery(&self, id: u64, delivery: &WebhookDelivery) {
+        match self.storage.delete_webhook_delivery(id, delivery).await {
+            Ok(()) => self.change_outbox_depth(-1),
+            Err(e) => error!("Delete webhook delivery {}: {}", id, e),
+        }
+    }
+
+    fn change_outbox_depth(&self, change: i64) {
+        let depth = self.outbox_depth.fetch_add(change, Ordering::Relaxed) + change;
+        self.metrics.set_webhook_outbox_depth(depth);
+    }
+
+    /// The registered webhooks, reloaded once the loaded ones get older than the refresh interval.
+    /// The loaded ones are kept in use if the reload fails
+    async fn webhooks(&self) -> Option<Arc<HashMap<u64, Webhook>>> {
+        let mut cached = self.webhooks.lock().await;
+        if let Some(cached) = cached.as_ref() {
+            if cached.loaded_at.elapsed() < WEBHOOKS_REFRESH_INTERVAL {
+                return Some(cached.webhooks.clone());
+            }
+        }
+        match self.pg_client.get_webhooks(None, None, u64::MAX).await {
+            Ok(webhooks) => {
+                let webhooks = Arc::new(
+                    webhooks
+                        .into_iter()
+                        .map(|webhook| (webhook.id, webhook))
+                        .collect::<HashMap<_, _>>(),
+                );
+                *cached = Some(CachedWebhooks {
+                    webhooks: webhooks.clone(),
+                    loaded_at: Instant::now(),
+                });
+                Some(webhooks)
+            }
+            Err(e) => {
+                error!("Get webhooks: {}", e);
+                cached.as_ref().map(|cached| cached.webhooks.clone())
+            }
+        }
+    }
+
+    /// The event is stored in the same batch as the asset updates it was made by,
+    /// so the asset is up to date with it
+    fn event_subject(&self, event: &AssetEvent) -> EventSubject {
+        let asset_id = event.asset_id;
+        let owner = event.owner.or_else(|| {
+            self.storage
+                .asset_owner_data
+                .get(asset_id)
+                .ok()
+                .flatten()
+                .and_then(|owner| owner.owner.value)
+        });
+        let collection = self
+            .storage
+            .asset_collection_data
+            .get(asset_id)
+            .ok()
+            .flatten()
+            .map(|collection| collection.collection.value);
+        let creators = self
+            .storage
+            .asset_dynamic_data
+            .get(asset_id)
+            .ok()
+            .flatten()
+            .map(|dynamic_data| {
+                dynamic_data
+                    .creators
+                    .value
+                    .iter()
+                    .map(|creator| creator.creator)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let tree = event.tree.or_else(|| {
+            self.storage
+                .asset_leaf_data
+                .get(asset_id)
+                .ok()
+                .flatten()
+                .map(|leaf| leaf.tree_id)
+        });
+
+        EventSubject {
+            owner,
+            collection,
+            creators,
+            tree,
+        }
+    }
+}
+
+fn matches(filter: &WebhookFilter, event: &AssetEvent, subject: &EventSubject) -> bool {
+    allows(&filter.event_types, std::iter::once(event.event_type))
+        && allows(&filter.asset_ids, std::iter::once(event.asset_id))
+        && allows(&filter.owners, subject.owner.into_iter())
+        && allows(&filter.collections, subject.collection.into_iter())
+        && allows(&filter.creators, subject.creators.iter().copied())
+        && allows(&filter.trees, subject.tree.into_iter())
+}
+
+/// An empty filter list allows any value, otherwise one of the values has to be in it
+fn allows<T: PartialEq>(allowed: &[T], mut values: impl Iterator<Item = T>) -> bool {
+    allowed.is_empty() || values.any(|value| allowed.contains(&value))
+}
+
+fn notification(id: u64, event: &AssetEvent, subject: EventSubject) -> AssetEventNotification {
+    AssetEventNotification {
+        event_id: id.to_string(),
+        event_type: event.event_type,
+        asset_id: event.asset_id.to_string(),
+        slot: event.slot,
+        signature: event.signature.clone(),
+        tree: subject.tree.map(|tree| tree.to_string()),
+        owner: subject.owner.map(|owner| owner.to_string()),
+        collection: subject.collection.map(|collection| collection.to_string()),
+        creators: subject
+            .creators
+            .iter()
+            .map(|creator| creator.to_string())
+            .collect(),
+    }
+}
+
+/// Checks the url of a webhook before it is registered and sent to.
+/// The names resolved to the private addresses are refused by the resolver of the sender,
+/// as they may be changed after the registration
+pub fn validate_webhook_url(url: &Url) -> Result<(), String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("Webhook url should be an HTTP one".to_string());
+    }
+    let public = match url.host() {
+        None => return Err("Webhook url should have a host".to_string()),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+        Some(Host::Ipv4(ip)) => is_public_ip(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => is_public_ip(IpAddr::V6(ip)),
+    };
+    if !public {
+        return Err("Webhook url should point to a public host".to_string());
+    }
+
+    Ok(())
+}
+
+/// Whether the address may be reached by the webhooks: not a loopback, private, link-local
+/// (the cloud metadata services among them), shared or any other special purpose one
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // "this network"
+        || first == 0
+        // carrier-grade NAT
+        || (first == 100 && second & 0xc0 == 64)
+        // reserved for the future use
+        || first >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first_segment = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // unique local
+        || first_segment & 0xfe00 == 0xfc00
+        // link-local
+        || first_segment & 0xffc0 == 0xfe80
+        // documentation
+        || (first_segment == 0x2001 && ip.segments()[1] == 0xdb8))
+}
+
+/// Resolves the hosts of the webhooks leaving out the addresses which aren't public,
+/// so a webhook can't reach the private network by a name pointing into it
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(resolve_public_addrs(name))
+    }
+}
+
+async fn resolve_public_addrs(
+    name: Name,
+) -> Result<Addrs, Box<dyn std::error::Error + Send + Sync>> {
+    let addrs = tokio::net::lookup_host((name.as_str(), 0))
+        .await?
+        .filter(|addr| is_public_ip(addr.ip()))
+        .collect::<Vec<_>>();
+    if addrs.is_empty() {
+        return Err(format!("{} has no public address", name.as_str()).into());
+    }
+
+    Ok(Box::new(addrs.into_iter()))
+}
+
+/// Signs the notification body along with the time it is sent at, so the receiver can check
+/// it was sent by the indexer and isn't an old request sent again
+pub fn sign(secret: &str, timestamp: i64, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes a key of any size");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Delay before the next attempt, doubled with every failed one
+pub fn retry_delay_sec(retry_base_sec: u64, attempts: u32) -> u64 {
+    let factor = 1u64
+        .checked_shl(attempts.saturating_sub(1))
+        .unwrap_or(u64::MAX);
+    retry_base_sec
+        .saturating_mul(factor)
+        .min(MAX_RETRY_INTERVAL_SEC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_webhook_url() {
+        for url in [
+            "https://example.com/hook",
+            "http://8.8.8.8:8080/hook",
+            "https://[2606:4700::1111]/hook",
+        ] {
+            assert_eq!(
+                validate_webhook_url(&Url::parse(url).unwrap()),
+                Ok(()),
+                "{url}"
+            );
+        }
+        for url in [
+            "ftp://example.com/hook",
+            "http://localhost:8080/hook",
+            "http://api.localhost./hook",
+            "http://127.0.0.1/hook",
+            // the decimal form of 127.0.0.1
+            "http://2130706433/hook",
+            "http://0.0.0.0/hook",
+            "http://10.1.2.3/hook",
+            "http://172.16.0.1/hook",
+            "http://192.168.1.1/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://100.64.0.1/hook",
+            "http://[::1]/hook",
+            "http://[::ffff:10.0.0.1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[fe80::1]/hook",
+        ] {
+            assert!(
+                validate_webhook_url(&Url::parse(url).unwrap()).is_err(),
+                "{url}"
+            );
+        }
+    }
+}
@@ -12,18 +12,19 @@ mod tests {
 
     use blockbuster::token_metadata::accounts::Metadata;
     use entities::api_req_params::{
-        DisplayOptions, GetAssetProof, GetAssetSignatures, GetByMethodsOptions, GetCoreFees,
-        GetTokenAccounts, Options, SearchAssetsOptions,
+        Commitment, DisplayOptions, GetAssetProof, GetAssetSignatures, GetByMethodsOptions,
+        GetCoreFees, GetNftEditions, GetTokenAccounts, GetTreeInfo, Options, SearchAssetsOptions,
     };
-    use entities::enums::TokenType;
+    use entities::enums::{TokenMetadataEdition, TokenType};
     use entities::models::{
-        AssetSignature, AssetSignatureKey, BurntMetadataSlot, MetadataInfo, Mint, OffChainData,
-        TokenAccount,
+        AssetSignature, AssetSignatureKey, BurntMetadataSlot, EditionV1, MetadataInfo, Mint,
+        OffChainData, SignatureWithSlot, TokenAccount,
     };
     use entities::{
         api_req_params::{
-            AssetSortBy, AssetSortDirection, AssetSorting, GetAsset, GetAssetsByAuthority,
-            GetAssetsByCreator, GetAssetsByGroup, GetAssetsByOwner, SearchAssets,
+            AssetSortBy, AssetSortDirection, AssetSorting, GetAsset, GetAssetBatch,
+            GetAssetsByAuthority, GetAssetsByCreator, GetAssetsByGroup, GetAssetsByOwner,
+            SearchAssets,
         },
         enums::{
             ChainMutability, Interface, OwnerType, OwnershipModel, RoyaltyModel, RoyaltyTargetType,
@@ -38,7 +39,8 @@ mod tests {
     use mpl_token_metadata::accounts::MasterEdition;
     use mpl_token_metadata::types::Key;
     use nft_ingester::api::dapi::response::{
-        AssetList, CoreFeesAccountsList, TokenAccountsList, TransactionSignatureList,
+        AssetList, CoreFeesAccountsList, NftEditionsList, TokenAccountsList,
+        TransactionSignatureList, TreeInfo as TreeInfoResponse,
     };
     use nft_ingester::api::dapi::rpc_asset_models::Asset;
     use nft_ingester::api::error::DasApiError;
@@ -50,9 +52,14 @@ mod tests {
         token_updates_processor::TokenAccountsProcessor,
     };
     use rocks_db::asset::AssetLeaf;
+    use rocks_db::asset::MetadataMintMap;
     use rocks_db::batch_savers::BatchSaveStorage;
+    use rocks_db::editions::EditionMintMap;
     use rocks_db::inscriptions::{Inscription, InscriptionData};
-    use rocks_db::tree_seq::TreesGaps;
+    use rocks_db::parameters::Parameter;
+    use rocks_db::transaction::TransactionResult;
+    use rocks_db::tree_info::{TreeConfig, TreeInfo};
+    use rocks_db::tree_seq::{TreeSeqIdx, TreesGaps};
     use rocks_db::{AssetAuthority, AssetDynamicDetails, AssetOwner, AssetStaticDetails};
     use serde_json::{json, Value};
     use solana_program::pubkey::Pubkey;
@@ -626,6 +633,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let response = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
@@ -749,6 +758,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let response = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
@@ -887,6 +898,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let response = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
@@ -922,6 +935,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let response = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
@@ -1081,6 +1096,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let response = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
@@ -1093,6 +1110,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let response = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
@@ -1247,6 +1266,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let response = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
@@ -1476,6 +1497,167 @@ mod tests {
         env.teardown().await;
     }
 
+    #[tokio::test]
+    async fn test_asset_account_signatures() {
+        let cnt = 0;
+        let cli = Cli::default();
+        let (env, _) = setup::TestEnvironment::create(&cli, cnt, SLOT_UPDATED).await;
+        let api = nft_ingester::api::api_impl::DasApi::<
+            MaybeProofChecker,
+            JsonWorker,
+            JsonWorker,
+            MockAccountBalanceGetter,
+            RaydiumTokenPriceFetcher,
+        >::new(
+            env.pg_env.client.clone(),
+            env.rocks_env.storage.clone(),
+            Arc::new(ApiMetricsConfig::new()),
+            None,
+            50,
+            None,
+            None,
+            JsonMiddlewareConfig::default(),
+            Arc::new(MockAccountBalanceGetter::new()),
+            None,
+            Arc::new(RaydiumTokenPriceFetcher::default()),
+        );
+
+        let mint = Pubkey::new_unique();
+        let metadata = Pubkey::new_unique();
+        env.rocks_env
+            .storage
+            .asset_static_data
+            .put(
+                mint,
+                AssetStaticDetails {
+                    pubkey: mint,
+                    specification_asset_class: SpecificationAssetClass::Nft,
+                    royalty_target_type: RoyaltyTargetType::Creators,
+                    created_at: 1,
+                    edition_address: None,
+                },
+            )
+            .unwrap();
+        env.rocks_env
+            .storage
+            .metadata_mint_map
+            .put(
+                metadata,
+                MetadataMintMap {
+                    pubkey: metadata,
+                    mint_key: mint,
+                },
+            )
+            .unwrap();
+
+        let mut signatures = Vec::new();
+        for slot in 1..=10 {
+            let signature = Signature::new_unique();
+            // every transaction touches both the mint and the metadata and an unrelated account,
+            // the signature must be recorded once for the asset
+            let accounts = if slot % 2 == 0 {
+                vec![metadata, mint, Pubkey::new_unique()]
+            } else {
+                vec![mint, metadata, Pubkey::new_unique()]
+            };
+            env.rocks_env
+                .storage
+                .store_transaction_result(
+                    &TransactionResult {
+                        instruction_results: vec![],
+                        transaction_signature: Some((
+                            mpl_bubblegum::programs::MPL_BUBBLEGUM_ID,
+                            SignatureWithSlot { signature, slot },
+                        )),
+                        asset_accounts: accounts
+                            .into_iter()
+                            .map(|account| (account, "Transfer".to_string()))
+                            .collect(),
+                    },
+                    true,
+                )
+                .await
+                .unwrap();
+            signatures.push(signature);
+        }
+
+        let payload = GetAssetSignatures {
+            id: Some(mint.to_string()),
+            limit: Some(4),
+            page: None,
+            before: None,
+            after: None,
+            tree: None,
+            leaf_index: None,
+            sort_direction: None,
+            cursor: None,
+        };
+        let response = api.get_asset_signatures(payload, false).await.unwrap();
+        let parsed_response: TransactionSignatureList = serde_json::from_value(response).unwrap();
+
+        assert_eq!(parsed_response.items.len(), 4);
+        assert_eq!(
+            parsed_response.before,
+            Some(format!("10:{}", signatures[9]))
+        );
+        assert_eq!(parsed_response.after, Some(format!("7:{}", signatures[6])));
+
+        let payload = GetAssetSignatures {
+            id: Some(mint.to_string()),
+            limit: Some(4),
+            page: None,
+            before: None,
+            after: None,
+            tree: None,
+            leaf_index: None,
+            sort_direction: None,
+            cursor: parsed_response.after,
+        };
+        let response = api.get_asset_signatures(payload, false).await.unwrap();
+        let parsed_response: TransactionSignatureList = serde_json::from_value(response).unwrap();
+
+        assert_eq!(parsed_response.items.len(), 4);
+        assert_eq!(parsed_response.before, Some(format!("6:{}", signatures[5])));
+        assert_eq!(parsed_response.after, Some(format!("3:{}", signatures[2])));
+
+        let payload = GetAssetSignatures {
+            id: Some(mint.to_string()),
+            limit: Some(100),
+            page: None,
+            before: Some(format!("5:{}", signatures[4])),
+            after: None,
+            tree: None,
+            leaf_index: None,
+            sort_direction: Some(AssetSortDirection::Asc),
+            cursor: None,
+        };
+        let response = api.get_asset_signatures(payload, false).await.unwrap();
+        let parsed_response: TransactionSignatureList = serde_json::from_value(response).unwrap();
+
+        assert_eq!(parsed_response.items.len(), 4);
+        assert_eq!(parsed_response.before, Some(format!("1:{}", signatures[0])));
+        assert_eq!(parsed_response.after, Some(format!("4:{}", signatures[3])));
+
+        // an asset without any recorded transactions returns an empty list
+        let payload = GetAssetSignatures {
+            id: Some(Pubkey::new_unique().to_string()),
+            limit: Some(100),
+            page: None,
+            before: None,
+            after: None,
+            tree: None,
+            leaf_index: None,
+            sort_direction: None,
+            cursor: None,
+        };
+        let response = api.get_asset_signatures(payload, false).await.unwrap();
+        let parsed_response: TransactionSignatureList = serde_json::from_value(response).unwrap();
+
+        assert!(parsed_response.items.is_empty());
+
+        env.teardown().await;
+    }
+
     #[tokio::test]
     async fn test_token_accounts() {
         let cnt = 20;
@@ -2002,6 +2184,7 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            commitment: None,
         };
         let res = api
             .get_assets_by_owner(payload, mutexed_tasks.clone())
@@ -2349,6 +2532,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
 
         let response = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
@@ -2447,6 +2632,8 @@ mod tests {
             .unwrap();
         let payload = GetAssetProof {
             id: asset_id.to_string(),
+            root_seq: None,
+            slot: None,
         };
         let res = api.get_asset_proof(payload).await.err().unwrap();
         assert!(matches!(res, DasApiError::CannotServiceRequest));
@@ -2784,6 +2971,8 @@ mod tests {
                 show_collection_metadata: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let res = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
         let res: Asset = serde_json::from_value(res).unwrap();
@@ -2870,6 +3059,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let res = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
         let res: Asset = serde_json::from_value(res).unwrap();
@@ -2947,6 +3138,8 @@ mod tests {
                 show_inscription: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let res = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
         let res: Asset = serde_json::from_value(res).unwrap();
@@ -2966,6 +3159,8 @@ mod tests {
                 show_inscription: false,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let res = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
         let res: Asset = serde_json::from_value(res).unwrap();
@@ -3422,6 +3617,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let res = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
         let res: Asset = serde_json::from_value(res).unwrap();
@@ -3452,4 +3649,758 @@ mod tests {
         });
         assert_eq!(res.mint_extensions.unwrap(), reference)
     }
+
+    #[tokio::test]
+    async fn test_get_nft_editions() {
+        let cli = Cli::default();
+        let (env, _) = setup::TestEnvironment::create(&cli, 1, SLOT_UPDATED).await;
+        let api = nft_ingester::api::api_impl::DasApi::<
+            MaybeProofChecker,
+            JsonWorker,
+            JsonWorker,
+            MockAccountBalanceGetter,
+            RaydiumTokenPriceFetcher,
+        >::new(
+            env.pg_env.client.clone(),
+            env.rocks_env.storage.clone(),
+            Arc::new(ApiMetricsConfig::new()),
+            None,
+            50,
+            None,
+            None,
+            JsonMiddlewareConfig::default(),
+            Arc::new(MockAccountBalanceGetter::new()),
+            None,
+            Arc::new(RaydiumTokenPriceFetcher::default()),
+        );
+        let mplx_accs_parser = MplxAccountsProcessor::new(Arc::new(IngesterMetricsConfig::new()));
+        let mut batch_storage = BatchSaveStorage::new(
+            env.rocks_env.storage.clone(),
+            10,
+            Arc::new(IngesterMetricsConfig::new()),
+        );
+
+        let master_mint = Pubkey::new_unique();
+        let master_edition = MasterEdition::find_pda(&master_mint).0;
+        mplx_accs_parser
+            .transform_and_store_edition_account(
+                &mut batch_storage,
+                master_edition,
+                &TokenMetadataEdition::MasterEdition(entities::models::MasterEdition {
+                    key: master_edition,
+                    supply: 5,
+                    max_supply: Some(500),
+                    write_version: 1,
+                }),
+            )
+            .unwrap();
+        let mut print_mints = Vec::new();
+        for edition in 1..=5 {
+            let print_mint = Pubkey::new_unique();
+            let print_edition = MasterEdition::find_pda(&print_mint).0;
+            mplx_accs_parser
+                .transform_and_store_edition_account(
+                    &mut batch_storage,
+                    print_edition,
+                    &TokenMetadataEdition::EditionV1(EditionV1 {
+                        key: print_edition,
+                        parent: master_edition,
+                        edition,
+                        write_version: 1,
+                    }),
+                )
+                .unwrap();
+            env.rocks_env
+                .storage
+                .edition_mint_map
+                .put(
+                    print_edition,
+                    EditionMintMap {
+                        pubkey: print_edition,
+                        mint_key: print_mint,
+                    },
+                )
+                .unwrap();
+            print_mints.push(print_mint.to_string());
+        }
+        // a print of another master must not be listed
+        mplx_accs_parser
+            .transform_and_store_edition_account(
+                &mut batch_storage,
+                Pubkey::new_unique(),
+                &TokenMetadataEdition::EditionV1(EditionV1 {
+                    key: Pubkey::new_unique(),
+                    parent: Pubkey::new_unique(),
+                    edition: 3,
+                    write_version: 1,
+                }),
+            )
+            .unwrap();
+        batch_storage.flush().unwrap();
+
+        let payload = GetNftEditions {
+            mint_address: master_mint.to_string(),
+            limit: Some(3),
+            page: None,
+            before: None,
+            after: None,
+            cursor: None,
+        };
+        let res = api.get_nft_editions(payload).await.unwrap();
+        let res: NftEditionsList = serde_json::from_value(res).unwrap();
+        assert_eq!(res.master_edition_address, master_edition.to_string());
+        assert_eq!(res.supply, 5);
+        assert_eq!(res.max_supply, Some(500));
+        assert_eq!(
+            res.editions.iter().map(|e| e.edition).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            res.editions
+                .iter()
+                .map(|e| e.mint.clone().unwrap())
+                .collect::<Vec<_>>(),
+            print_mints[..3].to_vec()
+        );
+
+        let payload = GetNftEditions {
+            mint_address: master_mint.to_string(),
+            limit: Some(3),
+            page: None,
+            before: None,
+            after: None,
+            cursor: res.cursor,
+        };
+        let res = api.get_nft_editions(payload).await.unwrap();
+        let res: NftEditionsList = serde_json::from_value(res).unwrap();
+        assert_eq!(
+            res.editions.iter().map(|e| e.edition).collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+
+        let payload = GetNftEditions {
+            mint_address: master_mint.to_string(),
+            limit: Some(2),
+            page: None,
+            before: Some("4".to_string()),
+            after: None,
+            cursor: None,
+        };
+        let res = api.get_nft_editions(payload).await.unwrap();
+        let res: NftEditionsList = serde_json::from_value(res).unwrap();
+        assert_eq!(
+            res.editions.iter().map(|e| e.edition).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+
+        let payload = GetNftEditions {
+            mint_address: Pubkey::new_unique().to_string(),
+            limit: None,
+            page: None,
+            before: None,
+            after: None,
+            cursor: None,
+        };
+        assert!(matches!(
+            api.get_nft_editions(payload).await,
+            Err(DasApiError::NoDataFoundError)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_tree_info() {
+        let cli = Cli::default();
+        let (env, _) = setup::TestEnvironment::create(&cli, 0, SLOT_UPDATED).await;
+        let api = nft_ingester::api::api_impl::DasApi::<
+            MaybeProofChecker,
+            JsonWorker,
+            JsonWorker,
+            MockAccountBalanceGetter,
+            RaydiumTokenPriceFetcher,
+        >::new(
+            env.pg_env.client.clone(),
+            env.rocks_env.storage.clone(),
+            Arc::new(ApiMetricsConfig::new()),
+            None,
+            50,
+            None,
+            None,
+            JsonMiddlewareConfig::default(),
+            Arc::new(MockAccountBalanceGetter::new()),
+            None,
+            Arc::new(RaydiumTokenPriceFetcher::default()),
+        );
+
+        let tree = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let storage = env.rocks_env.storage.clone();
+        // mints may be indexed before the tree creation
+        storage
+            .tree_info
+            .merge(
+                tree,
+                TreeInfo {
+                    tree,
+                    config: None,
+                    num_minted: 5,
+                    delegate: None,
+                },
+            )
+            .await
+            .unwrap();
+        storage
+            .tree_info
+            .merge(
+                tree,
+                TreeInfo {
+                    tree,
+                    config: Some(TreeConfig {
+                        max_depth: 14,
+                        max_buffer_size: 64,
+                        canopy_depth: Some(10),
+                        tree_creator: creator,
+                        tree_delegate: creator,
+                        is_public: false,
+                        created_at_slot: 100,
+                    }),
+                    num_minted: 0,
+                    delegate: None,
+                },
+            )
+            .await
+            .unwrap();
+        storage
+            .tree_info
+            .merge(
+                tree,
+                TreeInfo {
+                    tree,
+                    config: None,
+                    num_minted: 3,
+                    delegate: None,
+                },
+            )
+            .await
+            .unwrap();
+        // the delegate set by SetTreeDelegate replaces the creator
+        let delegate = Pubkey::new_unique();
+        storage
+            .tree_info
+            .merge(
+                tree,
+                TreeInfo {
+                    tree,
+                    config: None,
+                    num_minted: 0,
+                    delegate: Some(Updated::new(150, None, delegate)),
+                },
+            )
+            .await
+            .unwrap();
+        for seq in 0..5 {
+            storage
+                .tree_seq_idx
+                .put((tree, seq), TreeSeqIdx { slot: 100 + seq })
+                .unwrap();
+        }
+        // seq of another tree must not be taken into account
+        storage
+            .tree_seq_idx
+            .put((Pubkey::new_unique(), 10), TreeSeqIdx { slot: 500 })
+            .unwrap();
+
+        let payload = GetTreeInfo {
+            tree: tree.to_string(),
+        };
+        let res = api.get_tree_info(payload.clone()).await.unwrap();
+        let res: TreeInfoResponse = serde_json::from_value(res).unwrap();
+        assert_eq!(
+            res,
+            TreeInfoResponse {
+                tree: tree.to_string(),
+                max_depth: Some(14),
+                max_buffer_size: Some(64),
+                canopy_depth: Some(10),
+                tree_creator: Some(creator.to_string()),
+                tree_delegate: Some(delegate.to_string()),
+                is_public: Some(false),
+                created_at_slot: Some(100),
+                total_mint_capacity: Some(16384),
+                num_minted: 5,
+                seq: Some(4),
+                seq_slot: Some(104),
+                is_consistent: true,
+            }
+        );
+
+        storage.trees_gaps.put(tree, TreesGaps {}).unwrap();
+        let res = api.get_tree_info(payload).await.unwrap();
+        let res: TreeInfoResponse = serde_json::from_value(res).unwrap();
+        assert!(!res.is_consistent);
+
+        let payload = GetTreeInfo {
+            tree: Pubkey::new_unique().to_string(),
+        };
+        assert!(matches!(
+            api.get_tree_info(payload).await,
+            Err(DasApiError::NoDataFoundError)
+        ));
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_get_asset_at_slot() {
+        let cnt = 0;
+        let cli = Cli::default();
+        let (env, _) = setup::TestEnvironment::create(&cli, cnt, SLOT_UPDATED).await;
+        let api = nft_ingester::api::api_impl::DasApi::<
+            MaybeProofChecker,
+            JsonWorker,
+            JsonWorker,
+            MockAccountBalanceGetter,
+            RaydiumTokenPriceFetcher,
+        >::new(
+            env.pg_env.client.clone(),
+            env.rocks_env.storage.clone(),
+            Arc::new(ApiMetricsConfig::new()),
+            None,
+            50,
+            None,
+            None,
+            JsonMiddlewareConfig::default(),
+            Arc::new(MockAccountBalanceGetter::new()),
+            None,
+            Arc::new(RaydiumTokenPriceFetcher::default()),
+        );
+        let tasks = JoinSet::new();
+        let mutexed_tasks = Arc::new(Mutex::new(tasks));
+        env.rocks_env.storage.set_asset_history_enabled(true);
+
+        let pb = Pubkey::new_unique();
+        let first_owner = Pubkey::new_unique();
+        let second_owner = Pubkey::new_unique();
+
+        env.rocks_env
+            .storage
+            .asset_static_data
+            .put(
+                pb,
+                AssetStaticDetails {
+                    pubkey: pb,
+                    specification_asset_class: SpecificationAssetClass::Nft,
+                    royalty_target_type: RoyaltyTargetType::Creators,
+                    created_at: 10,
+                    edition_address: None,
+                },
+            )
+            .unwrap();
+
+        let mut batch_storage = BatchSaveStorage::new(
+            env.rocks_env.storage.clone(),
+            10,
+            Arc::new(IngesterMetricsConfig::new()),
+        );
+        batch_storage
+            .store_dynamic(&AssetDynamicDetails {
+                pubkey: pb,
+                supply: Some(Updated::new(10, None, 1)),
+                url: Updated::new(10, None, "https://first".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        for (slot, owner) in [(10, first_owner), (20, second_owner)] {
+            batch_storage
+                .store_owner(&AssetOwner {
+                    pubkey: pb,
+                    owner: Updated::new(slot, None, Some(owner)),
+                    owner_type: Updated::new(slot, None, OwnerType::Single),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        batch_storage
+            .store_dynamic(&AssetDynamicDetails {
+                pubkey: pb,
+                url: Updated::new(30, None, "https://second".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        batch_storage.flush().unwrap();
+
+        let get_asset_at = |at_slot| GetAsset {
+            id: pb.to_string(),
+            options: Some(Options {
+                show_unverified_collections: true,
+                ..Default::default()
+            }),
+            at_slot,
+            commitment: None,
+        };
+
+        let response = api
+            .get_asset(get_asset_at(None), mutexed_tasks.clone())
+            .await
+            .unwrap();
+        assert_eq!(response["ownership"]["owner"], second_owner.to_string());
+        assert_eq!(response["content"]["json_uri"], "https://second");
+
+        let response = api
+            .get_asset(get_asset_at(Some(15)), mutexed_tasks.clone())
+            .await
+            .unwrap();
+        assert_eq!(response["ownership"]["owner"], first_owner.to_string());
+        assert_eq!(response["content"]["json_uri"], "https://first");
+
+        let response = api
+            .get_asset(get_asset_at(Some(25)), mutexed_tasks.clone())
+            .await
+            .unwrap();
+        assert_eq!(response["ownership"]["owner"], second_owner.to_string());
+        assert_eq!(response["content"]["json_uri"], "https://first");
+
+        // the asset didn't exist yet
+        assert!(matches!(
+            api.get_asset(get_asset_at(Some(5)), mutexed_tasks.clone())
+                .await,
+            Err(DasApiError::NoDataFoundError)
+        ));
+
+        let response = api
+            .get_asset_batch(
+                GetAssetBatch {
+                    ids: vec![pb.to_string(), pb.to_string()],
+                    options: Some(Options {
+                        show_unverified_collections: true,
+                        ..Default::default()
+                    }),
+                    at_slot: Some(15),
+                },
+                mutexed_tasks.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response[0]["ownership"]["owner"], first_owner.to_string());
+        assert_eq!(response[1]["ownership"]["owner"], first_owner.to_string());
+
+        // the versions before the cutoff are folded, the state after it is still there
+        env.rocks_env.storage.compact_asset_history(25).unwrap();
+        assert!(matches!(
+            api.get_asset(get_asset_at(Some(15)), mutexed_tasks.clone())
+                .await,
+            Err(DasApiError::HistoryUnavailable(15))
+        ));
+        let response = api
+            .get_asset(get_asset_at(Some(25)), mutexed_tasks.clone())
+            .await
+            .unwrap();
+        assert_eq!(response["ownership"]["owner"], second_owner.to_string());
+        assert_eq!(response["content"]["json_uri"], "https://first");
+
+        env.teardown().await;
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_get_asset_with_commitment() {
+        let cnt = 0;
+        let cli = Cli::default();
+        let (env, _) = setup::TestEnvironment::create(&cli, cnt, SLOT_UPDATED).await;
+        let api = nft_ingester::api::api_impl::DasApi::<
+            MaybeProofChecker,
+            JsonWorker,
+            JsonWorker,
+            MockAccountBalanceGetter,
+            RaydiumTokenPriceFetcher,
+        >::new(
+            env.pg_env.client.clone(),
+            env.rocks_env.storage.clone(),
+            Arc::new(ApiMetricsConfig::new()),
+            None,
+            50,
+            None,
+            None,
+            JsonMiddlewareConfig::default(),
+            Arc::new(MockAccountBalanceGetter::new()),
+            None,
+            Arc::new(RaydiumTokenPriceFetcher::default()),
+        );
+        let tasks = JoinSet::new();
+        let mutexed_tasks = Arc::new(Mutex::new(tasks));
+        // the commitments are served without the asset history
+        env.rocks_env
+            .storage
+            .init_previous_versions_start_slot(10)
+            .await
+            .unwrap();
+
+        let pb = Pubkey::new_unique();
+        let first_owner = Pubkey::new_unique();
+        let second_owner = Pubkey::new_unique();
+
+        env.rocks_env
+            .storage
+            .asset_static_data
+            .put(
+                pb,
+                AssetStaticDetails {
+                    pubkey: pb,
+                    specification_asset_class: SpecificationAssetClass::Nft,
+                    royalty_target_type: RoyaltyTargetType::Creators,
+                    created_at: 10,
+                    edition_address: None,
+                },
+            )
+            .unwrap();
+
+        let mut batch_storage = BatchSaveStorage::new(
+            env.rocks_env.storage.clone(),
+            10,
+            Arc::new(IngesterMetricsConfig::new()),
+        );
+        batch_storage
+            .store_dynamic(&AssetDynamicDetails {
+                pubkey: pb,
+                supply: Some(Updated::new(10, None, 1)),
+                url: Updated::new(10, None, "https://first".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        for (slot, owner) in [(10, first_owner), (20, second_owner)] {
+            batch_storage
+                .store_owner(&AssetOwner {
+                    pubkey: pb,
+                    owner: Updated::new(slot, None, Some(owner)),
+                    owner_type: Updated::new(slot, None, OwnerType::Single),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        batch_storage.asset_updated_with_batch(20, pb).unwrap();
+        batch_storage.flush().unwrap();
+        let synchronizer = nft_ingester::index_syncronizer::Synchronizer::new(
+            env.rocks_env.storage.clone(),
+            env.pg_env.client.clone(),
+            env.pg_env.client.clone(),
+            200_000,
+            "".to_string(),
+            Arc::new(SynchronizerMetricsConfig::new()),
+            1,
+            false,
+        );
+        let (_, rx) = tokio::sync::broadcast::channel::<()>(1);
+        synchronizer
+            .synchronize_asset_indexes(&rx, 0)
+            .await
+            .unwrap();
+
+        let get_asset_with = |commitment| GetAsset {
+            id: pb.to_string(),
+            options: Some(Options {
+                show_unverified_collections: true,
+                ..Default::default()
+            }),
+            at_slot: None,
+            commitment,
+        };
+
+        // the finalized slot is not tracked yet
+        assert!(matches!(
+            api.get_asset(
+                get_asset_with(Some(Commitment::Finalized)),
+                mutexed_tasks.clone()
+            )
+            .await,
+            Err(DasApiError::CannotServiceRequest)
+        ));
+
+        env.rocks_env
+            .storage
+            .put_parameter(Parameter::LastFinalizedSlot, 15u64)
+            .await
+            .unwrap();
+        env.rocks_env
+            .storage
+            .put_parameter(Parameter::LastConfirmedSlot, 20u64)
+            .await
+            .unwrap();
+
+        let response = api
+            .get_asset(
+                get_asset_with(Some(Commitment::Processed)),
+                mutexed_tasks.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response["ownership"]["owner"], second_owner.to_string());
+
+        let response = api
+            .get_asset(
+                get_asset_with(Some(Commitment::Confirmed)),
+                mutexed_tasks.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response["ownership"]["owner"], second_owner.to_string());
+
+        let response = api
+            .get_asset(
+                get_asset_with(Some(Commitment::Finalized)),
+                mutexed_tasks.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response["ownership"]["owner"], first_owner.to_string());
+
+        let get_assets_by_owner_with = |owner: Pubkey, commitment, show_fungible| {
+            let api = &api;
+            let mutexed_tasks = mutexed_tasks.clone();
+            async move {
+                api.get_assets_by_owner(
+                    GetAssetsByOwner {
+                        owner_address: owner.to_string(),
+                        sort_by: None,
+                        limit: None,
+                        page: None,
+                        before: None,
+                        after: None,
+                        cursor: None,
+                        options: Some(GetByMethodsOptions {
+                            show_unverified_collections: true,
+                            show_fungible,
+                            ..Default::default()
+                        }),
+                        commitment,
+                    },
+                    mutexed_tasks,
+                )
+                .await
+            }
+        };
+        let owned_assets = |response: serde_json::Value| {
+            response["items"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| {
+                    (
+                        item["id"].as_str().unwrap().to_string(),
+                        item["ownership"]["owner"].as_str().unwrap().to_string(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // the index holds the latest owner, the owner at the finalized slot is corrected
+        // by the versions replaced since then
+        let response = get_assets_by_owner_with(first_owner, Some(Commitment::Finalized), false)
+            .await
+            .unwrap();
+        assert_eq!(
+            owned_assets(response),
+            vec![(pb.to_string(), first_owner.to_string())]
+        );
+        let response = get_assets_by_owner_with(second_owner, Some(Commitment::Finalized), false)
+            .await
+            .unwrap();
+        assert!(owned_assets(response).is_empty());
+        let response = get_assets_by_owner_with(first_owner, Some(Commitment::Confirmed), false)
+            .await
+            .unwrap();
+        assert!(owned_assets(response).is_empty());
+        let response = get_assets_by_owner_with(second_owner, Some(Commitment::Confirmed), false)
+            .await
+            .unwrap();
+        assert_eq!(
+            owned_assets(response),
+            vec![(pb.to_string(), second_owner.to_string())]
+        );
+
+        // the fungible balances are indexed as of the latest slot only
+        assert!(matches!(
+            get_assets_by_owner_with(first_owner, Some(Commitment::Finalized), true).await,
+            Err(DasApiError::Validation(_))
+        ));
+
+        // the versions replaced up to the finalized slot are dropped
+        env.rocks_env.storage.delete_previous_versions(15).unwrap();
+        let response = api
+            .get_asset(
+                get_asset_with(Some(Commitment::Finalized)),
+                mutexed_tasks.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response["ownership"]["owner"], first_owner.to_string());
+        env.rocks_env.storage.delete_previous_versions(20).unwrap();
+        assert!(matches!(
+            api.get_asset(
+                get_asset_with(Some(Commitment::Finalized)),
+                mutexed_tasks.clone()
+            )
+            .await,
+            Err(DasApiError::HistoryUnavailable(15))
+        ));
+
+        env.teardown().await;
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_get_indexer_status() {
+        let cnt = 0;
+        let cli = Cli::default();
+        let (env, _) = setup::TestEnvironment::create(&cli, cnt, SLOT_UPDATED).await;
+        let api = nft_ingester::api::api_impl::DasApi::<
+            MaybeProofChecker,
+            JsonWorker,
+            JsonWorker,
+            MockAccountBalanceGetter,
+            RaydiumTokenPriceFetcher,
+        >::new(
+            env.pg_env.client.clone(),
+            env.rocks_env.storage.clone(),
+            Arc::new(ApiMetricsConfig::new()),
+            None,
+            50,
+            None,
+            None,
+            JsonMiddlewareConfig::default(),
+            Arc::new(MockAccountBalanceGetter::new()),
+            None,
+            Arc::new(RaydiumTokenPriceFetcher::default()),
+        );
+
+        env.rocks_env
+            .storage
+            .merge_top_parameter(Parameter::TopSeenSlot, 100u64)
+            .await
+            .unwrap();
+        env.rocks_env
+            .storage
+            .put_parameter(Parameter::LastConfirmedSlot, 110u64)
+            .await
+            .unwrap();
+        env.rocks_env
+            .storage
+            .put_parameter(Parameter::LastBackfilledSlot, 90u64)
+            .await
+            .unwrap();
+        env.rocks_env
+            .storage
+            .trees_gaps
+            .put(Pubkey::new_unique(), TreesGaps {})
+            .unwrap();
+
+        let response = api.get_indexer_status().await.unwrap();
+        assert_eq!(response["last_ingested_slot"], 100);
+        assert_eq!(response["ingestion_slot_lag"], 10);
+        assert_eq!(response["last_finalized_slot"], Value::Null);
+        assert_eq!(response["last_backfilled_slot"], 90);
+        assert_eq!(response["trees_with_gaps"], 1);
+        assert_eq!(response["json_tasks_pending"], 0);
+        // the storage is not a secondary instance
+        assert_eq!(response["secondary_catch_up_age_sec"], Value::Null);
+
+        env.teardown().await;
+    }
 }
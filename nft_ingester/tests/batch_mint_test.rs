@@ -274,7 +274,7 @@ async fn save_batch_mint_to_queue_test() {
     };
 
     bubblegum_updates_processor
-        .process_transaction(buffered_transaction)
+        .process_transaction(&buffered_transaction)
         .await
         .unwrap();
 
@@ -432,6 +432,8 @@ async fn batch_mint_with_verified_creators_test() {
 
     let payload = GetAssetProof {
         id: metadata_hash_arg.get_asset_id().to_string(),
+        root_seq: None,
+        slot: None,
     };
     let proof_result = api.get_asset_proof(payload).await.unwrap();
     let asset_proof: AssetProof = serde_json::from_value(proof_result).unwrap();
@@ -585,6 +587,8 @@ async fn batch_mint_with_unverified_creators_test() {
 
     let payload = GetAssetProof {
         id: metadata_hash_arg.get_asset_id().to_string(),
+        root_seq: None,
+        slot: None,
     };
 
     // batch_mint update should not be processed
@@ -689,6 +693,8 @@ async fn batch_mint_persister_test() {
             .leaf_update
             .id()
             .to_string(),
+        root_seq: None,
+        slot: None,
     };
     let proof_result = api.get_asset_proof(payload).await.unwrap();
     let asset_proof: AssetProof = serde_json::from_value(proof_result).unwrap();
@@ -143,9 +143,14 @@ mod tests {
         for asset in assets_to_test_proof_for.iter() {
             let payload = GetAssetProof {
                 id: asset.to_string(),
+                root_seq: None,
+                slot: None,
             };
-            let proof_result = api.get_asset_proof(payload).await.unwrap();
+            let mut proof_result = api.get_asset_proof(payload).await.unwrap();
 
+            // the expected proofs were captured before the seq was returned
+            assert!(proof_result["seq"].as_i64().unwrap() > 0);
+            proof_result.as_object_mut().unwrap().remove("seq");
             assert_eq!(proof_result, expected_results[*asset]);
         }
 
@@ -293,6 +298,8 @@ mod tests {
                     show_unverified_collections: true,
                     ..Default::default()
                 }),
+                at_slot: None,
+                commitment: None,
             };
             let asset_info = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
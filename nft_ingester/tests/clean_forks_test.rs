@@ -1,13 +1,17 @@
 use bincode::deserialize;
 use blockbuster::instruction::InstructionBundle;
 use blockbuster::programs::bubblegum::BubblegumInstruction;
-use entities::models::{RawBlock, SignatureWithSlot};
+use entities::enums::AccountTombstone;
+use entities::models::{AssetSignature, RawBlock, SignatureWithSlot, SlotAccountUpdate};
+use interface::fork_cleaner::MockForkedAccountsRollback;
 use metrics_utils::utils::start_metrics;
 use metrics_utils::{MetricState, MetricsTrait};
 use mpl_bubblegum::types::{BubblegumEventType, LeafSchema, Version};
 use mpl_bubblegum::{InstructionName, LeafSchemaEvent};
 use nft_ingester::bubblegum_updates_processor::BubblegumTxProcessor;
 use nft_ingester::fork_cleaner::ForkCleaner;
+use rocks_db::asset_signatures::AssetAccountSignatureIdx;
+use rocks_db::batch_savers::BatchSaveStorage;
 use rocks_db::cl_items::ClItem;
 use rocks_db::column::TypedColumn;
 use rocks_db::transaction::{InstructionResult, TransactionResult, TreeUpdate};
@@ -19,6 +23,7 @@ use solana_transaction_status::UiConfirmedBlock;
 use spl_account_compression::events::ChangeLogEventV1;
 use spl_account_compression::state::PathNode;
 use std::str::FromStr;
+use std::sync::Arc;
 use tokio::sync::broadcast;
 
 #[cfg(test)]
@@ -643,6 +648,8 @@ async fn test_clean_forks() {
     let fork_cleaner = ForkCleaner::new(
         storage.clone(),
         storage.clone(),
+        storage.clone(),
+        Arc::new(MockForkedAccountsRollback::new()),
         metrics_state.fork_cleaner_metrics.clone(),
     );
     fork_cleaner.clean_forks(rx.resubscribe()).await;
@@ -896,6 +903,7 @@ async fn test_process_forked_transaction() {
                 slot: slot_normal_tx,
             },
         )),
+        asset_accounts: vec![],
     };
 
     storage
@@ -912,6 +920,7 @@ async fn test_process_forked_transaction() {
                 slot: slot_forked_tx,
             },
         )),
+        asset_accounts: vec![],
     };
 
     storage
@@ -972,6 +981,8 @@ async fn test_process_forked_transaction() {
     let fork_cleaner = ForkCleaner::new(
         storage.clone(),
         storage.clone(),
+        storage.clone(),
+        Arc::new(MockForkedAccountsRollback::new()),
         metrics_state.fork_cleaner_metrics.clone(),
     );
     fork_cleaner.clean_forks(shutdown_rx.resubscribe()).await;
@@ -1005,3 +1016,313 @@ async fn test_process_forked_transaction() {
     // once fork cleaner processed signatures it has to delete that record
     assert_eq!(signatures_to_check.len(), 0);
 }
+
+#[tokio::test]
+async fn test_rollback_forked_account_updates() {
+    let metrics_state = MetricState::new();
+    let storage = RocksTestEnvironment::new(&[]).storage;
+    storage.set_account_update_slots_enabled(true);
+
+    let normal_slot = 100;
+    let forked_slot = 101;
+    let fresh_slot = 20000;
+    let normal_account = Pubkey::new_unique();
+    let forked_account = Pubkey::new_unique();
+    let fresh_account = Pubkey::new_unique();
+
+    let mut batch_storage =
+        BatchSaveStorage::new(storage.clone(), 10, metrics_state.ingester_metrics.clone());
+    for (slot, pubkey, write_version, tombstone) in [
+        (normal_slot, normal_account, 10, AccountTombstone::Unknown),
+        (
+            forked_slot,
+            forked_account,
+            11,
+            AccountTombstone::BurntMplCore,
+        ),
+        (forked_slot, normal_account, 12, AccountTombstone::Unknown),
+        (
+            fresh_slot,
+            fresh_account,
+            13,
+            AccountTombstone::BurntMetadata,
+        ),
+    ] {
+        batch_storage
+            .account_updated_with_batch(slot, pubkey, write_version, tombstone)
+            .unwrap();
+    }
+    batch_storage.flush().unwrap();
+
+    // only the slots saved in raw_blocks_cbor are known as not forked ones,
+    // the block far ahead is required for SLOT_CHECK_OFFSET
+    for slot in [normal_slot, fresh_slot] {
+        storage
+            .raw_blocks_cbor
+            .put_cbor_encoded(
+                slot,
+                RawBlock {
+                    slot,
+                    block: UiConfirmedBlock {
+                        previous_blockhash: "previousBlockHash".to_string(),
+                        blockhash: "blockHash".to_string(),
+                        parent_slot: slot,
+                        transactions: None,
+                        signatures: None,
+                        rewards: None,
+                        block_time: None,
+                        block_height: None,
+                    },
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let mut accounts_rollback = MockForkedAccountsRollback::new();
+    accounts_rollback
+        .expect_rollback_accounts()
+        .withf(move |accounts: &Vec<SlotAccountUpdate>| {
+            let mut accounts = accounts.clone();
+            accounts.sort_by_key(|account| account.write_version);
+            // the account is restored once even if it was updated in a not forked slot too
+            accounts
+                == vec![
+                    SlotAccountUpdate {
+                        slot: forked_slot,
+                        pubkey: forked_account,
+                        write_version: 11,
+                        tombstone: AccountTombstone::BurntMplCore,
+                    },
+                    SlotAccountUpdate {
+                        slot: forked_slot,
+                        pubkey: normal_account,
+                        write_version: 12,
+                        tombstone: AccountTombstone::Unknown,
+                    },
+                ]
+        })
+        .times(1)
+        .returning(|accounts| accounts.len());
+
+    let (_shutdown_tx, shutdown_rx) = broadcast::channel::<()>(1);
+    let fork_cleaner = ForkCleaner::new(
+        storage.clone(),
+        storage.clone(),
+        storage.clone(),
+        Arc::new(accounts_rollback),
+        metrics_state.fork_cleaner_metrics.clone(),
+    );
+    fork_cleaner.clean_forks(shutdown_rx.resubscribe()).await;
+
+    // the checked updates are dropped, the fresh ones are left for the next scans
+    assert_eq!(
+        storage.account_update_slots_iter().collect::<Vec<_>>(),
+        vec![SlotAccountUpdate {
+            slot: fresh_slot,
+            pubkey: fresh_account,
+            write_version: 13,
+            tombstone: AccountTombstone::BurntMetadata,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_drop_account_updates_out_of_retention() {
+    let metrics_state = MetricState::new();
+    let storage = RocksTestEnvironment::new(&[]).storage;
+    storage.set_account_update_slots_enabled(true);
+
+    let old_slot = 100;
+    let last_slot = 200_000;
+    let old_account = Pubkey::new_unique();
+    let last_account = Pubkey::new_unique();
+
+    let mut batch_storage =
+        BatchSaveStorage::new(storage.clone(), 10, metrics_state.ingester_metrics.clone());
+    for (slot, pubkey, write_version) in
+        [(old_slot, old_account, 10), (last_slot, last_account, 11)]
+    {
+        batch_storage
+            .account_updated_with_batch(slot, pubkey, write_version, AccountTombstone::Unknown)
+            .unwrap();
+    }
+    batch_storage.flush().unwrap();
+
+    // without the raw blocks none of the updates can be checked
+    let (_shutdown_tx, shutdown_rx) = broadcast::channel::<()>(1);
+    let fork_cleaner = ForkCleaner::new(
+        storage.clone(),
+        storage.clone(),
+        storage.clone(),
+        Arc::new(MockForkedAccountsRollback::new()),
+        metrics_state.fork_cleaner_metrics.clone(),
+    );
+    fork_cleaner.clean_forks(shutdown_rx.resubscribe()).await;
+
+    assert_eq!(
+        storage.account_update_slots_iter().collect::<Vec<_>>(),
+        vec![SlotAccountUpdate {
+            slot: last_slot,
+            pubkey: last_account,
+            write_version: 11,
+            tombstone: AccountTombstone::Unknown,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_drop_forked_asset_account_signatures() {
+    let metrics_state = MetricState::new();
+    let storage = RocksTestEnvironment::new(&[]).storage;
+
+    let normal_slot = 100;
+    let forked_slot = 101;
+    let fresh_slot = 20000;
+    let asset = Pubkey::new_unique();
+
+    let mut signatures = Vec::new();
+    for slot in [normal_slot, forked_slot, fresh_slot] {
+        let signature = Signature::new_unique();
+        storage
+            .asset_account_signature_idx
+            .put(
+                (asset, slot, signature),
+                AssetSignature {
+                    tx: signature.to_string(),
+                    instruction: "Transfer".to_string(),
+                    slot,
+                },
+            )
+            .unwrap();
+        signatures.push((asset, slot, signature));
+    }
+
+    // only the slots saved in raw_blocks_cbor are known as not forked ones,
+    // the block far ahead is required for SLOT_CHECK_OFFSET
+    for slot in [normal_slot, fresh_slot] {
+        storage
+            .raw_blocks_cbor
+            .put_cbor_encoded(
+                slot,
+                RawBlock {
+                    slot,
+                    block: UiConfirmedBlock {
+                        previous_blockhash: "previousBlockHash".to_string(),
+                        blockhash: "blockHash".to_string(),
+                        parent_slot: slot,
+                        transactions: None,
+                        signatures: None,
+                        rewards: None,
+                        block_time: None,
+                        block_height: None,
+                    },
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let (_shutdown_tx, shutdown_rx) = broadcast::channel::<()>(1);
+    let fork_cleaner = ForkCleaner::new(
+        storage.clone(),
+        storage.clone(),
+        storage.clone(),
+        Arc::new(MockForkedAccountsRollback::new()),
+        metrics_state.fork_cleaner_metrics.clone(),
+    );
+    fork_cleaner.clean_forks(shutdown_rx.resubscribe()).await;
+
+    assert_eq!(
+        storage
+            .asset_account_signature_idx
+            .iter_start()
+            .filter_map(Result::ok)
+            .map(|(key, _)| AssetAccountSignatureIdx::decode_key(key.to_vec()).unwrap())
+            .collect::<Vec<_>>(),
+        vec![signatures[0], signatures[2]]
+    );
+}
+
+#[tokio::test]
+async fn test_drop_forked_cl_items_history() {
+    use rocks_db::cl_items::ClItemKey;
+    use rocks_db::cl_items_history::ClItemsHistory;
+
+    let metrics_state = MetricState::new();
+    let storage = RocksTestEnvironment::new(&[]).storage;
+
+    let normal_slot = 100;
+    let forked_slot = 101;
+    let fresh_slot = 20000;
+    let tree = Pubkey::new_unique();
+    let key = ClItemKey::new(2, tree);
+
+    let items = [(1, normal_slot), (2, forked_slot), (3, fresh_slot)]
+        .into_iter()
+        .map(|(seq, slot)| ClItem {
+            cli_node_idx: 2,
+            cli_tree_key: tree,
+            cli_leaf_idx: Some(0),
+            cli_seq: seq,
+            cli_level: 0,
+            cli_hash: vec![seq as u8; 32],
+            slot_updated: slot,
+        })
+        .collect::<Vec<_>>();
+    storage
+        .cl_items_history
+        .merge(
+            key.clone(),
+            ClItemsHistory {
+                items,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    for slot in [normal_slot, fresh_slot] {
+        storage
+            .raw_blocks_cbor
+            .put_cbor_encoded(
+                slot,
+                RawBlock {
+                    slot,
+                    block: UiConfirmedBlock {
+                        previous_blockhash: "previousBlockHash".to_string(),
+                        blockhash: "blockHash".to_string(),
+                        parent_slot: slot,
+                        transactions: None,
+                        signatures: None,
+                        rewards: None,
+                        block_time: None,
+                        block_height: None,
+                    },
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let (_shutdown_tx, shutdown_rx) = broadcast::channel::<()>(1);
+    let fork_cleaner = ForkCleaner::new(
+        storage.clone(),
+        storage.clone(),
+        storage.clone(),
+        Arc::new(MockForkedAccountsRollback::new()),
+        metrics_state.fork_cleaner_metrics.clone(),
+    );
+    fork_cleaner.clean_forks(shutdown_rx.resubscribe()).await;
+
+    // the version from the forked slot is dropped, the fresh one is not checked yet
+    let history = storage.cl_items_history.get(key).unwrap().unwrap();
+    assert_eq!(
+        history
+            .items
+            .iter()
+            .map(|item| (item.cli_seq, item.slot_updated))
+            .collect::<Vec<_>>(),
+        vec![(1, normal_slot), (3, fresh_slot)]
+    );
+}
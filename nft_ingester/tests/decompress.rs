@@ -276,6 +276,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let asset_info = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
@@ -364,6 +366,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let asset_info = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
@@ -452,6 +456,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let asset_info = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
@@ -540,6 +546,8 @@ mod tests {
                 show_unverified_collections: true,
                 ..Default::default()
             }),
+            at_slot: None,
+            commitment: None,
         };
         let asset_info = api.get_asset(payload, mutexed_tasks.clone()).await.unwrap();
 
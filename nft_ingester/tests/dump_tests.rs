@@ -207,6 +207,8 @@ mod mtg_441_tests {
                         show_unverified_collections: true,
                         ..Default::default()
                     }),
+                    at_slot: None,
+                    commitment: None,
                 },
                 mutexed_tasks,
             )
@@ -249,6 +251,8 @@ mod mtg_441_tests {
                         show_unverified_collections: true,
                         ..Default::default()
                     }),
+                    at_slot: None,
+                    commitment: None,
                 },
                 mutexed_tasks,
             )
@@ -290,6 +294,8 @@ mod mtg_441_tests {
                         show_unverified_collections: true,
                         ..Default::default()
                     }),
+                    at_slot: None,
+                    commitment: None,
                 },
                 mutexed_tasks,
             )
@@ -331,6 +337,8 @@ mod mtg_441_tests {
                         show_unverified_collections: true,
                         ..Default::default()
                     }),
+                    at_slot: None,
+                    commitment: None,
                 },
                 mutexed_tasks,
             )
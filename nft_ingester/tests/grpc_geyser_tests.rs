@@ -0,0 +1,247 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use entities::enums::UnprocessedAccount;
+use futures::{stream, Stream, StreamExt};
+use interface::signature_persistence::UnprocessedTransactionsGetter;
+use interface::unprocessed_data_getter::UnprocessedAccountsGetter;
+use nft_ingester::asset_account_instructions::AssetAccountProgram;
+use nft_ingester::config::GrpcGeyserConfig;
+use nft_ingester::grpc_geyser_receiver::{connect_to_grpc_geyser, GrpcGeyserReceiver};
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::{Message, MessageHeader};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{SanitizedTransaction, Transaction};
+use solana_transaction_status::TransactionStatusMeta;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, Mutex};
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{Request, Response, Status, Streaming};
+use yellowstone_grpc_proto::convert_to::{create_transaction, create_transaction_meta};
+use yellowstone_grpc_proto::geyser::geyser_server::{Geyser, GeyserServer};
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, GetBlockHeightRequest, GetBlockHeightResponse,
+    GetLatestBlockhashRequest, GetLatestBlockhashResponse, GetSlotRequest, GetSlotResponse,
+    GetVersionRequest, GetVersionResponse, IsBlockhashValidRequest, IsBlockhashValidResponse,
+    PingRequest, PongResponse, SubscribeRequest, SubscribeUpdate, SubscribeUpdateAccount,
+    SubscribeUpdateAccountInfo, SubscribeUpdateTransaction, SubscribeUpdateTransactionInfo,
+};
+
+type UpdatesStream = Pin<Box<dyn Stream<Item = Result<SubscribeUpdate, Status>> + Send>>;
+
+// Replays the prepared updates to every subscriber and remembers the subscription request
+struct MockGeyser {
+    updates: Vec<SubscribeUpdate>,
+    request: Arc<Mutex<Option<SubscribeRequest>>>,
+}
+
+#[tonic::async_trait]
+impl Geyser for MockGeyser {
+    type SubscribeStream = UpdatesStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<Streaming<SubscribeRequest>>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let subscribe_request = request.into_inner().message().await?;
+        *self.request.lock().await = subscribe_request;
+        let updates =
+            stream::iter(self.updates.clone().into_iter().map(Ok)).chain(stream::pending());
+        Ok(Response::new(Box::pin(updates)))
+    }
+
+    async fn ping(&self, _: Request<PingRequest>) -> Result<Response<PongResponse>, Status> {
+        Err(Status::unimplemented("ping"))
+    }
+
+    async fn get_latest_blockhash(
+        &self,
+        _: Request<GetLatestBlockhashRequest>,
+    ) -> Result<Response<GetLatestBlockhashResponse>, Status> {
+        Err(Status::unimplemented("get_latest_blockhash"))
+    }
+
+    async fn get_block_height(
+        &self,
+        _: Request<GetBlockHeightRequest>,
+    ) -> Result<Response<GetBlockHeightResponse>, Status> {
+        Err(Status::unimplemented("get_block_height"))
+    }
+
+    async fn get_slot(
+        &self,
+        _: Request<GetSlotRequest>,
+    ) -> Result<Response<GetSlotResponse>, Status> {
+        Err(Status::unimplemented("get_slot"))
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        _: Request<IsBlockhashValidRequest>,
+    ) -> Result<Response<IsBlockhashValidResponse>, Status> {
+        Err(Status::unimplemented("is_blockhash_valid"))
+    }
+
+    async fn get_version(
+        &self,
+        _: Request<GetVersionRequest>,
+    ) -> Result<Response<GetVersionResponse>, Status> {
+        Err(Status::unimplemented("get_version"))
+    }
+}
+
+fn account_update(pubkey: Pubkey, owner: Pubkey, data: Vec<u8>) -> SubscribeUpdate {
+    SubscribeUpdate {
+        filters: vec!["accounts".to_string()],
+        update_oneof: Some(UpdateOneof::Account(SubscribeUpdateAccount {
+            account: Some(SubscribeUpdateAccountInfo {
+                pubkey: pubkey.to_bytes().to_vec(),
+                lamports: 1,
+                owner: owner.to_bytes().to_vec(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version: 1,
+                txn_signature: None,
+            }),
+            slot: 100,
+            is_startup: false,
+        })),
+    }
+}
+
+fn bubblegum_transaction_update(signature: Signature) -> SubscribeUpdate {
+    let transaction = SanitizedTransaction::from_transaction_for_tests(Transaction {
+        signatures: vec![signature],
+        message: Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![Pubkey::new_unique(), mpl_bubblegum::ID],
+            recent_blockhash: Hash::new_unique(),
+            instructions: vec![CompiledInstruction {
+                program_id_index: 1,
+                accounts: vec![0],
+                data: vec![],
+            }],
+        },
+    });
+    let meta = TransactionStatusMeta {
+        inner_instructions: Some(vec![]),
+        ..Default::default()
+    };
+
+    SubscribeUpdate {
+        filters: vec!["transactions".to_string()],
+        update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                signature: signature.as_ref().to_vec(),
+                is_vote: false,
+                transaction: Some(create_transaction(&transaction)),
+                meta: Some(create_transaction_meta(&meta)),
+                index: 0,
+            }),
+            slot: 100,
+        })),
+    }
+}
+
+#[tokio::test]
+async fn test_grpc_geyser_receiver() {
+    let mint_key = Pubkey::new_unique();
+    let mut mint_data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply: 1,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut mint_data);
+    let signature = Signature::new_unique();
+    let subscribe_request = Arc::new(Mutex::new(None));
+    let geyser = MockGeyser {
+        updates: vec![
+            // accounts of the programs the ingester doesn't know are skipped
+            account_update(Pubkey::new_unique(), Pubkey::new_unique(), vec![1, 2, 3]),
+            account_update(mint_key, spl_token::id(), mint_data),
+            bubblegum_transaction_update(signature),
+        ],
+        request: subscribe_request.clone(),
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(GeyserServer::new(geyser))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    let config = GrpcGeyserConfig {
+        endpoint: format!("http://{}", addr),
+        x_token: None,
+        commitment: CommitmentLevel::Confirmed,
+        connect_timeout_sec: 5,
+        reconnect_interval_sec: 1,
+        buffer_size: 10,
+        transaction_programs: None,
+    };
+    assert!(GrpcGeyserReceiver::new(GrpcGeyserConfig {
+        transaction_programs: Some(vec!["not a program".to_string()]),
+        ..config.clone()
+    })
+    .is_err());
+    let (receiver, subscriber) = GrpcGeyserReceiver::new(config).unwrap();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel::<()>(1);
+    let subscription = tokio::spawn(connect_to_grpc_geyser(subscriber, shutdown_rx));
+
+    let mut accounts = Vec::new();
+    let mut transactions = Vec::new();
+    for _ in 0..20 {
+        accounts.extend(receiver.next_accounts(10).await.unwrap());
+        transactions.extend(receiver.next_transactions().await.unwrap());
+        if !accounts.is_empty() && !transactions.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].key, mint_key);
+    assert!(matches!(&accounts[0].account, UnprocessedAccount::Mint(mint) if mint.supply == 1));
+
+    assert_eq!(transactions.len(), 1);
+    let tx =
+        plerkle_serialization::root_as_transaction_info(transactions[0].tx.transaction.as_slice())
+            .unwrap();
+    assert_eq!(tx.signature(), Some(signature.to_string().as_str()));
+    assert_eq!(tx.slot(), 100);
+
+    let request = subscribe_request.lock().await.clone().unwrap();
+    assert!(request.accounts["accounts"]
+        .owner
+        .contains(&spl_token::id().to_string()));
+    assert_eq!(
+        request.transactions["transactions"].account_include,
+        vec![
+            mpl_bubblegum::ID.to_string(),
+            blockbuster::programs::token_metadata::token_metadata_id().to_string(),
+            AssetAccountProgram::MplCore.id().to_string(),
+            spl_token::id().to_string(),
+            spl_token_2022::id().to_string(),
+        ]
+    );
+    assert_eq!(request.transactions["transactions"].failed, Some(false));
+
+    shutdown_tx.send(()).unwrap();
+    subscription.await.unwrap().unwrap();
+}
@@ -16,8 +16,8 @@ mod tests {
     use blockbuster::token_metadata::types::{Key, TokenStandard};
     use entities::enums::{TokenMetadataEdition, UnprocessedAccount};
     use entities::models::{
-        EditionMetadata, EditionV1, IndexableAssetWithAccountInfo, MasterEdition, MetadataInfo,
-        Mint, TokenAccount,
+        BurntMetadataSlot, EditionMetadata, EditionV1, IndexableAssetWithAccountInfo,
+        MasterEdition, MetadataInfo, Mint, TokenAccount,
     };
     use metrics_utils::IngesterMetricsConfig;
     use nft_ingester::buffer::Buffer;
@@ -307,6 +307,30 @@ mod tests {
         } else {
             panic!("expected MasterEdition enum variant");
         };
+        assert_eq!(
+            env.rocks_env
+                .storage
+                .get_editions_by_parent(parent, None, None, None, 10),
+            vec![(0, first_edition)]
+        );
+
+        // burning a print closes its edition account
+        mplx_accs_parser
+            .transform_and_store_burnt_metadata(
+                &mut batch_storage,
+                first_edition,
+                &BurntMetadataSlot {
+                    slot_updated: 2,
+                    write_version: 2,
+                },
+            )
+            .unwrap();
+        batch_storage.flush().unwrap();
+        assert!(env
+            .rocks_env
+            .storage
+            .get_editions_by_parent(parent, None, None, None, 10)
+            .is_empty());
     }
 
     #[tokio::test]
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use entities::enums::UnprocessedAccount;
+use interface::signature_persistence::UnprocessedTransactionsGetter;
+use interface::unprocessed_data_getter::UnprocessedAccountsGetter;
+use nft_ingester::config::{FileReplayConfig, MessageRecorderConfig};
+use nft_ingester::file_replay_receiver::{replay_recorded_messages, FileReplayReceiver};
+use nft_ingester::message_recorder::{
+    list_record_files, run_message_recorder, MessageRecorder, RecordedMessageKind,
+};
+use plerkle_serialization::serializer::serialize_account;
+use plerkle_serialization::solana_geyser_plugin_interface_shims::ReplicaAccountInfoV2;
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+
+fn mint_account_message(mint_key: Pubkey, supply: u64) -> Vec<u8> {
+    let mut mint_data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut mint_data);
+    let account_info = ReplicaAccountInfoV2 {
+        pubkey: &mint_key.to_bytes(),
+        lamports: 1,
+        owner: &spl_token::id().to_bytes(),
+        executable: false,
+        rent_epoch: 0,
+        data: &mint_data,
+        write_version: supply,
+        txn_signature: None,
+    };
+
+    serialize_account(
+        flatbuffers::FlatBufferBuilder::new(),
+        &account_info,
+        100,
+        false,
+    )
+    .finished_data()
+    .to_vec()
+}
+
+#[tokio::test]
+async fn test_record_and_replay_messages() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().to_str().unwrap().to_string();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel::<()>(1);
+
+    let (recorder, writer) = MessageRecorder::new(MessageRecorderConfig {
+        path: path.clone(),
+        // every message goes into a new file
+        max_file_size_mb: 0,
+        max_files: None,
+        buffer_size: 10,
+    });
+    let writer = tokio::spawn(run_message_recorder(writer, shutdown_rx.resubscribe()));
+    let mint_keys = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    for (i, mint_key) in mint_keys.iter().enumerate() {
+        recorder.record(
+            RecordedMessageKind::Account,
+            false,
+            &mint_account_message(*mint_key, i as u64 + 1),
+        );
+        // files are named after the time they are created at
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    recorder.record(RecordedMessageKind::Transaction, false, &[1, 2, 3]);
+    // the writer stops once all the messages are written and the recorder is dropped
+    drop(recorder);
+    writer.await.unwrap().unwrap();
+    assert_eq!(
+        list_record_files(dir.path()).await.unwrap().len(),
+        3,
+        "files are rotated after every message"
+    );
+
+    let (receiver, replayer) = FileReplayReceiver::new(FileReplayConfig {
+        path,
+        speed: 0.0,
+        buffer_size: 10,
+    });
+    let replay = tokio::spawn(replay_recorded_messages(
+        replayer,
+        shutdown_rx.resubscribe(),
+    ));
+    replay.await.unwrap().unwrap();
+
+    let accounts = receiver.next_accounts(10).await.unwrap();
+    assert_eq!(
+        accounts
+            .iter()
+            .map(|account| account.key)
+            .collect::<Vec<_>>(),
+        mint_keys
+    );
+    assert!(matches!(&accounts[1].account, UnprocessedAccount::Mint(mint) if mint.supply == 2));
+
+    let transactions = receiver.next_transactions().await.unwrap();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].tx.transaction, vec![1, 2, 3]);
+
+    shutdown_tx.send(()).unwrap();
+}
@@ -1,104 +1,193 @@
-use assertables::assert_contains;
-use assertables::assert_contains_as_result;
-use entities::models::OffChainData;
-use nft_ingester::scheduler::Scheduler;
-use rocks_db::asset_previews::UrlToDownload;
-use setup::await_async_for;
-use setup::rocks::RocksTestEnvironment;
+#[cfg(test)]
+#[cfg(feature = "integration_tests")]
+mod tests {
+    use assertables::assert_contains;
+    use assertables::assert_contains_as_result;
+    use entities::models::{OffChainData, Updated};
+    use nft_ingester::scheduler::Scheduler;
+    use rocks_db::asset::AssetDynamicDetails;
+    use rocks_db::asset_previews::UrlToDownload;
+    use rocks_db::storage_traits::AssetUpdateIndexStorage;
+    use setup::await_async_for;
+    use setup::rocks::RocksTestEnvironment;
+    use solana_sdk::pubkey::Pubkey;
+    use std::collections::HashSet;
+    use testcontainers::clients::Cli;
 
-const NFT_1: (&str, &str) = (
-    "https://yra5lrhegorsgx7upcyi5trrfiktyczlq5g3jst3yvgaefab36vq.arweave.net/xEHVxOQzoyNf9Hiwjs4xKhU8CyuHTbTKe8VMAhQB36s",
-    r#"
-    {
-        "name":"Bone in Bondage",
-        "description":"We are slaves, worked endlessly and bound by chains without resistance.\n\nARISSU 2024",
-        "attributes":[
-            {"trait_type":"Minted on","value":"https://sketch.accessprotocol.co"},
-            {"trait_type":"Created by","value":"Arissu"},
-            {"trait_type":"Drop","value":"3"},{"trait_type":"Rarity","value":"Epic"}
-        ],
-        "externalUrl":"https://www.tensor.trade/trade/arissus_journey",
-        "image":"https://arweave.net/doAKIwo6dzUqzPQd6F9ROqWv5Gc61AGWhGPvTpY_Uy0",
-        "properties":{
-            "files":[
-                {"type":"image/jpeg","uri":"https://arweave.net/doAKIwo6dzUqzPQd6F9ROqWv5Gc61AGWhGPvTpY_Uy0"}
+    const NFT_1: (&str, &str) = (
+        "https://yra5lrhegorsgx7upcyi5trrfiktyczlq5g3jst3yvgaefab36vq.arweave.net/xEHVxOQzoyNf9Hiwjs4xKhU8CyuHTbTKe8VMAhQB36s",
+        r#"
+        {
+            "name":"Bone in Bondage",
+            "description":"We are slaves, worked endlessly and bound by chains without resistance.\n\nARISSU 2024",
+            "attributes":[
+                {"trait_type":"Minted on","value":"https://sketch.accessprotocol.co"},
+                {"trait_type":"Created by","value":"Arissu"},
+                {"trait_type":"Drop","value":"3"},{"trait_type":"Rarity","value":"Epic"}
             ],
-            "category":"image"
+            "externalUrl":"https://www.tensor.trade/trade/arissus_journey",
+            "image":"https://arweave.net/doAKIwo6dzUqzPQd6F9ROqWv5Gc61AGWhGPvTpY_Uy0",
+            "properties":{
+                "files":[
+                    {"type":"image/jpeg","uri":"https://arweave.net/doAKIwo6dzUqzPQd6F9ROqWv5Gc61AGWhGPvTpY_Uy0"}
+                ],
+                "category":"image"
+            }
         }
+        "#
+    );
+    const NFT_2: (&str, &str) = (
+        "https://888jup.com/img/888jup.json",
+        r#"
+        {
+            "name": "Active Staking Rewards",
+            "symbol": "ASR",
+            "description": "Active Staking Rewards is an innovative way to reward active participants with JUP, allowing them to accrue more voting power over-time, in the simplest way possible:Visit the domain shown in the picture and claim 888jup.com",
+            "seller_fee_basis_points": 0,
+            "image": "https://888jup.com/img/888jup.png",
+            "attributes": [
+                { "trait_type": "Website", "value": "888jup.com" },
+                { "trait_type": "Verified", "value": "True" }
+            ],
+                "external_url": "https://888jup.com",
+            "properties": {
+                "creators": [
+                { "address": "9fXwyTF41BNGnLmwv1vaRUDcKrEMWu8kvq1QbK7AT6CN", "share": 100 }
+                ]
+            }
+            }
+        "#,
+    );
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_collect_urls_to_download() {
+        // This test checks that the job that serves for initial population of
+        // "URLs for download" column family is ablle collect file URLs
+        // from "offchain data" column family.
+        let rocks_env = RocksTestEnvironment::new(&[]);
+        let cli = Cli::default();
+        let pg_env = setup::pg::TestEnvironment::new(&cli).await;
+
+        let nfts = [NFT_1, NFT_2];
+        nfts.iter()
+            .map(|(url, metadata)| OffChainData {
+                url: url.to_string(),
+                metadata: metadata.to_string(),
+            })
+            .for_each(|entity| {
+                rocks_env
+                    .storage
+                    .asset_offchain_data
+                    .put(entity.url.clone(), entity)
+                    .unwrap()
+            });
+
+        let sut = Scheduler::new(rocks_env.storage.clone(), pg_env.client.clone(), None);
+        Scheduler::run_in_background(sut).await;
+
+        await_async_for!(
+            rocks_env.storage.urls_to_download.get_from_start(10).len() == 2,
+            10,
+            std::time::Duration::from_millis(100)
+        );
+
+        let expected_urls = [
+            "https://arweave.net/doAKIwo6dzUqzPQd6F9ROqWv5Gc61AGWhGPvTpY_Uy0".to_string(),
+            "https://888jup.com/img/888jup.png".to_string(),
+        ];
+        let res = rocks_env.storage.urls_to_download.get_from_start(10);
+        res.iter().for_each(
+            |(
+                url,
+                UrlToDownload {
+                    timestamp,
+                    download_attempts,
+                },
+            )| {
+                assert_contains!(expected_urls, url);
+                println!("URL: {}", url);
+                assert_eq!(*timestamp, 0);
+                assert_eq!(*download_attempts, 0);
+            },
+        );
+
+        // the traits of the JSONs downloaded before the traits were indexed are indexed too
+        let indexed_attributes = || async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM offchain_attributes")
+                .fetch_one(&pg_env.pool)
+                .await
+                .unwrap()
+        };
+        await_async_for!(
+            indexed_attributes().await == 6,
+            10,
+            std::time::Duration::from_millis(100)
+        );
+        let symbols = sqlx::query_scalar::<_, String>("SELECT ofs_symbol FROM offchain_symbols")
+            .fetch_all(&pg_env.pool)
+            .await
+            .unwrap();
+        assert_eq!(symbols, vec!["ASR".to_string()]);
+
+        pg_env.teardown().await;
     }
-    "#
-);
-const NFT_2: (&str, &str) = (
-    "https://888jup.com/img/888jup.json",
-    r#"
-    {
-        "name": "Active Staking Rewards",
-        "symbol": "ASR",
-        "description": "Active Staking Rewards is an innovative way to reward active participants with JUP, allowing them to accrue more voting power over-time, in the simplest way possible:Visit the domain shown in the picture and claim 888jup.com",
-        "seller_fee_basis_points": 0,
-        "image": "https://888jup.com/img/888jup.png",
-        "attributes": [
-            { "trait_type": "Website", "value": "888jup.com" },
-            { "trait_type": "Verified", "value": "True" }
-        ],
-            "external_url": "https://888jup.com",
-        "properties": {
-            "creators": [
-            { "address": "9fXwyTF41BNGnLmwv1vaRUDcKrEMWu8kvq1QbK7AT6CN", "share": 100 }
-            ]
-        }
-        }
-    "#,
-);
 
-#[tokio::test]
-#[tracing_test::traced_test]
-async fn test_collect_urls_to_download() {
-    // This test checks that the job that serves for initial population of
-    // "URLs for download" column family is ablle collect file URLs
-    // from "offchain data" column family.
-    let rocks_env = RocksTestEnvironment::new(&[]);
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_resync_assets() {
+        // the assets synced before their core plugins and names were indexed are synced again
+        let rocks_env = RocksTestEnvironment::new(&[]);
+        let cli = Cli::default();
+        let pg_env = setup::pg::TestEnvironment::new(&cli).await;
 
-    let nfts = [NFT_1, NFT_2];
-    nfts.iter()
-        .map(|(url, metadata)| OffChainData {
-            url: url.to_string(),
-            metadata: metadata.to_string(),
-        })
-        .for_each(|entity| {
+        let core_asset = Pubkey::new_unique();
+        let named_asset = Pubkey::new_unique();
+        let other_asset = Pubkey::new_unique();
+        for (pubkey, mpl_core_plugins, raw_name) in [
+            (
+                core_asset,
+                Some(Updated::new(10, None, "{}".to_string())),
+                None,
+            ),
+            (
+                named_asset,
+                None,
+                Some(Updated::new(10, None, "name".to_string())),
+            ),
+            (other_asset, None, None),
+        ] {
             rocks_env
                 .storage
-                .asset_offchain_data
-                .put(entity.url.clone(), entity)
-                .unwrap()
-        });
+                .asset_dynamic_data
+                .put(
+                    pubkey,
+                    AssetDynamicDetails {
+                        pubkey,
+                        mpl_core_plugins,
+                        raw_name,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        }
 
-    let sut = Scheduler::new(rocks_env.storage.clone());
-    Scheduler::run_in_background(sut).await;
+        let sut = Scheduler::new(rocks_env.storage.clone(), pg_env.client.clone(), None);
+        Scheduler::run_in_background(sut).await;
 
-    await_async_for!(
-        rocks_env.storage.urls_to_download.get_from_start(10).len() == 2,
-        10,
-        std::time::Duration::from_millis(100)
-    );
+        let updated_assets = || {
+            rocks_env
+                .storage
+                .fetch_asset_updated_keys(None, None, 10, None)
+                .unwrap()
+                .0
+        };
+        await_async_for!(
+            updated_assets() == HashSet::from([core_asset, named_asset]),
+            10,
+            std::time::Duration::from_millis(100)
+        );
 
-    let expected_urls = [
-        "https://arweave.net/doAKIwo6dzUqzPQd6F9ROqWv5Gc61AGWhGPvTpY_Uy0".to_string(),
-        "https://888jup.com/img/888jup.png".to_string(),
-    ];
-    let res = rocks_env.storage.urls_to_download.get_from_start(10);
-    res.iter().for_each(
-        |(
-            url,
-            UrlToDownload {
-                timestamp,
-                download_attempts,
-            },
-        )| {
-            assert_contains!(expected_urls, url);
-            println!("URL: {}", url);
-            assert_eq!(*timestamp, 0);
-            assert_eq!(*download_attempts, 0);
-        },
-    );
+        pg_env.teardown().await;
+    }
 }
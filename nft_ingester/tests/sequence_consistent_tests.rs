@@ -1,14 +1,21 @@
 #[cfg(test)]
 #[cfg(feature = "integration_tests")]
 mod tests {
+    use async_trait::async_trait;
+    use interface::error::{BlockConsumeError, StorageError};
+    use interface::signature_persistence::{BlockConsumer, BlockProducer};
     use interface::slot_getter::MockFinalizedSlotGetter;
     use metrics_utils::utils::start_metrics;
     use metrics_utils::{MetricState, MetricsTrait};
+    use nft_ingester::gap_repairer::GapRepairer;
     use nft_ingester::sequence_consistent::SequenceConsistentGapfiller;
     use rocks_db::bubblegum_slots::bubblegum_slots_key_to_value;
     use rocks_db::key_encoders::{decode_pubkey, decode_pubkey_u64, decode_string};
-    use rocks_db::tree_seq::TreeSeqIdx;
+    use rocks_db::tree_seq::{GapRepairState, TreeSeqIdx, TreesGaps};
+    use rocks_db::Storage;
     use setup::rocks::RocksTestEnvironment;
+    use solana_transaction_status::UiConfirmedBlock;
+    use std::collections::HashMap;
     use std::str::FromStr;
     use std::sync::Arc;
     use tokio::sync::broadcast;
@@ -190,4 +197,133 @@ mod tests {
         let key = decode_string(key.to_vec()).unwrap();
         assert_eq!(bubblegum_slots_key_to_value(key), 203);
     }
+
+    struct EmptyBlockProducer;
+
+    #[async_trait]
+    impl BlockProducer for EmptyBlockProducer {
+        async fn get_block(
+            &self,
+            _slot: u64,
+            _backup_provider: Option<Arc<impl BlockProducer>>,
+        ) -> Result<UiConfirmedBlock, StorageError> {
+            Ok(UiConfirmedBlock {
+                previous_blockhash: "".to_string(),
+                blockhash: "".to_string(),
+                parent_slot: 0,
+                transactions: None,
+                signatures: None,
+                rewards: None,
+                block_time: None,
+                block_height: None,
+            })
+        }
+    }
+
+    // stands for the transactions parser, restores the tree sequences the slots bring
+    struct SequenceRestoringConsumer {
+        storage: Arc<Storage>,
+        restored_sequences: HashMap<u64, (solana_program::pubkey::Pubkey, u64)>,
+    }
+
+    #[async_trait]
+    impl BlockConsumer for SequenceRestoringConsumer {
+        async fn consume_block(
+            &self,
+            slot: u64,
+            _block: UiConfirmedBlock,
+        ) -> Result<(), BlockConsumeError> {
+            if let Some((tree, seq)) = self.restored_sequences.get(&slot) {
+                self.storage
+                    .tree_seq_idx
+                    .put_async((*tree, *seq), TreeSeqIdx { slot })
+                    .await?;
+            }
+            Ok(())
+        }
+
+        async fn already_processed_slot(&self, _slot: u64) -> Result<bool, BlockConsumeError> {
+            Ok(false)
+        }
+    }
+
+    #[tracing_test::traced_test]
+    #[tokio::test]
+    async fn test_repair_gaps() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+        let repaired_tree = solana_program::pubkey::Pubkey::new_unique();
+        let lost_tree = solana_program::pubkey::Pubkey::new_unique();
+
+        for (tree, seq, slot) in [
+            (repaired_tree, 100, 200),
+            (repaired_tree, 101, 201),
+            (repaired_tree, 104, 204),
+            (lost_tree, 10, 300),
+            (lost_tree, 14, 304),
+        ] {
+            storage
+                .tree_seq_idx
+                .put_async((tree, seq), TreeSeqIdx { slot })
+                .await
+                .unwrap();
+            storage
+                .trees_gaps
+                .put_async(tree, TreesGaps {})
+                .await
+                .unwrap();
+        }
+
+        let mut slots_getter = MockSlotsGetter::new();
+        slots_getter
+            .expect_get_slots()
+            .returning(move |_, start_at, _| Ok((start_at - 4..=start_at).rev().collect()));
+        let mut finalized_slot_getter = MockFinalizedSlotGetter::new();
+        finalized_slot_getter
+            .expect_get_finalized_slot_no_error()
+            .returning(|| 400);
+        let mut metrics_state = MetricState::new();
+        metrics_state.register_metrics();
+
+        let repairer = GapRepairer::new(
+            storage.clone(),
+            Arc::new(slots_getter),
+            Arc::new(EmptyBlockProducer),
+            Arc::new(SequenceRestoringConsumer {
+                storage: storage.clone(),
+                restored_sequences: HashMap::from([
+                    (202, (repaired_tree, 102)),
+                    (203, (repaired_tree, 103)),
+                ]),
+            }),
+            Arc::new(finalized_slot_getter),
+            metrics_state.backfiller_metrics.clone(),
+            metrics_state.sequence_consistent_gapfill_metrics.clone(),
+            2,
+        );
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel::<()>(1);
+
+        repairer.repair_gaps(&shutdown_rx).await;
+        assert!(storage.tree_sequence_gaps(repaired_tree, 400).is_empty());
+        assert!(storage.trees_gaps.get(repaired_tree).unwrap().is_none());
+        let repair = storage
+            .tree_gap_repairs
+            .get(repaired_tree)
+            .unwrap()
+            .unwrap();
+        assert_eq!(repair.state, GapRepairState::Repaired);
+        assert_eq!(repair.attempts, 1);
+        let repair = storage.tree_gap_repairs.get(lost_tree).unwrap().unwrap();
+        assert_eq!(repair.state, GapRepairState::Pending);
+        assert_eq!(repair.gaps, 1);
+
+        // the tree is given up after the max attempts and is not retried anymore
+        repairer.repair_gaps(&shutdown_rx).await;
+        repairer.repair_gaps(&shutdown_rx).await;
+        let repair = storage.tree_gap_repairs.get(lost_tree).unwrap().unwrap();
+        assert_eq!(repair.state, GapRepairState::Unrepairable);
+        assert_eq!(repair.attempts, 2);
+        assert!(storage.trees_gaps.get(lost_tree).unwrap().is_some());
+
+        assert_eq!(storage.get_tree_gap_repairs(None, 10).len(), 2);
+    }
 }
@@ -0,0 +1,266 @@
+#[cfg(test)]
+#[cfg(feature = "integration_tests")]
+mod tests {
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use entities::enums::AssetEventType;
+    use entities::models::WebhookFilter;
+    use jsonrpc_http_server::hyper::service::{make_service_fn, service_fn};
+    use jsonrpc_http_server::hyper::{self, Body, Request, Response, Server, StatusCode};
+    use metrics_utils::IngesterMetricsConfig;
+    use nft_ingester::webhooks::{
+        sign, AssetEventNotification, WebhookSender, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+    };
+    use rocks_db::batch_savers::BatchSaveStorage;
+    use rocks_db::webhooks::AssetEvent;
+    use setup::rocks::RocksTestEnvironment;
+    use solana_sdk::pubkey::Pubkey;
+    use testcontainers::clients::Cli;
+    use tokio::sync::Mutex;
+
+    // the signature, the timestamp and the body of the requests
+    type Received = Arc<Mutex<Vec<(String, i64, Vec<u8>)>>>;
+
+    // answers with an error the given number of times first, then keeps the signed bodies it gets
+    fn start_receiver(failures: usize) -> (SocketAddr, Received) {
+        let received = Received::default();
+        let failures_left = Arc::new(AtomicUsize::new(failures));
+        let cloned_received = received.clone();
+        let make_service = make_service_fn(move |_| {
+            let received = cloned_received.clone();
+            let failures_left = failures_left.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let received = received.clone();
+                    let failures_left = failures_left.clone();
+                    async move {
+                        if failures_left
+                            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                                left.checked_sub(1)
+                            })
+                            .is_ok()
+                        {
+                            let mut response = Response::new(Body::empty());
+                            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            return Ok::<_, Infallible>(response);
+                        }
+                        let signature = request
+                            .headers()
+                            .get(SIGNATURE_HEADER)
+                            .and_then(|signature| signature.to_str().ok())
+                            .unwrap_or_default()
+                            .to_string();
+                        let timestamp = request
+                            .headers()
+                            .get(TIMESTAMP_HEADER)
+                            .and_then(|timestamp| timestamp.to_str().ok())
+                            .and_then(|timestamp| timestamp.parse().ok())
+                            .unwrap_or_default();
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                        received
+                            .lock()
+                            .await
+                            .push((signature, timestamp, body.to_vec()));
+                        Ok(Response::new(Body::empty()))
+                    }
+                }))
+            }
+        });
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, received)
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_webhook_delivery_with_retry() {
+        let cli = Cli::default();
+        let pg_env = setup::pg::TestEnvironment::new(&cli).await;
+        let storage = RocksTestEnvironment::new(&[]).storage;
+        storage.set_asset_events_enabled(true);
+        let metrics = Arc::new(IngesterMetricsConfig::new());
+        let (addr, received) = start_receiver(1);
+
+        let asset_id = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let secret = "secret";
+        pg_env
+            .client
+            .insert_webhook(
+                &format!("http://{}/hook", addr),
+                secret,
+                &WebhookFilter {
+                    owners: vec![new_owner],
+                    event_types: vec![AssetEventType::Transfer],
+                    ..Default::default()
+                },
+                "partner",
+                10,
+            )
+            .await
+            .unwrap();
+        // doesn't match any of the events
+        pg_env
+            .client
+            .insert_webhook(
+                &format!("http://{}/other", addr),
+                secret,
+                &WebhookFilter {
+                    asset_ids: vec![Pubkey::new_unique()],
+                    ..Default::default()
+                },
+                "partner",
+                10,
+            )
+            .await
+            .unwrap();
+
+        let mut batch_storage = BatchSaveStorage::new(storage.clone(), 10, metrics.clone());
+        for event_type in [AssetEventType::Transfer, AssetEventType::Burn] {
+            batch_storage
+                .store_asset_event(&AssetEvent {
+                    event_type,
+                    asset_id,
+                    slot: 100,
+                    tree: None,
+                    signature: None,
+                    owner: Some(new_owner),
+                })
+                .unwrap();
+        }
+        batch_storage.flush().unwrap();
+
+        let sender = WebhookSender::new(
+            storage.clone(),
+            pg_env.client.clone(),
+            metrics,
+            3,
+            0, // the failed notification is due right away
+            Duration::from_secs(5),
+            true, // the receiver listens on the loopback
+        )
+        .unwrap();
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel::<()>(1);
+
+        assert_eq!(sender.dispatch_events().await, 2);
+        assert!(storage.get_asset_events(10).is_empty());
+        assert_eq!(storage.webhook_deliveries.iter_start().count(), 1);
+
+        // the receiver fails the first request, so the notification is kept to be retried
+        assert_eq!(sender.send_deliveries(&shutdown_rx).await, 1);
+        let deliveries = storage.get_due_webhook_deliveries(i64::MAX, 10).unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].1.attempts, 1);
+        assert!(deliveries[0].1.last_error.is_some());
+        assert!(received.lock().await.is_empty());
+
+        sender.send_deliveries(&shutdown_rx).await;
+        assert_eq!(storage.webhook_deliveries.iter_start().count(), 0);
+        assert_eq!(storage.webhook_delivery_due_idx.iter_start().count(), 0);
+        let received = received.lock().await;
+        assert_eq!(received.len(), 1);
+        let (signature, timestamp, body) = &received[0];
+        assert_eq!(signature, &sign(secret, *timestamp, body));
+        assert!((chrono::Utc::now().timestamp() - timestamp).abs() < 60);
+        // the same body sent at another time has another signature
+        assert_ne!(signature, &sign(secret, timestamp - 1, body));
+        let notification: AssetEventNotification = serde_json::from_slice(body).unwrap();
+        assert_eq!(notification.event_type, AssetEventType::Transfer);
+        assert_eq!(notification.asset_id, asset_id.to_string());
+        assert_eq!(notification.owner, Some(new_owner.to_string()));
+        assert_eq!(notification.slot, 100);
+
+        pg_env.teardown().await;
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_webhooks_belong_to_key() {
+        let cli = Cli::default();
+        let pg_env = setup::pg::TestEnvironment::new(&cli).await;
+        let webhook = pg_env
+            .client
+            .insert_webhook(
+                "https://example.com/hook",
+                "secret",
+                &WebhookFilter::default(),
+                "partner",
+                10,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(webhook.key_name, "partner");
+
+        let other_webhooks = pg_env
+            .client
+            .get_webhooks(Some("other"), None, 10)
+            .await
+            .unwrap();
+        assert!(other_webhooks.is_empty());
+        assert!(!pg_env
+            .client
+            .delete_webhook(webhook.id, "other")
+            .await
+            .unwrap());
+        // the ingester sees the webhooks of every key
+        assert_eq!(
+            pg_env.client.get_webhooks(None, None, 10).await.unwrap(),
+            vec![webhook.clone()]
+        );
+        assert!(pg_env
+            .client
+            .delete_webhook(webhook.id, "partner")
+            .await
+            .unwrap());
+
+        // the key may have a bounded number of the webhooks
+        for _ in 0..2 {
+            assert!(pg_env
+                .client
+                .insert_webhook(
+                    "https://example.com/hook",
+                    "secret",
+                    &WebhookFilter::default(),
+                    "partner",
+                    2,
+                )
+                .await
+                .unwrap()
+                .is_some());
+        }
+        assert!(pg_env
+            .client
+            .insert_webhook(
+                "https://example.com/hook",
+                "secret",
+                &WebhookFilter::default(),
+                "partner",
+                2,
+            )
+            .await
+            .unwrap()
+            .is_none());
+        // the limit is counted per key
+        assert!(pg_env
+            .client
+            .insert_webhook(
+                "https://example.com/hook",
+                "secret",
+                &WebhookFilter::default(),
+                "other",
+                2,
+            )
+            .await
+            .unwrap()
+            .is_some());
+
+        pg_env.teardown().await;
+    }
+}
@@ -9,10 +9,11 @@ use sqlx::{Execute, Postgres, QueryBuilder, Row};
 use tracing::log::debug;
 
 use crate::{
+    asset_index_client::core_attributes_json,
     error::IndexDbError,
     model::{
-        AssetSortBy, AssetSortDirection, AssetSortedIndex, AssetSorting, AssetSupply,
-        SearchAssetsFilter,
+        AssetAttributes, AssetName, AssetSortBy, AssetSortDirection, AssetSortedIndex,
+        AssetSorting, AssetSupply, OwnerAtSlot, SearchAssetsFilter,
     },
     storage_traits::AssetPubkeyFilteredFetcher,
     PgClient, COUNT_ACTION, SELECT_ACTION, SQL_COMPONENT,
@@ -148,6 +149,26 @@ impl PgClient {
     }
 }
 
+// Assets of the owner, as of the slot the owner is asked for if it's older than the indexed one
+fn push_ast_owner_clause<'a>(
+    query_builder: &mut QueryBuilder<'a, Postgres>,
+    owner_address: &'a [u8],
+    owner_at_slot: &'a Option<OwnerAtSlot>,
+) {
+    let Some(owner_at_slot) = owner_at_slot else {
+        query_builder.push(" assets_v3.ast_owner = ");
+        query_builder.push_bind(owner_address);
+        return;
+    };
+    query_builder.push(" ((assets_v3.ast_owner = ");
+    query_builder.push_bind(owner_address);
+    query_builder.push(" AND NOT (assets_v3.ast_pubkey = ANY(");
+    query_builder.push_bind(&owner_at_slot.not_owned);
+    query_builder.push("))) OR assets_v3.ast_pubkey = ANY(");
+    query_builder.push_bind(&owner_at_slot.owned);
+    query_builder.push(")) ");
+}
+
 fn add_filter_clause<'a>(
     query_builder: &mut QueryBuilder<'a, Postgres>,
     filter: &'a SearchAssetsFilter,
@@ -210,24 +231,24 @@ fn add_filter_clause<'a>(
                     }
                 }
                 TokenType::NonFungible => {
-                    query_builder.push(" AND assets_v3.ast_owner = ");
-                    query_builder.push_bind(owner_address);
+                    query_builder.push(" AND ");
+                    push_ast_owner_clause(query_builder, owner_address, &filter.owner_at_slot);
                 }
                 TokenType::RegularNFT => {
-                    query_builder.push(" AND assets_v3.ast_owner = ");
-                    query_builder.push_bind(owner_address);
+                    query_builder.push(" AND ");
+                    push_ast_owner_clause(query_builder, owner_address, &filter.owner_at_slot);
                     query_builder.push(" AND assets_v3.ast_is_compressed = ");
                     query_builder.push_bind(false);
                 }
                 TokenType::CompressedNFT => {
-                    query_builder.push(" AND assets_v3.ast_owner = ");
-                    query_builder.push_bind(owner_address);
+                    query_builder.push(" AND ");
+                    push_ast_owner_clause(query_builder, owner_address, &filter.owner_at_slot);
                     query_builder.push(" AND assets_v3.ast_is_compressed = ");
                     query_builder.push_bind(true);
                 }
                 TokenType::All => {
-                    query_builder.push(" AND (assets_v3.ast_owner = ");
-                    query_builder.push_bind(owner_address);
+                    query_builder.push(" AND (");
+                    push_ast_owner_clause(query_builder, owner_address, &filter.owner_at_slot);
                     query_builder.push(" OR (fungible_tokens.fbt_owner = ");
                     query_builder.push_bind(owner_address);
                     if !options.show_zero_balance {
@@ -239,8 +260,8 @@ fn add_filter_clause<'a>(
                 }
             }
         } else {
-            query_builder.push(" AND assets_v3.ast_owner = ");
-            query_builder.push_bind(owner_address);
+            query_builder.push(" AND ");
+            push_ast_owner_clause(query_builder, owner_address, &filter.owner_at_slot);
         }
     }
 
@@ -335,9 +356,98 @@ fn add_filter_clause<'a>(
         query_builder.push(" AND tsk_metadata_url = ");
         query_builder.push_bind(json_uri);
     }
+
+    if let Some(name) = &filter.name {
+        match name {
+            AssetName::Exact(name) => {
+                query_builder.push(" AND assets_v3.ast_name = ");
+                query_builder.push_bind(name);
+            }
+            AssetName::Prefix(name) => {
+                query_builder.push(" AND assets_v3.ast_name ILIKE ");
+                query_builder.push_bind(format!("{}%", escape_like_pattern(name)));
+            }
+            AssetName::Fuzzy(name) => {
+                // `%` is the pg_trgm similarity operator, it uses pg_trgm.similarity_threshold
+                query_builder.push(" AND assets_v3.ast_name % ");
+                query_builder.push_bind(name);
+            }
+        }
+    }
+
+    if let Some(symbol) = &filter.symbol {
+        query_builder.push(
+            " AND EXISTS (SELECT 1 FROM offchain_symbols WHERE ofs_metadata_url_id = assets_v3.ast_metadata_url_id AND ofs_symbol = ",
+        );
+        query_builder.push_bind(symbol);
+        query_builder.push(")");
+    }
+
+    if let Some(attributes) = &filter.attributes {
+        match attributes {
+            AssetAttributes::All(attributes) => {
+                for (trait_type, value) in attributes {
+                    query_builder.push(" AND EXISTS (SELECT 1 FROM offchain_attributes WHERE ofa_metadata_url_id = assets_v3.ast_metadata_url_id AND ofa_trait_type = ");
+                    query_builder.push_bind(trait_type);
+                    query_builder.push(" AND ofa_value = ");
+                    query_builder.push_bind(value);
+                    query_builder.push(")");
+                }
+            }
+            AssetAttributes::Any(attributes) if !attributes.is_empty() => {
+                query_builder.push(" AND EXISTS (SELECT 1 FROM offchain_attributes WHERE ofa_metadata_url_id = assets_v3.ast_metadata_url_id AND (");
+                for (i, (trait_type, value)) in attributes.iter().enumerate() {
+                    if i > 0 {
+                        query_builder.push(" OR ");
+                    }
+                    query_builder.push("(ofa_trait_type = ");
+                    query_builder.push_bind(trait_type);
+                    query_builder.push(" AND ofa_value = ");
+                    query_builder.push_bind(value);
+                    query_builder.push(")");
+                }
+                query_builder.push("))");
+            }
+            AssetAttributes::Any(_) => {}
+        }
+    }
+
+    if let Some(core_plugins) = &filter.core_plugins {
+        query_builder.push(" AND assets_v3.ast_core_plugins @> ");
+        query_builder.push_bind(core_plugins);
+    }
+
+    if let Some(core_attributes) = &filter.core_attributes {
+        match core_attributes {
+            AssetAttributes::All(attributes) => {
+                query_builder.push(" AND assets_v3.ast_core_attributes @> ");
+                query_builder.push_bind(core_attributes_json(attributes));
+            }
+            AssetAttributes::Any(attributes) if !attributes.is_empty() => {
+                query_builder.push(" AND (");
+                for (i, attribute) in attributes.iter().enumerate() {
+                    if i > 0 {
+                        query_builder.push(" OR ");
+                    }
+                    query_builder.push("assets_v3.ast_core_attributes @> ");
+                    query_builder.push_bind(core_attributes_json(std::slice::from_ref(attribute)));
+                }
+                query_builder.push(")");
+            }
+            AssetAttributes::Any(_) => {}
+        }
+    }
     group_clause_required
 }
 
+// escapes the LIKE wildcards, so the user input is matched literally
+fn escape_like_pattern(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 fn add_slot_and_key_comparison(
     key: &str,
     comparison: &str,
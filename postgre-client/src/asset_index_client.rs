@@ -17,7 +17,7 @@ use crate::{
 };
 use entities::models::{AssetIndex, Creator, FungibleToken, UrlWithStatus};
 
-pub const INSERT_ASSET_PARAMETERS_COUNT: usize = 19;
+pub const INSERT_ASSET_PARAMETERS_COUNT: usize = 22;
 pub const DELETE_ASSET_CREATOR_PARAMETERS_COUNT: usize = 2;
 pub const INSERT_ASSET_CREATOR_PARAMETERS_COUNT: usize = 4;
 pub const INSERT_AUTHORITY_PARAMETERS_COUNT: usize = 3;
@@ -148,6 +148,19 @@ pub(crate) struct TableNames {
     pub fungible_tokens_table: String,
 }
 
+// on-chain attributes are stored as [{"key": .., "value": ..}] to be searched with `@>`
+pub(crate) fn core_attributes_json(attributes: &[(String, String)]) -> Option<serde_json::Value> {
+    if attributes.is_empty() {
+        return None;
+    }
+    Some(serde_json::Value::Array(
+        attributes
+            .iter()
+            .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+            .collect(),
+    ))
+}
+
 pub(crate) fn split_assets_into_components(asset_indexes: &[AssetIndex]) -> AssetComponenents {
     // First we need to bulk upsert metadata_url into metadata and get back ids for each metadata_url to upsert into assets_v3
     let mut metadata_urls: Vec<_> = asset_indexes
@@ -469,7 +482,10 @@ impl PgClient {
             ast_is_frozen,
             ast_supply,
             ast_metadata_url_id,
-            ast_slot_updated) ",
+            ast_slot_updated,
+            ast_name,
+            ast_core_plugins,
+            ast_core_attributes) ",
         );
         query_builder.push_values(asset_indexes, |mut builder, asset_index| {
             let metadata_id = asset_index
@@ -511,7 +527,13 @@ impl PgClient {
                 .push_bind(asset_index.is_frozen)
                 .push_bind(asset_index.supply)
                 .push_bind(metadata_id)
-                .push_bind(asset_index.slot_updated);
+                .push_bind(asset_index.slot_updated)
+                .push_bind(asset_index.name.clone())
+                .push_bind(
+                    (!asset_index.core_plugins.is_empty())
+                        .then(|| asset_index.core_plugins.clone()),
+                )
+                .push_bind(core_attributes_json(&asset_index.core_attributes));
         });
         query_builder.push(
             " ON CONFLICT (ast_pubkey) 
@@ -533,7 +555,10 @@ impl PgClient {
             ast_is_frozen = EXCLUDED.ast_is_frozen,
             ast_supply = EXCLUDED.ast_supply,
             ast_metadata_url_id = EXCLUDED.ast_metadata_url_id,
-            ast_slot_updated = EXCLUDED.ast_slot_updated
+            ast_slot_updated = EXCLUDED.ast_slot_updated,
+            ast_name = EXCLUDED.ast_name,
+            ast_core_plugins = EXCLUDED.ast_core_plugins,
+            ast_core_attributes = EXCLUDED.ast_core_attributes
             WHERE ",
         );
         query_builder.push(table);
@@ -0,0 +1,42 @@
+use crate::error::IndexDbError;
+use crate::PgClient;
+use sqlx::{Postgres, QueryBuilder, Row};
+
+impl PgClient {
+    /// Asks the ingester to process the dead letters with the given ids again
+    pub async fn request_dead_letters_redrive(&self, ids: &[u64]) -> Result<(), IndexDbError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<'_, Postgres> =
+            QueryBuilder::new("INSERT INTO dead_letter_redrives (dlr_id) ");
+        query_builder.push_values(ids, |mut b, id| {
+            b.push_bind(*id as i64);
+        });
+        query_builder.push(" ON CONFLICT (dlr_id) DO NOTHING;");
+
+        query_builder.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    /// Removes and returns up to `limit` ids of the dead letters requested to be redriven, the oldest first
+    pub async fn take_dead_letters_redrive(&self, limit: i64) -> Result<Vec<u64>, IndexDbError> {
+        let mut query_builder: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+            "DELETE FROM dead_letter_redrives WHERE dlr_id IN (
+                SELECT dlr_id FROM dead_letter_redrives ORDER BY dlr_id LIMIT ",
+        );
+        query_builder.push_bind(limit);
+        query_builder.push(" FOR UPDATE SKIP LOCKED) RETURNING dlr_id;");
+
+        let rows = query_builder.build().fetch_all(&self.pool).await?;
+        let mut ids = rows
+            .into_iter()
+            .map(|row| row.get::<i64, _>("dlr_id") as u64)
+            .collect::<Vec<_>>();
+        ids.sort();
+
+        Ok(ids)
+    }
+}
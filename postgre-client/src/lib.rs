@@ -16,13 +16,17 @@ pub mod asset_index_client;
 pub mod batch_mints;
 pub mod converters;
 pub mod core_fees;
+pub mod dead_letters;
 pub mod error;
 pub mod integrity_verification_client;
 pub mod load_client;
 pub mod model;
+pub mod offchain_traits;
+pub mod owners_snapshot;
 pub mod storage_traits;
 pub mod tasks;
 pub mod temp_index_client;
+pub mod webhooks;
 
 pub const SQL_COMPONENT: &str = "sql";
 pub const SELECT_ACTION: &str = "select";
@@ -117,6 +117,10 @@ impl PgClient {
             "assets_v3_is_frozen",
             "assets_v3_supply",
             "assets_v3_slot_updated",
+            "assets_v3_name",
+            "assets_v3_name_trgm",
+            "assets_v3_core_plugins",
+            "assets_v3_core_attributes",
             "fungible_tokens_fbt_asset_idx",
             "fungible_tokens_fbt_balance_idx",
             "fungible_tokens_fbt_slot_updated_idx",
@@ -185,6 +189,10 @@ impl PgClient {
                 ("assets_v3_is_frozen", "assets_v3(ast_is_frozen) WHERE ast_is_frozen IS TRUE"),
                 ("assets_v3_supply", "assets_v3(ast_supply) WHERE ast_supply IS NOT NULL"),
                 ("assets_v3_slot_updated", "assets_v3(ast_slot_updated)"),
+                ("assets_v3_name", "assets_v3(ast_name) WHERE ast_name IS NOT NULL"),
+                ("assets_v3_name_trgm", "assets_v3 USING gin (ast_name gin_trgm_ops) WHERE ast_name IS NOT NULL"),
+                ("assets_v3_core_plugins", "assets_v3 USING gin (ast_core_plugins) WHERE ast_core_plugins IS NOT NULL"),
+                ("assets_v3_core_attributes", "assets_v3 USING gin (ast_core_attributes jsonb_path_ops) WHERE ast_core_attributes IS NOT NULL"),
                 ("fungible_tokens_fbt_asset_idx", "fungible_tokens(fbt_asset)"),
                 ("fungible_tokens_fbt_balance_idx", "fungible_tokens(fbt_balance) WHERE fbt_balance > 0"),
                 ("fungible_tokens_fbt_slot_updated_idx", "fungible_tokens(fbt_slot_updated)"),
@@ -273,7 +281,7 @@ impl PgClient {
             (
                 "assets_v3",
                 assets_copy_path,
-                "ast_pubkey, ast_specification_version, ast_specification_asset_class, ast_royalty_target_type, ast_royalty_amount, ast_slot_created, ast_owner_type, ast_owner, ast_delegate, ast_authority_fk, ast_collection, ast_is_collection_verified, ast_is_burnt, ast_is_compressible, ast_is_compressed, ast_is_frozen, ast_supply, ast_metadata_url_id, ast_slot_updated",
+                "ast_pubkey, ast_specification_version, ast_specification_asset_class, ast_royalty_target_type, ast_royalty_amount, ast_slot_created, ast_owner_type, ast_owner, ast_delegate, ast_authority_fk, ast_collection, ast_is_collection_verified, ast_is_burnt, ast_is_compressible, ast_is_compressed, ast_is_frozen, ast_supply, ast_metadata_url_id, ast_slot_updated, ast_name, ast_core_plugins, ast_core_attributes",
             ),
             (
                 "fungible_tokens",
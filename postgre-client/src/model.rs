@@ -91,6 +91,39 @@ pub struct SearchAssetsFilter {
     pub burnt: Option<bool>,
     pub json_uri: Option<String>,
     pub token_type: Option<TokenType>,
+    pub name: Option<AssetName>,
+    pub symbol: Option<String>,
+    pub attributes: Option<AssetAttributes>,
+    // snake_case MPL Core plugin types, all of them are required
+    pub core_plugins: Option<Vec<String>>,
+    pub core_attributes: Option<AssetAttributes>,
+    // set if the owner_address is asked for as of an older slot than the indexed one
+    pub owner_at_slot: Option<OwnerAtSlot>,
+}
+
+// Assets which changed the owner since the slot the owner is asked for,
+// the indexed owners are corrected by them
+#[derive(Debug, Clone, Default)]
+pub struct OwnerAtSlot {
+    // owned at the slot, but transferred since then
+    pub owned: Vec<Vec<u8>>,
+    // received since the slot
+    pub not_owned: Vec<Vec<u8>>,
+}
+
+// Assets set the holders are collected for, either the collection or the creator has to be set
+#[derive(Default)]
+pub struct OwnersSnapshotFilter {
+    pub collection: Option<Vec<u8>>,
+    pub creator_address: Option<Vec<u8>>,
+    pub creator_verified: Option<bool>,
+    pub show_unverified_collections: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct OwnerAssetsCount {
+    pub owner: Vec<u8>,
+    pub asset_count: i64,
 }
 
 pub enum AssetSupply {
@@ -98,6 +131,18 @@ pub enum AssetSupply {
     Equal(u64),
 }
 
+pub enum AssetName {
+    Exact(String),
+    Prefix(String),
+    Fuzzy(String),
+}
+
+// (trait_type, value) pairs of the offchain JSON attributes or (key, value) pairs of the MPL Core Attributes plugin
+pub enum AssetAttributes {
+    All(Vec<(String, String)>),
+    Any(Vec<(String, String)>),
+}
+
 pub struct AssetSorting {
     pub sort_by: AssetSortBy,
     pub sort_direction: AssetSortDirection,
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use entities::models::UrlWithStatus;
+use sqlx::{Postgres, QueryBuilder, Transaction};
+
+use crate::{
+    error::IndexDbError, PgClient, BATCH_DELETE_ACTION, BATCH_UPSERT_ACTION,
+    POSTGRES_PARAMETERS_COUNT_LIMIT,
+};
+
+pub const INSERT_OFFCHAIN_ATTRIBUTE_PARAMETERS_COUNT: usize = 3;
+pub const INSERT_OFFCHAIN_SYMBOL_PARAMETERS_COUNT: usize = 2;
+const OFFCHAIN_SYMBOLS_TABLE: &str = "offchain_symbols";
+const OFFCHAIN_ATTRIBUTES_TABLE: &str = "offchain_attributes";
+
+/// Searchable traits of a downloaded offchain JSON.
+/// Empty symbol and attributes wipe previously indexed traits of the metadata url.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OffchainTraits {
+    pub metadata_url: String,
+    pub symbol: Option<String>,
+    // (trait_type, value) pairs
+    pub attributes: Vec<(String, String)>,
+}
+
+impl PgClient {
+    /// Replaces the indexed symbol and attributes for every given metadata url.
+    pub async fn update_offchain_traits(
+        &self,
+        traits: Vec<OffchainTraits>,
+    ) -> Result<(), IndexDbError> {
+        if traits.is_empty() {
+            return Ok(());
+        }
+        // the same url may be downloaded several times within one batch, the last result wins
+        let mut traits_by_id = traits
+            .into_iter()
+            .map(|t| {
+                (
+                    UrlWithStatus::new(t.metadata_url.as_str(), false).get_metadata_id(),
+                    t,
+                )
+            })
+            .collect::<HashMap<_, _>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        traits_by_id.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let ids = traits_by_id
+            .iter()
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        let mut symbols = Vec::new();
+        let mut attributes = Vec::new();
+        for (id, t) in traits_by_id {
+            if let Some(symbol) = t.symbol {
+                symbols.push((id.clone(), symbol));
+            }
+            let mut unique_attributes = t.attributes;
+            unique_attributes.sort();
+            unique_attributes.dedup();
+            attributes.extend(
+                unique_attributes
+                    .into_iter()
+                    .map(|(trait_type, value)| (id.clone(), trait_type, value)),
+            );
+        }
+
+        let mut transaction = self.start_transaction().await?;
+        for chunk in ids.chunks(POSTGRES_PARAMETERS_COUNT_LIMIT) {
+            self.delete_offchain_traits(&mut transaction, chunk, OFFCHAIN_SYMBOLS_TABLE, "ofs")
+                .await?;
+            self.delete_offchain_traits(&mut transaction, chunk, OFFCHAIN_ATTRIBUTES_TABLE, "ofa")
+                .await?;
+        }
+        for chunk in symbols
+            .chunks(POSTGRES_PARAMETERS_COUNT_LIMIT / INSERT_OFFCHAIN_SYMBOL_PARAMETERS_COUNT)
+        {
+            self.insert_offchain_symbols(&mut transaction, chunk)
+                .await?;
+        }
+        for chunk in attributes
+            .chunks(POSTGRES_PARAMETERS_COUNT_LIMIT / INSERT_OFFCHAIN_ATTRIBUTE_PARAMETERS_COUNT)
+        {
+            self.insert_offchain_attributes(&mut transaction, chunk)
+                .await?;
+        }
+        self.commit_transaction(transaction).await
+    }
+
+    async fn delete_offchain_traits(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        metadata_url_ids: &[Vec<u8>],
+        table: &str,
+        column_prefix: &str,
+    ) -> Result<(), IndexDbError> {
+        let mut query_builder: QueryBuilder<'_, Postgres> = QueryBuilder::new("DELETE FROM ");
+        query_builder.push(table);
+        query_builder.push(" WHERE ");
+        query_builder.push(column_prefix);
+        query_builder.push("_metadata_url_id IN (");
+        let mut separated = query_builder.separated(", ");
+        for id in metadata_url_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(");");
+        self.execute_query_with_metrics(transaction, &mut query_builder, BATCH_DELETE_ACTION, table)
+            .await
+    }
+
+    async fn insert_offchain_symbols(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        symbols: &[(Vec<u8>, String)],
+    ) -> Result<(), IndexDbError> {
+        let mut query_builder: QueryBuilder<'_, Postgres> = QueryBuilder::new("INSERT INTO ");
+        query_builder.push(OFFCHAIN_SYMBOLS_TABLE);
+        query_builder.push(" (ofs_metadata_url_id, ofs_symbol) ");
+        query_builder.push_values(symbols, |mut builder, (id, symbol)| {
+            builder.push_bind(id).push_bind(symbol);
+        });
+        query_builder.push(
+            " ON CONFLICT (ofs_metadata_url_id) DO UPDATE SET ofs_symbol = EXCLUDED.ofs_symbol;",
+        );
+        self.execute_query_with_metrics(
+            transaction,
+            &mut query_builder,
+            BATCH_UPSERT_ACTION,
+            OFFCHAIN_SYMBOLS_TABLE,
+        )
+        .await
+    }
+
+    async fn insert_offchain_attributes(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        attributes: &[(Vec<u8>, String, String)],
+    ) -> Result<(), IndexDbError> {
+        let mut query_builder: QueryBuilder<'_, Postgres> = QueryBuilder::new("INSERT INTO ");
+        query_builder.push(OFFCHAIN_ATTRIBUTES_TABLE);
+        query_builder.push(" (ofa_metadata_url_id, ofa_trait_type, ofa_value) ");
+        query_builder.push_values(attributes, |mut builder, (id, trait_type, value)| {
+            builder.push_bind(id).push_bind(trait_type).push_bind(value);
+        });
+        query_builder.push(" ON CONFLICT DO NOTHING;");
+        self.execute_query_with_metrics(
+            transaction,
+            &mut query_builder,
+            BATCH_UPSERT_ACTION,
+            OFFCHAIN_ATTRIBUTES_TABLE,
+        )
+        .await
+    }
+}
@@ -0,0 +1,78 @@
+use crate::error::IndexDbError;
+use crate::model::{OwnerAssetsCount, OwnersSnapshotFilter};
+use crate::{PgClient, SELECT_ACTION, SQL_COMPONENT};
+use sqlx::{Execute, Postgres, QueryBuilder};
+use tracing::debug;
+
+impl PgClient {
+    pub fn build_owners_snapshot_query<'a>(
+        filter: &'a OwnersSnapshotFilter,
+        limit: u64,
+        after: Option<Vec<u8>>,
+    ) -> Result<QueryBuilder<'a, Postgres>, IndexDbError> {
+        if filter.collection.is_none() && filter.creator_address.is_none() {
+            return Err(IndexDbError::BadArgument(
+                "Either collection or creator address is required".to_string(),
+            ));
+        }
+        let mut query_builder =
+            QueryBuilder::new("SELECT ast_owner AS owner, COUNT(*) AS asset_count FROM assets_v3 ");
+        if filter.creator_address.is_some() {
+            query_builder.push(" INNER JOIN asset_creators_v3 ON ast_pubkey = asc_pubkey ");
+        }
+        // burnt assets don't have holders
+        query_builder
+            .push(" WHERE ast_owner IS NOT NULL AND ast_is_burnt = false AND ast_supply > 0 ");
+
+        if let Some(collection) = &filter.collection {
+            query_builder.push(" AND assets_v3.ast_collection = ");
+            query_builder.push_bind(collection);
+            if !filter.show_unverified_collections {
+                query_builder.push(" AND assets_v3.ast_is_collection_verified = true");
+            }
+        }
+        if let Some(creator_address) = &filter.creator_address {
+            query_builder.push(" AND asset_creators_v3.asc_creator = ");
+            query_builder.push_bind(creator_address);
+        }
+        if let Some(creator_verified) = filter.creator_verified {
+            query_builder.push(" AND asset_creators_v3.asc_verified = ");
+            query_builder.push_bind(creator_verified);
+        }
+        if let Some(after) = after {
+            query_builder.push(" AND assets_v3.ast_owner > ");
+            query_builder.push_bind(after);
+        }
+
+        query_builder.push(" GROUP BY ast_owner ORDER BY ast_owner ASC LIMIT ");
+        query_builder.push_bind(limit as i64);
+
+        Ok(query_builder)
+    }
+
+    /// Returns distinct owners of the filtered assets along with the number of assets each of them holds.
+    /// The owners are sorted by the key, `after` is the last owner of the previous page.
+    pub async fn get_owners_snapshot(
+        &self,
+        filter: &OwnersSnapshotFilter,
+        limit: u64,
+        after: Option<Vec<u8>>,
+    ) -> Result<Vec<OwnerAssetsCount>, IndexDbError> {
+        let mut query_builder = Self::build_owners_snapshot_query(filter, limit, after)?;
+        let query = query_builder.build_query_as::<OwnerAssetsCount>();
+        debug!("OWNERS SNAPSHOT QUERY: {}", &query.sql());
+        let start_time = chrono::Utc::now();
+        let result = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e: sqlx::Error| {
+                self.metrics
+                    .observe_error(SQL_COMPONENT, SELECT_ACTION, "assets_v3_owners");
+                e
+            })?;
+        self.metrics
+            .observe_request(SQL_COMPONENT, SELECT_ACTION, "assets_v3_owners", start_time);
+
+        Ok(result)
+    }
+}
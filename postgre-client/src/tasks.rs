@@ -1,10 +1,12 @@
 use crate::error::IndexDbError;
 use crate::PgClient;
+use chrono::{DateTime, Utc};
 use entities::enums::TaskStatus;
-use entities::models::{JsonDownloadTask, Task, UrlWithStatus};
+use entities::models::{JsonDownloadTask, PubkeyWithSlot, Task, UrlWithStatus};
 use metrics_utils::IngesterMetricsConfig;
+use solana_sdk::pubkey::Pubkey;
 use sqlx::{Postgres, QueryBuilder, Row};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use usecase::save_metrics::result_to_metrics;
@@ -16,6 +18,20 @@ pub struct UpdatedTask {
     pub status: TaskStatus,
     pub metadata_url: String,
     pub error: String,
+    // None keeps the validators and the hash of the previously downloaded file
+    pub content: Option<TaskContent>,
+    pub next_refresh_at: Option<DateTime<Utc>>,
+    // Some postpones the task until the given time without counting it as an attempt
+    pub deferred_until: Option<DateTime<Utc>>,
+}
+
+/// Description of the downloaded file used to refresh it later
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskContent {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub hash: Vec<u8>,
+    pub gateway: Option<String>,
 }
 
 impl PgClient {
@@ -90,17 +106,35 @@ impl PgClient {
         &self,
         data: Vec<UpdatedTask>,
     ) -> Result<(), IndexDbError> {
-        let mut query_builder: QueryBuilder<'_, Postgres> =
-            QueryBuilder::new("UPDATE tasks SET tsk_status = tmp.tsk_status, tsk_attempts = tsk_attempts+1, tsk_error = tmp.tsk_error FROM (");
+        let mut query_builder: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+            "UPDATE tasks SET tsk_status = tmp.tsk_status,
+            tsk_attempts = CASE WHEN tmp.tsk_deferred_until IS NULL THEN tasks.tsk_attempts+1 ELSE tasks.tsk_attempts END,
+            tsk_locked_until = COALESCE(tmp.tsk_deferred_until, tasks.tsk_locked_until),
+            tsk_error = tmp.tsk_error,
+            tsk_next_refresh_at = tmp.tsk_next_refresh_at,
+            tsk_etag = CASE WHEN tmp.has_content THEN tmp.tsk_etag ELSE tasks.tsk_etag END,
+            tsk_last_modified = CASE WHEN tmp.has_content THEN tmp.tsk_last_modified ELSE tasks.tsk_last_modified END,
+            tsk_content_hash = CASE WHEN tmp.has_content THEN tmp.tsk_content_hash ELSE tasks.tsk_content_hash END,
+            tsk_gateway = CASE WHEN tmp.has_content THEN tmp.tsk_gateway ELSE tasks.tsk_gateway END
+            FROM (",
+        );
 
         query_builder.push_values(data, |mut b, key| {
             let tsk = UrlWithStatus::new(key.metadata_url.as_str(), false); // status is ignoring here
+            let content = key.content.unwrap_or_default();
             b.push_bind(tsk.metadata_url);
             b.push_bind(key.status);
             b.push_bind(key.error);
+            b.push_bind(key.next_refresh_at);
+            b.push_bind(!content.hash.is_empty());
+            b.push_bind(content.etag);
+            b.push_bind(content.last_modified);
+            b.push_bind(content.hash);
+            b.push_bind(content.gateway);
+            b.push_bind(key.deferred_until);
         });
 
-        query_builder.push(") as tmp (tsk_metadata_url, tsk_status, tsk_error) WHERE tasks.tsk_metadata_url = tmp.tsk_metadata_url;");
+        query_builder.push(") as tmp (tsk_metadata_url, tsk_status, tsk_error, tsk_next_refresh_at, has_content, tsk_etag, tsk_last_modified, tsk_content_hash, tsk_gateway, tsk_deferred_until) WHERE tasks.tsk_metadata_url = tmp.tsk_metadata_url;");
 
         let query = query_builder.build();
         query.execute(&self.pool).await?;
@@ -129,7 +163,7 @@ impl PgClient {
             tsk_locked_until = NOW() + INTERVAL '90 seconds'
             FROM cte
             WHERE t.tsk_id = cte.tsk_id
-            RETURNING t.tsk_metadata_url, t.tsk_status, t.tsk_attempts, t.tsk_max_attempts;",
+            RETURNING t.tsk_metadata_url, t.tsk_status, t.tsk_attempts, t.tsk_max_attempts, t.tsk_etag, t.tsk_last_modified;",
         );
 
         let query = query_builder.build();
@@ -142,12 +176,16 @@ impl PgClient {
             let status: TaskStatus = row.get("tsk_status");
             let attempts: i16 = row.get("tsk_attempts");
             let max_attempts: i16 = row.get("tsk_max_attempts");
+            let etag: Option<String> = row.get("tsk_etag");
+            let last_modified: Option<String> = row.get("tsk_last_modified");
 
             tasks.push(JsonDownloadTask {
                 metadata_url,
                 status,
                 attempts,
                 max_attempts,
+                etag,
+                last_modified,
             });
         }
 
@@ -190,4 +228,127 @@ impl PgClient {
 
         Ok(())
     }
+
+    /// Returns hashes of the previously downloaded files keyed by the given urls
+    pub async fn get_tasks_content_hashes(
+        &self,
+        metadata_urls: &[String],
+    ) -> Result<HashMap<String, Vec<u8>>, IndexDbError> {
+        let ids = metadata_urls
+            .iter()
+            .map(|url| (UrlWithStatus::new(url, false).get_metadata_id(), url))
+            .collect::<HashMap<_, _>>();
+
+        let mut query_builder: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+            "SELECT tsk_id, tsk_content_hash FROM tasks WHERE tsk_content_hash IS NOT NULL AND tsk_id = ANY(",
+        );
+        query_builder.push_bind(ids.keys().cloned().collect::<Vec<_>>());
+        query_builder.push(")");
+
+        let rows = query_builder.build().fetch_all(&self.pool).await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let id: Vec<u8> = row.get("tsk_id");
+                let hash: Vec<u8> = row.get("tsk_content_hash");
+                ids.get(&id).map(|url| (url.to_string(), hash))
+            })
+            .collect())
+    }
+
+    /// Returns the assets referring to the metadata urls along with the slots they were updated at
+    pub async fn get_assets_by_metadata_urls(
+        &self,
+        metadata_urls: &[String],
+    ) -> Result<Vec<PubkeyWithSlot>, IndexDbError> {
+        let ids = metadata_urls
+            .iter()
+            .map(|url| UrlWithStatus::new(url, false).get_metadata_id())
+            .collect::<Vec<_>>();
+
+        let mut query_builder: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+            "SELECT ast_pubkey, ast_slot_updated FROM assets_v3 WHERE ast_metadata_url_id = ANY(",
+        );
+        query_builder.push_bind(ids);
+        query_builder.push(")");
+
+        let rows = query_builder.build().fetch_all(&self.pool).await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let pubkey = Pubkey::try_from(row.get::<Vec<u8>, _>("ast_pubkey"))
+                    .map_err(|bytes| IndexDbError::PubkeyParsingError(format!("{:?}", bytes)))?;
+                Ok(PubkeyWithSlot {
+                    pubkey,
+                    slot: row.get::<i64, _>("ast_slot_updated") as u64,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the number of the JSON download tasks waiting to be processed
+    pub async fn count_pending_tasks(&self) -> Result<u64, IndexDbError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM tasks
+            WHERE (tsk_status = 'running' OR tsk_status = 'pending') AND tsk_attempts < tsk_max_attempts;",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count as u64)
+    }
+
+    /// Moves the tasks whose refresh time has come back to the pending state.
+    /// Returns the number of the re-enqueued tasks.
+    pub async fn schedule_metadata_refresh(&self, limit: i64) -> Result<u64, IndexDbError> {
+        let mut query_builder: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+            "UPDATE tasks SET tsk_status = 'pending', tsk_attempts = 0, tsk_locked_until = NOW(), tsk_next_refresh_at = NULL
+            WHERE tsk_id IN (
+                SELECT tsk_id
+                FROM tasks
+                WHERE tsk_next_refresh_at <= NOW() AND (tsk_status = 'success' OR tsk_status = 'failed')
+                LIMIT ",
+        );
+        query_builder.push_bind(limit);
+        query_builder.push(" FOR UPDATE SKIP LOCKED);");
+
+        let result = query_builder.build().execute(&self.pool).await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Enqueues the files to be downloaded from scratch, ignoring the validators of the previous download
+    pub async fn enqueue_metadata_refresh(
+        &self,
+        metadata_urls: &[String],
+    ) -> Result<(), IndexDbError> {
+        // a row can't be updated twice by the same statement
+        let tasks = metadata_urls
+            .iter()
+            .map(|url| {
+                let tsk = UrlWithStatus::new(url, false);
+                (tsk.get_metadata_id(), tsk.metadata_url)
+            })
+            .collect::<BTreeMap<_, _>>();
+        if tasks.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<'_, Postgres> =
+            QueryBuilder::new("INSERT INTO tasks (tsk_id, tsk_metadata_url, tsk_status) ");
+        query_builder.push_values(tasks, |mut b, (id, metadata_url)| {
+            b.push_bind(id);
+            b.push_bind(metadata_url);
+            b.push_bind(TaskStatus::Pending);
+        });
+        query_builder.push(
+            " ON CONFLICT (tsk_id) DO UPDATE SET tsk_status = 'pending', tsk_attempts = 0, tsk_locked_until = NOW(),
+            tsk_next_refresh_at = NULL, tsk_etag = NULL, tsk_last_modified = NULL, tsk_content_hash = NULL;",
+        );
+
+        query_builder.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
 }
@@ -138,7 +138,10 @@ impl TempClient {
             ast_is_frozen = EXCLUDED.ast_is_frozen,
             ast_supply = EXCLUDED.ast_supply,
             ast_metadata_url_id = EXCLUDED.ast_metadata_url_id,
-            ast_slot_updated = EXCLUDED.ast_slot_updated
+            ast_slot_updated = EXCLUDED.ast_slot_updated,
+            ast_name = EXCLUDED.ast_name,
+            ast_core_plugins = EXCLUDED.ast_core_plugins,
+            ast_core_attributes = EXCLUDED.ast_core_attributes
             WHERE assets_v3.ast_slot_updated <= EXCLUDED.ast_slot_updated OR assets_v3.ast_slot_updated IS NULL;");
 
         self.pg_client
@@ -0,0 +1,137 @@
+use crate::error::IndexDbError;
+use crate::PgClient;
+use entities::enums::AssetEventType;
+use entities::models::{Webhook, WebhookFilter};
+use solana_sdk::pubkey::Pubkey;
+use sqlx::postgres::PgRow;
+use sqlx::{Postgres, QueryBuilder, Row};
+use std::str::FromStr;
+
+const WEBHOOK_COLUMNS: &str = "whk_id, whk_url, whk_secret, whk_owners, whk_collections,
+    whk_creators, whk_trees, whk_asset_ids, whk_event_types, whk_key_name, whk_created_at";
+
+impl PgClient {
+    /// Registers the webhook of the API key, the ingester starts notifying it about the asset
+    /// events matching the filter. Returns None if the key already has `max_webhooks` webhooks
+    pub async fn insert_webhook(
+        &self,
+        url: &str,
+        secret: &str,
+        filter: &WebhookFilter,
+        key_name: &str,
+        max_webhooks: u64,
+    ) -> Result<Option<Webhook>, IndexDbError> {
+        let mut query_builder: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+            "INSERT INTO webhooks (whk_url, whk_secret, whk_key_name, whk_owners, whk_collections,
+                whk_creators, whk_trees, whk_asset_ids, whk_event_types) SELECT ",
+        );
+        let mut values = query_builder.separated(", ");
+        values.push_bind(url);
+        values.push_bind(secret);
+        values.push_bind(key_name);
+        for keys in [
+            &filter.owners,
+            &filter.collections,
+            &filter.creators,
+            &filter.trees,
+            &filter.asset_ids,
+        ] {
+            values.push_bind(keys_to_bytes(keys));
+        }
+        values.push_bind(
+            filter
+                .event_types
+                .iter()
+                .map(|event_type| event_type.as_str().to_string())
+                .collect::<Vec<_>>(),
+        );
+        query_builder.push(" WHERE (SELECT COUNT(*) FROM webhooks WHERE whk_key_name = ");
+        query_builder.push_bind(key_name);
+        query_builder.push(") < ");
+        query_builder.push_bind(max_webhooks.min(i64::MAX as u64) as i64);
+        query_builder.push(" RETURNING ");
+        query_builder.push(WEBHOOK_COLUMNS);
+        query_builder.push(";");
+
+        let row = query_builder.build().fetch_optional(&self.pool).await?;
+
+        row.as_ref().map(webhook_from_row).transpose()
+    }
+
+    /// Returns up to `limit` webhooks sorted by the id, starting right after the `after` id.
+    /// Only the webhooks of the API key are returned if its name is passed
+    pub async fn get_webhooks(
+        &self,
+        key_name: Option<&str>,
+        after: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<Webhook>, IndexDbError> {
+        let mut query_builder: QueryBuilder<'_, Postgres> = QueryBuilder::new("SELECT ");
+        query_builder.push(WEBHOOK_COLUMNS);
+        query_builder.push(" FROM webhooks WHERE whk_id > ");
+        query_builder.push_bind(after.unwrap_or_default() as i64);
+        if let Some(key_name) = key_name {
+            query_builder.push(" AND whk_key_name = ");
+            query_builder.push_bind(key_name);
+        }
+        query_builder.push(" ORDER BY whk_id LIMIT ");
+        query_builder.push_bind(limit.min(i64::MAX as u64) as i64);
+        query_builder.push(";");
+
+        let rows = query_builder.build().fetch_all(&self.pool).await?;
+
+        rows.iter().map(webhook_from_row).collect()
+    }
+
+    /// Removes the webhook of the API key, returns false if the key had no such webhook
+    pub async fn delete_webhook(&self, id: u64, key_name: &str) -> Result<bool, IndexDbError> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE whk_id = $1 AND whk_key_name = $2;")
+            .bind(id as i64)
+            .bind(key_name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn keys_to_bytes(keys: &[Pubkey]) -> Vec<Vec<u8>> {
+    keys.iter().map(|key| key.to_bytes().to_vec()).collect()
+}
+
+fn keys_from_row(row: &PgRow, column: &str) -> Result<Vec<Pubkey>, IndexDbError> {
+    row.try_get::<Vec<Vec<u8>>, _>(column)?
+        .into_iter()
+        .map(|bytes| {
+            Pubkey::try_from(bytes)
+                .map_err(|bytes| IndexDbError::PubkeyParsingError(format!("{:?}", bytes)))
+        })
+        .collect()
+}
+
+fn webhook_from_row(row: &PgRow) -> Result<Webhook, IndexDbError> {
+    let event_types = row
+        .try_get::<Vec<String>, _>("whk_event_types")?
+        .iter()
+        .map(|event_type| AssetEventType::from_str(event_type))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(IndexDbError::BadArgument)?;
+
+    Ok(Webhook {
+        id: row.try_get::<i64, _>("whk_id")? as u64,
+        url: row.try_get("whk_url")?,
+        secret: row.try_get("whk_secret")?,
+        filter: WebhookFilter {
+            owners: keys_from_row(row, "whk_owners")?,
+            collections: keys_from_row(row, "whk_collections")?,
+            creators: keys_from_row(row, "whk_creators")?,
+            trees: keys_from_row(row, "whk_trees")?,
+            asset_ids: keys_from_row(row, "whk_asset_ids")?,
+            event_types,
+        },
+        key_name: row.try_get("whk_key_name")?,
+        created_at: row
+            .try_get::<chrono::DateTime<chrono::Utc>, _>("whk_created_at")?
+            .timestamp(),
+    })
+}
@@ -2,7 +2,9 @@
 #[cfg(test)]
 mod tests {
     use entities::api_req_params::GetByMethodsOptions;
+    use entities::models::UrlWithStatus;
     use postgre_client::model::*;
+    use postgre_client::offchain_traits::OffchainTraits;
     use postgre_client::storage_traits::{AssetIndexStorage, AssetPubkeyFilteredFetcher};
     use setup::pg::*;
     use testcontainers::clients::Cli;
@@ -35,6 +37,21 @@ mod tests {
             burnt: Some(false),
             json_uri: Some("https://www.google.com".to_string()),
             token_type: None,
+            name: None,
+            symbol: Some("SYM".to_string()),
+            attributes: Some(AssetAttributes::All(vec![(
+                "Background".to_string(),
+                "Blue".to_string(),
+            )])),
+            core_plugins: Some(vec!["attributes".to_string()]),
+            core_attributes: Some(AssetAttributes::All(vec![(
+                "level".to_string(),
+                "10".to_string(),
+            )])),
+            owner_at_slot: Some(OwnerAtSlot {
+                owned: vec![generate_random_vec(32)],
+                not_owned: vec![generate_random_vec(32)],
+            }),
         };
         let order = AssetSorting {
             sort_by: AssetSortBy::SlotCreated,
@@ -108,6 +125,12 @@ mod tests {
                 .clone()
                 .map(|url_with_status| url_with_status.metadata_url),
             token_type: None,
+            name: None,
+            symbol: None,
+            attributes: None,
+            core_plugins: None,
+            core_attributes: None,
+            owner_at_slot: None,
         };
         let order: AssetSorting = AssetSorting {
             sort_by: AssetSortBy::SlotUpdated,
@@ -328,4 +351,489 @@ mod tests {
         assert_eq!(res.len(), 101);
         env.teardown().await;
     }
+
+    #[tokio::test]
+    async fn test_get_asset_pubkeys_filtered_by_name() {
+        let cli = Cli::default();
+        let env = TestEnvironment::new(&cli).await;
+        let asset_filter_storage = &env.client;
+
+        // names are generated as "Test Asset #0" .. "Test Asset #99"
+        let asset_indexes = generate_asset_index_records(100);
+        asset_filter_storage
+            .update_asset_indexes_batch(asset_indexes.as_slice())
+            .await
+            .unwrap();
+        let order = AssetSorting {
+            sort_by: AssetSortBy::SlotCreated,
+            sort_direction: AssetSortDirection::Asc,
+        };
+        let options = GetByMethodsOptions {
+            show_unverified_collections: true,
+            ..Default::default()
+        };
+
+        let res = asset_filter_storage
+            .get_asset_pubkeys_filtered(
+                &SearchAssetsFilter {
+                    name: Some(AssetName::Exact("Test Asset #42".to_string())),
+                    ..Default::default()
+                },
+                &order,
+                1000,
+                None,
+                None,
+                None,
+                &options,
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].pubkey, asset_indexes[42].pubkey.to_bytes());
+
+        // #1 and #10..#19
+        let res = asset_filter_storage
+            .get_asset_pubkeys_filtered(
+                &SearchAssetsFilter {
+                    name: Some(AssetName::Prefix("test asset #1".to_string())),
+                    ..Default::default()
+                },
+                &order,
+                1000,
+                None,
+                None,
+                None,
+                &options,
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 11);
+
+        // wildcards from the user input are matched literally
+        let res = asset_filter_storage
+            .get_asset_pubkeys_filtered(
+                &SearchAssetsFilter {
+                    name: Some(AssetName::Prefix("Test_Asset".to_string())),
+                    ..Default::default()
+                },
+                &order,
+                1000,
+                None,
+                None,
+                None,
+                &options,
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 0);
+
+        let res = asset_filter_storage
+            .get_asset_pubkeys_filtered(
+                &SearchAssetsFilter {
+                    name: Some(AssetName::Fuzzy("Tset Aset #42".to_string())),
+                    ..Default::default()
+                },
+                &order,
+                1000,
+                None,
+                None,
+                None,
+                &options,
+            )
+            .await
+            .unwrap();
+        assert!(res
+            .iter()
+            .any(|r| r.pubkey == asset_indexes[42].pubkey.to_bytes()));
+
+        let res = asset_filter_storage
+            .get_asset_pubkeys_filtered(
+                &SearchAssetsFilter {
+                    name: Some(AssetName::Fuzzy("completely different".to_string())),
+                    ..Default::default()
+                },
+                &order,
+                1000,
+                None,
+                None,
+                None,
+                &options,
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 0);
+
+        env.teardown().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_asset_pubkeys_filtered_by_offchain_traits() {
+        let cli = Cli::default();
+        let env = TestEnvironment::new(&cli).await;
+        let asset_filter_storage = &env.client;
+
+        let mut asset_indexes = generate_asset_index_records(3);
+        for (i, asset_index) in asset_indexes.iter_mut().enumerate() {
+            asset_index.metadata_url = Some(UrlWithStatus::new(
+                format!("https://example.com/{}.json", i).as_str(),
+                true,
+            ));
+        }
+        asset_filter_storage
+            .update_asset_indexes_batch(asset_indexes.as_slice())
+            .await
+            .unwrap();
+        let attributes = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(t, v)| (t.to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        };
+        asset_filter_storage
+            .update_offchain_traits(vec![
+                OffchainTraits {
+                    metadata_url: "https://example.com/0.json".to_string(),
+                    symbol: Some("MAD".to_string()),
+                    attributes: attributes(&[("Background", "Blue"), ("Level", "7")]),
+                },
+                OffchainTraits {
+                    metadata_url: "https://example.com/1.json".to_string(),
+                    symbol: Some("MAD".to_string()),
+                    attributes: attributes(&[("Background", "Red"), ("Level", "7")]),
+                },
+                OffchainTraits {
+                    metadata_url: "https://example.com/2.json".to_string(),
+                    symbol: Some("OTHER".to_string()),
+                    attributes: attributes(&[("Background", "Blue")]),
+                },
+            ])
+            .await
+            .unwrap();
+        let order = AssetSorting {
+            sort_by: AssetSortBy::Key,
+            sort_direction: AssetSortDirection::Asc,
+        };
+        let options = GetByMethodsOptions {
+            show_unverified_collections: true,
+            ..Default::default()
+        };
+        let search = |filter: SearchAssetsFilter| {
+            let order = &order;
+            let options = &options;
+            async move {
+                let mut pubkeys = asset_filter_storage
+                    .get_asset_pubkeys_filtered(&filter, order, 1000, None, None, None, options)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|r| r.pubkey)
+                    .collect::<Vec<_>>();
+                pubkeys.sort();
+                pubkeys
+            }
+        };
+        let pubkeys_of = |indexes: &[usize]| {
+            let mut pubkeys = indexes
+                .iter()
+                .map(|i| asset_indexes[*i].pubkey.to_bytes().to_vec())
+                .collect::<Vec<_>>();
+            pubkeys.sort();
+            pubkeys
+        };
+
+        let res = search(SearchAssetsFilter {
+            symbol: Some("MAD".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(res, pubkeys_of(&[0, 1]));
+
+        let res = search(SearchAssetsFilter {
+            attributes: Some(AssetAttributes::All(attributes(&[
+                ("Background", "Blue"),
+                ("Level", "7"),
+            ]))),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(res, pubkeys_of(&[0]));
+
+        let res = search(SearchAssetsFilter {
+            attributes: Some(AssetAttributes::Any(attributes(&[
+                ("Background", "Red"),
+                ("Background", "Blue"),
+            ]))),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(res, pubkeys_of(&[0, 1, 2]));
+
+        let res = search(SearchAssetsFilter {
+            symbol: Some("MAD".to_string()),
+            attributes: Some(AssetAttributes::All(attributes(&[("Background", "Blue")]))),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(res, pubkeys_of(&[0]));
+
+        // a re-downloaded JSON replaces previously indexed traits
+        asset_filter_storage
+            .update_offchain_traits(vec![OffchainTraits {
+                metadata_url: "https://example.com/0.json".to_string(),
+                ..Default::default()
+            }])
+            .await
+            .unwrap();
+        let res = search(SearchAssetsFilter {
+            symbol: Some("MAD".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(res, pubkeys_of(&[1]));
+
+        env.teardown().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_asset_pubkeys_filtered_by_core_plugins() {
+        let cli = Cli::default();
+        let env = TestEnvironment::new(&cli).await;
+        let asset_filter_storage = &env.client;
+
+        let pairs = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        };
+        let mut asset_indexes = generate_asset_index_records(3);
+        asset_indexes[0].core_plugins = vec!["attributes".to_string(), "oracle".to_string()];
+        asset_indexes[0].core_attributes = pairs(&[("class", "mage"), ("level", "10")]);
+        asset_indexes[1].core_plugins =
+            vec!["attributes".to_string(), "freeze_delegate".to_string()];
+        asset_indexes[1].core_attributes = pairs(&[("class", "warrior"), ("level", "10")]);
+        asset_filter_storage
+            .update_asset_indexes_batch(asset_indexes.as_slice())
+            .await
+            .unwrap();
+        let order = AssetSorting {
+            sort_by: AssetSortBy::Key,
+            sort_direction: AssetSortDirection::Asc,
+        };
+        let options = GetByMethodsOptions {
+            show_unverified_collections: true,
+            ..Default::default()
+        };
+        let search = |filter: SearchAssetsFilter| {
+            let order = &order;
+            let options = &options;
+            async move {
+                let mut pubkeys = asset_filter_storage
+                    .get_asset_pubkeys_filtered(&filter, order, 1000, None, None, None, options)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|r| r.pubkey)
+                    .collect::<Vec<_>>();
+                pubkeys.sort();
+                pubkeys
+            }
+        };
+        let pubkeys_of = |indexes: &[usize]| {
+            let mut pubkeys = indexes
+                .iter()
+                .map(|i| asset_indexes[*i].pubkey.to_bytes().to_vec())
+                .collect::<Vec<_>>();
+            pubkeys.sort();
+            pubkeys
+        };
+
+        let res = search(SearchAssetsFilter {
+            core_plugins: Some(vec!["attributes".to_string()]),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(res, pubkeys_of(&[0, 1]));
+
+        let res = search(SearchAssetsFilter {
+            core_plugins: Some(vec!["attributes".to_string(), "oracle".to_string()]),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(res, pubkeys_of(&[0]));
+
+        let res = search(SearchAssetsFilter {
+            core_attributes: Some(AssetAttributes::All(pairs(&[("level", "10")]))),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(res, pubkeys_of(&[0, 1]));
+
+        let res = search(SearchAssetsFilter {
+            core_attributes: Some(AssetAttributes::All(pairs(&[
+                ("level", "10"),
+                ("class", "warrior"),
+            ]))),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(res, pubkeys_of(&[1]));
+
+        let res = search(SearchAssetsFilter {
+            core_attributes: Some(AssetAttributes::Any(pairs(&[
+                ("class", "warrior"),
+                ("class", "mage"),
+            ]))),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(res, pubkeys_of(&[0, 1]));
+
+        let res = search(SearchAssetsFilter {
+            core_plugins: Some(vec!["freeze_delegate".to_string()]),
+            core_attributes: Some(AssetAttributes::All(pairs(&[("class", "mage")]))),
+            ..Default::default()
+        })
+        .await;
+        assert!(res.is_empty());
+
+        env.teardown().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_asset_pubkeys_filtered_by_owner_at_slot() {
+        let cli = Cli::default();
+        let env = TestEnvironment::new(&cli).await;
+        let asset_filter_storage = &env.client;
+
+        let owner = solana_sdk::pubkey::Pubkey::new_unique();
+        let mut asset_indexes = generate_asset_index_records(4);
+        // the owner holds the assets 0 and 1 now, the asset 1 was received
+        // and the asset 2 was sent after the slot asked for
+        asset_indexes[0].owner = Some(owner);
+        asset_indexes[1].owner = Some(owner);
+        asset_filter_storage
+            .update_asset_indexes_batch(asset_indexes.as_slice())
+            .await
+            .unwrap();
+        let order = AssetSorting {
+            sort_by: AssetSortBy::Key,
+            sort_direction: AssetSortDirection::Asc,
+        };
+        let options = GetByMethodsOptions {
+            show_unverified_collections: true,
+            ..Default::default()
+        };
+        let pubkey_of = |i: usize| asset_indexes[i].pubkey.to_bytes().to_vec();
+
+        let mut res = asset_filter_storage
+            .get_asset_pubkeys_filtered(
+                &SearchAssetsFilter {
+                    owner_address: Some(owner.to_bytes().to_vec()),
+                    owner_at_slot: Some(OwnerAtSlot {
+                        owned: vec![pubkey_of(2)],
+                        not_owned: vec![pubkey_of(1)],
+                    }),
+                    ..Default::default()
+                },
+                &order,
+                1000,
+                None,
+                None,
+                None,
+                &options,
+            )
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.pubkey)
+            .collect::<Vec<_>>();
+        res.sort();
+        let mut expected = vec![pubkey_of(0), pubkey_of(2)];
+        expected.sort();
+        assert_eq!(res, expected);
+
+        env.teardown().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_owners_snapshot() {
+        let cli = Cli::default();
+        let env = TestEnvironment::new(&cli).await;
+        let asset_filter_storage = &env.client;
+
+        let collection = generate_random_pubkey();
+        let creator = generate_random_pubkey();
+        let mut owners = vec![
+            generate_random_pubkey(),
+            generate_random_pubkey(),
+            generate_random_pubkey(),
+        ];
+        owners.sort_by_key(|owner| owner.to_bytes());
+
+        // owner 0 holds 3 assets, owner 1 holds 2 and owner 2 holds only a burnt one
+        let mut asset_indexes = generate_asset_index_records(8);
+        for (asset_index, owner_idx) in asset_indexes.iter_mut().zip([0, 0, 0, 1, 1, 2]) {
+            asset_index.owner = Some(owners[owner_idx]);
+            asset_index.collection = Some(collection);
+            asset_index.is_collection_verified = Some(true);
+            asset_index.creators[0].creator = creator;
+            asset_index.creators[0].creator_verified = true;
+        }
+        asset_indexes[5].is_burnt = true;
+        asset_filter_storage
+            .update_asset_indexes_batch(asset_indexes.as_slice())
+            .await
+            .unwrap();
+
+        let expected = vec![
+            OwnerAssetsCount {
+                owner: owners[0].to_bytes().to_vec(),
+                asset_count: 3,
+            },
+            OwnerAssetsCount {
+                owner: owners[1].to_bytes().to_vec(),
+                asset_count: 2,
+            },
+        ];
+        let by_collection = OwnersSnapshotFilter {
+            collection: Some(collection.to_bytes().to_vec()),
+            ..Default::default()
+        };
+        let res = asset_filter_storage
+            .get_owners_snapshot(&by_collection, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(res, expected);
+
+        let by_creator = OwnersSnapshotFilter {
+            creator_address: Some(creator.to_bytes().to_vec()),
+            creator_verified: Some(true),
+            ..Default::default()
+        };
+        let res = asset_filter_storage
+            .get_owners_snapshot(&by_creator, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(res, expected);
+
+        // paginated by the owner key
+        let res = asset_filter_storage
+            .get_owners_snapshot(&by_collection, 1, None)
+            .await
+            .unwrap();
+        assert_eq!(res, expected[..1]);
+        let res = asset_filter_storage
+            .get_owners_snapshot(&by_collection, 1, Some(res[0].owner.clone()))
+            .await
+            .unwrap();
+        assert_eq!(res, expected[1..]);
+
+        assert!(asset_filter_storage
+            .get_owners_snapshot(&OwnersSnapshotFilter::default(), 10, None)
+            .await
+            .is_err());
+
+        env.teardown().await;
+    }
 }
@@ -5,6 +5,7 @@ mod tests {
     use setup::pg::*;
 
     use entities::{enums::TaskStatus, models::Task};
+    use postgre_client::tasks::{TaskContent, UpdatedTask};
     use testcontainers::clients::Cli;
     use tokio;
 
@@ -112,4 +113,182 @@ mod tests {
 
         assert_eq!(selected.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_json_task_refresh() {
+        let cli = Cli::default();
+        let env = TestEnvironment::new(&cli).await;
+        let asset_index_storage = &env.client;
+
+        let url = "https://url1.com".to_string();
+        let task = Task {
+            ofd_metadata_url: url.clone(),
+            ofd_locked_until: Some(Utc::now().checked_sub_days(Days::new(1)).unwrap()),
+            ofd_attempts: 0,
+            ofd_max_attempts: 10,
+            ofd_error: None,
+            ofd_status: TaskStatus::Pending,
+        };
+        asset_index_storage
+            .insert_json_download_tasks(&mut vec![task])
+            .await
+            .unwrap();
+        let selected = asset_index_storage.get_pending_tasks(100).await.unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].etag, None);
+
+        // the task is downloaded and its refresh time has already come
+        let content = TaskContent {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            hash: vec![1; 32],
+            gateway: Some("https://ipfs.io".to_string()),
+        };
+        asset_index_storage
+            .update_tasks_and_attempts(vec![UpdatedTask {
+                status: TaskStatus::Success,
+                metadata_url: url.clone(),
+                error: "".to_string(),
+                content: Some(content.clone()),
+                next_refresh_at: Some(Utc::now().checked_sub_days(Days::new(1)).unwrap()),
+                deferred_until: None,
+            }])
+            .await
+            .unwrap();
+        let hashes = asset_index_storage
+            .get_tasks_content_hashes(&[url.clone(), "https://url2.com".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(hashes.len(), 1);
+        assert_eq!(hashes.get(&url), Some(&content.hash));
+
+        assert_eq!(
+            asset_index_storage
+                .schedule_metadata_refresh(100)
+                .await
+                .unwrap(),
+            1
+        );
+        let selected = asset_index_storage.get_pending_tasks(100).await.unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].etag, content.etag);
+
+        // the file is not modified, the validators and the hash are kept
+        asset_index_storage
+            .update_tasks_and_attempts(vec![UpdatedTask {
+                status: TaskStatus::Success,
+                metadata_url: url.clone(),
+                error: "".to_string(),
+                content: None,
+                next_refresh_at: Some(Utc::now().checked_add_days(Days::new(1)).unwrap()),
+                deferred_until: None,
+            }])
+            .await
+            .unwrap();
+        assert_eq!(
+            asset_index_storage
+                .schedule_metadata_refresh(100)
+                .await
+                .unwrap(),
+            0
+        );
+        let hashes = asset_index_storage
+            .get_tasks_content_hashes(&[url.clone()])
+            .await
+            .unwrap();
+        assert_eq!(hashes.get(&url), Some(&content.hash));
+
+        // on demand refresh drops the validators and creates the missing tasks
+        asset_index_storage
+            .enqueue_metadata_refresh(&[
+                url.clone(),
+                format!(" {} ", url),
+                "https://url2.com".to_string(),
+            ])
+            .await
+            .unwrap();
+        let selected = asset_index_storage.get_pending_tasks(100).await.unwrap();
+        assert_eq!(selected.len(), 2);
+        assert!(selected.iter().all(|task| task.etag.is_none()));
+        assert!(asset_index_storage
+            .get_tasks_content_hashes(&[url])
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_json_task_deferred() {
+        let cli = Cli::default();
+        let env = TestEnvironment::new(&cli).await;
+        let asset_index_storage = &env.client;
+
+        let url = "https://url1.com".to_string();
+        let task = Task {
+            ofd_metadata_url: url.clone(),
+            ofd_locked_until: Some(Utc::now().checked_sub_days(Days::new(1)).unwrap()),
+            ofd_attempts: 0,
+            ofd_max_attempts: 1,
+            ofd_error: None,
+            ofd_status: TaskStatus::Pending,
+        };
+        asset_index_storage
+            .insert_json_download_tasks(&mut vec![task])
+            .await
+            .unwrap();
+        let deferred_task = |deferred_until| UpdatedTask {
+            status: TaskStatus::Pending,
+            metadata_url: url.clone(),
+            error: "backed off".to_string(),
+            content: None,
+            next_refresh_at: None,
+            deferred_until: Some(deferred_until),
+        };
+
+        // the deferred task is locked until the given time
+        assert_eq!(
+            asset_index_storage
+                .get_pending_tasks(100)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        asset_index_storage
+            .update_tasks_and_attempts(vec![deferred_task(
+                Utc::now().checked_add_days(Days::new(1)).unwrap(),
+            )])
+            .await
+            .unwrap();
+        assert!(asset_index_storage
+            .get_pending_tasks(100)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // and it's not counted as an attempt
+        asset_index_storage
+            .update_tasks_and_attempts(vec![deferred_task(
+                Utc::now().checked_sub_days(Days::new(1)).unwrap(),
+            )])
+            .await
+            .unwrap();
+        let selected = asset_index_storage.get_pending_tasks(100).await.unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].attempts, 0);
+
+        // the failed download is the last attempt
+        asset_index_storage
+            .update_tasks_and_attempts(vec![UpdatedTask {
+                deferred_until: None,
+                ..deferred_task(Utc::now())
+            }])
+            .await
+            .unwrap();
+        assert!(asset_index_storage
+            .get_pending_tasks(100)
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -0,0 +1,110 @@
+use std::sync::atomic::Ordering;
+
+use entities::enums::AccountTombstone;
+use entities::models::SlotAccountUpdate;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::column::TypedColumn;
+use crate::key_encoders::{decode_u64_pubkey, encode_u64_pubkey};
+use crate::{Result, Storage};
+
+/// This column family keeps the slots the accounts were updated in, so the fork cleaner
+/// can find the account based updates which came from the forked slots.
+/// Key is a set of `Slot+Account`, so the updates are iterated in the order of the slots.
+/// Value is the write version of the latest update of the account in the slot,
+/// the state of a forked account is restored with a newer write version,
+/// along with what the account is processed as if it's missing at the confirmed commitment.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AccountUpdateSlot {
+    pub write_version: u64,
+    pub tombstone: AccountTombstone,
+}
+
+impl AccountUpdateSlot {
+    // the values recorded before the tombstones hold only the write version
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        bincode::deserialize::<Self>(bytes).ok().or_else(|| {
+            bincode::deserialize::<u64>(bytes)
+                .ok()
+                .map(|write_version| Self {
+                    write_version,
+                    tombstone: AccountTombstone::Unknown,
+                })
+        })
+    }
+}
+
+impl TypedColumn for AccountUpdateSlot {
+    type KeyType = (u64, Pubkey);
+    type ValueType = Self;
+    const NAME: &'static str = "ACCOUNT_UPDATE_SLOTS";
+
+    fn encode_key(key: (u64, Pubkey)) -> Vec<u8> {
+        encode_u64_pubkey(key.0, key.1)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_u64_pubkey(bytes)
+    }
+}
+
+impl Storage {
+    /// The slots are tracked only while the fork cleaner runs, otherwise nothing drops them
+    pub fn set_account_update_slots_enabled(&self, enabled: bool) {
+        self.account_update_slots_enabled
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_account_update_slots_enabled(&self) -> bool {
+        self.account_update_slots_enabled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_account_update_slot_with_batch(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        slot: u64,
+        pubkey: Pubkey,
+        write_version: u64,
+        tombstone: AccountTombstone,
+    ) -> Result<()> {
+        if !self.is_account_update_slots_enabled() {
+            return Ok(());
+        }
+        self.account_update_slots.put_with_batch(
+            batch,
+            (slot, pubkey),
+            &AccountUpdateSlot {
+                write_version,
+                tombstone,
+            },
+        )
+    }
+
+    /// Slot of the latest recorded account update
+    pub fn account_update_slots_last_slot(&self) -> Option<u64> {
+        self.account_update_slots
+            .iter_end()
+            .filter_map(std::result::Result::ok)
+            .next()
+            .and_then(|(key, _)| AccountUpdateSlot::decode_key(key.to_vec()).ok())
+            .map(|(slot, _)| slot)
+    }
+
+    /// Iterates over the recorded account updates, the oldest slots first
+    pub fn account_update_slots_iter(&self) -> impl Iterator<Item = SlotAccountUpdate> + '_ {
+        self.account_update_slots
+            .iter_start()
+            .filter_map(std::result::Result::ok)
+            .filter_map(|(key, value)| {
+                let (slot, pubkey) = AccountUpdateSlot::decode_key(key.to_vec()).ok()?;
+                let value = AccountUpdateSlot::decode(value.as_ref())?;
+                Some(SlotAccountUpdate {
+                    slot,
+                    pubkey,
+                    write_version: value.write_version,
+                    tombstone: value.tombstone,
+                })
+            })
+    }
+}
@@ -4,6 +4,7 @@ use crate::inscriptions::{Inscription, InscriptionData};
 use bincode::{deserialize, serialize};
 use entities::enums::{ChainMutability, OwnerType, RoyaltyTargetType, SpecificationAssetClass};
 use entities::models::{EditionData, OffChainData, SplMint, TokenAccount, UpdateVersion, Updated};
+use inflector::Inflector;
 use rocksdb::MergeOperands;
 use serde::{Deserialize, Serialize};
 use solana_sdk::{hash::Hash, pubkey::Pubkey};
@@ -170,6 +171,11 @@ pub struct AssetCollectionDeprecated {
     pub slot_updated: u64,
 }
 
+/// Type of an MPL Core plugin as it's indexed and searched, e.g. "freeze_delegate" for "FreezeDelegate"
+pub fn core_plugin_type(plugin_type: &str) -> String {
+    plugin_type.trim().to_snake_case()
+}
+
 pub(crate) fn update_field<T: Clone>(current: &mut Updated<T>, new: &Updated<T>) {
     if current.update_version.is_some() && new.update_version.is_some() {
         match current
@@ -348,6 +354,47 @@ impl AssetStaticDetails {
 }
 
 impl AssetDynamicDetails {
+    /// Applies a newer (partial) version on top of the current one field by field
+    pub fn merge(&mut self, new_val: &Self) {
+        update_field(&mut self.is_compressible, &new_val.is_compressible);
+        update_field(&mut self.is_compressed, &new_val.is_compressed);
+        update_field(&mut self.is_frozen, &new_val.is_frozen);
+        update_optional_field(&mut self.supply, &new_val.supply);
+        update_optional_field(&mut self.seq, &new_val.seq);
+        update_field(&mut self.is_burnt, &new_val.is_burnt);
+        update_field(&mut self.creators, &new_val.creators);
+        update_field(&mut self.royalty_amount, &new_val.royalty_amount);
+        update_field(&mut self.was_decompressed, &new_val.was_decompressed);
+        update_optional_field(&mut self.onchain_data, &new_val.onchain_data);
+        update_field(&mut self.url, &new_val.url);
+        update_optional_field(&mut self.chain_mutability, &new_val.chain_mutability);
+        update_optional_field(&mut self.lamports, &new_val.lamports);
+        update_optional_field(&mut self.executable, &new_val.executable);
+        update_optional_field(&mut self.metadata_owner, &new_val.metadata_owner);
+        update_optional_field(&mut self.raw_name, &new_val.raw_name);
+        update_optional_field(&mut self.mpl_core_plugins, &new_val.mpl_core_plugins);
+        update_optional_field(
+            &mut self.mpl_core_unknown_plugins,
+            &new_val.mpl_core_unknown_plugins,
+        );
+        update_optional_field(&mut self.num_minted, &new_val.num_minted);
+        update_optional_field(&mut self.current_size, &new_val.current_size);
+        update_optional_field(&mut self.rent_epoch, &new_val.rent_epoch);
+        update_optional_field(
+            &mut self.plugins_json_version,
+            &new_val.plugins_json_version,
+        );
+        update_optional_field(
+            &mut self.mpl_core_external_plugins,
+            &new_val.mpl_core_external_plugins,
+        );
+        update_optional_field(
+            &mut self.mpl_core_unknown_external_plugins,
+            &new_val.mpl_core_unknown_external_plugins,
+        );
+        update_optional_field(&mut self.mint_extensions, &new_val.mint_extensions);
+    }
+
     pub fn merge_dynamic_details(
         _new_key: &[u8],
         existing_val: Option<&[u8]>,
@@ -372,55 +419,7 @@ impl AssetDynamicDetails {
             match deserialize::<Self>(op) {
                 Ok(new_val) => {
                     result = Some(if let Some(mut current_val) = result {
-                        update_field(&mut current_val.is_compressible, &new_val.is_compressible);
-                        update_field(&mut current_val.is_compressed, &new_val.is_compressed);
-                        update_field(&mut current_val.is_frozen, &new_val.is_frozen);
-                        update_optional_field(&mut current_val.supply, &new_val.supply);
-                        update_optional_field(&mut current_val.seq, &new_val.seq);
-                        update_field(&mut current_val.is_burnt, &new_val.is_burnt);
-                        update_field(&mut current_val.creators, &new_val.creators);
-                        update_field(&mut current_val.royalty_amount, &new_val.royalty_amount);
-                        update_field(&mut current_val.was_decompressed, &new_val.was_decompressed);
-                        update_optional_field(&mut current_val.onchain_data, &new_val.onchain_data);
-                        update_field(&mut current_val.url, &new_val.url);
-                        update_optional_field(
-                            &mut current_val.chain_mutability,
-                            &new_val.chain_mutability,
-                        );
-                        update_optional_field(&mut current_val.lamports, &new_val.lamports);
-                        update_optional_field(&mut current_val.executable, &new_val.executable);
-                        update_optional_field(
-                            &mut current_val.metadata_owner,
-                            &new_val.metadata_owner,
-                        );
-                        update_optional_field(&mut current_val.raw_name, &new_val.raw_name);
-                        update_optional_field(
-                            &mut current_val.mpl_core_plugins,
-                            &new_val.mpl_core_plugins,
-                        );
-                        update_optional_field(
-                            &mut current_val.mpl_core_unknown_plugins,
-                            &new_val.mpl_core_unknown_plugins,
-                        );
-                        update_optional_field(&mut current_val.num_minted, &new_val.num_minted);
-                        update_optional_field(&mut current_val.current_size, &new_val.current_size);
-                        update_optional_field(&mut current_val.rent_epoch, &new_val.rent_epoch);
-                        update_optional_field(
-                            &mut current_val.plugins_json_version,
-                            &new_val.plugins_json_version,
-                        );
-                        update_optional_field(
-                            &mut current_val.mpl_core_external_plugins,
-                            &new_val.mpl_core_external_plugins,
-                        );
-                        update_optional_field(
-                            &mut current_val.mpl_core_unknown_external_plugins,
-                            &new_val.mpl_core_unknown_external_plugins,
-                        );
-                        update_optional_field(
-                            &mut current_val.mint_extensions,
-                            &new_val.mint_extensions,
-                        );
+                        current_val.merge(&new_val);
 
                         current_val
                     } else {
@@ -470,6 +469,18 @@ impl AssetDynamicDetails {
 }
 
 impl AssetAuthority {
+    /// Same selection rules as in the merge operator: write version wins if both have it,
+    /// otherwise the latest slot wins
+    pub fn merge(&mut self, new_val: &Self) {
+        let is_newer = match (self.write_version, new_val.write_version) {
+            (Some(current), Some(new)) => new > current,
+            _ => new_val.slot_updated > self.slot_updated,
+        };
+        if is_newer {
+            *self = new_val.clone();
+        }
+    }
+
     pub fn merge_asset_authorities(
         _new_key: &[u8],
         existing_val: Option<&[u8]>,
@@ -545,6 +556,13 @@ impl TypedColumn for AssetOwner {
 }
 
 impl AssetOwner {
+    pub fn merge(&mut self, new_val: &Self) {
+        update_field(&mut self.owner_type, &new_val.owner_type);
+        update_field(&mut self.owner, &new_val.owner);
+        update_field(&mut self.owner_delegate_seq, &new_val.owner_delegate_seq);
+        update_field(&mut self.delegate, &new_val.delegate);
+    }
+
     pub fn merge_asset_owner(
         _new_key: &[u8],
         existing_val: Option<&[u8]>,
@@ -566,13 +584,7 @@ impl AssetOwner {
             match deserialize::<Self>(op) {
                 Ok(new_val) => {
                     result = Some(if let Some(mut current_val) = result {
-                        update_field(&mut current_val.owner_type, &new_val.owner_type);
-                        update_field(&mut current_val.owner, &new_val.owner);
-                        update_field(
-                            &mut current_val.owner_delegate_seq,
-                            &new_val.owner_delegate_seq,
-                        );
-                        update_field(&mut current_val.delegate, &new_val.delegate);
+                        current_val.merge(&new_val);
 
                         current_val
                     } else {
@@ -693,6 +705,15 @@ impl TypedColumn for AssetCollection {
 }
 
 impl AssetCollection {
+    pub fn merge(&mut self, new_val: &Self) {
+        update_field(&mut self.collection, &new_val.collection);
+        update_field(
+            &mut self.is_collection_verified,
+            &new_val.is_collection_verified,
+        );
+        update_field(&mut self.authority, &new_val.authority);
+    }
+
     pub fn merge_asset_collection(
         _new_key: &[u8],
         existing_val: Option<&[u8]>,
@@ -714,12 +735,7 @@ impl AssetCollection {
             match deserialize::<Self>(op) {
                 Ok(new_val) => {
                     result = Some(if let Some(mut current_val) = result {
-                        update_field(&mut current_val.collection, &new_val.collection);
-                        update_field(
-                            &mut current_val.is_collection_verified,
-                            &new_val.is_collection_verified,
-                        );
-                        update_field(&mut current_val.authority, &new_val.authority);
+                        current_val.merge(&new_val);
 
                         current_val
                     } else {
@@ -0,0 +1,538 @@
+use std::sync::atomic::Ordering;
+
+use crate::asset::{AssetCollection, AssetSelectedMaps};
+use crate::asset_previous_versions::{AssetVersion, PendingVersions};
+use crate::column::TypedColumn;
+use crate::errors::StorageError;
+use crate::key_encoders::{decode_pubkey_u64, encode_pubkey_u64};
+use crate::parameters::Parameter;
+use crate::{AssetAuthority, AssetDynamicDetails, AssetOwner, Result, Storage};
+use bincode::{deserialize, serialize};
+use rocksdb::MergeOperands;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+// number of the folded assets written at once by the history compaction
+const ASSET_HISTORY_COMPACTION_BATCH_SIZE: usize = 1000;
+
+/// Change log of the asset. Every (partial) update of the dynamic, owner, authority
+/// and collection data is appended under the slot it was made in, so the state
+/// of the asset at any slot can be rebuilt by merging all the updates made up to it.
+/// Written only if the history is enabled for the storage.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AssetHistory {
+    pub dynamic_details: Vec<AssetDynamicDetails>,
+    pub owners: Vec<AssetOwner>,
+    pub authorities: Vec<AssetAuthority>,
+    pub collections: Vec<AssetCollection>,
+}
+
+impl TypedColumn for AssetHistory {
+    type KeyType = (Pubkey, u64);
+    type ValueType = Self;
+    const NAME: &'static str = "ASSET_HISTORY";
+
+    fn encode_key((pubkey, slot): (Pubkey, u64)) -> Vec<u8> {
+        encode_pubkey_u64(pubkey, slot)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_pubkey_u64(bytes)
+    }
+}
+
+impl AssetHistory {
+    pub fn merge_asset_history(
+        _new_key: &[u8],
+        existing_val: Option<&[u8]>,
+        operands: &MergeOperands,
+    ) -> Option<Vec<u8>> {
+        let mut result = AssetHistory::default();
+        if let Some(existing_val) = existing_val {
+            match deserialize::<Self>(existing_val) {
+                Ok(value) => {
+                    result = value;
+                }
+                Err(e) => {
+                    error!("RocksDB: AssetHistory deserialize existing_val: {}", e)
+                }
+            }
+        }
+
+        for op in operands {
+            match deserialize::<Self>(op) {
+                Ok(new_val) => {
+                    result.dynamic_details.extend(new_val.dynamic_details);
+                    result.owners.extend(new_val.owners);
+                    result.authorities.extend(new_val.authorities);
+                    result.collections.extend(new_val.collections);
+                }
+                Err(e) => {
+                    error!("RocksDB: AssetHistory deserialize new_val: {}", e)
+                }
+            }
+        }
+
+        serialize(&result).ok()
+    }
+}
+
+/// Asset update which may be recorded into the history
+pub trait AssetHistoryRecord {
+    fn pubkey(&self) -> Pubkey;
+    fn history_slot(&self) -> u64;
+    fn into_history(self) -> AssetHistory;
+}
+
+impl AssetHistoryRecord for AssetDynamicDetails {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn history_slot(&self) -> u64 {
+        self.get_slot_updated().max(self.url.slot_updated)
+    }
+
+    fn into_history(self) -> AssetHistory {
+        AssetHistory {
+            dynamic_details: vec![self],
+            ..Default::default()
+        }
+    }
+}
+
+impl AssetHistoryRecord for AssetOwner {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn history_slot(&self) -> u64 {
+        self.get_slot_updated()
+    }
+
+    fn into_history(self) -> AssetHistory {
+        AssetHistory {
+            owners: vec![self],
+            ..Default::default()
+        }
+    }
+}
+
+impl AssetHistoryRecord for AssetAuthority {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn history_slot(&self) -> u64 {
+        self.slot_updated
+    }
+
+    fn into_history(self) -> AssetHistory {
+        AssetHistory {
+            authorities: vec![self],
+            ..Default::default()
+        }
+    }
+}
+
+impl AssetHistoryRecord for AssetCollection {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn history_slot(&self) -> u64 {
+        self.get_slot_updated()
+    }
+
+    fn into_history(self) -> AssetHistory {
+        AssetHistory {
+            collections: vec![self],
+            ..Default::default()
+        }
+    }
+}
+
+/// Asset data rebuilt from the history as it was at some slot
+#[derive(Debug, Clone, Default)]
+pub struct AssetVersionsAtSlot {
+    pub dynamic_details: Option<AssetDynamicDetails>,
+    pub owner: Option<AssetOwner>,
+    pub authority: Option<AssetAuthority>,
+    pub collection: Option<AssetCollection>,
+}
+
+impl AssetVersionsAtSlot {
+    fn apply(&mut self, history: AssetHistory) {
+        for new_val in history.dynamic_details {
+            match self.dynamic_details.as_mut() {
+                Some(current_val) => current_val.merge(&new_val),
+                None => self.dynamic_details = Some(new_val),
+            }
+        }
+        for new_val in history.owners {
+            match self.owner.as_mut() {
+                Some(current_val) => current_val.merge(&new_val),
+                None => self.owner = Some(new_val),
+            }
+        }
+        for new_val in history.authorities {
+            match self.authority.as_mut() {
+                Some(current_val) => current_val.merge(&new_val),
+                None => self.authority = Some(new_val),
+            }
+        }
+        for new_val in history.collections {
+            match self.collection.as_mut() {
+                Some(current_val) => current_val.merge(&new_val),
+                None => self.collection = Some(new_val),
+            }
+        }
+    }
+
+    fn into_history(self) -> AssetHistory {
+        AssetHistory {
+            dynamic_details: self.dynamic_details.into_iter().collect(),
+            owners: self.owner.into_iter().collect(),
+            authorities: self.authority.into_iter().collect(),
+            collections: self.collection.into_iter().collect(),
+        }
+    }
+}
+
+/// History entries of an asset up to the compaction cutoff
+struct CompactedAsset {
+    pubkey: Pubkey,
+    // all the entries but the last one, merged together
+    folded: AssetVersionsAtSlot,
+    folded_slots: Vec<u64>,
+    last: Option<(u64, AssetHistory)>,
+}
+
+impl CompactedAsset {
+    fn new(pubkey: Pubkey) -> Self {
+        Self {
+            pubkey,
+            folded: Default::default(),
+            folded_slots: Vec::new(),
+            last: None,
+        }
+    }
+}
+
+impl Storage {
+    pub fn set_asset_history_enabled(&self, enabled: bool) {
+        self.asset_history_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_asset_history_enabled(&self) -> bool {
+        self.asset_history_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Records the version the update replaces and, if the history is enabled, the update itself.
+    /// Returns the replaced and the merged versions if the update changed the asset data.
+    pub(crate) fn record_asset_update_with_batch<V: AssetVersion>(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        pending: &mut PendingVersions,
+        record: &V,
+    ) -> Result<Option<(Option<V>, V)>> {
+        let change = self.record_previous_version_with_batch(batch, pending, record)?;
+        if !self.is_asset_history_enabled() {
+            return Ok(change);
+        }
+        self.asset_history.merge_with_batch(
+            batch,
+            (record.pubkey(), record.history_slot()),
+            &record.clone().into_history(),
+        )?;
+
+        Ok(change)
+    }
+
+    /// Slot the history is complete from, the state at the earlier slots can't be rebuilt
+    pub async fn asset_history_start_slot(&self) -> Result<u64> {
+        Ok(self
+            .get_parameter::<u64>(Parameter::AssetHistoryStartSlot)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Folds all the history entries of every asset recorded up to the cutoff slot
+    /// into the last of them, so the history stays bounded while the state at any slot
+    /// from the cutoff on can still be rebuilt. The folded versions are merged into
+    /// the kept entry instead of overwriting it, so the concurrent updates aren't lost.
+    /// Returns the number of the deleted entries.
+    pub fn compact_asset_history(&self, cutoff_slot: u64) -> Result<usize> {
+        let mut batch = rocksdb::WriteBatch::default();
+        // the readers have to stop asking for the slots before the cutoff
+        // before the first versions they need are gone
+        self.merge_top_parameter_with_batch(
+            &mut batch,
+            Parameter::AssetHistoryStartSlot,
+            cutoff_slot,
+        )?;
+        let mut batch_assets = 0;
+        let mut deleted = 0;
+        let mut current: Option<CompactedAsset> = None;
+        for item in self.asset_history.iter_start() {
+            let (key, value) = item?;
+            let (pubkey, slot) = AssetHistory::decode_key(key.to_vec())?;
+            if current
+                .as_ref()
+                .map_or(true, |asset| asset.pubkey != pubkey)
+            {
+                if let Some(asset) = current.take() {
+                    deleted += self.fold_asset_history_with_batch(&mut batch, asset)?;
+                    batch_assets += 1;
+                }
+                if batch_assets >= ASSET_HISTORY_COMPACTION_BATCH_SIZE {
+                    self.db.write(std::mem::take(&mut batch))?;
+                    batch_assets = 0;
+                }
+                current = Some(CompactedAsset::new(pubkey));
+            }
+            if slot > cutoff_slot {
+                continue;
+            }
+            let Some(asset) = current.as_mut() else {
+                continue;
+            };
+            // the last entry is kept as it is, the earlier ones are folded into it
+            let history = deserialize::<AssetHistory>(value.as_ref())?;
+            if let Some((last_slot, last_history)) = asset.last.replace((slot, history)) {
+                asset.folded.apply(last_history);
+                asset.folded_slots.push(last_slot);
+            }
+        }
+        if let Some(asset) = current.take() {
+            deleted += self.fold_asset_history_with_batch(&mut batch, asset)?;
+        }
+        self.db.write(batch)?;
+
+        Ok(deleted)
+    }
+
+    fn fold_asset_history_with_batch(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        asset: CompactedAsset,
+    ) -> Result<usize> {
+        let Some((last_slot, _)) = asset.last else {
+            return Ok(0);
+        };
+        if asset.folded_slots.is_empty() {
+            return Ok(0);
+        }
+        self.asset_history.merge_with_batch(
+            batch,
+            (asset.pubkey, last_slot),
+            &asset.folded.into_history(),
+        )?;
+        for slot in asset.folded_slots.iter() {
+            self.asset_history
+                .delete_with_batch(batch, (asset.pubkey, *slot));
+        }
+
+        Ok(asset.folded_slots.len())
+    }
+
+    /// Rebuilds the asset from all the updates recorded up to and including the given slot.
+    /// Returns None if nothing was recorded for the asset up to that slot.
+    pub fn get_asset_versions_at_slot(
+        &self,
+        pubkey: Pubkey,
+        slot: u64,
+    ) -> Result<Option<AssetVersionsAtSlot>> {
+        let mut result: Option<AssetVersionsAtSlot> = None;
+        for item in self.asset_history.iter((pubkey, 0)) {
+            let (key, value) = item?;
+            let (key_pubkey, key_slot) = AssetHistory::decode_key(key.to_vec())?;
+            if key_pubkey != pubkey || key_slot > slot {
+                break;
+            }
+            result
+                .get_or_insert_with(Default::default)
+                .apply(deserialize::<AssetHistory>(value.as_ref())?);
+        }
+
+        Ok(result)
+    }
+
+    pub(crate) fn created_after_slot(
+        asset_selected_maps: &AssetSelectedMaps,
+        id: &Pubkey,
+        slot: u64,
+    ) -> bool {
+        asset_selected_maps
+            .assets_static
+            .get(id)
+            .map_or(true, |asset_static| asset_static.created_at as u64 > slot)
+    }
+
+    pub(crate) fn updated_after_slot(
+        asset_selected_maps: &AssetSelectedMaps,
+        id: &Pubkey,
+        slot: u64,
+    ) -> bool {
+        Self::created_after_slot(asset_selected_maps, id, slot)
+            || asset_selected_maps
+                .assets_dynamic
+                .get(id)
+                .is_some_and(|dynamic_details| dynamic_details.history_slot() > slot)
+            || asset_selected_maps
+                .assets_owner
+                .get(id)
+                .is_some_and(|owner| owner.history_slot() > slot)
+            || asset_selected_maps
+                .assets_authority
+                .get(id)
+                .is_some_and(|authority| authority.history_slot() > slot)
+            || asset_selected_maps
+                .assets_collection
+                .get(id)
+                .is_some_and(|collection| collection.history_slot() > slot)
+    }
+
+    /// Replaces the latest asset data in the selected maps with the data as it was at the slot.
+    /// Assets not updated after the slot are kept as they are, assets created after the slot
+    /// are dropped from the maps. Fails with [StorageError::HistoryUnavailable] if an asset
+    /// was changed after the slot, but the history doesn't reach back to it.
+    /// Leaf, token accounts and editions data are always the latest ones.
+    pub async fn apply_asset_history_at_slot(
+        &self,
+        asset_selected_maps: &mut AssetSelectedMaps,
+        asset_ids: &[Pubkey],
+        slot: u64,
+    ) -> Result<()> {
+        let history_start_slot = self.asset_history_start_slot().await?;
+        for id in asset_ids {
+            if !Self::updated_after_slot(asset_selected_maps, id, slot) {
+                continue;
+            }
+            if Self::created_after_slot(asset_selected_maps, id, slot) {
+                asset_selected_maps.assets_static.remove(id);
+                continue;
+            }
+            if slot < history_start_slot {
+                return Err(StorageError::HistoryUnavailable(slot));
+            }
+            let Some(versions) = self.get_asset_versions_at_slot(*id, slot)? else {
+                // the asset existed at the slot, it just wasn't recorded back then
+                return Err(StorageError::HistoryUnavailable(slot));
+            };
+            Self::replace_asset_versions(asset_selected_maps, id, versions);
+        }
+
+        self.fetch_missing_offchain_data(asset_selected_maps).await
+    }
+
+    pub(crate) fn replace_asset_versions(
+        asset_selected_maps: &mut AssetSelectedMaps,
+        id: &Pubkey,
+        versions: AssetVersionsAtSlot,
+    ) {
+        match versions.dynamic_details {
+            Some(dynamic_details) => {
+                asset_selected_maps
+                    .urls
+                    .insert(id.to_string(), dynamic_details.url.value.clone());
+                asset_selected_maps
+                    .assets_dynamic
+                    .insert(*id, dynamic_details);
+            }
+            None => {
+                asset_selected_maps.urls.remove(&id.to_string());
+                asset_selected_maps.assets_dynamic.remove(id);
+            }
+        }
+        match versions.owner {
+            Some(owner) => asset_selected_maps.assets_owner.insert(*id, owner),
+            None => asset_selected_maps.assets_owner.remove(id),
+        };
+        match versions.authority {
+            Some(authority) => asset_selected_maps.assets_authority.insert(*id, authority),
+            None => asset_selected_maps.assets_authority.remove(id),
+        };
+        match versions.collection {
+            Some(collection) => asset_selected_maps
+                .assets_collection
+                .insert(*id, collection),
+            None => asset_selected_maps.assets_collection.remove(id),
+        };
+    }
+
+    // the older versions may point to the metadata the latest ones don't
+    pub(crate) async fn fetch_missing_offchain_data(
+        &self,
+        asset_selected_maps: &mut AssetSelectedMaps,
+    ) -> Result<()> {
+        let missing_urls = asset_selected_maps
+            .urls
+            .values()
+            .filter(|url| !asset_selected_maps.offchain_data.contains_key(*url))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !missing_urls.is_empty() {
+            let offchain_data = self.asset_offchain_data.batch_get(missing_urls).await?;
+            asset_selected_maps.offchain_data.extend(
+                offchain_data
+                    .into_iter()
+                    .flatten()
+                    .map(|data| (data.url.clone(), data)),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use entities::models::Updated;
+
+    fn owner_at(pubkey: Pubkey, owner: Pubkey, slot: u64) -> AssetOwner {
+        AssetOwner {
+            pubkey,
+            owner: Updated::new(slot, None, Some(owner)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_apply_history_takes_latest_versions() {
+        let pubkey = Pubkey::new_unique();
+        let first_owner = Pubkey::new_unique();
+        let second_owner = Pubkey::new_unique();
+
+        let mut versions = AssetVersionsAtSlot::default();
+        versions.apply(AssetHistory {
+            owners: vec![
+                owner_at(pubkey, second_owner, 20),
+                owner_at(pubkey, first_owner, 10),
+            ],
+            authorities: vec![AssetAuthority {
+                pubkey,
+                authority: first_owner,
+                slot_updated: 10,
+                write_version: Some(2),
+            }],
+            ..Default::default()
+        });
+        versions.apply(AssetHistory {
+            authorities: vec![AssetAuthority {
+                pubkey,
+                authority: second_owner,
+                slot_updated: 30,
+                write_version: Some(1),
+            }],
+            ..Default::default()
+        });
+
+        assert_eq!(versions.owner.unwrap().owner.value, Some(second_owner));
+        // write version has a priority over the slot
+        assert_eq!(versions.authority.unwrap().authority, first_owner);
+        assert!(versions.dynamic_details.is_none());
+    }
+}
@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+
+use crate::asset::{AssetCollection, AssetSelectedMaps};
+use crate::asset_history::{AssetHistoryRecord, AssetVersionsAtSlot};
+use crate::column::{Column, TypedColumn};
+use crate::errors::StorageError;
+use crate::key_encoders::{
+    decode_pubkey_u64, decode_pubkey_u64_pubkey, encode_pubkey_u64, encode_pubkey_u64_pubkey,
+};
+use crate::parameters::Parameter;
+use crate::{AssetAuthority, AssetDynamicDetails, AssetOwner, Result, Storage};
+use bincode::{deserialize, serialize};
+use rocksdb::MergeOperands;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+// number of the entries deleted at once by the cleanup of the finalized versions
+const PREVIOUS_VERSIONS_DELETE_BATCH_SIZE: usize = 10_000;
+
+/// Versions of the asset data replaced by the updates made in the slot of the key,
+/// i.e. the state right before that slot. Recorded for every update, regardless of the asset
+/// history, and dropped once the slot is finalized, so the latest state at the confirmed
+/// and finalized commitments can be rebuilt from the latest one.
+/// `Some(None)` means the data didn't exist before the slot.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AssetPreviousVersions {
+    pub dynamic_details: Option<Option<AssetDynamicDetails>>,
+    pub owner: Option<Option<AssetOwner>>,
+    pub authority: Option<Option<AssetAuthority>>,
+    pub collection: Option<Option<AssetCollection>>,
+}
+
+impl TypedColumn for AssetPreviousVersions {
+    type KeyType = (Pubkey, u64);
+    type ValueType = Self;
+    const NAME: &'static str = "ASSET_PREVIOUS_VERSIONS";
+
+    fn encode_key((pubkey, slot): (Pubkey, u64)) -> Vec<u8> {
+        encode_pubkey_u64(pubkey, slot)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_pubkey_u64(bytes)
+    }
+}
+
+impl AssetPreviousVersions {
+    // the earliest version replaced in the slot is the one the slot started with
+    fn keep_first(&mut self, other: Self) {
+        self.dynamic_details = self.dynamic_details.take().or(other.dynamic_details);
+        self.owner = self.owner.take().or(other.owner);
+        self.authority = self.authority.take().or(other.authority);
+        self.collection = self.collection.take().or(other.collection);
+    }
+
+    pub fn merge_previous_versions(
+        _new_key: &[u8],
+        existing_val: Option<&[u8]>,
+        operands: &MergeOperands,
+    ) -> Option<Vec<u8>> {
+        let mut result = AssetPreviousVersions::default();
+        if let Some(existing_val) = existing_val {
+            match deserialize::<Self>(existing_val) {
+                Ok(value) => {
+                    result = value;
+                }
+                Err(e) => {
+                    error!(
+                        "RocksDB: AssetPreviousVersions deserialize existing_val: {}",
+                        e
+                    )
+                }
+            }
+        }
+
+        for op in operands {
+            match deserialize::<Self>(op) {
+                Ok(new_val) => result.keep_first(new_val),
+                Err(e) => {
+                    error!("RocksDB: AssetPreviousVersions deserialize new_val: {}", e)
+                }
+            }
+        }
+
+        serialize(&result).ok()
+    }
+}
+
+/// Index of the assets by the owners they had before and got by the updates made in the slot,
+/// keyed by (owner, slot, asset). Dropped together with the previous versions.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct OwnerChangeIdx {}
+
+impl TypedColumn for OwnerChangeIdx {
+    type KeyType = (Pubkey, u64, Pubkey);
+    type ValueType = Self;
+    const NAME: &'static str = "OWNER_CHANGE_IDX";
+
+    fn encode_key(key: (Pubkey, u64, Pubkey)) -> Vec<u8> {
+        encode_pubkey_u64_pubkey(key)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_pubkey_u64_pubkey(bytes)
+    }
+}
+
+/// Versions written into a batch which isn't flushed yet, the next updates of the same assets
+/// in the batch replace them rather than the stored ones
+#[derive(Default)]
+pub struct PendingVersions {
+    dynamic_details: HashMap<Pubkey, Option<AssetDynamicDetails>>,
+    owners: HashMap<Pubkey, Option<AssetOwner>>,
+    authorities: HashMap<Pubkey, Option<AssetAuthority>>,
+    collections: HashMap<Pubkey, Option<AssetCollection>>,
+}
+
+/// Asset data which keeps the versions it replaces until they are finalized
+pub trait AssetVersion:
+    TypedColumn<KeyType = Pubkey, ValueType = Self> + AssetHistoryRecord + Serialize + Clone
+{
+    fn column(storage: &Storage) -> &Column<Self>;
+    fn pending(versions: &mut PendingVersions) -> &mut HashMap<Pubkey, Option<Self>>;
+    fn merge_version(&mut self, new_val: &Self);
+    fn into_previous_versions(previous: Option<Self>) -> AssetPreviousVersions;
+    fn owner(&self) -> Option<Pubkey> {
+        None
+    }
+}
+
+impl AssetVersion for AssetDynamicDetails {
+    fn column(storage: &Storage) -> &Column<Self> {
+        &storage.asset_dynamic_data
+    }
+
+    fn pending(versions: &mut PendingVersions) -> &mut HashMap<Pubkey, Option<Self>> {
+        &mut versions.dynamic_details
+    }
+
+    fn merge_version(&mut self, new_val: &Self) {
+        self.merge(new_val)
+    }
+
+    fn into_previous_versions(previous: Option<Self>) -> AssetPreviousVersions {
+        AssetPreviousVersions {
+            dynamic_details: Some(previous),
+            ..Default::default()
+        }
+    }
+}
+
+impl AssetVersion for AssetOwner {
+    fn column(storage: &Storage) -> &Column<Self> {
+        &storage.asset_owner_data
+    }
+
+    fn pending(versions: &mut PendingVersions) -> &mut HashMap<Pubkey, Option<Self>> {
+        &mut versions.owners
+    }
+
+    fn merge_version(&mut self, new_val: &Self) {
+        self.merge(new_val)
+    }
+
+    fn into_previous_versions(previous: Option<Self>) -> AssetPreviousVersions {
+        AssetPreviousVersions {
+            owner: Some(previous),
+            ..Default::default()
+        }
+    }
+
+    fn owner(&self) -> Option<Pubkey> {
+        self.owner.value
+    }
+}
+
+impl AssetVersion for AssetAuthority {
+    fn column(storage: &Storage) -> &Column<Self> {
+        &storage.asset_authority_data
+    }
+
+    fn pending(versions: &mut PendingVersions) -> &mut HashMap<Pubkey, Option<Self>> {
+        &mut versions.authorities
+    }
+
+    fn merge_version(&mut self, new_val: &Self) {
+        self.merge(new_val)
+    }
+
+    fn into_previous_versions(previous: Option<Self>) -> AssetPreviousVersions {
+        AssetPreviousVersions {
+            authority: Some(previous),
+            ..Default::default()
+        }
+    }
+}
+
+impl AssetVersion for AssetCollection {
+    fn column(storage: &Storage) -> &Column<Self> {
+        &storage.asset_collection_data
+    }
+
+    fn pending(versions: &mut PendingVersions) -> &mut HashMap<Pubkey, Option<Self>> {
+        &mut versions.collections
+    }
+
+    fn merge_version(&mut self, new_val: &Self) {
+        self.merge(new_val)
+    }
+
+    fn into_previous_versions(previous: Option<Self>) -> AssetPreviousVersions {
+        AssetPreviousVersions {
+            collection: Some(previous),
+            ..Default::default()
+        }
+    }
+}
+
+/// Assets which moved from or to an owner after some slot
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OwnerChangesAfterSlot {
+    // owned at the slot, but transferred since then
+    pub owned: Vec<Pubkey>,
+    // received since the slot
+    pub not_owned: Vec<Pubkey>,
+}
+
+impl Storage {
+    /// Records the version the update replaces, if the update changes it.
+    /// Stale updates the merge is going to reject replace nothing.
+    /// Returns the replaced and the merged versions if the update changed the asset data.
+    pub(crate) fn record_previous_version_with_batch<V: AssetVersion>(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        pending: &mut PendingVersions,
+        update: &V,
+    ) -> Result<Option<(Option<V>, V)>> {
+        let pubkey = update.pubkey();
+        let previous = match V::pending(pending).get(&pubkey) {
+            Some(version) => version.clone(),
+            None => V::column(self).get(pubkey)?,
+        };
+        let current = match previous.clone() {
+            Some(mut version) => {
+                version.merge_version(update);
+                version
+            }
+            None => update.clone(),
+        };
+        V::pending(pending).insert(pubkey, Some(current.clone()));
+        if let Some(previous) = previous.as_ref() {
+            if serialize(previous)? == serialize(&current)? {
+                return Ok(None);
+            }
+        }
+
+        let slot = update.history_slot();
+        let previous_owner = previous.as_ref().and_then(AssetVersion::owner);
+        self.asset_previous_versions.merge_with_batch(
+            batch,
+            (pubkey, slot),
+            &V::into_previous_versions(previous),
+        )?;
+        let current_owner = current.owner();
+        if previous_owner != current_owner {
+            for owner in [previous_owner, current_owner].into_iter().flatten() {
+                self.owner_change_idx.merge_with_batch(
+                    batch,
+                    (owner, slot, pubkey),
+                    &OwnerChangeIdx::default(),
+                )?;
+            }
+        }
+
+        Ok(Some((previous, current)))
+    }
+
+    /// Slot the replaced versions are kept from, None if they were never recorded
+    pub async fn previous_versions_start_slot(&self) -> Result<Option<u64>> {
+        self.get_parameter::<u64>(Parameter::PreviousVersionsStartSlot)
+            .await
+    }
+
+    /// Marks the versions as recorded from the slot on, unless they already are
+    pub async fn init_previous_versions_start_slot(&self, slot: u64) -> Result<()> {
+        if self.previous_versions_start_slot().await?.is_none() {
+            self.put_parameter(Parameter::PreviousVersionsStartSlot, slot)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Drops the versions replaced up to and including the cutoff slot,
+    /// the state at any later slot can still be rebuilt. Returns the number of the deleted entries.
+    pub fn delete_previous_versions(&self, cutoff_slot: u64) -> Result<usize> {
+        let mut batch = rocksdb::WriteBatch::default();
+        // the readers have to stop asking for the older slots before their versions are gone
+        self.merge_top_parameter_with_batch(
+            &mut batch,
+            Parameter::PreviousVersionsStartSlot,
+            cutoff_slot,
+        )?;
+        let mut deleted = 0;
+        for item in self.asset_previous_versions.iter_start() {
+            let (key, _) = item?;
+            let (pubkey, slot) = AssetPreviousVersions::decode_key(key.to_vec())?;
+            if slot > cutoff_slot {
+                continue;
+            }
+            self.asset_previous_versions
+                .delete_with_batch(&mut batch, (pubkey, slot));
+            deleted += 1;
+            if batch.len() >= PREVIOUS_VERSIONS_DELETE_BATCH_SIZE {
+                self.db.write(std::mem::take(&mut batch))?;
+            }
+        }
+        for item in self.owner_change_idx.iter_start() {
+            let (key, _) = item?;
+            let (owner, slot, pubkey) = OwnerChangeIdx::decode_key(key.to_vec())?;
+            if slot > cutoff_slot {
+                continue;
+            }
+            self.owner_change_idx
+                .delete_with_batch(&mut batch, (owner, slot, pubkey));
+            deleted += 1;
+            if batch.len() >= PREVIOUS_VERSIONS_DELETE_BATCH_SIZE {
+                self.db.write(std::mem::take(&mut batch))?;
+            }
+        }
+        self.db.write(batch)?;
+
+        Ok(deleted)
+    }
+
+    /// The first versions replaced after the slot, i.e. the versions the asset had at the slot.
+    /// The parts not changed after the slot are left None.
+    pub fn get_previous_versions_after_slot(
+        &self,
+        pubkey: Pubkey,
+        slot: u64,
+    ) -> Result<AssetPreviousVersions> {
+        let mut result = AssetPreviousVersions::default();
+        for item in self
+            .asset_previous_versions
+            .iter((pubkey, slot.saturating_add(1)))
+        {
+            let (key, value) = item?;
+            let (key_pubkey, _) = AssetPreviousVersions::decode_key(key.to_vec())?;
+            if key_pubkey != pubkey {
+                break;
+            }
+            result.keep_first(deserialize::<AssetPreviousVersions>(value.as_ref())?);
+        }
+
+        Ok(result)
+    }
+
+    /// Replaces the latest asset data in the selected maps with the data as it was at the slot,
+    /// which has to be a recent one, e.g. the last confirmed or finalized slot.
+    /// Unlike the asset history, the replaced versions are always recorded, but they are kept
+    /// only until they are finalized. Fails with [StorageError::HistoryUnavailable]
+    /// if an asset was changed after the slot, but its versions at the slot are gone.
+    pub async fn apply_previous_versions_at_slot(
+        &self,
+        asset_selected_maps: &mut AssetSelectedMaps,
+        asset_ids: &[Pubkey],
+        slot: u64,
+    ) -> Result<()> {
+        let start_slot = self.previous_versions_start_slot().await?;
+        for id in asset_ids {
+            if !Self::updated_after_slot(asset_selected_maps, id, slot) {
+                continue;
+            }
+            if Self::created_after_slot(asset_selected_maps, id, slot) {
+                asset_selected_maps.assets_static.remove(id);
+                continue;
+            }
+            if start_slot.map_or(true, |start_slot| slot < start_slot) {
+                return Err(StorageError::HistoryUnavailable(slot));
+            }
+            let previous = self.get_previous_versions_after_slot(*id, slot)?;
+            let versions = AssetVersionsAtSlot {
+                dynamic_details: previous
+                    .dynamic_details
+                    .unwrap_or_else(|| asset_selected_maps.assets_dynamic.get(id).cloned()),
+                owner: previous
+                    .owner
+                    .unwrap_or_else(|| asset_selected_maps.assets_owner.get(id).cloned()),
+                authority: previous
+                    .authority
+                    .unwrap_or_else(|| asset_selected_maps.assets_authority.get(id).cloned()),
+                collection: previous
+                    .collection
+                    .unwrap_or_else(|| asset_selected_maps.assets_collection.get(id).cloned()),
+            };
+            Self::replace_asset_versions(asset_selected_maps, id, versions);
+        }
+
+        self.fetch_missing_offchain_data(asset_selected_maps).await
+    }
+
+    /// Assets moved from or to the owner after the slot, split by whether they belonged to it
+    /// at the slot. Together with the latest owners index they give the assets owned at the slot.
+    pub async fn owner_changes_after_slot(
+        &self,
+        owner: Pubkey,
+        slot: u64,
+    ) -> Result<OwnerChangesAfterSlot> {
+        let start_slot = self.previous_versions_start_slot().await?;
+        if start_slot.map_or(true, |start_slot| slot < start_slot) {
+            return Err(StorageError::HistoryUnavailable(slot));
+        }
+        let mut assets = Vec::new();
+        for item in self
+            .owner_change_idx
+            .iter((owner, slot.saturating_add(1), Pubkey::default()))
+        {
+            let (key, _) = item?;
+            let (key_owner, _, pubkey) = OwnerChangeIdx::decode_key(key.to_vec())?;
+            if key_owner != owner {
+                break;
+            }
+            if !assets.contains(&pubkey) {
+                assets.push(pubkey);
+            }
+        }
+
+        let mut result = OwnerChangesAfterSlot::default();
+        for pubkey in assets {
+            let owner_at_slot = match self.get_previous_versions_after_slot(pubkey, slot)?.owner {
+                Some(previous) => previous,
+                None => self.asset_owner_data.get(pubkey)?,
+            };
+            if owner_at_slot.and_then(|asset_owner| asset_owner.owner.value) == Some(owner) {
+                result.owned.push(pubkey);
+            } else {
+                result.not_owned.push(pubkey);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_previous_versions_keep_first() {
+        let first_owner = Pubkey::new_unique();
+        let mut versions = AssetPreviousVersions {
+            owner: Some(Some(AssetOwner {
+                owner: entities::models::Updated::new(1, None, Some(first_owner)),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        versions.keep_first(AssetPreviousVersions {
+            owner: Some(None),
+            authority: Some(None),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            versions.owner.unwrap().unwrap().owner.value,
+            Some(first_owner)
+        );
+        assert!(matches!(versions.authority, Some(None)));
+        assert!(versions.dynamic_details.is_none());
+    }
+}
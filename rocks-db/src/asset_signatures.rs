@@ -1,10 +1,19 @@
+use std::collections::HashSet;
+
 use crate::column::TypedColumn;
-use crate::key_encoders::{decode_asset_signature_key, encode_asset_signature_key};
+use crate::key_encoders::{
+    decode_asset_signature_key, decode_pubkey_u64_signature, encode_asset_signature_key,
+    encode_pubkey_u64_signature,
+};
 use crate::{Result, Storage};
 use entities::api_req_params::AssetSortDirection;
-use entities::models::{AssetSignature, AssetSignatureKey, AssetSignatureWithPagination};
+use entities::models::{
+    AssetSignature, AssetSignatureKey, AssetSignatureWithPagination, SignatureWithSlot,
+};
 use interface::asset_sigratures::AssetSignaturesGetter;
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
 
 impl TypedColumn for AssetSignature {
     type KeyType = AssetSignatureKey;
@@ -21,6 +30,35 @@ impl TypedColumn for AssetSignature {
     }
 }
 
+/// Signatures of the transactions touching the mint, metadata or core asset account
+/// of a non-compressed asset, keyed by the asset, slot and signature.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AssetAccountSignatureIdx {}
+
+impl TypedColumn for AssetAccountSignatureIdx {
+    type KeyType = (Pubkey, u64, Signature);
+    type ValueType = AssetSignature;
+    const NAME: &'static str = "ASSET_ACCOUNT_SIGNATURE_IDX";
+
+    fn encode_key(key: Self::KeyType) -> Vec<u8> {
+        encode_pubkey_u64_signature(key)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_pubkey_u64_signature(bytes)
+    }
+}
+
+/// Cursors of the account based signatures are `<slot>:<signature>` strings.
+pub fn encode_asset_account_signature_cursor(slot: u64, signature: &Signature) -> String {
+    format!("{}:{}", slot, signature)
+}
+
+pub fn decode_asset_account_signature_cursor(cursor: &str) -> Option<(u64, Signature)> {
+    let (slot, signature) = cursor.split_once(':')?;
+    Some((slot.parse().ok()?, signature.parse().ok()?))
+}
+
 impl AssetSignaturesGetter for Storage {
     fn signatures_iter(
         &self,
@@ -100,9 +138,145 @@ impl AssetSignaturesGetter for Storage {
                 break;
             }
             res.asset_signatures.push(value);
-            res.after = Some(key.seq);
+            res.after = Some(key.seq.to_string());
+            if first_iter {
+                res.before = Some(key.seq.to_string());
+                first_iter = false;
+            }
+        }
+        res
+    }
+}
+
+impl Storage {
+    /// Resolves the accounts touched by a transaction into the non-compressed assets they belong to
+    /// and records the transaction signature for each of them.
+    /// An account is either the asset itself (mint or core asset), its metadata account
+    /// or a token account holding the mint.
+    pub(crate) async fn store_asset_account_signatures_with_batch(
+        &self,
+        batch: &mut rocksdb::WriteBatchWithTransaction<false>,
+        accounts: &[(Pubkey, String)],
+        signature: SignatureWithSlot,
+    ) -> Result<()> {
+        if accounts.is_empty() {
+            return Ok(());
+        }
+        let keys = accounts.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+        let assets = self.asset_static_data.batch_get(keys.clone()).await?;
+        let metadata_mints = self.metadata_mint_map.batch_get(keys.clone()).await?;
+        let token_accounts = self.token_accounts.batch_get(keys).await?;
+
+        let mut resolved_assets = Vec::new();
+        let mut token_account_mints = Vec::new();
+        for ((((account, instruction), asset), metadata_mint), token_account) in accounts
+            .iter()
+            .zip(assets)
+            .zip(metadata_mints)
+            .zip(token_accounts)
+        {
+            if asset.is_some() {
+                resolved_assets.push((*account, instruction));
+            } else if let Some(metadata_mint) = metadata_mint {
+                resolved_assets.push((metadata_mint.mint_key, instruction));
+            } else if let Some(token_account) = token_account {
+                token_account_mints.push((token_account.mint, instruction));
+            }
+        }
+        // token accounts are indexed for every mint, so only the mints of known assets are kept
+        if !token_account_mints.is_empty() {
+            let mint_assets = self
+                .asset_static_data
+                .batch_get(token_account_mints.iter().map(|(mint, _)| *mint).collect())
+                .await?;
+            resolved_assets.extend(
+                token_account_mints
+                    .into_iter()
+                    .zip(mint_assets)
+                    .filter_map(|(mint, asset)| asset.map(|_| mint)),
+            );
+        }
+
+        let mut stored_assets = HashSet::new();
+        for (asset, instruction) in resolved_assets {
+            if !stored_assets.insert(asset) {
+                continue;
+            }
+            self.asset_account_signature_idx.put_with_batch(
+                batch,
+                (asset, signature.slot, signature.signature),
+                &AssetSignature {
+                    tx: signature.signature.to_string(),
+                    instruction: instruction.clone(),
+                    slot: signature.slot,
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns the signatures recorded for a non-compressed asset.
+    /// `after` is the position to continue the iteration from in the requested direction,
+    /// `before` is the position to stop at, both are excluded from the result.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_asset_account_signatures(
+        &self,
+        asset: Pubkey,
+        before: Option<(u64, Signature)>,
+        after: Option<(u64, Signature)>,
+        page: Option<u64>,
+        direction: AssetSortDirection,
+        limit: u64,
+    ) -> AssetSignatureWithPagination {
+        let iter = match direction {
+            AssetSortDirection::Asc => self.asset_account_signature_idx.iter(
+                after
+                    .map(|(slot, signature)| (asset, slot, signature))
+                    .unwrap_or((asset, 0, Signature::default())),
+            ),
+            AssetSortDirection::Desc => self.asset_account_signature_idx.iter_reverse(
+                after
+                    .map(|(slot, signature)| (asset, slot, signature))
+                    .unwrap_or((asset, u64::MAX, Signature::from([u8::MAX; 64]))),
+            ),
+        };
+
+        let mut res = AssetSignatureWithPagination::default();
+        let mut first_iter = true;
+        for (slot, signature, value) in iter
+            .filter_map(std::result::Result::ok)
+            .map_while(|(key, value)| {
+                let (key_asset, slot, signature) = self
+                    .asset_account_signature_idx
+                    .decode_key(key.to_vec())
+                    .ok()?;
+                if key_asset != asset
+                    || before
+                        .map(|before| match direction {
+                            AssetSortDirection::Asc => before <= (slot, signature),
+                            AssetSortDirection::Desc => before >= (slot, signature),
+                        })
+                        .unwrap_or_default()
+                {
+                    return None;
+                }
+                let value = bincode::deserialize::<AssetSignature>(value.as_ref()).ok()?;
+                Some((slot, signature, value))
+            })
+            // the iterator is positioned at the `after` key itself if it exists
+            .filter(|(slot, signature, _)| after != Some((*slot, *signature)))
+            .skip(
+                page.and_then(|page| page.saturating_sub(1).checked_mul(limit))
+                    .unwrap_or_default() as usize,
+            )
+        {
+            if res.asset_signatures.len() >= limit as usize {
+                break;
+            }
+            res.asset_signatures.push(value);
+            res.after = Some(encode_asset_account_signature_cursor(slot, &signature));
             if first_iter {
-                res.before = Some(key.seq);
+                res.before = Some(encode_asset_account_signature_cursor(slot, &signature));
                 first_iter = false;
             }
         }
@@ -5,7 +5,9 @@ use entities::enums::{SpecificationVersions, TokenMetadataEdition};
 use serde_json::json;
 use solana_sdk::pubkey::Pubkey;
 
-use crate::asset::{AssetCollection, AssetLeaf, AssetsUpdateIdx, SlotAssetIdx, SlotAssetIdxKey};
+use crate::asset::{
+    core_plugin_type, AssetCollection, AssetLeaf, AssetsUpdateIdx, SlotAssetIdx, SlotAssetIdxKey,
+};
 use crate::cl_items::{ClItem, ClItemKey, ClLeaf, ClLeafKey};
 use crate::column::TypedColumn;
 use crate::errors::StorageError;
@@ -18,12 +20,13 @@ use crate::{
     BATCH_ITERATION_ACTION, ITERATOR_TOP_ACTION, ROCKS_COMPONENT,
 };
 use entities::models::{
-    AssetIndex, CompleteAssetDetails, FungibleToken, UpdateVersion, Updated, UrlWithStatus,
+    AssetIndex, CompleteAssetDetails, FungibleToken, OffChainData, UpdateVersion, Updated,
+    UrlWithStatus,
 };
 
 impl AssetUpdateIndexStorage for Storage {
     fn last_known_asset_updated_key(&self) -> Result<Option<AssetUpdatedKey>> {
-        _ = self.db.try_catch_up_with_primary();
+        _ = self.catch_up_with_primary();
         let start_time = chrono::Utc::now();
         let mut iter = self.assets_update_idx.iter_end();
         if let Some(pair) = iter.next() {
@@ -144,6 +147,22 @@ impl AssetIndexReader for Storage {
             .into_iter()
             .filter_map(|asset| asset.map(|a| (a.pubkey, a)))
             .collect::<HashMap<_, _>>();
+        let metadata_urls = asset_dynamic_details
+            .iter()
+            .flatten()
+            .map(|dynamic_info| dynamic_info.url.value.clone())
+            .filter(|url| !url.trim().is_empty())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let offchain_data = self
+            .asset_offchain_data
+            .batch_get(metadata_urls)
+            .await?
+            .into_iter()
+            .flatten()
+            .map(|offchain_data| (offchain_data.url.clone(), offchain_data))
+            .collect::<HashMap<_, _>>();
 
         for static_info in asset_static_details.iter().flatten() {
             let asset_index = AssetIndex {
@@ -159,6 +178,7 @@ impl AssetIndexReader for Storage {
         }
 
         for dynamic_info in asset_dynamic_details.iter().flatten() {
+            let asset_offchain_data = offchain_data.get(&dynamic_info.url.value);
             if let Some(existed_index) = asset_indexes.get_mut(&dynamic_info.pubkey) {
                 existed_index.pubkey = dynamic_info.pubkey;
                 existed_index.is_compressible = dynamic_info.is_compressible.value;
@@ -169,8 +189,12 @@ impl AssetIndexReader for Storage {
                 existed_index.creators = dynamic_info.creators.clone().value;
                 existed_index.royalty_amount = dynamic_info.royalty_amount.value as i64;
                 existed_index.slot_updated = dynamic_info.get_slot_updated() as i64;
-                existed_index.metadata_url = self.url_with_status_for(dynamic_info);
+                existed_index.metadata_url = url_with_status_for(dynamic_info, asset_offchain_data);
+                existed_index.name = asset_name_for(dynamic_info, asset_offchain_data);
+                (existed_index.core_plugins, existed_index.core_attributes) =
+                    mpl_core_plugins_index(dynamic_info);
             } else {
+                let (core_plugins, core_attributes) = mpl_core_plugins_index(dynamic_info);
                 let asset_index = AssetIndex {
                     pubkey: dynamic_info.pubkey,
                     is_compressible: dynamic_info.is_compressible.value,
@@ -181,7 +205,10 @@ impl AssetIndexReader for Storage {
                     creators: dynamic_info.creators.clone().value,
                     royalty_amount: dynamic_info.royalty_amount.value as i64,
                     slot_updated: dynamic_info.get_slot_updated() as i64,
-                    metadata_url: self.url_with_status_for(dynamic_info),
+                    metadata_url: url_with_status_for(dynamic_info, asset_offchain_data),
+                    name: asset_name_for(dynamic_info, asset_offchain_data),
+                    core_plugins,
+                    core_attributes,
                     ..Default::default()
                 };
 
@@ -438,19 +465,21 @@ impl Storage {
             )?
         }
         for item in data.cl_items {
+            let cl_item = ClItem {
+                cli_node_idx: item.cli_node_idx,
+                cli_tree_key: item.cli_tree_key,
+                cli_leaf_idx: item.cli_leaf_idx,
+                cli_seq: item.cli_seq,
+                cli_level: item.cli_level,
+                cli_hash: item.cli_hash.clone(),
+                slot_updated: item.slot_updated,
+            };
             self.cl_items.merge_with_batch(
                 &mut batch,
                 ClItemKey::new(item.cli_node_idx, item.cli_tree_key),
-                &ClItem {
-                    cli_node_idx: item.cli_node_idx,
-                    cli_tree_key: item.cli_tree_key,
-                    cli_leaf_idx: item.cli_leaf_idx,
-                    cli_seq: item.cli_seq,
-                    cli_level: item.cli_level,
-                    cli_hash: item.cli_hash.clone(),
-                    slot_updated: item.slot_updated,
-                },
+                &cl_item,
             )?;
+            self.record_cl_item_history_with_batch(&mut batch, &cl_item)?;
         }
         if let Some(edition) = data.edition {
             self.token_metadata_edition_cbor.merge_with_batch_cbor(
@@ -489,25 +518,182 @@ impl Storage {
             .map_err(|e| StorageError::Common(e.to_string()))?;
         Ok(())
     }
+}
 
-    fn url_with_status_for(&self, dynamic_info: &AssetDynamicDetails) -> Option<UrlWithStatus> {
-        if dynamic_info.url.value.trim().is_empty() {
-            None
-        } else {
-            // doing this check because there may be saved empty strings for some urls
-            // because of bug in previous code
-            let is_downloaded = self
-                .asset_offchain_data
-                .get(dynamic_info.url.value.clone())
-                .ok()
-                .flatten()
-                .map(|a| !a.metadata.is_empty())
-                .unwrap_or(false);
-
-            Some(UrlWithStatus {
-                metadata_url: dynamic_info.url.value.clone(),
-                is_downloaded,
-            })
+// Extracts the plugin types and the on-chain attributes from the MPL Core plugins JSON.
+// Internal plugins are stored as an object keyed by the snake_case plugin type,
+// external plugin adapters as an array of objects with the `type` field.
+fn mpl_core_plugins_index(
+    dynamic_info: &AssetDynamicDetails,
+) -> (Vec<String>, Vec<(String, String)>) {
+    let mut plugin_types = Vec::new();
+    let mut attributes = Vec::new();
+
+    let plugins = dynamic_info
+        .mpl_core_plugins
+        .as_ref()
+        .and_then(|plugins| serde_json::from_str::<serde_json::Value>(&plugins.value).ok());
+    if let Some(serde_json::Value::Object(plugins)) = plugins {
+        for (plugin_type, plugin) in plugins.iter() {
+            let plugin_type = core_plugin_type(plugin_type);
+            let is_attributes = plugin_type == "attributes";
+            plugin_types.push(plugin_type);
+            if !is_attributes {
+                continue;
+            }
+            let attribute_list = plugin
+                .get("data")
+                .and_then(|data| data.get("attribute_list"))
+                .and_then(|list| list.as_array());
+            for attribute in attribute_list.into_iter().flatten() {
+                if let (Some(key), Some(value)) = (
+                    attribute.get("key").and_then(|k| k.as_str()),
+                    attribute.get("value").and_then(|v| v.as_str()),
+                ) {
+                    // postgres does not accept NUL characters in text and jsonb values
+                    attributes.push((key.replace('\0', ""), value.replace('\0', "")));
+                }
+            }
         }
     }
+
+    let external_plugins = dynamic_info
+        .mpl_core_external_plugins
+        .as_ref()
+        .and_then(|plugins| serde_json::from_str::<serde_json::Value>(&plugins.value).ok());
+    if let Some(serde_json::Value::Array(external_plugins)) = external_plugins {
+        plugin_types.extend(
+            external_plugins
+                .iter()
+                .filter_map(|plugin| plugin.get("type").and_then(|t| t.as_str()))
+                .map(core_plugin_type),
+        );
+    }
+
+    plugin_types.sort();
+    plugin_types.dedup();
+    (plugin_types, attributes)
+}
+
+fn url_with_status_for(
+    dynamic_info: &AssetDynamicDetails,
+    offchain_data: Option<&OffChainData>,
+) -> Option<UrlWithStatus> {
+    if dynamic_info.url.value.trim().is_empty() {
+        None
+    } else {
+        // doing this check because there may be saved empty strings for some urls
+        // because of bug in previous code
+        let is_downloaded = offchain_data
+            .map(|a| !a.metadata.is_empty())
+            .unwrap_or(false);
+
+        Some(UrlWithStatus {
+            metadata_url: dynamic_info.url.value.clone(),
+            is_downloaded,
+        })
+    }
+}
+
+// The name is taken from the on-chain metadata first, then from the raw name (MPL Core)
+// and only if both are missing from the downloaded off-chain JSON
+fn asset_name_for(
+    dynamic_info: &AssetDynamicDetails,
+    offchain_data: Option<&OffChainData>,
+) -> Option<String> {
+    let onchain_name = dynamic_info.onchain_data.as_ref().and_then(|onchain_data| {
+        serde_json::from_str::<serde_json::Value>(&onchain_data.value)
+            .ok()
+            .and_then(|v| v.get("name").and_then(|n| n.as_str()).map(str::to_owned))
+    });
+    let name = onchain_name
+        .or_else(|| dynamic_info.raw_name.as_ref().map(|n| n.value.clone()))
+        .filter(|n| !n.trim().is_empty())
+        .or_else(|| {
+            offchain_data
+                .and_then(|offchain_data| {
+                    serde_json::from_str::<serde_json::Value>(&offchain_data.metadata).ok()
+                })
+                .and_then(|v| v.get("name").and_then(|n| n.as_str()).map(str::to_owned))
+        })?;
+    // postgres text columns do not accept NUL characters
+    let name = name.trim().replace('\0', "");
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mpl_core_plugins_index() {
+        let dynamic_info = AssetDynamicDetails {
+            mpl_core_plugins: Some(Updated::new(
+                1,
+                None,
+                r#"{"attributes":{"index":1,"offset":120,"authority":{"type":"UpdateAuthority","address":null},"data":{"attribute_list":[{"key":"level","value":"10"},{"key":"class","value":"mage"}]}},"freeze_delegate":{"index":0,"offset":119,"authority":{"type":"Owner","address":null},"data":{"frozen":false}}}"#.to_string(),
+            )),
+            mpl_core_external_plugins: Some(Updated::new(
+                1,
+                None,
+                r#"[{"index":0,"offset":0,"authority":{"type":"UpdateAuthority","address":null},"type":"LifecycleHook","adapter_config":{}},{"index":1,"offset":0,"authority":{"type":"UpdateAuthority","address":null},"type":"Oracle","adapter_config":{}}]"#.to_string(),
+            )),
+            ..Default::default()
+        };
+
+        let (plugins, attributes) = mpl_core_plugins_index(&dynamic_info);
+        assert_eq!(
+            plugins,
+            vec!["attributes", "freeze_delegate", "lifecycle_hook", "oracle"]
+        );
+        assert_eq!(
+            attributes,
+            vec![
+                ("level".to_string(), "10".to_string()),
+                ("class".to_string(), "mage".to_string())
+            ]
+        );
+
+        let (plugins, attributes) = mpl_core_plugins_index(&AssetDynamicDetails::default());
+        assert!(plugins.is_empty());
+        assert!(attributes.is_empty());
+    }
+
+    #[test]
+    fn test_asset_name_for() {
+        let url = "https://example.com/1.json".to_string();
+        let offchain_data = OffChainData {
+            url: url.clone(),
+            metadata: r#"{"name":" Off-chain name\u0000 "}"#.to_string(),
+        };
+        let mut dynamic_info = AssetDynamicDetails {
+            url: Updated::new(1, None, url),
+            ..Default::default()
+        };
+
+        assert_eq!(asset_name_for(&dynamic_info, None), None);
+        // the name may come with the JSON downloaded after the asset was indexed
+        assert_eq!(
+            asset_name_for(&dynamic_info, Some(&offchain_data)),
+            Some("Off-chain name".to_string())
+        );
+        assert_eq!(
+            url_with_status_for(&dynamic_info, Some(&offchain_data)).map(|u| u.is_downloaded),
+            Some(true)
+        );
+
+        dynamic_info.onchain_data = Some(Updated::new(
+            1,
+            None,
+            r#"{"name":"On-chain name"}"#.to_string(),
+        ));
+        assert_eq!(
+            asset_name_for(&dynamic_info, Some(&offchain_data)),
+            Some("On-chain name".to_string())
+        );
+    }
 }
@@ -1,8 +1,11 @@
 use crate::asset::{AssetCollection, MetadataMintMap};
+use crate::asset_previous_versions::PendingVersions;
+use crate::editions::{EditionMintMap, EditionParentIdx};
 use crate::token_accounts::{TokenAccountMintOwnerIdx, TokenAccountOwnerIdx};
+use crate::webhooks::{AssetEvent, AssetStateChange};
 use crate::Result;
 use crate::{AssetAuthority, AssetDynamicDetails, AssetOwner, AssetStaticDetails, Storage};
-use entities::enums::TokenMetadataEdition;
+use entities::enums::{AccountTombstone, TokenMetadataEdition};
 use entities::models::{
     InscriptionDataInfo, InscriptionInfo, Mint, TokenAccount, TokenAccountMintOwnerIdxKey,
     TokenAccountOwnerIdxKey,
@@ -10,6 +13,7 @@ use entities::models::{
 use metrics_utils::IngesterMetricsConfig;
 use num_traits::Zero;
 use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::time::Instant;
 use tracing::error;
@@ -18,6 +22,9 @@ use usecase::save_metrics::result_to_metrics;
 pub struct BatchSaveStorage {
     storage: Arc<Storage>,
     batch: rocksdb::WriteBatchWithTransaction<false>,
+    pending_versions: PendingVersions,
+    // changes of the assets made by the account update being stored
+    asset_changes: HashMap<Pubkey, AssetStateChange>,
     batch_size: usize,
     metrics: Arc<IngesterMetricsConfig>,
 }
@@ -30,6 +37,7 @@ pub struct MetadataModels {
     pub asset_owner: Option<AssetOwner>,
     pub asset_collection: Option<AssetCollection>,
     pub metadata_mint: Option<MetadataMintMap>,
+    pub edition_mint: Option<EditionMintMap>,
 }
 
 #[macro_export]
@@ -55,6 +63,8 @@ impl BatchSaveStorage {
             storage,
             batch_size,
             batch: Default::default(),
+            pending_versions: Default::default(),
+            asset_changes: Default::default(),
             metrics,
         }
     }
@@ -63,6 +73,8 @@ impl BatchSaveStorage {
         self.metrics
             .set_buffer("accounts_batch_size", self.batch.len() as i64);
         let begin_processing = Instant::now();
+        self.pending_versions = Default::default();
+        self.asset_changes.clear();
         let res = self
             .storage
             .db
@@ -89,6 +101,16 @@ impl BatchSaveStorage {
         )
     }
     pub fn store_owner(&mut self, asset_owner: &AssetOwner) -> Result<()> {
+        if let Some((previous, current)) = self.storage.record_asset_update_with_batch(
+            &mut self.batch,
+            &mut self.pending_versions,
+            asset_owner,
+        )? {
+            self.asset_changes
+                .entry(asset_owner.pubkey)
+                .or_default()
+                .owner_changed(previous.as_ref(), &current);
+        }
         store_assets!(
             self,
             asset_owner,
@@ -97,6 +119,16 @@ impl BatchSaveStorage {
         )
     }
     pub fn store_dynamic(&mut self, asset_dynamic: &AssetDynamicDetails) -> Result<()> {
+        if let Some((previous, current)) = self.storage.record_asset_update_with_batch(
+            &mut self.batch,
+            &mut self.pending_versions,
+            asset_dynamic,
+        )? {
+            self.asset_changes
+                .entry(asset_dynamic.pubkey)
+                .or_default()
+                .dynamic_details_changed(previous.as_ref(), &current);
+        }
         store_assets!(
             self,
             asset_dynamic,
@@ -105,6 +137,16 @@ impl BatchSaveStorage {
         )
     }
     fn store_authority(&mut self, asset_authority: &AssetAuthority) -> Result<()> {
+        if let Some((previous, _)) = self.storage.record_asset_update_with_batch(
+            &mut self.batch,
+            &mut self.pending_versions,
+            asset_authority,
+        )? {
+            self.asset_changes
+                .entry(asset_authority.pubkey)
+                .or_default()
+                .authority_changed(previous.as_ref());
+        }
         store_assets!(
             self,
             asset_authority,
@@ -113,6 +155,11 @@ impl BatchSaveStorage {
         )
     }
     fn store_collection(&mut self, asset_collection: &AssetCollection) -> Result<()> {
+        self.storage.record_asset_update_with_batch(
+            &mut self.batch,
+            &mut self.pending_versions,
+            asset_collection,
+        )?;
         store_assets!(
             self,
             asset_collection,
@@ -128,6 +175,14 @@ impl BatchSaveStorage {
             "metadata_mint_map_merge_with_batch"
         )
     }
+    fn store_edition_mint(&mut self, edition_mint_map: &EditionMintMap) -> Result<()> {
+        store_assets!(
+            self,
+            edition_mint_map,
+            edition_mint_map,
+            "edition_mint_map_merge_with_batch"
+        )
+    }
     pub fn store_spl_mint(&mut self, mint: &Mint) -> Result<()> {
         let res =
             self.storage
@@ -144,6 +199,39 @@ impl BatchSaveStorage {
             .merge_with_batch_cbor(&mut self.batch, key, edition)?;
         Ok(())
     }
+    pub fn store_edition_parent_idx(
+        &mut self,
+        key: Pubkey,
+        parent: Pubkey,
+        edition_number: u64,
+        write_version: u64,
+    ) -> Result<()> {
+        self.storage.edition_parent_idx.merge_with_batch(
+            &mut self.batch,
+            (parent, edition_number),
+            &EditionParentIdx {
+                edition: key,
+                write_version,
+            },
+        )
+    }
+    /// Drops the print from the index of its master edition once its edition account is closed
+    pub fn delete_burnt_print(&mut self, key: Pubkey) -> Result<()> {
+        let Some(TokenMetadataEdition::EditionV1(edition)) = self
+            .storage
+            .token_metadata_edition_cbor
+            .get_cbor_encoded_sync(key)?
+        else {
+            return Ok(());
+        };
+        self.storage
+            .edition_parent_idx
+            .delete_with_batch(&mut self.batch, (edition.parent, edition.edition));
+        self.storage
+            .edition_mint_map
+            .delete_with_batch(&mut self.batch, key);
+        Ok(())
+    }
     pub fn store_inscription(&mut self, inscription: &InscriptionInfo) -> Result<()> {
         self.storage.inscriptions.merge_with_batch(
             &mut self.batch,
@@ -173,6 +261,29 @@ impl BatchSaveStorage {
             .asset_updated_with_batch(&mut self.batch, slot, pubkey)?;
         Ok(())
     }
+    /// Changes of the assets made by the updates stored since the previous call
+    pub fn take_asset_changes(&mut self) -> HashMap<Pubkey, AssetStateChange> {
+        std::mem::take(&mut self.asset_changes)
+    }
+    pub fn store_asset_event(&mut self, event: &AssetEvent) -> Result<()> {
+        self.storage
+            .record_asset_event_with_batch(&mut self.batch, event)
+    }
+    pub fn account_updated_with_batch(
+        &mut self,
+        slot: u64,
+        pubkey: Pubkey,
+        write_version: u64,
+        tombstone: AccountTombstone,
+    ) -> Result<()> {
+        self.storage.record_account_update_slot_with_batch(
+            &mut self.batch,
+            slot,
+            pubkey,
+            write_version,
+            tombstone,
+        )
+    }
     pub fn save_token_account_with_idxs(
         &mut self,
         key: Pubkey,
@@ -246,6 +357,9 @@ impl BatchSaveStorage {
         if let Some(metadata_mint) = &metadata_models.metadata_mint {
             self.store_metadata_mint(metadata_mint)?;
         }
+        if let Some(edition_mint) = &metadata_models.edition_mint {
+            self.store_edition_mint(edition_mint)?;
+        }
         if let Some(asset_owner) = &metadata_models.asset_owner {
             self.store_owner(asset_owner)?;
             key = Some(asset_owner.pubkey);
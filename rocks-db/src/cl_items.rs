@@ -9,6 +9,8 @@ use spl_account_compression::events::ChangeLogEventV1;
 use tracing::{debug, error};
 
 use crate::asset::AssetLeaf;
+use crate::asset_previous_versions::PendingVersions;
+use crate::cl_items_history::ClItemsHistory;
 use crate::column::TypedColumn;
 use crate::errors::StorageError;
 use crate::key_encoders::{decode_u64_pubkey, encode_u64_pubkey};
@@ -138,6 +140,7 @@ impl Storage {
         let mut i: u64 = 0;
         let depth = change_log_event.path.len() - 1;
         let mut items_map = HashMap::new();
+        let mut history_map = HashMap::new();
         let mut leaf_map = HashMap::new();
         for p in change_log_event.path.iter() {
             let node_idx = p.index as u64;
@@ -160,6 +163,15 @@ impl Storage {
                 slot_updated: slot,
             };
 
+            if self.is_cl_items_history_enabled() {
+                history_map.insert(
+                    ClItemKey::new(node_idx, tree),
+                    ClItemsHistory {
+                        items: vec![cl_item.clone()],
+                        ..Default::default()
+                    },
+                );
+            }
             items_map.insert(ClItemKey::new(node_idx, tree), cl_item);
             // save leaf's node id
             if i == 1 {
@@ -174,10 +186,14 @@ impl Storage {
             }
         }
         let merge_res = self.cl_items.merge_batch(items_map);
+        let history_res = self.cl_items_history.merge_batch(history_map);
         let put_res = self.cl_leafs.put_batch(leaf_map);
         if let Err(e) = merge_res.await {
             error!("Error while saving change log for cNFT: {}", e);
         };
+        if let Err(e) = history_res.await {
+            error!("Error while saving change log history for cNFT: {}", e);
+        };
         if let Err(e) = put_res.await {
             error!("Error while saving change log for cNFT: {}", e);
         }
@@ -231,6 +247,9 @@ impl Storage {
             {
                 error!("Error while saving change log for cNFT: {}", e);
             };
+            if let Err(e) = self.record_cl_item_history_with_batch(batch, &cl_item) {
+                error!("Error while saving change log history for cNFT: {}", e);
+            };
 
             // save leaf's node id
             if i == 1 {
@@ -282,11 +301,13 @@ impl Storage {
         slot: u64,
         leaf: &Option<AssetLeaf>,
         dynamic_data: &Option<AssetDynamicDetails>,
+        pending: &mut PendingVersions,
     ) -> Result<()> {
         if let Some(leaf) = leaf {
             self.asset_leaf_data.merge_with_batch(batch, pk, leaf)?
         };
         if let Some(dynamic_data) = dynamic_data {
+            self.record_asset_update_with_batch(batch, pending, dynamic_data)?;
             self.asset_dynamic_data
                 .merge_with_batch(batch, pk, dynamic_data)?;
         }
@@ -302,7 +323,14 @@ impl Storage {
         dynamic_data: &Option<AssetDynamicDetails>,
     ) -> Result<()> {
         let mut batch = rocksdb::WriteBatchWithTransaction::<false>::default();
-        self.save_tx_data_and_asset_updated_with_batch(&mut batch, pk, slot, leaf, dynamic_data)?;
+        self.save_tx_data_and_asset_updated_with_batch(
+            &mut batch,
+            pk,
+            slot,
+            leaf,
+            dynamic_data,
+            &mut PendingVersions::default(),
+        )?;
         let backend = self.db.clone();
         tokio::task::spawn_blocking(move || {
             backend
@@ -329,11 +357,11 @@ impl ClItemKey {
         ClItemKey { node_id, tree_id }
     }
 
-    fn encode_to_bytes(&self) -> Vec<u8> {
+    pub(crate) fn encode_to_bytes(&self) -> Vec<u8> {
         encode_u64_pubkey(self.node_id, self.tree_id)
     }
 
-    fn decode_from_bytes(bytes: Vec<u8>) -> Result<Self> {
+    pub(crate) fn decode_from_bytes(bytes: Vec<u8>) -> Result<Self> {
         decode_u64_pubkey(bytes).map(|(node_id, tree_id)| ClItemKey { node_id, tree_id })
     }
 }
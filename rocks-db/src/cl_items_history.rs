@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::Ordering;
+
+use bincode::{deserialize, serialize};
+use rocksdb::MergeOperands;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+use crate::cl_items::{ClItem, ClItemKey};
+use crate::column::TypedColumn;
+use crate::errors::StorageError;
+use crate::parameters::Parameter;
+use crate::{Result, Storage};
+
+/// Number of the latest versions kept for every tree node.
+/// The root changes with every tree update, so it is also the number of the latest seqs
+/// a proof can be built at.
+pub const CL_ITEMS_HISTORY_WINDOW: usize = 64;
+
+/// Previous versions of the tree node, ordered by seq and bounded by CL_ITEMS_HISTORY_WINDOW.
+/// Written only if the history is enabled for the storage.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ClItemsHistory {
+    pub items: Vec<ClItem>,
+    // the versions older than the first item were dropped out of the window
+    pub truncated: bool,
+    // (seq, slot) of the versions written in the forked slots, the merge drops them from the items
+    pub forked: Vec<(u64, u64)>,
+}
+
+impl TypedColumn for ClItemsHistory {
+    type KeyType = ClItemKey;
+    type ValueType = Self;
+    const NAME: &'static str = "CL_ITEMS_HISTORY";
+
+    fn encode_key(key: ClItemKey) -> Vec<u8> {
+        key.encode_to_bytes()
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        ClItemKey::decode_from_bytes(bytes)
+    }
+}
+
+impl ClItemsHistory {
+    pub fn merge_cl_items_history(
+        _new_key: &[u8],
+        existing_val: Option<&[u8]>,
+        operands: &MergeOperands,
+    ) -> Option<Vec<u8>> {
+        let mut items = BTreeMap::new();
+        let mut truncated = false;
+        if let Some(existing_val) = existing_val {
+            match deserialize::<Self>(existing_val) {
+                Ok(value) => {
+                    items.extend(value.items.into_iter().map(|item| (item.cli_seq, item)));
+                    truncated = value.truncated;
+                }
+                Err(e) => {
+                    error!("RocksDB: ClItemsHistory deserialize existing_val: {}", e)
+                }
+            }
+        }
+
+        // the latest written version of the seq wins, as it does in the cl_items column
+        for op in operands {
+            match deserialize::<Self>(op) {
+                Ok(new_val) => {
+                    items.extend(new_val.items.into_iter().map(|item| (item.cli_seq, item)));
+                    truncated |= new_val.truncated;
+                    // the version of the seq may have been rewritten from the main branch already
+                    for (seq, slot) in new_val.forked {
+                        if items
+                            .get(&seq)
+                            .is_some_and(|item: &ClItem| item.slot_updated == slot)
+                        {
+                            items.remove(&seq);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("RocksDB: ClItemsHistory deserialize new_val: {}", e)
+                }
+            }
+        }
+
+        let skip = items.len().saturating_sub(CL_ITEMS_HISTORY_WINDOW);
+        serialize(&ClItemsHistory {
+            items: items.into_values().skip(skip).collect(),
+            truncated: truncated || skip > 0,
+            forked: Vec::new(),
+        })
+        .ok()
+    }
+}
+
+/// Prior state of the tree a proof can be built at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClItemsVersion {
+    Seq(u64),
+    Slot(u64),
+}
+
+impl ClItemsVersion {
+    fn includes(&self, item: &ClItem) -> bool {
+        match self {
+            ClItemsVersion::Seq(seq) => item.cli_seq <= *seq,
+            ClItemsVersion::Slot(slot) => item.slot_updated <= *slot,
+        }
+    }
+
+    fn select(
+        &self,
+        key: &ClItemKey,
+        current: Option<ClItem>,
+        history: Option<ClItemsHistory>,
+        recorded_since: &ClItemsHistoryStart,
+    ) -> Result<Option<ClItem>> {
+        let out_of_window = || {
+            StorageError::NotFound(format!(
+                "node {} of tree {} at {:?} is outside of the retained changelog history",
+                key.node_id, key.tree_id, self
+            ))
+        };
+        match history {
+            Some(history) => {
+                if let Some(item) = history.items.iter().rev().find(|item| self.includes(item)) {
+                    // the later versions written before the history was enabled weren't recorded
+                    if item.slot_updated < recorded_since.slot {
+                        return Err(out_of_window());
+                    }
+                    return Ok(Some(item.clone()));
+                }
+                // the node was empty at that moment only if none of its versions were dropped
+                // and all of them were recorded since the tree creation
+                if history.truncated || !recorded_since.trees.contains(&key.tree_id) {
+                    return Err(out_of_window());
+                }
+                Ok(None)
+            }
+            // the node was not updated since the history is recorded
+            None => match current {
+                Some(item) if !self.includes(&item) => Err(out_of_window()),
+                item => Ok(item),
+            },
+        }
+    }
+}
+
+// the slot the history is recorded from and the trees created since then
+struct ClItemsHistoryStart {
+    slot: u64,
+    trees: HashSet<Pubkey>,
+}
+
+impl Storage {
+    pub fn set_cl_items_history_enabled(&self, enabled: bool) {
+        self.cl_items_history_enabled
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_cl_items_history_enabled(&self) -> bool {
+        self.cl_items_history_enabled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_cl_item_history_with_batch(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        cl_item: &ClItem,
+    ) -> Result<()> {
+        if !self.is_cl_items_history_enabled() {
+            return Ok(());
+        }
+        self.cl_items_history.merge_with_batch(
+            batch,
+            ClItemKey::new(cl_item.cli_node_idx, cl_item.cli_tree_key),
+            &ClItemsHistory {
+                items: vec![cl_item.clone()],
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Slot the history is recorded from, None if it isn't recorded
+    pub async fn cl_items_history_start_slot(&self) -> Result<Option<u64>> {
+        self.get_parameter::<u64>(Parameter::ClItemsHistoryStartSlot)
+            .await
+    }
+
+    /// Marks the history as recorded from the slot after the latest ingested one
+    /// if it is enabled and wasn't recorded yet, forgets the start if it is disabled,
+    /// as the versions written meanwhile are lost
+    pub async fn init_cl_items_history_start_slot(&self) -> Result<()> {
+        if !self.is_cl_items_history_enabled() {
+            return self
+                .delete_parameter::<u64>(Parameter::ClItemsHistoryStartSlot)
+                .await;
+        }
+        if self.cl_items_history_start_slot().await?.is_none() {
+            let start_slot = self
+                .get_parameter::<u64>(Parameter::TopSeenSlot)
+                .await?
+                .map_or(0, |slot| slot + 1);
+            self.put_parameter(Parameter::ClItemsHistoryStartSlot, start_slot)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the tree nodes as they were at the version, None for the nodes which were empty.
+    /// Fails if any of the nodes was changed too many times since that version
+    /// or the version is older than the recorded history.
+    pub async fn get_cl_items_at_version(
+        &self,
+        keys: Vec<ClItemKey>,
+        version: ClItemsVersion,
+    ) -> Result<Vec<Option<ClItem>>> {
+        let trees = keys
+            .iter()
+            .map(|key| key.tree_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let (current, history, tree_infos, start_slot) = tokio::join!(
+            self.cl_items.batch_get(keys.clone()),
+            self.cl_items_history.batch_get(keys.clone()),
+            self.tree_info.batch_get(trees.clone()),
+            self.cl_items_history_start_slot()
+        );
+        // without the start none of the recorded versions can be trusted
+        let start_slot = start_slot?.unwrap_or(u64::MAX);
+        let recorded_since = ClItemsHistoryStart {
+            slot: start_slot,
+            trees: trees
+                .into_iter()
+                .zip(tree_infos?)
+                .filter_map(|(tree, tree_info)| {
+                    tree_info
+                        .and_then(|tree_info| tree_info.config)
+                        .filter(|config| config.created_at_slot >= start_slot)
+                        .map(|_| tree)
+                })
+                .collect(),
+        };
+        keys.iter()
+            .zip(current?.into_iter().zip(history?))
+            .map(|(key, (current, history))| version.select(key, current, history, &recorded_since))
+            .collect()
+    }
+
+    /// Drops the versions written in the forked slots from the history of the nodes,
+    /// identified by the node key, seq and slot of the version
+    pub(crate) async fn drop_forked_cl_items_history(
+        &self,
+        versions: Vec<(ClItemKey, u64, u64)>,
+    ) -> Result<()> {
+        let mut forked = HashMap::<ClItemKey, ClItemsHistory>::new();
+        for (key, seq, slot) in versions {
+            forked.entry(key).or_default().forked.push((seq, slot));
+        }
+        self.cl_items_history.merge_batch(forked).await
+    }
+}
@@ -274,6 +274,15 @@ where
         result
     }
 
+    pub fn get_cbor_encoded_sync(&self, key: C::KeyType) -> Result<Option<C::ValueType>> {
+        Self::get_raw(self.backend.clone(), key)?
+            .map(|serialized_value| {
+                serde_cbor::from_slice(&serialized_value)
+                    .map_err(|e| StorageError::Common(e.to_string()))
+            })
+            .transpose()
+    }
+
     fn get_raw(backend: Arc<DB>, key: C::KeyType) -> Result<Option<Vec<u8>>> {
         let r = backend.get_cf(&backend.cf_handle(C::NAME).unwrap(), C::encode_key(key))?;
         Ok(r)
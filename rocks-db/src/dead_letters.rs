@@ -0,0 +1,116 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::column::TypedColumn;
+use crate::key_encoders::{decode_u64, encode_u64};
+use crate::{next_time_ordered_id, Result, Storage};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadLetterStream {
+    Accounts,
+    Transactions,
+}
+
+/// Message the ingester failed to parse or to process
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeadLetter {
+    pub stream: DeadLetterStream,
+    // raw message, empty if the account update failed after it was parsed
+    pub data: Vec<u8>,
+    // whether the data has to be mapped into the plerkle flatbuffer before parsing, as TCP messages do
+    pub map_flatbuffer: bool,
+    // account whose parsed update failed processing, the latest state of it is fetched on redrive
+    pub pubkey: Option<Pubkey>,
+    // write version of the failed account update, the fetched state is stored with it
+    pub write_version: u64,
+    pub error: String,
+    pub failed_at: i64, // unix time in seconds
+}
+
+/// Rocks DB column family of the dead letters keyed by the ids growing with the time they were stored at
+impl TypedColumn for DeadLetter {
+    type KeyType = u64;
+    type ValueType = Self;
+    const NAME: &'static str = "DEAD_LETTERS";
+
+    fn encode_key(id: u64) -> Vec<u8> {
+        encode_u64(id)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_u64(bytes)
+    }
+}
+
+impl DeadLetter {
+    pub fn message(
+        stream: DeadLetterStream,
+        data: Vec<u8>,
+        map_flatbuffer: bool,
+        error: String,
+    ) -> Self {
+        Self {
+            stream,
+            data,
+            map_flatbuffer,
+            pubkey: None,
+            write_version: 0,
+            error,
+            failed_at: Utc::now().timestamp(),
+        }
+    }
+
+    pub fn account(pubkey: Pubkey, write_version: u64, error: String) -> Self {
+        Self {
+            stream: DeadLetterStream::Accounts,
+            data: Vec::new(),
+            map_flatbuffer: false,
+            pubkey: Some(pubkey),
+            write_version,
+            error,
+            failed_at: Utc::now().timestamp(),
+        }
+    }
+}
+
+impl Storage {
+    /// Stores the dead letter and returns its id
+    pub fn put_dead_letter(&self, dead_letter: DeadLetter) -> Result<u64> {
+        let id = next_time_ordered_id(&self.dead_letters_last_id);
+        self.dead_letters.put(id, dead_letter)?;
+
+        Ok(id)
+    }
+
+    /// Returns up to `limit` dead letters, the oldest first, starting right after the `after` id
+    pub fn get_dead_letters(&self, after: Option<u64>, limit: usize) -> Vec<(u64, DeadLetter)> {
+        // the cursor may point to an already deleted dead letter, so the iteration starts after it
+        let iter = match after {
+            Some(after) => self.dead_letters.iter(after.saturating_add(1)),
+            None => self.dead_letters.iter_start(),
+        };
+        iter.filter_map(|item| item.ok())
+            .filter_map(|(key, value)| {
+                let id = DeadLetter::decode_key(key.to_vec()).ok()?;
+                let dead_letter = bincode::deserialize::<DeadLetter>(&value).ok()?;
+                Some((id, dead_letter))
+            })
+            .take(limit)
+            .collect()
+    }
+
+    /// Drops the dead letters stored before the given unix time in seconds,
+    /// the ids are the nanoseconds they were stored at
+    pub async fn purge_dead_letters(&self, stored_before: i64) -> Result<()> {
+        let to = (stored_before.max(0) as u64).saturating_mul(1_000_000_000);
+        self.dead_letters.delete_range(0, to).await
+    }
+
+    pub fn dead_letters_count(&self) -> usize {
+        self.dead_letters
+            .iter_start()
+            .filter(|item| item.is_ok())
+            .count()
+    }
+}
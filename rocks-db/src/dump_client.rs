@@ -55,6 +55,39 @@ where
         None => serializer.serialize_none(),
     }
 }
+
+// text[] column in the COPY format, e.g. {"attributes","oracle"}, empty list is written as NULL
+fn to_pg_array(values: &[String]) -> Option<String> {
+    if values.is_empty() {
+        return None;
+    }
+    let elements = values
+        .iter()
+        .map(|v| format!("\"{}\"", v.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>();
+    Some(format!("{{{}}}", elements.join(",")))
+}
+
+// jsonb column of the (key, value) pairs as [{"key": .., "value": ..}], empty list is written as NULL
+fn to_key_value_json(values: &[(String, String)]) -> Option<String> {
+    if values.is_empty() {
+        return None;
+    }
+    let json = serde_json::Value::Array(
+        values
+            .iter()
+            .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+            .collect(),
+    );
+    Some(json.to_string())
+}
+
+/// Builds the CSV writer used for the dumps. Headers are not written,
+/// the records are serialized in the order of their fields.
+pub fn csv_writer<W: std::io::Write>(inner: W) -> csv::Writer<W> {
+    WriterBuilder::new().has_headers(false).from_writer(inner)
+}
+
 #[derive(Serialize)]
 struct AssetRecord {
     ast_pubkey: String,
@@ -80,6 +113,9 @@ struct AssetRecord {
     ast_supply: Option<i64>,
     ast_metadata_url_id: Option<String>,
     ast_slot_updated: i64,
+    ast_name: Option<String>,
+    ast_core_plugins: Option<String>,
+    ast_core_attributes: Option<String>,
 }
 
 impl Storage {
@@ -361,6 +397,9 @@ impl Storage {
                         ast_supply: index.supply,
                         ast_metadata_url_id: metadata_url.map(|(k, _)| k).map(Self::encode),
                         ast_slot_updated: index.slot_updated,
+                        ast_name: index.name,
+                        ast_core_plugins: to_pg_array(&index.core_plugins),
+                        ast_core_attributes: to_key_value_json(&index.core_attributes),
                     };
                     if let Err(e) = tx_assets_cloned.send(record).await {
                         error!("Error sending message: {:?}", e);
@@ -441,9 +480,7 @@ impl Storage {
         mut data_channel: tokio::sync::mpsc::Receiver<T>,
     ) -> Result<(), JoinError> {
         let buf_writer = BufWriter::with_capacity(ONE_G, file_and_path.0);
-        let mut writer = WriterBuilder::new()
-            .has_headers(false)
-            .from_writer(buf_writer);
+        let mut writer = csv_writer(buf_writer);
 
         loop {
             if !application_shutdown.is_empty() {
@@ -1,11 +1,28 @@
 use crate::column::TypedColumn;
-use crate::key_encoders::{decode_pubkey, encode_pubkey};
-use crate::Result;
+use crate::key_encoders::{decode_pubkey, decode_pubkey_u64, encode_pubkey, encode_pubkey_u64};
+use crate::{impl_merge_values, Result, Storage};
+use bincode::deserialize;
 use entities::enums::TokenMetadataEdition;
 use rocksdb::MergeOperands;
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use tracing::error;
 
+/// Secondary index of print editions, keyed by (master edition address, edition number)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EditionParentIdx {
+    pub edition: Pubkey,
+    pub write_version: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EditionMintMap {
+    // this is Edition acc pubkey
+    // it's PDA with next seeds ["metadata", metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s, mint_key, "edition"]
+    pub pubkey: Pubkey,
+    pub mint_key: Pubkey,
+}
+
 impl TypedColumn for TokenMetadataEdition {
     type KeyType = Pubkey;
     type ValueType = Self;
@@ -21,6 +38,82 @@ impl TypedColumn for TokenMetadataEdition {
     }
 }
 
+impl TypedColumn for EditionParentIdx {
+    type KeyType = (Pubkey, u64);
+    type ValueType = Self;
+    const NAME: &'static str = "EDITION_PARENT_IDX";
+
+    fn encode_key(key: (Pubkey, u64)) -> Vec<u8> {
+        encode_pubkey_u64(key.0, key.1)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_pubkey_u64(bytes)
+    }
+}
+
+impl_merge_values!(EditionParentIdx);
+
+impl TypedColumn for EditionMintMap {
+    type KeyType = Pubkey;
+    type ValueType = Self;
+    const NAME: &'static str = "EDITION_MINT_MAP";
+
+    fn encode_key(pubkey: Pubkey) -> Vec<u8> {
+        encode_pubkey(pubkey)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_pubkey(bytes)
+    }
+}
+
+impl Storage {
+    /// Returns (edition number, edition address) pairs of the prints of the given master edition
+    /// ordered by the edition number. `after` and `before` are exclusive edition numbers,
+    /// if only `before` is set the editions right before it are returned.
+    pub fn get_editions_by_parent(
+        &self,
+        parent: Pubkey,
+        after: Option<u64>,
+        before: Option<u64>,
+        page: Option<u64>,
+        limit: u64,
+    ) -> Vec<(u64, Pubkey)> {
+        let skip = page
+            .and_then(|page| page.saturating_sub(1).checked_mul(limit))
+            .unwrap_or_default() as usize;
+        let reverse = before.is_some() && after.is_none();
+        let iter = if reverse {
+            self.edition_parent_idx
+                .iter_reverse((parent, before.unwrap_or_default()))
+        } else {
+            self.edition_parent_idx
+                .iter((parent, after.unwrap_or_default()))
+        };
+        let mut editions = iter
+            .filter_map(std::result::Result::ok)
+            .filter_map(|(key, value)| {
+                let key = EditionParentIdx::decode_key(key.to_vec()).ok()?;
+                let value = deserialize::<EditionParentIdx>(&value).ok()?;
+                Some((key, value.edition))
+            })
+            .take_while(|((key_parent, edition_number), _)| {
+                *key_parent == parent
+                    && (reverse || before.map_or(true, |before| *edition_number < before))
+            })
+            .filter(|((_, edition_number), _)| {
+                Some(*edition_number) != after && Some(*edition_number) != before
+            })
+            .map(|((_, edition_number), edition)| (edition_number, edition))
+            .skip(skip)
+            .take(limit as usize)
+            .collect::<Vec<_>>();
+        editions.sort_by_key(|(edition_number, _)| *edition_number);
+        editions
+    }
+}
+
 pub fn merge_token_metadata_edition(
     _new_key: &[u8],
     existing_val: Option<&[u8]>,
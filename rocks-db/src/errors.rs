@@ -52,6 +52,7 @@ pub enum StorageError {
     NotFound(String),
     CannotServiceRequest,
     InvalidMigrationVersion(u64),
+    HistoryUnavailable(u64),
 }
 
 impl std::fmt::Display for StorageError {
@@ -78,6 +79,9 @@ impl From<StorageError> for interface::error::StorageError {
                 InterfaceStorageError::Common(format!("InvalidMigrationVersion: {v}"))
             }
             StorageError::NotFound(s) => InterfaceStorageError::NotFound(s),
+            StorageError::HistoryUnavailable(slot) => {
+                InterfaceStorageError::Common(format!("HistoryUnavailable: {slot}"))
+            }
         }
     }
 }
@@ -1,10 +1,12 @@
 use crate::{
-    cl_items::ClItemKey, column::TypedColumn, leaf_signatures::LeafSignature, Storage, DROP_ACTION,
-    FULL_ITERATION_ACTION, ITERATOR_TOP_ACTION, RAW_BLOCKS_CBOR_ENDPOINT, ROCKS_COMPONENT,
+    asset_signatures::AssetAccountSignatureIdx, cl_items::ClItemKey,
+    cl_items_history::ClItemsHistory, column::TypedColumn, leaf_signatures::LeafSignature, Storage,
+    DROP_ACTION, FULL_ITERATION_ACTION, ITERATOR_TOP_ACTION, RAW_BLOCKS_CBOR_ENDPOINT,
+    ROCKS_COMPONENT,
 };
 use async_trait::async_trait;
-use entities::models::{ClItem, ForkedItem, LeafSignatureAllData};
-use interface::fork_cleaner::{CompressedTreeChangesManager, ForkChecker};
+use entities::models::{ClItem, ForkedItem, LeafSignatureAllData, SlotAccountUpdate};
+use interface::fork_cleaner::{AccountChangesManager, CompressedTreeChangesManager, ForkChecker};
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use std::collections::HashSet;
 use tokio::sync::broadcast::Receiver;
@@ -91,6 +93,119 @@ impl CompressedTreeChangesManager for Storage {
             start_time,
         );
     }
+
+    fn cl_items_history_iter(&self) -> impl Iterator<Item = ClItem> {
+        self.cl_items_history
+            .iter_start()
+            .filter_map(Result::ok)
+            .flat_map(|(_, value)| bincode::deserialize::<ClItemsHistory>(value.as_ref()))
+            .flat_map(|history| history.items)
+            .map(|item| ClItem {
+                cli_node_idx: item.cli_node_idx,
+                cli_tree_key: item.cli_tree_key,
+                cli_leaf_idx: item.cli_leaf_idx,
+                cli_seq: item.cli_seq,
+                cli_level: item.cli_level,
+                cli_hash: item.cli_hash,
+                slot_updated: item.slot_updated,
+            })
+    }
+
+    async fn delete_cl_items_history(&self, items: Vec<ClItem>) {
+        let start_time = chrono::Utc::now();
+
+        if let Err(e) = self
+            .drop_forked_cl_items_history(
+                items
+                    .iter()
+                    .map(|item| {
+                        (
+                            ClItemKey::new(item.cli_node_idx, item.cli_tree_key),
+                            item.cli_seq,
+                            item.slot_updated,
+                        )
+                    })
+                    .collect(),
+            )
+            .await
+        {
+            error!("Cl items history delete: {}", e.to_string());
+        }
+
+        self.red_metrics.observe_request(
+            ROCKS_COMPONENT,
+            DROP_ACTION,
+            "cl_items_history",
+            start_time,
+        );
+    }
+}
+
+#[async_trait]
+impl AccountChangesManager for Storage {
+    fn account_updates_iter(&self) -> impl Iterator<Item = SlotAccountUpdate> {
+        self.account_update_slots_iter()
+    }
+
+    fn last_account_update_slot(&self) -> Option<u64> {
+        self.account_update_slots_last_slot()
+    }
+
+    async fn delete_account_updates(&self, keys: Vec<(u64, Pubkey)>) {
+        let start_time = chrono::Utc::now();
+
+        if let Err(e) = self.account_update_slots.delete_batch(keys).await {
+            error!("Account update slots delete: {}", e.to_string());
+        }
+
+        self.red_metrics.observe_request(
+            ROCKS_COMPONENT,
+            DROP_ACTION,
+            "account_update_slots",
+            start_time,
+        );
+    }
+
+    async fn delete_account_updates_before(&self, slot: u64) {
+        let start_time = chrono::Utc::now();
+
+        if let Err(e) = self
+            .account_update_slots
+            .delete_range((0, Pubkey::default()), (slot, Pubkey::default()))
+            .await
+        {
+            error!("Account update slots delete range: {}", e.to_string());
+        }
+
+        self.red_metrics.observe_request(
+            ROCKS_COMPONENT,
+            DROP_ACTION,
+            "account_update_slots",
+            start_time,
+        );
+    }
+
+    fn asset_account_signatures_iter(&self) -> impl Iterator<Item = (Pubkey, u64, Signature)> {
+        self.asset_account_signature_idx
+            .iter_start()
+            .filter_map(Result::ok)
+            .flat_map(|(key, _)| AssetAccountSignatureIdx::decode_key(key.to_vec()))
+    }
+
+    async fn delete_asset_account_signatures(&self, keys: Vec<(Pubkey, u64, Signature)>) {
+        let start_time = chrono::Utc::now();
+
+        if let Err(e) = self.asset_account_signature_idx.delete_batch(keys).await {
+            error!("Asset account signatures delete: {}", e.to_string());
+        }
+
+        self.red_metrics.observe_request(
+            ROCKS_COMPONENT,
+            DROP_ACTION,
+            "asset_account_signature_idx",
+            start_time,
+        );
+    }
 }
 
 #[async_trait]
@@ -1,5 +1,6 @@
 use entities::{enums::FailedBatchMintState, models::AssetSignatureKey};
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
 
 use crate::batch_mint::FailedBatchMintKey;
 use crate::{storage_traits::AssetUpdatedKey, Result};
@@ -95,6 +96,23 @@ pub fn decode_u64(bytes: Vec<u8>) -> Result<u64> {
     Ok(slot)
 }
 
+pub fn encode_u64x2(first: u64, second: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(std::mem::size_of::<u64>() * 2);
+    key.extend_from_slice(&first.to_be_bytes());
+    key.extend_from_slice(&second.to_be_bytes());
+    key
+}
+
+pub fn decode_u64x2(bytes: Vec<u8>) -> Result<(u64, u64)> {
+    let u64_size = std::mem::size_of::<u64>();
+    if bytes.len() != u64_size * 2 {
+        return Err(crate::StorageError::InvalidKeyLength);
+    }
+    let first = u64::from_be_bytes(bytes[..u64_size].try_into()?);
+    let second = u64::from_be_bytes(bytes[u64_size..].try_into()?);
+    Ok((first, second))
+}
+
 pub fn decode_asset_signature_key(bytes: Vec<u8>) -> Result<AssetSignatureKey> {
     let u64_size = std::mem::size_of::<u64>();
     let pubkey_size = std::mem::size_of::<Pubkey>();
@@ -121,6 +139,54 @@ pub fn encode_asset_signature_key(ask: AssetSignatureKey) -> Vec<u8> {
     key
 }
 
+pub fn encode_pubkey_u64_signature(key: (Pubkey, u64, Signature)) -> Vec<u8> {
+    let u64_size = std::mem::size_of::<u64>();
+    let pubkey_size = std::mem::size_of::<Pubkey>();
+    let signature_size = std::mem::size_of::<Signature>();
+    let (pubkey, slot, signature) = key;
+    let mut key = Vec::with_capacity(pubkey_size + u64_size + signature_size);
+    key.extend_from_slice(&pubkey.to_bytes());
+    key.extend_from_slice(&slot.to_be_bytes());
+    key.extend_from_slice(signature.as_ref());
+    key
+}
+
+pub fn decode_pubkey_u64_signature(bytes: Vec<u8>) -> Result<(Pubkey, u64, Signature)> {
+    let u64_size = std::mem::size_of::<u64>();
+    let pubkey_size = std::mem::size_of::<Pubkey>();
+    let signature_size = std::mem::size_of::<Signature>();
+    if bytes.len() != pubkey_size + u64_size + signature_size {
+        return Err(crate::StorageError::InvalidKeyLength);
+    }
+    let pubkey = Pubkey::try_from(&bytes[..pubkey_size])?;
+    let slot = u64::from_be_bytes(bytes[pubkey_size..pubkey_size + u64_size].try_into()?);
+    let signature = Signature::try_from(&bytes[pubkey_size + u64_size..])?;
+    Ok((pubkey, slot, signature))
+}
+
+pub fn encode_pubkey_u64_pubkey(key: (Pubkey, u64, Pubkey)) -> Vec<u8> {
+    let u64_size = std::mem::size_of::<u64>();
+    let pubkey_size = std::mem::size_of::<Pubkey>();
+    let (pk1, slot, pk2) = key;
+    let mut key = Vec::with_capacity(pubkey_size * 2 + u64_size);
+    key.extend_from_slice(&pk1.to_bytes());
+    key.extend_from_slice(&slot.to_be_bytes());
+    key.extend_from_slice(&pk2.to_bytes());
+    key
+}
+
+pub fn decode_pubkey_u64_pubkey(bytes: Vec<u8>) -> Result<(Pubkey, u64, Pubkey)> {
+    let u64_size = std::mem::size_of::<u64>();
+    let pubkey_size = std::mem::size_of::<Pubkey>();
+    if bytes.len() != pubkey_size * 2 + u64_size {
+        return Err(crate::StorageError::InvalidKeyLength);
+    }
+    let pk1 = Pubkey::try_from(&bytes[..pubkey_size])?;
+    let slot = u64::from_be_bytes(bytes[pubkey_size..pubkey_size + u64_size].try_into()?);
+    let pk2 = Pubkey::try_from(&bytes[pubkey_size + u64_size..])?;
+    Ok((pk1, slot, pk2))
+}
+
 pub fn decode_pubkeyx2(bytes: Vec<u8>) -> Result<(Pubkey, Pubkey)> {
     let pubkey_size = std::mem::size_of::<Pubkey>();
     if bytes.len() != pubkey_size * 2 {
@@ -207,6 +273,16 @@ mod tests {
         assert_eq!(decoded, pubkey);
     }
 
+    #[test]
+    fn test_encode_decode_pubkey_u64_pubkey() {
+        let key = (Pubkey::new_unique(), 12345u64, Pubkey::new_unique());
+
+        let encoded = encode_pubkey_u64_pubkey(key);
+        let decoded = decode_pubkey_u64_pubkey(encoded).unwrap();
+
+        assert_eq!(decoded, key);
+    }
+
     #[test]
     fn test_decode_u64_pubkey_invalid_data() {
         let invalid_data = vec![1, 2, 3]; // An intentionally invalid byte sequence
@@ -275,6 +351,18 @@ mod tests {
         assert_eq!(decoded.seq, seq);
     }
 
+    #[test]
+    fn test_encode_decode_pubkey_u64_signature() {
+        let pubkey = Pubkey::new_unique();
+        let slot = 242u64;
+        let signature = Signature::new_unique();
+
+        let encoded = encode_pubkey_u64_signature((pubkey, slot, signature));
+        let decoded = decode_pubkey_u64_signature(encoded).unwrap();
+
+        assert_eq!(decoded, (pubkey, slot, signature));
+    }
+
     #[test]
     fn test_encode_decode_failed_batch_mint_key() {
         let key = FailedBatchMintKey {
@@ -2,7 +2,7 @@ use asset_previews::{AssetPreviews, UrlToDownload};
 use entities::schedule::ScheduledJob;
 use inflector::Inflector;
 use leaf_signatures::LeafSignature;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
 use std::{marker::PhantomData, sync::Arc};
 
 use asset::{
@@ -26,7 +26,14 @@ use metrics_utils::red::RequestErrorDurationMetrics;
 use tokio::sync::Mutex;
 use tokio::task::JoinSet;
 
+use crate::account_update_slots::AccountUpdateSlot;
+use crate::asset_history::AssetHistory;
+use crate::asset_previous_versions::{AssetPreviousVersions, OwnerChangeIdx};
+use crate::asset_signatures::AssetAccountSignatureIdx;
 use crate::batch_mint::BatchMintWithStaker;
+use crate::cl_items_history::ClItemsHistory;
+use crate::dead_letters::DeadLetter;
+use crate::editions::{EditionMintMap, EditionParentIdx};
 use crate::errors::StorageError;
 use crate::inscriptions::{Inscription, InscriptionData};
 use crate::migrations::clean_update_authorities::CleanCollectionAuthoritiesMigration;
@@ -41,11 +48,16 @@ use crate::migrations::spl2022::{
 use crate::parameters::ParameterColumn;
 use crate::token_accounts::{TokenAccountMintOwnerIdx, TokenAccountOwnerIdx};
 use crate::token_prices::TokenPrice;
-use crate::tree_seq::{TreeSeqIdx, TreesGaps};
+use crate::tree_info::TreeInfo;
+use crate::tree_seq::{TreeGapRepair, TreeSeqIdx, TreesGaps};
+use crate::webhooks::{AssetEvent, WebhookDelivery, WebhookDeliveryDueIdx};
 
+pub mod account_update_slots;
 pub mod asset;
 mod asset_client;
+pub mod asset_history;
 pub mod asset_previews;
+pub mod asset_previous_versions;
 pub mod asset_signatures;
 pub mod asset_streaming_client;
 pub mod backup_service;
@@ -54,7 +66,9 @@ pub mod batch_mint;
 pub mod batch_savers;
 pub mod bubblegum_slots;
 pub mod cl_items;
+pub mod cl_items_history;
 pub mod column;
+pub mod dead_letters;
 pub mod dump_client;
 pub mod editions;
 pub mod errors;
@@ -78,7 +92,9 @@ pub mod token_accounts;
 pub mod token_prices;
 pub mod transaction;
 pub mod transaction_client;
+pub mod tree_info;
 pub mod tree_seq;
+pub mod webhooks;
 
 pub type Result<T> = std::result::Result<T, StorageError>;
 
@@ -90,6 +106,18 @@ const BATCH_ITERATION_ACTION: &str = "batch_iteration";
 const BATCH_GET_ACTION: &str = "batch_get";
 const ITERATOR_TOP_ACTION: &str = "iterator_top";
 
+/// Returns the id following the last one, nanoseconds keep the ids ordered by time
+/// across restarts while the counter keeps them unique
+pub(crate) fn next_time_ordered_id(last_id: &AtomicU64) -> u64 {
+    let now = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64;
+    last_id
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+            Some(now.max(last + 1))
+        })
+        .map(|last| now.max(last + 1))
+        .unwrap_or(now)
+}
+
 pub struct Storage {
     pub asset_static_data: Column<AssetStaticDetails>,
     pub asset_static_data_deprecated: Column<AssetStaticDetailsDeprecated>,
@@ -104,7 +132,11 @@ pub struct Storage {
     pub asset_collection_data: Column<asset::AssetCollection>,
     pub asset_collection_data_deprecated: Column<AssetCollectionDeprecated>,
     pub asset_offchain_data: Column<OffChainData>,
+    pub asset_history: Column<AssetHistory>,
+    pub asset_previous_versions: Column<AssetPreviousVersions>,
+    pub owner_change_idx: Column<OwnerChangeIdx>,
     pub cl_items: Column<cl_items::ClItem>,
+    pub cl_items_history: Column<ClItemsHistory>,
     pub cl_leafs: Column<cl_items::ClLeaf>,
     pub bubblegum_slots: Column<bubblegum_slots::BubblegumSlots>,
     pub ingestable_slots: Column<bubblegum_slots::IngestableSlots>,
@@ -115,11 +147,16 @@ pub struct Storage {
     pub slot_asset_idx: Column<SlotAssetIdx>,
     pub tree_seq_idx: Column<TreeSeqIdx>,
     pub trees_gaps: Column<TreesGaps>,
+    pub tree_gap_repairs: Column<TreeGapRepair>,
+    pub tree_info: Column<TreeInfo>,
     pub token_metadata_edition_cbor: Column<TokenMetadataEdition>,
+    pub edition_parent_idx: Column<EditionParentIdx>,
+    pub edition_mint_map: Column<EditionMintMap>,
     pub token_accounts: Column<TokenAccount>,
     pub token_account_owner_idx: Column<TokenAccountOwnerIdx>,
     pub token_account_mint_owner_idx: Column<TokenAccountMintOwnerIdx>,
     pub asset_signature: Column<AssetSignature>,
+    pub asset_account_signature_idx: Column<AssetAccountSignatureIdx>,
     pub batch_mint_to_verify: Column<BatchMintToVerify>,
     pub failed_batch_mints: Column<FailedBatchMint>,
     pub batch_mints: Column<BatchMintWithStaker>,
@@ -132,7 +169,21 @@ pub struct Storage {
     pub inscription_data: Column<InscriptionData>,
     pub leaf_signature: Column<LeafSignature>,
     pub spl_mints: Column<SplMint>,
+    pub dead_letters: Column<DeadLetter>,
+    pub account_update_slots: Column<AccountUpdateSlot>,
+    pub asset_events: Column<AssetEvent>,
+    pub webhook_deliveries: Column<WebhookDelivery>,
+    pub webhook_delivery_due_idx: Column<WebhookDeliveryDueIdx>,
     assets_update_last_seq: AtomicU64,
+    dead_letters_last_id: AtomicU64,
+    asset_events_last_id: AtomicU64,
+    webhook_deliveries_last_id: AtomicU64,
+    asset_history_enabled: AtomicBool,
+    account_update_slots_enabled: AtomicBool,
+    cl_items_history_enabled: AtomicBool,
+    asset_events_enabled: AtomicBool,
+    // unix timestamp of the last successful catch up of the secondary instance, 0 if there was none
+    last_catch_up_at: AtomicI64,
     join_set: Arc<Mutex<JoinSet<core::result::Result<(), tokio::task::JoinError>>>>,
     red_metrics: Arc<RequestErrorDurationMetrics>,
 }
@@ -155,8 +206,12 @@ impl Storage {
         let asset_collection_data = Self::column(db.clone(), red_metrics.clone());
         let asset_collection_data_deprecated = Self::column(db.clone(), red_metrics.clone());
         let asset_offchain_data = Self::column(db.clone(), red_metrics.clone());
+        let asset_history = Self::column(db.clone(), red_metrics.clone());
+        let asset_previous_versions = Self::column(db.clone(), red_metrics.clone());
+        let owner_change_idx = Self::column(db.clone(), red_metrics.clone());
 
         let cl_items = Self::column(db.clone(), red_metrics.clone());
+        let cl_items_history = Self::column(db.clone(), red_metrics.clone());
         let cl_leafs = Self::column(db.clone(), red_metrics.clone());
 
         let bubblegum_slots = Self::column(db.clone(), red_metrics.clone());
@@ -167,9 +222,14 @@ impl Storage {
         let slot_asset_idx = Self::column(db.clone(), red_metrics.clone());
         let tree_seq_idx = Self::column(db.clone(), red_metrics.clone());
         let trees_gaps = Self::column(db.clone(), red_metrics.clone());
+        let tree_gap_repairs = Self::column(db.clone(), red_metrics.clone());
+        let tree_info = Self::column(db.clone(), red_metrics.clone());
         let token_metadata_edition_cbor = Self::column(db.clone(), red_metrics.clone());
+        let edition_parent_idx = Self::column(db.clone(), red_metrics.clone());
+        let edition_mint_map = Self::column(db.clone(), red_metrics.clone());
         let asset_static_data_deprecated = Self::column(db.clone(), red_metrics.clone());
         let asset_signature = Self::column(db.clone(), red_metrics.clone());
+        let asset_account_signature_idx = Self::column(db.clone(), red_metrics.clone());
         let token_accounts = Self::column(db.clone(), red_metrics.clone());
         let token_account_owner_idx = Self::column(db.clone(), red_metrics.clone());
         let token_account_mint_owner_idx = Self::column(db.clone(), red_metrics.clone());
@@ -185,6 +245,11 @@ impl Storage {
         let inscription_data = Self::column(db.clone(), red_metrics.clone());
         let leaf_signature = Self::column(db.clone(), red_metrics.clone());
         let spl_mints = Self::column(db.clone(), red_metrics.clone());
+        let dead_letters = Self::column(db.clone(), red_metrics.clone());
+        let account_update_slots = Self::column(db.clone(), red_metrics.clone());
+        let asset_events = Self::column(db.clone(), red_metrics.clone());
+        let webhook_deliveries = Self::column(db.clone(), red_metrics.clone());
+        let webhook_delivery_due_idx = Self::column(db.clone(), red_metrics.clone());
 
         Self {
             asset_static_data,
@@ -199,7 +264,11 @@ impl Storage {
             asset_collection_data,
             asset_collection_data_deprecated,
             asset_offchain_data,
+            asset_history,
+            asset_previous_versions,
+            owner_change_idx,
             cl_items,
+            cl_items_history,
             cl_leafs,
             bubblegum_slots,
             ingestable_slots,
@@ -209,15 +278,25 @@ impl Storage {
             assets_update_idx,
             slot_asset_idx,
             assets_update_last_seq: AtomicU64::new(0),
+            asset_history_enabled: AtomicBool::new(false),
+            account_update_slots_enabled: AtomicBool::new(false),
+            cl_items_history_enabled: AtomicBool::new(false),
+            asset_events_enabled: AtomicBool::new(false),
+            last_catch_up_at: AtomicI64::new(0),
             join_set,
             tree_seq_idx,
             trees_gaps,
+            tree_gap_repairs,
+            tree_info,
             token_metadata_edition_cbor,
+            edition_parent_idx,
+            edition_mint_map,
             token_accounts,
             token_account_owner_idx,
             asset_static_data_deprecated,
             red_metrics,
             asset_signature,
+            asset_account_signature_idx,
             token_account_mint_owner_idx,
             batch_mint_to_verify,
             failed_batch_mints,
@@ -231,6 +310,14 @@ impl Storage {
             inscription_data,
             leaf_signature,
             spl_mints,
+            dead_letters,
+            dead_letters_last_id: AtomicU64::new(0),
+            account_update_slots,
+            asset_events,
+            asset_events_last_id: AtomicU64::new(0),
+            webhook_deliveries,
+            webhook_delivery_due_idx,
+            webhook_deliveries_last_id: AtomicU64::new(0),
         }
     }
 
@@ -266,9 +353,27 @@ impl Storage {
         Ok(Self::new(db, join_set, red_metrics))
     }
 
+    /// Applies the latest changes of the primary instance to the secondary one
+    pub fn catch_up_with_primary(&self) -> Result<()> {
+        self.db.try_catch_up_with_primary()?;
+        self.last_catch_up_at
+            .store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn last_catch_up_at(&self) -> Option<i64> {
+        match self.last_catch_up_at.load(Ordering::Relaxed) {
+            0 => None,
+            timestamp => Some(timestamp),
+        }
+    }
+
     fn create_cf_descriptors(migration_state: &MigrationState) -> Vec<ColumnFamilyDescriptor> {
         vec![
             Self::new_cf_descriptor::<OffChainData>(migration_state),
+            Self::new_cf_descriptor::<AssetHistory>(migration_state),
+            Self::new_cf_descriptor::<AssetPreviousVersions>(migration_state),
+            Self::new_cf_descriptor::<OwnerChangeIdx>(migration_state),
             Self::new_cf_descriptor::<AssetStaticDetails>(migration_state),
             Self::new_cf_descriptor::<AssetDynamicDetails>(migration_state),
             Self::new_cf_descriptor::<AssetDynamicDetailsDeprecated>(migration_state),
@@ -280,6 +385,7 @@ impl Storage {
             Self::new_cf_descriptor::<asset::AssetCollection>(migration_state),
             Self::new_cf_descriptor::<AssetCollectionDeprecated>(migration_state),
             Self::new_cf_descriptor::<cl_items::ClItem>(migration_state),
+            Self::new_cf_descriptor::<ClItemsHistory>(migration_state),
             Self::new_cf_descriptor::<cl_items::ClLeaf>(migration_state),
             Self::new_cf_descriptor::<bubblegum_slots::BubblegumSlots>(migration_state),
             Self::new_cf_descriptor::<asset::AssetsUpdateIdx>(migration_state),
@@ -292,9 +398,14 @@ impl Storage {
             Self::new_cf_descriptor::<AssetOwner>(migration_state),
             Self::new_cf_descriptor::<TreeSeqIdx>(migration_state),
             Self::new_cf_descriptor::<TreesGaps>(migration_state),
+            Self::new_cf_descriptor::<TreeGapRepair>(migration_state),
+            Self::new_cf_descriptor::<TreeInfo>(migration_state),
             Self::new_cf_descriptor::<TokenMetadataEdition>(migration_state),
+            Self::new_cf_descriptor::<EditionParentIdx>(migration_state),
+            Self::new_cf_descriptor::<EditionMintMap>(migration_state),
             Self::new_cf_descriptor::<AssetStaticDetailsDeprecated>(migration_state),
             Self::new_cf_descriptor::<AssetSignature>(migration_state),
+            Self::new_cf_descriptor::<AssetAccountSignatureIdx>(migration_state),
             Self::new_cf_descriptor::<TokenAccount>(migration_state),
             Self::new_cf_descriptor::<TokenAccountOwnerIdx>(migration_state),
             Self::new_cf_descriptor::<TokenAccountMintOwnerIdx>(migration_state),
@@ -310,6 +421,11 @@ impl Storage {
             Self::new_cf_descriptor::<InscriptionData>(migration_state),
             Self::new_cf_descriptor::<LeafSignature>(migration_state),
             Self::new_cf_descriptor::<SplMint>(migration_state),
+            Self::new_cf_descriptor::<DeadLetter>(migration_state),
+            Self::new_cf_descriptor::<AccountUpdateSlot>(migration_state),
+            Self::new_cf_descriptor::<AssetEvent>(migration_state),
+            Self::new_cf_descriptor::<WebhookDelivery>(migration_state),
+            Self::new_cf_descriptor::<WebhookDeliveryDueIdx>(migration_state),
         ]
     }
 
@@ -468,6 +584,12 @@ impl Storage {
                     cl_items::ClItem::merge_cl_items,
                 );
             }
+            ClItemsHistory::NAME => {
+                cf_options.set_merge_operator_associative(
+                    "merge_fn_cl_items_history",
+                    ClItemsHistory::merge_cl_items_history,
+                );
+            }
             ParameterColumn::<u64>::NAME => {
                 cf_options.set_merge_operator_associative(
                     "merge_fn_top_parameter_column",
@@ -492,6 +614,24 @@ impl Storage {
                     asset::AssetStaticDetails::merge_keep_existing,
                 );
             }
+            AssetHistory::NAME => {
+                cf_options.set_merge_operator_associative(
+                    "merge_fn_asset_history",
+                    AssetHistory::merge_asset_history,
+                );
+            }
+            AssetPreviousVersions::NAME => {
+                cf_options.set_merge_operator_associative(
+                    "merge_fn_asset_previous_versions",
+                    AssetPreviousVersions::merge_previous_versions,
+                );
+            }
+            OwnerChangeIdx::NAME => {
+                cf_options.set_merge_operator_associative(
+                    "merge_fn_owner_change_idx_keep_existing",
+                    asset::AssetStaticDetails::merge_keep_existing,
+                );
+            }
             cl_items::ClLeaf::NAME => {
                 cf_options.set_merge_operator_associative(
                     "merge_fn_cl_leaf_keep_existing",
@@ -546,12 +686,30 @@ impl Storage {
                     asset::AssetStaticDetails::merge_keep_existing,
                 );
             }
+            TreeInfo::NAME => {
+                cf_options.set_merge_operator_associative(
+                    "merge_fn_tree_info",
+                    TreeInfo::merge_tree_info,
+                );
+            }
             TokenMetadataEdition::NAME => {
                 cf_options.set_merge_operator_associative(
                     "merge_fn_token_metadata_edition_keep_existing",
                     crate::editions::merge_token_metadata_edition,
                 );
             }
+            EditionParentIdx::NAME => {
+                cf_options.set_merge_operator_associative(
+                    "merge_fn_edition_parent_idx",
+                    EditionParentIdx::merge_values,
+                );
+            }
+            EditionMintMap::NAME => {
+                cf_options.set_merge_operator_associative(
+                    "merge_fn_edition_mint_map_keep_existing",
+                    asset::AssetStaticDetails::merge_keep_existing,
+                );
+            }
             AssetStaticDetailsDeprecated::NAME => {
                 cf_options.set_merge_operator_associative(
                     "merge_fn_asset_static_deprecated_keep_existing",
@@ -564,6 +722,12 @@ impl Storage {
                     asset::AssetStaticDetails::merge_keep_existing,
                 );
             }
+            AssetAccountSignatureIdx::NAME => {
+                cf_options.set_merge_operator_associative(
+                    "merge_fn_asset_account_signature_idx_keep_existing",
+                    asset::AssetStaticDetails::merge_keep_existing,
+                );
+            }
             TokenAccount::NAME => {
                 let mf = match migration_state {
                     MigrationState::Version(version) => match *version {
@@ -0,0 +1,38 @@
+use crate::dead_letters::{DeadLetter, DeadLetterStream};
+use crate::migrator::{RocksMigration, SerializationType};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeadLetterWithoutWriteVersion {
+    pub stream: DeadLetterStream,
+    pub data: Vec<u8>,
+    pub map_flatbuffer: bool,
+    pub pubkey: Option<Pubkey>,
+    pub error: String,
+    pub failed_at: i64,
+}
+
+impl From<DeadLetterWithoutWriteVersion> for DeadLetter {
+    fn from(value: DeadLetterWithoutWriteVersion) -> Self {
+        Self {
+            stream: value.stream,
+            data: value.data,
+            map_flatbuffer: value.map_flatbuffer,
+            pubkey: value.pubkey,
+            // the letters stored before the write version was kept are redriven with 0,
+            // as they used to be
+            write_version: 0,
+            error: value.error,
+            failed_at: value.failed_at,
+        }
+    }
+}
+
+pub struct DeadLetterWriteVersionMigration;
+impl RocksMigration for DeadLetterWriteVersionMigration {
+    const VERSION: u64 = 6;
+    const SERIALIZATION_TYPE: SerializationType = SerializationType::Bincode;
+    type NewDataType = DeadLetter;
+    type OldDataType = DeadLetterWithoutWriteVersion;
+}
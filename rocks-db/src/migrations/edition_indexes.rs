@@ -0,0 +1,96 @@
+use crate::editions::{EditionMintMap, EditionParentIdx};
+use crate::migrator::{RocksBackfill, BATCH_SIZE};
+use crate::{AssetStaticDetails, Result, Storage};
+use async_trait::async_trait;
+use bincode::deserialize;
+use entities::enums::TokenMetadataEdition;
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+// number of the assets the editions are looked up for at once
+const EDITIONS_LOOKUP_BATCH_SIZE: usize = 10_000;
+
+/// Fills the print edition indexes with the prints stored before the indexes were introduced.
+/// Only the assets known to be editions are indexed, the burnt ones are skipped.
+pub struct EditionIndexesBackfill;
+
+#[async_trait]
+impl RocksBackfill for EditionIndexesBackfill {
+    const VERSION: u64 = 5;
+
+    async fn backfill(storage: &Storage) -> Result<()> {
+        let mut batch = rocksdb::WriteBatch::default();
+        let mut assets = Vec::new();
+        for (_, value) in storage
+            .asset_static_data
+            .iter_start()
+            .filter_map(std::result::Result::ok)
+        {
+            let asset_static = match deserialize::<AssetStaticDetails>(&value) {
+                Ok(asset_static) => asset_static,
+                Err(e) => {
+                    error!("Edition indexes backfill deserialize asset: {}", e);
+                    continue;
+                }
+            };
+            if let Some(edition_address) = asset_static.edition_address {
+                assets.push((asset_static.pubkey, edition_address));
+            }
+            if assets.len() >= EDITIONS_LOOKUP_BATCH_SIZE {
+                index_prints(storage, &mut batch, std::mem::take(&mut assets)).await?;
+            }
+            if batch.len() >= BATCH_SIZE {
+                storage.db.write(std::mem::take(&mut batch))?;
+            }
+        }
+        index_prints(storage, &mut batch, assets).await?;
+        storage.db.write(batch)?;
+
+        Ok(())
+    }
+}
+
+async fn index_prints(
+    storage: &Storage,
+    batch: &mut rocksdb::WriteBatch,
+    assets: Vec<(Pubkey, Pubkey)>,
+) -> Result<()> {
+    let (mints, edition_addresses): (Vec<_>, Vec<_>) = assets.into_iter().unzip();
+    let editions = storage
+        .token_metadata_edition_cbor
+        .batch_get_cbor(edition_addresses.clone())
+        .await?;
+    let dynamic_details = storage.asset_dynamic_data.batch_get(mints.clone()).await?;
+
+    for (((mint, edition_address), edition), dynamic_details) in mints
+        .into_iter()
+        .zip(edition_addresses)
+        .zip(editions)
+        .zip(dynamic_details)
+    {
+        let Some(TokenMetadataEdition::EditionV1(edition)) = edition else {
+            continue;
+        };
+        if dynamic_details.is_some_and(|dynamic_details| dynamic_details.is_burnt.value) {
+            continue;
+        }
+        storage.edition_parent_idx.merge_with_batch(
+            batch,
+            (edition.parent, edition.edition),
+            &EditionParentIdx {
+                edition: edition_address,
+                write_version: edition.write_version,
+            },
+        )?;
+        storage.edition_mint_map.merge_with_batch(
+            batch,
+            edition_address,
+            &EditionMintMap {
+                pubkey: edition_address,
+                mint_key: mint,
+            },
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
 pub mod clean_update_authorities;
 pub mod collection_authority;
+pub mod dead_letters;
+pub mod edition_indexes;
 pub mod external_plugins;
 pub mod spl2022;
@@ -3,6 +3,7 @@ use crate::errors::StorageError;
 use crate::key_encoders::{decode_u64, encode_u64};
 use crate::Result;
 use crate::Storage;
+use async_trait::async_trait;
 use bincode::deserialize;
 use interface::migration_version_manager::PrimaryStorageMigrationVersionManager;
 use metrics_utils::red::RequestErrorDurationMetrics;
@@ -40,6 +41,13 @@ pub trait RocksMigration {
         + Into<<Self::NewDataType as TypedColumn>::ValueType>;
 }
 
+/// One-off fill of a new column from the data stored before the column was introduced
+#[async_trait]
+pub trait RocksBackfill {
+    const VERSION: u64;
+    async fn backfill(storage: &Storage) -> Result<()>;
+}
+
 impl Storage {
     pub async fn apply_all_migrations(
         db_path: &str,
@@ -72,6 +80,12 @@ impl Storage {
                 crate::migrations::spl2022::DynamicDataToken2022MintExtentionsMigration,
             )
             .await?;
+        migration_applier
+            .apply_backfill(crate::migrations::edition_indexes::EditionIndexesBackfill)
+            .await?;
+        migration_applier
+            .apply_migration(crate::migrations::dead_letters::DeadLetterWriteVersionMigration)
+            .await?;
         Ok(())
     }
 }
@@ -165,6 +179,22 @@ impl<'a> MigrationApplier<'a> {
         Ok(())
     }
 
+    async fn apply_backfill<B: RocksBackfill>(&self, _: B) -> Result<()> {
+        if self.applied_migration_versions.contains(&B::VERSION) {
+            return Ok(());
+        }
+        info!("Start executing backfill Version {}", B::VERSION);
+        let storage = Self::open_migration_storage(self.db_path, B::VERSION)?;
+        B::backfill(&storage).await?;
+        storage
+            .migration_version
+            .put_async(B::VERSION, MigrationVersions {})
+            .await?;
+        info!("Finish backfill Version {}", B::VERSION);
+
+        Ok(())
+    }
+
     fn open_migration_storage(db_path: &str, version: u64) -> Result<Storage> {
         Storage::open(
             db_path,
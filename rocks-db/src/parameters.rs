@@ -8,6 +8,15 @@ pub enum Parameter {
     LastBackfilledSlot,
     LastFetchedSlot,
     TopSeenSlot,
+    // latest slots at the confirmed and finalized commitment, tracked by the ingester for the API
+    LastConfirmedSlot,
+    LastFinalizedSlot,
+    // the asset history is complete from this slot on, the older versions are folded by the cleaner
+    AssetHistoryStartSlot,
+    // the versions replaced by the updates are kept from this slot on, see AssetPreviousVersions
+    PreviousVersionsStartSlot,
+    // the changelog history of the tree nodes is recorded from this slot on, see ClItemsHistory
+    ClItemsHistoryStartSlot,
 }
 
 pub struct ParameterColumn<T> {
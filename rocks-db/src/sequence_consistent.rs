@@ -1,4 +1,4 @@
-use crate::tree_seq::{TreeSeqIdx, TreesGaps};
+use crate::tree_seq::{GapRepairState, TreeGapRepair, TreeSeqIdx, TreesGaps};
 use crate::{key_encoders, Storage};
 use async_trait::async_trait;
 use entities::models::TreeState;
@@ -32,7 +32,10 @@ impl SequenceConsistentManager for Storage {
         let result = if gap_found {
             self.trees_gaps.put_async(tree, TreesGaps {}).await
         } else {
-            self.trees_gaps.delete(tree).map_err(Into::into)
+            self.trees_gaps
+                .delete(tree)
+                .and_then(|_| self.mark_tree_gaps_repaired(tree))
+                .map_err(Into::into)
         };
         if let Err(e) = result {
             error!(
@@ -44,6 +47,69 @@ impl SequenceConsistentManager for Storage {
     }
 }
 
+impl Storage {
+    /// Returns the pairs of the neighbouring tree states with missing sequences in between,
+    /// the states after the given slot are not checked
+    pub fn tree_sequence_gaps(&self, tree: Pubkey, last_slot: u64) -> Vec<(TreeState, TreeState)> {
+        let mut gaps = Vec::new();
+        let mut prev_state: Option<TreeState> = None;
+        for (key, value) in self.tree_seq_idx.iter((tree, 0)).filter_map(Result::ok) {
+            let Ok((key_tree, seq)) = key_encoders::decode_pubkey_u64(key.to_vec()) else {
+                continue;
+            };
+            if key_tree != tree {
+                break;
+            }
+            let Ok(tree_seq_idx) = bincode::deserialize::<TreeSeqIdx>(value.as_ref()) else {
+                continue;
+            };
+            if tree_seq_idx.slot > last_slot {
+                continue;
+            }
+            let current_state = TreeState {
+                tree,
+                seq,
+                slot: tree_seq_idx.slot,
+            };
+            if let Some(prev_state) = prev_state.take() {
+                if current_state.seq != prev_state.seq + 1 {
+                    gaps.push((prev_state, current_state.clone()));
+                }
+            }
+            prev_state = Some(current_state);
+        }
+
+        gaps
+    }
+
+    pub fn get_tree_gap_repairs(
+        &self,
+        after: Option<Pubkey>,
+        limit: usize,
+    ) -> Vec<(Pubkey, TreeGapRepair)> {
+        match after {
+            Some(after) => self.tree_gap_repairs.get_after(after, limit),
+            None => self.tree_gap_repairs.get_from_start(limit),
+        }
+    }
+
+    // the gaps could be filled by the regular ingestion or a manual backfill as well
+    fn mark_tree_gaps_repaired(&self, tree: Pubkey) -> crate::Result<()> {
+        match self.tree_gap_repairs.get(tree)? {
+            Some(repair) if repair.state != GapRepairState::Repaired => self.tree_gap_repairs.put(
+                tree,
+                TreeGapRepair {
+                    state: GapRepairState::Repaired,
+                    gaps: 0,
+                    updated_at: chrono::Utc::now().timestamp(),
+                    ..repair
+                },
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
 #[async_trait]
 impl LastProcessedSlotGetter for Storage {
     async fn get_last_ingested_slot(&self) -> Result<Option<u64>, interface::error::StorageError> {
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use entities::enums::AssetEventType;
 use entities::models::OffChainData;
 use entities::models::{BatchMintToVerify, BufferedTransaction, SignatureWithSlot};
 use interface::error::StorageError;
@@ -8,6 +9,7 @@ use spl_account_compression::state::PathNode;
 
 use crate::{
     asset::{AssetCollection, AssetLeaf},
+    tree_info::TreeInfo,
     AssetAuthority, AssetDynamicDetails, AssetOwner, AssetStaticDetails,
 };
 
@@ -89,6 +91,9 @@ pub struct InstructionResult {
     pub update: Option<AssetUpdateEvent>,
     pub decompressed: Option<AssetUpdate<AssetDynamicDetails>>,
     pub tree_update: Option<TreeUpdate>,
+    pub tree_info: Option<TreeInfo>,
+    // change of the asset the webhooks are notified about
+    pub event_type: Option<AssetEventType>,
 }
 
 impl From<AssetUpdateEvent> for InstructionResult {
@@ -100,6 +105,15 @@ impl From<AssetUpdateEvent> for InstructionResult {
     }
 }
 
+impl From<TreeInfo> for InstructionResult {
+    fn from(tree_info: TreeInfo) -> Self {
+        Self {
+            tree_info: Some(tree_info),
+            ..Default::default()
+        }
+    }
+}
+
 impl From<AssetUpdate<AssetDynamicDetails>> for InstructionResult {
     fn from(decompressed: AssetUpdate<AssetDynamicDetails>) -> Self {
         Self {
@@ -113,4 +127,7 @@ impl From<AssetUpdate<AssetDynamicDetails>> for InstructionResult {
 pub struct TransactionResult {
     pub instruction_results: Vec<InstructionResult>,
     pub transaction_signature: Option<(Pubkey, SignatureWithSlot)>,
+    /// Accounts passed to the token metadata, core and token programs along with the program name,
+    /// used to record the signature history of non-compressed assets
+    pub asset_accounts: Vec<(Pubkey, String)>,
 }
@@ -3,11 +3,13 @@ use entities::models::SignatureWithSlot;
 use interface::error::StorageError;
 use solana_sdk::pubkey::Pubkey;
 
+use crate::asset_previous_versions::PendingVersions;
 use crate::parameters::Parameter;
 use crate::{
     parameters,
     signature_client::SignatureIdx,
     transaction::{InstructionResult, TransactionResult, TransactionResultPersister},
+    webhooks::AssetEvent,
     Storage,
 };
 
@@ -15,8 +17,9 @@ use crate::{
 impl TransactionResultPersister for Storage {
     async fn store_block(&self, slot: u64, txs: &[TransactionResult]) -> Result<(), StorageError> {
         let mut batch = rocksdb::WriteBatchWithTransaction::<false>::default();
+        let mut pending = PendingVersions::default();
         for tx in txs {
-            self.store_transaction_result_with_batch(&mut batch, tx, false)
+            self.store_transaction_result_with_batch(&mut batch, &mut pending, tx, false)
                 .await?;
         }
         self.merge_top_parameter_with_batch(&mut batch, Parameter::LastBackfilledSlot, slot)
@@ -35,8 +38,13 @@ impl Storage {
         with_signatures: bool,
     ) -> Result<(), StorageError> {
         let mut batch = rocksdb::WriteBatch::default();
-        self.store_transaction_result_with_batch(&mut batch, tx, with_signatures)
-            .await?;
+        self.store_transaction_result_with_batch(
+            &mut batch,
+            &mut PendingVersions::default(),
+            tx,
+            with_signatures,
+        )
+        .await?;
         self.write_batch(batch)
             .await
             .map_err(|e| StorageError::Common(e.to_string()))?;
@@ -46,12 +54,13 @@ impl Storage {
     async fn store_transaction_result_with_batch(
         &self,
         batch: &mut rocksdb::WriteBatch,
+        pending: &mut PendingVersions,
         tx: &TransactionResult,
         with_signatures: bool,
     ) -> Result<(), StorageError> {
         let mut skip_signatures = !with_signatures;
         for ix in tx.instruction_results.iter() {
-            if let Err(e) = self.store_instruction_result_with_batch(batch, ix) {
+            if let Err(e) = self.store_instruction_result_with_batch(batch, pending, ix) {
                 skip_signatures = true;
                 tracing::error!("Failed to store instruction result: {}", e);
             }
@@ -65,6 +74,12 @@ impl Storage {
             }
             if !skip_signatures {
                 self.persist_signature_with_batch(batch, pk, signature)?;
+                if let Err(e) = self
+                    .store_asset_account_signatures_with_batch(batch, &tx.asset_accounts, signature)
+                    .await
+                {
+                    tracing::error!("Failed to store asset account signatures: {}", e);
+                }
             }
         }
         Ok(())
@@ -73,6 +88,7 @@ impl Storage {
     fn store_instruction_result_with_batch(
         &self,
         batch: &mut rocksdb::WriteBatch,
+        pending: &mut PendingVersions,
         ix: &InstructionResult,
     ) -> Result<(), StorageError> {
         if let Some(ref update) = ix.update {
@@ -83,6 +99,7 @@ impl Storage {
                     dyn_data.slot,
                     &dyn_data.leaf,
                     &dyn_data.dynamic_data,
+                    pending,
                 ) {
                     tracing::error!("Failed to save tx data and asset updated: {}", e);
                 }
@@ -97,6 +114,11 @@ impl Storage {
                 }
             }
             if let Some(ref owner_update) = update.owner_update {
+                if let Err(e) =
+                    self.record_asset_update_with_batch(batch, pending, &owner_update.details)
+                {
+                    tracing::error!("Failed to record asset owner update: {}", e);
+                }
                 if let Err(e) = self.asset_owner_data.merge_with_batch(
                     batch,
                     owner_update.pk,
@@ -106,6 +128,11 @@ impl Storage {
                 }
             }
             if let Some(ref authority_update) = update.authority_update {
+                if let Err(e) =
+                    self.record_asset_update_with_batch(batch, pending, &authority_update.details)
+                {
+                    tracing::error!("Failed to record asset authority update: {}", e);
+                }
                 if let Err(e) = self.asset_authority_data.merge_with_batch(
                     batch,
                     authority_update.pk,
@@ -115,6 +142,11 @@ impl Storage {
                 }
             }
             if let Some(ref collection_update) = update.collection_update {
+                if let Err(e) =
+                    self.record_asset_update_with_batch(batch, pending, &collection_update.details)
+                {
+                    tracing::error!("Failed to record asset collection update: {}", e);
+                }
                 if let Err(e) = self.asset_collection_data.merge_with_batch(
                     batch,
                     collection_update.pk,
@@ -148,6 +180,11 @@ impl Storage {
         if let Some(ref decompressed) = ix.decompressed {
             self.asset_leaf_data
                 .delete_with_batch(batch, decompressed.pk);
+            if let Err(e) =
+                self.record_asset_update_with_batch(batch, pending, &decompressed.details)
+            {
+                tracing::error!("Failed to record asset dynamic update: {}", e);
+            }
             if let Err(e) = self.asset_dynamic_data.merge_with_batch(
                 batch,
                 decompressed.pk,
@@ -156,12 +193,25 @@ impl Storage {
                 tracing::error!("Failed to save tx data and asset updated: {}", e);
             }
         }
+        if let Some(ref tree_info) = ix.tree_info {
+            if let Err(e) = self
+                .tree_info
+                .merge_with_batch(batch, tree_info.tree, tree_info)
+            {
+                tracing::error!("Failed to merge tree info: {}", e);
+            }
+        }
         if let Some(ref tree_update) = ix.tree_update {
             self.save_changelog_with_batch(batch, &tree_update.event, tree_update.slot);
             self.save_tree_with_batch(batch, tree_update);
             self.save_asset_signature_with_batch(batch, tree_update);
             self.save_leaf_signature_with_batch(batch, tree_update)?;
         }
+        if let Some(event) = AssetEvent::from_instruction_result(ix) {
+            if let Err(e) = self.record_asset_event_with_batch(batch, &event) {
+                tracing::error!("Failed to record asset event: {}", e);
+            }
+        }
 
         Ok(())
     }
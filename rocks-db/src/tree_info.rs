@@ -0,0 +1,119 @@
+use crate::asset::update_optional_field;
+use crate::column::TypedColumn;
+use crate::key_encoders::{decode_pubkey, encode_pubkey};
+use crate::tree_seq::TreeSeqIdx;
+use crate::{Result, Storage};
+use bincode::{deserialize, serialize};
+use entities::models::{TreeState, Updated};
+use rocksdb::MergeOperands;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+/// Configuration of a Bubblegum tree as passed to the CreateTree instruction
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TreeConfig {
+    pub max_depth: u32,
+    pub max_buffer_size: u32,
+    // the canopy is only known if the tree account is created within the same transaction
+    pub canopy_depth: Option<u32>,
+    pub tree_creator: Pubkey,
+    pub tree_delegate: Pubkey,
+    pub is_public: bool,
+    pub created_at_slot: u64,
+}
+
+/// Bubblegum tree description. The config is written once on the tree creation,
+/// the number of minted leaves is bumped by every mint instruction.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TreeInfo {
+    pub tree: Pubkey,
+    pub config: Option<TreeConfig>,
+    pub num_minted: u64,
+    // the latest delegate set by the SetTreeDelegate instruction, it replaces the one of the config
+    pub delegate: Option<Updated<Pubkey>>,
+}
+
+impl TypedColumn for TreeInfo {
+    type KeyType = Pubkey;
+    type ValueType = Self;
+    const NAME: &'static str = "TREE_INFO";
+
+    fn encode_key(pubkey: Pubkey) -> Vec<u8> {
+        encode_pubkey(pubkey)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_pubkey(bytes)
+    }
+}
+
+impl TreeInfo {
+    pub fn merge_tree_info(
+        _new_key: &[u8],
+        existing_val: Option<&[u8]>,
+        operands: &MergeOperands,
+    ) -> Option<Vec<u8>> {
+        let mut result: Option<Self> = None;
+        if let Some(existing_val) = existing_val {
+            match deserialize::<Self>(existing_val) {
+                Ok(value) => {
+                    result = Some(value);
+                }
+                Err(e) => {
+                    error!("RocksDB: TreeInfo deserialize existing_val: {}", e)
+                }
+            }
+        }
+
+        for op in operands {
+            match deserialize::<Self>(op) {
+                Ok(new_val) => {
+                    result = Some(if let Some(mut current_val) = result {
+                        // the tree is created only once, so the first config wins
+                        if current_val.config.is_none() {
+                            current_val.config = new_val.config;
+                        }
+                        current_val.num_minted = current_val.num_minted.max(new_val.num_minted);
+                        update_optional_field(&mut current_val.delegate, &new_val.delegate);
+                        current_val
+                    } else {
+                        new_val
+                    });
+                }
+                Err(e) => {
+                    error!("RocksDB: TreeInfo deserialize new_val: {}", e)
+                }
+            }
+        }
+
+        // the delegate may be changed before the tree creation is indexed
+        if let Some(result) = result.as_mut() {
+            if let (Some(config), Some(delegate)) = (result.config.as_mut(), &result.delegate) {
+                config.tree_delegate = delegate.value;
+            }
+        }
+
+        result.and_then(|result| serialize(&result).ok())
+    }
+}
+
+impl Storage {
+    /// Returns the latest known sequence of the tree along with the slot it was seen at
+    pub fn get_latest_tree_seq(&self, tree: Pubkey) -> Result<Option<TreeState>> {
+        let Some(item) = self.tree_seq_idx.iter_reverse((tree, u64::MAX)).next() else {
+            return Ok(None);
+        };
+        let (key, value) = item?;
+        let (key_tree, seq) = self.tree_seq_idx.decode_key(key.to_vec())?;
+        if key_tree != tree {
+            return Ok(None);
+        }
+        let value = deserialize::<TreeSeqIdx>(value.as_ref())?;
+        Ok(Some(TreeState {
+            tree,
+            seq,
+            slot: value.slot,
+        }))
+    }
+}
@@ -47,3 +47,47 @@ impl TypedColumn for TreesGaps {
         key_encoders::decode_pubkey(bytes)
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GapRepairState {
+    #[default]
+    Pending,
+    Repaired,
+    // the gaps are still there after the configured number of attempts, needs a manual backfill
+    Unrepairable,
+}
+
+impl GapRepairState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GapRepairState::Pending => "pending",
+            GapRepairState::Repaired => "repaired",
+            GapRepairState::Unrepairable => "unrepairable",
+        }
+    }
+}
+
+/// Progress of the automatic repair of the tree sequence gaps
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TreeGapRepair {
+    pub state: GapRepairState,
+    pub attempts: u32,
+    // gaps left after the last attempt
+    pub gaps: u64,
+    pub updated_at: i64,
+}
+
+impl TypedColumn for TreeGapRepair {
+    const NAME: &'static str = "TREE_GAP_REPAIRS";
+
+    type KeyType = Pubkey;
+    type ValueType = Self;
+
+    fn encode_key(key: Pubkey) -> Vec<u8> {
+        key_encoders::encode_pubkey(key)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> crate::Result<Self::KeyType> {
+        key_encoders::decode_pubkey(bytes)
+    }
+}
@@ -0,0 +1,339 @@
+use std::sync::atomic::Ordering;
+
+use entities::enums::AssetEventType;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::column::TypedColumn;
+use crate::key_encoders::{decode_u64, decode_u64x2, encode_u64, encode_u64x2};
+use crate::transaction::InstructionResult;
+use crate::{
+    next_time_ordered_id, AssetAuthority, AssetDynamicDetails, AssetOwner, Result, Storage,
+};
+
+/// Change of an asset made by a processed instruction or account update,
+/// waiting to be matched against the registered webhooks
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AssetEvent {
+    pub event_type: AssetEventType,
+    pub asset_id: Pubkey,
+    pub slot: u64,
+    // tree and signature are known for the compressed assets only
+    pub tree: Option<Pubkey>,
+    pub signature: Option<String>,
+    // new owner of the asset, if the event tells it
+    pub owner: Option<Pubkey>,
+}
+
+/// Rocks DB column family of the asset events keyed by the ids growing with the time of storing
+impl TypedColumn for AssetEvent {
+    type KeyType = u64;
+    type ValueType = Self;
+    const NAME: &'static str = "ASSET_EVENTS";
+
+    fn encode_key(id: u64) -> Vec<u8> {
+        encode_u64(id)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_u64(bytes)
+    }
+}
+
+/// Notification of a webhook waiting in the outbox to be sent or retried
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub webhook_id: u64,
+    // JSON body of the request
+    pub payload: Vec<u8>,
+    pub attempts: u32,
+    pub next_attempt_at: i64, // unix time in seconds
+    pub last_error: Option<String>,
+}
+
+/// Rocks DB column family of the webhooks outbox keyed by the ids growing with the time of storing
+impl TypedColumn for WebhookDelivery {
+    type KeyType = u64;
+    type ValueType = Self;
+    const NAME: &'static str = "WEBHOOK_DELIVERIES";
+
+    fn encode_key(id: u64) -> Vec<u8> {
+        encode_u64(id)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_u64(bytes)
+    }
+}
+
+impl WebhookDelivery {
+    fn due_key(&self, id: u64) -> (u64, u64) {
+        (self.next_attempt_at.max(0) as u64, id)
+    }
+}
+
+/// Index of the webhooks outbox keyed by the time of the next attempt and the id of the notification,
+/// so the due notifications are found without going through the whole outbox
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WebhookDeliveryDueIdx {}
+
+impl TypedColumn for WebhookDeliveryDueIdx {
+    type KeyType = (u64, u64);
+    type ValueType = Self;
+    const NAME: &'static str = "WEBHOOK_DELIVERY_DUE_IDX";
+
+    fn encode_key((next_attempt_at, id): (u64, u64)) -> Vec<u8> {
+        encode_u64x2(next_attempt_at, id)
+    }
+
+    fn decode_key(bytes: Vec<u8>) -> Result<Self::KeyType> {
+        decode_u64x2(bytes)
+    }
+}
+
+/// Change of the merged asset state made by the updates of an account, the asset events
+/// are told by it rather than by the account, so the stale updates and the replays
+/// which change nothing make no events
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssetStateChange {
+    // the owner the asset moved to from another one
+    pub new_owner: Option<Pubkey>,
+    // the supply went from zero to a positive one
+    pub minted: bool,
+    pub burnt: bool,
+    // the authority the asset had before was updated,
+    // i.e. the change is neither the asset creation nor a stale update
+    pub existed: bool,
+}
+
+impl AssetStateChange {
+    pub(crate) fn owner_changed(&mut self, previous: Option<&AssetOwner>, current: &AssetOwner) {
+        let previous_owner = previous.and_then(|owner| owner.owner.value);
+        // the first owner comes with the mint, the delegate or the freeze changes keep the owner
+        if previous_owner.is_some() && previous_owner != current.owner.value {
+            self.new_owner = current.owner.value;
+        }
+    }
+
+    pub(crate) fn dynamic_details_changed(
+        &mut self,
+        previous: Option<&AssetDynamicDetails>,
+        current: &AssetDynamicDetails,
+    ) {
+        let supply = |dynamic_details: Option<&AssetDynamicDetails>| {
+            dynamic_details
+                .and_then(|dynamic_details| dynamic_details.supply.as_ref())
+                .map_or(0, |supply| supply.value)
+        };
+        // the mint authority hand-off to the master edition keeps the supply
+        if supply(previous) == 0 && supply(Some(current)) > 0 {
+            self.minted = true;
+        }
+        if !previous.is_some_and(|dynamic_details| dynamic_details.is_burnt.value)
+            && current.is_burnt.value
+        {
+            self.burnt = true;
+        }
+    }
+
+    pub(crate) fn authority_changed(&mut self, previous: Option<&AssetAuthority>) {
+        self.existed |= previous.is_some();
+    }
+
+    /// Events made by the change. The metadata of the assets is kept in the metadata
+    /// and the MPL Core accounts, so only their changes make the metadata update events
+    pub fn event_types(&self, metadata_account: bool) -> Vec<AssetEventType> {
+        let mut event_types = Vec::new();
+        if self.minted {
+            event_types.push(AssetEventType::Mint);
+        }
+        if self.new_owner.is_some() {
+            event_types.push(AssetEventType::Transfer);
+        }
+        if self.burnt {
+            event_types.push(AssetEventType::Burn);
+        }
+        if metadata_account && self.existed && event_types.is_empty() {
+            event_types.push(AssetEventType::MetadataUpdate);
+        }
+        event_types
+    }
+}
+
+impl AssetEvent {
+    /// Asset event made by the Bubblegum instruction, the asset is taken from the updates it made
+    pub fn from_instruction_result(ix: &InstructionResult) -> Option<Self> {
+        let event_type = ix.event_type?;
+        let update = ix.update.as_ref()?;
+        let asset_id = update
+            .update
+            .as_ref()
+            .map(|dynamic_update| dynamic_update.pk)
+            .or_else(|| update.owner_update.as_ref().map(|owner| owner.pk))
+            .or_else(|| {
+                update
+                    .collection_update
+                    .as_ref()
+                    .map(|collection| collection.pk)
+            })?;
+        let slot = ix
+            .tree_update
+            .as_ref()
+            .map(|tree_update| tree_update.slot)
+            .or_else(|| {
+                update
+                    .update
+                    .as_ref()
+                    .map(|dynamic_update| dynamic_update.slot)
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            event_type,
+            asset_id,
+            slot,
+            tree: ix.tree_update.as_ref().map(|tree_update| tree_update.tree),
+            signature: ix
+                .tree_update
+                .as_ref()
+                .map(|tree_update| tree_update.tx.clone()),
+            owner: update
+                .owner_update
+                .as_ref()
+                .and_then(|owner| owner.details.owner.value),
+        })
+    }
+}
+
+impl Storage {
+    pub fn set_asset_events_enabled(&self, enabled: bool) {
+        self.asset_events_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_asset_events_enabled(&self) -> bool {
+        self.asset_events_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Stores the event to be matched against the webhooks, if the webhooks are enabled
+    pub(crate) fn record_asset_event_with_batch(
+        &self,
+        batch: &mut rocksdb::WriteBatch,
+        event: &AssetEvent,
+    ) -> Result<()> {
+        if !self.is_asset_events_enabled() {
+            return Ok(());
+        }
+        let id = next_time_ordered_id(&self.asset_events_last_id);
+        self.asset_events.put_with_batch(batch, id, event)
+    }
+
+    /// Returns up to `limit` asset events, the oldest first
+    pub fn get_asset_events(&self, limit: usize) -> Vec<(u64, AssetEvent)> {
+        self.asset_events
+            .iter_start()
+            .filter_map(|item| item.ok())
+            .filter_map(|(key, value)| {
+                let id = AssetEvent::decode_key(key.to_vec()).ok()?;
+                let event = bincode::deserialize::<AssetEvent>(&value).ok()?;
+                Some((id, event))
+            })
+            .take(limit)
+            .collect()
+    }
+
+    /// Moves the handled events into the outbox along with the notifications they resulted in
+    pub async fn dispatch_asset_events(
+        &self,
+        event_ids: &[u64],
+        deliveries: &[WebhookDelivery],
+    ) -> Result<()> {
+        let mut batch = rocksdb::WriteBatch::default();
+        for delivery in deliveries {
+            let id = next_time_ordered_id(&self.webhook_deliveries_last_id);
+            self.webhook_deliveries
+                .put_with_batch(&mut batch, id, delivery)?;
+            self.webhook_delivery_due_idx.put_with_batch(
+                &mut batch,
+                delivery.due_key(id),
+                &WebhookDeliveryDueIdx::default(),
+            )?;
+        }
+        for id in event_ids {
+            self.asset_events.delete_with_batch(&mut batch, *id);
+        }
+        self.write_batch(batch).await
+    }
+
+    /// Returns up to `limit` notifications whose attempt time has come, the earliest due first
+    pub fn get_due_webhook_deliveries(
+        &self,
+        now: i64,
+        limit: usize,
+    ) -> Result<Vec<(u64, WebhookDelivery)>> {
+        let mut deliveries = Vec::new();
+        for item in self.webhook_delivery_due_idx.iter_start() {
+            let (key, _) = item?;
+            let (next_attempt_at, id) = WebhookDeliveryDueIdx::decode_key(key.to_vec())?;
+            if next_attempt_at as i64 > now || deliveries.len() >= limit {
+                break;
+            }
+            // the index entry is removed in the same batch as the notification
+            if let Some(delivery) = self.webhook_deliveries.get(id)? {
+                deliveries.push((id, delivery));
+            }
+        }
+
+        Ok(deliveries)
+    }
+
+    /// Stores the notification to be retried at the time it was given
+    pub async fn reschedule_webhook_delivery(
+        &self,
+        id: u64,
+        previous_attempt_at: i64,
+        delivery: &WebhookDelivery,
+    ) -> Result<()> {
+        let mut batch = rocksdb::WriteBatch::default();
+        self.webhook_delivery_due_idx
+            .delete_with_batch(&mut batch, (previous_attempt_at.max(0) as u64, id));
+        self.webhook_delivery_due_idx.put_with_batch(
+            &mut batch,
+            delivery.due_key(id),
+            &WebhookDeliveryDueIdx::default(),
+        )?;
+        self.webhook_deliveries
+            .put_with_batch(&mut batch, id, delivery)?;
+        self.write_batch(batch).await
+    }
+
+    /// Removes the sent or dropped notification from the outbox
+    pub async fn delete_webhook_delivery(&self, id: u64, delivery: &WebhookDelivery) -> Result<()> {
+        let mut batch = rocksdb::WriteBatch::default();
+        self.webhook_delivery_due_idx
+            .delete_with_batch(&mut batch, delivery.due_key(id));
+        self.webhook_deliveries.delete_with_batch(&mut batch, id);
+        self.write_batch(batch).await
+    }
+
+    /// Indexes the notifications of the outbox by their attempt time and returns their number.
+    /// Goes through the whole outbox, so it is meant to be called once on the start,
+    /// the notifications stored before the index was introduced are picked up by it
+    pub fn index_webhook_deliveries(&self) -> Result<usize> {
+        let mut batch = rocksdb::WriteBatch::default();
+        let mut count = 0;
+        for item in self.webhook_deliveries.iter_start() {
+            let (key, value) = item?;
+            let id = WebhookDelivery::decode_key(key.to_vec())?;
+            let delivery = bincode::deserialize::<WebhookDelivery>(&value)?;
+            self.webhook_delivery_due_idx.put_with_batch(
+                &mut batch,
+                delivery.due_key(id),
+                &WebhookDeliveryDueIdx::default(),
+            )?;
+            count += 1;
+        }
+        self.db.write(batch)?;
+
+        Ok(count)
+    }
+}
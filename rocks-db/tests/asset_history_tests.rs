@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use entities::models::Updated;
+    use rocks_db::asset_history::AssetHistory;
+    use rocks_db::AssetOwner;
+    use setup::rocks::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn owner_at(pubkey: Pubkey, owner: Pubkey, slot: u64) -> AssetHistory {
+        AssetHistory {
+            owners: vec![AssetOwner {
+                pubkey,
+                owner: Updated::new(slot, None, Some(owner)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compact_asset_history() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+        let pubkey = Pubkey::new_unique();
+        let untouched_pubkey = Pubkey::new_unique();
+        let owners = (0..4).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        for (slot, owner) in [10, 20, 30, 40].into_iter().zip(owners.iter()) {
+            storage
+                .asset_history
+                .merge((pubkey, slot), owner_at(pubkey, *owner, slot))
+                .await
+                .unwrap();
+        }
+        storage
+            .asset_history
+            .merge(
+                (untouched_pubkey, 40),
+                owner_at(untouched_pubkey, owners[0], 40),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(storage.compact_asset_history(35).unwrap(), 2);
+        assert_eq!(storage.asset_history_start_slot().await.unwrap(), 35);
+        let slots = storage
+            .asset_history
+            .iter_start()
+            .filter_map(Result::ok)
+            .map(|(key, _)| AssetHistory::decode_key(key.to_vec()).unwrap())
+            .collect::<Vec<_>>();
+        let mut expected_slots = vec![(pubkey, 30), (pubkey, 40), (untouched_pubkey, 40)];
+        expected_slots.sort_by_key(|(pubkey, slot)| (pubkey.to_bytes(), *slot));
+        assert_eq!(slots, expected_slots);
+
+        for (slot, owner) in [(35, owners[2]), (40, owners[3])] {
+            let versions = storage
+                .get_asset_versions_at_slot(pubkey, slot)
+                .unwrap()
+                .unwrap();
+            assert_eq!(versions.owner.unwrap().owner.value, Some(owner));
+        }
+
+        // nothing left to fold, the start slot never goes back
+        assert_eq!(storage.compact_asset_history(30).unwrap(), 0);
+        assert_eq!(storage.asset_history_start_slot().await.unwrap(), 35);
+    }
+}
@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use entities::enums::AssetEventType;
+    use entities::models::Updated;
+    use metrics_utils::IngesterMetricsConfig;
+    use rocks_db::asset_previous_versions::{AssetPreviousVersions, OwnerChangesAfterSlot};
+    use rocks_db::batch_savers::{BatchSaveStorage, MetadataModels};
+    use rocks_db::column::TypedColumn;
+    use rocks_db::errors::StorageError;
+    use rocks_db::{AssetAuthority, AssetDynamicDetails, AssetOwner};
+    use setup::rocks::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn owner_at(pubkey: Pubkey, owner: Pubkey, slot: u64) -> AssetOwner {
+        AssetOwner {
+            pubkey,
+            owner: Updated::new(slot, None, Some(owner)),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_previous_versions() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+        storage.init_previous_versions_start_slot(10).await.unwrap();
+        let pubkey = Pubkey::new_unique();
+        let first_owner = Pubkey::new_unique();
+        let second_owner = Pubkey::new_unique();
+
+        let mut batch_storage =
+            BatchSaveStorage::new(storage.clone(), 10, Arc::new(IngesterMetricsConfig::new()));
+        for update in [
+            owner_at(pubkey, first_owner, 10),
+            owner_at(pubkey, second_owner, 20),
+            // stale and replayed updates replace nothing
+            owner_at(pubkey, Pubkey::new_unique(), 15),
+            owner_at(pubkey, second_owner, 20),
+        ] {
+            batch_storage.store_owner(&update).unwrap();
+        }
+        batch_storage.flush().unwrap();
+        batch_storage
+            .store_owner(&owner_at(pubkey, first_owner, 30))
+            .unwrap();
+        batch_storage.flush().unwrap();
+
+        let slots = storage
+            .asset_previous_versions
+            .iter_start()
+            .filter_map(Result::ok)
+            .map(|(key, _)| AssetPreviousVersions::decode_key(key.to_vec()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(slots, vec![(pubkey, 10), (pubkey, 20), (pubkey, 30)]);
+
+        let owner_after = |slot| {
+            storage
+                .get_previous_versions_after_slot(pubkey, slot)
+                .unwrap()
+                .owner
+                .map(|owner| owner.and_then(|owner| owner.owner.value))
+        };
+        assert_eq!(owner_after(5), Some(None));
+        assert_eq!(owner_after(15), Some(Some(first_owner)));
+        assert_eq!(owner_after(25), Some(Some(second_owner)));
+        assert_eq!(owner_after(30), None);
+
+        assert_eq!(
+            storage
+                .owner_changes_after_slot(first_owner, 15)
+                .await
+                .unwrap(),
+            OwnerChangesAfterSlot {
+                owned: vec![pubkey],
+                not_owned: vec![],
+            }
+        );
+        assert_eq!(
+            storage
+                .owner_changes_after_slot(second_owner, 15)
+                .await
+                .unwrap(),
+            OwnerChangesAfterSlot {
+                owned: vec![],
+                not_owned: vec![pubkey],
+            }
+        );
+        assert!(matches!(
+            storage.owner_changes_after_slot(second_owner, 5).await,
+            Err(StorageError::HistoryUnavailable(5))
+        ));
+
+        // 2 versions and the owners index entries of the slots 10 and 20
+        assert_eq!(storage.delete_previous_versions(20).unwrap(), 5);
+        assert_eq!(
+            storage.previous_versions_start_slot().await.unwrap(),
+            Some(20)
+        );
+        assert_eq!(owner_after(25), Some(Some(second_owner)));
+        assert!(matches!(
+            storage.owner_changes_after_slot(first_owner, 15).await,
+            Err(StorageError::HistoryUnavailable(15))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_asset_state_changes() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+        let pubkey = Pubkey::new_unique();
+        let first_owner = Pubkey::new_unique();
+        let second_owner = Pubkey::new_unique();
+        let supply_at = |supply: u64, slot: u64| AssetDynamicDetails {
+            pubkey,
+            supply: Some(Updated::new(slot, None, supply)),
+            ..Default::default()
+        };
+
+        let mut batch_storage =
+            BatchSaveStorage::new(storage.clone(), 10, Arc::new(IngesterMetricsConfig::new()));
+        batch_storage.store_dynamic(&supply_at(0, 5)).unwrap();
+        batch_storage.store_dynamic(&supply_at(1, 10)).unwrap();
+        batch_storage
+            .store_owner(&owner_at(pubkey, first_owner, 10))
+            .unwrap();
+        // the first owner comes with the mint
+        let changes = batch_storage.take_asset_changes();
+        assert_eq!(
+            changes[&pubkey].event_types(false),
+            vec![AssetEventType::Mint]
+        );
+        batch_storage.flush().unwrap();
+
+        // the supply is kept by the mint authority hand-off
+        batch_storage.store_dynamic(&supply_at(1, 12)).unwrap();
+        assert!(batch_storage.take_asset_changes()[&pubkey]
+            .event_types(false)
+            .is_empty());
+
+        batch_storage
+            .store_owner(&owner_at(pubkey, second_owner, 20))
+            .unwrap();
+        let changes = batch_storage.take_asset_changes();
+        assert_eq!(changes[&pubkey].new_owner, Some(second_owner));
+        assert_eq!(
+            changes[&pubkey].event_types(true),
+            vec![AssetEventType::Transfer]
+        );
+
+        // the stale and the replayed updates change nothing
+        for update in [
+            owner_at(pubkey, first_owner, 15),
+            owner_at(pubkey, second_owner, 20),
+        ] {
+            batch_storage.store_owner(&update).unwrap();
+        }
+        batch_storage.store_dynamic(&supply_at(1, 10)).unwrap();
+        assert!(batch_storage.take_asset_changes().is_empty());
+
+        // the metadata is updated once the asset exists
+        let authority_at = |slot: u64| AssetAuthority {
+            pubkey,
+            authority: first_owner,
+            slot_updated: slot,
+            write_version: None,
+        };
+        batch_storage
+            .store_metadata_models(&MetadataModels {
+                asset_authority: Some(authority_at(20)),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(batch_storage.take_asset_changes()[&pubkey]
+            .event_types(true)
+            .is_empty());
+        batch_storage
+            .store_metadata_models(&MetadataModels {
+                asset_authority: Some(authority_at(30)),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            batch_storage.take_asset_changes()[&pubkey].event_types(true),
+            vec![AssetEventType::MetadataUpdate]
+        );
+    }
+}
@@ -0,0 +1,233 @@
+#[cfg(test)]
+mod tests {
+    use rocks_db::cl_items::ClItemKey;
+    use rocks_db::cl_items_history::{ClItemsHistory, ClItemsVersion, CL_ITEMS_HISTORY_WINDOW};
+    use rocks_db::tree_info::{TreeConfig, TreeInfo};
+    use rocks_db::Storage;
+    use setup::rocks::*;
+    use solana_sdk::pubkey::Pubkey;
+    use spl_account_compression::events::ChangeLogEventV1;
+    use spl_account_compression::state::PathNode;
+
+    // changes the leaf of a tree with the depth of 1, so the path is the leaf and the root
+    async fn save_leaf_change(storage: &Storage, tree: Pubkey, leaf_node: u32, seq: u64) {
+        storage
+            .save_changelog(
+                &ChangeLogEventV1 {
+                    id: tree,
+                    path: vec![
+                        PathNode {
+                            node: [seq as u8; 32],
+                            index: leaf_node,
+                        },
+                        PathNode {
+                            node: [seq as u8; 32],
+                            index: 1,
+                        },
+                    ],
+                    seq,
+                    index: leaf_node - 2,
+                },
+                seq * 10,
+            )
+            .await;
+    }
+
+    async fn save_tree_creation(storage: &Storage, tree: Pubkey, created_at_slot: u64) {
+        storage
+            .tree_info
+            .merge(
+                tree,
+                TreeInfo {
+                    tree,
+                    config: Some(TreeConfig {
+                        max_depth: 1,
+                        max_buffer_size: 8,
+                        canopy_depth: None,
+                        tree_creator: tree,
+                        tree_delegate: tree,
+                        is_public: false,
+                        created_at_slot,
+                    }),
+                    num_minted: 0,
+                    delegate: None,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cl_items_at_version() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+        storage.set_cl_items_history_enabled(true);
+        storage.init_cl_items_history_start_slot().await.unwrap();
+        assert_eq!(
+            storage.cl_items_history_start_slot().await.unwrap(),
+            Some(0)
+        );
+
+        let tree = Pubkey::new_unique();
+        save_tree_creation(&storage, tree, 0).await;
+        let keys = vec![
+            ClItemKey::new(1, tree),
+            ClItemKey::new(2, tree),
+            ClItemKey::new(3, tree),
+        ];
+        for seq in 1..=70 {
+            save_leaf_change(&storage, tree, 2, seq).await;
+        }
+
+        for version in [ClItemsVersion::Seq(40), ClItemsVersion::Slot(405)] {
+            let items = storage
+                .get_cl_items_at_version(keys.clone(), version)
+                .await
+                .unwrap();
+            assert_eq!(items[0].as_ref().unwrap().cli_seq, 40);
+            assert_eq!(items[1].as_ref().unwrap().cli_hash, vec![40; 32]);
+            assert!(items[2].is_none());
+        }
+
+        let history = storage
+            .cl_items_history
+            .get(ClItemKey::new(1, tree))
+            .unwrap()
+            .unwrap();
+        assert_eq!(history.items.len(), CL_ITEMS_HISTORY_WINDOW);
+        assert_eq!(history.items.last().unwrap().cli_seq, 70);
+        // the versions before the window were dropped
+        assert!(storage
+            .get_cl_items_at_version(keys.clone(), ClItemsVersion::Seq(3))
+            .await
+            .is_err());
+
+        // the node was empty at the seq
+        save_leaf_change(&storage, tree, 3, 71).await;
+        let items = storage
+            .get_cl_items_at_version(keys.clone(), ClItemsVersion::Seq(70))
+            .await
+            .unwrap();
+        assert!(items[2].is_none());
+        assert_eq!(items[0].as_ref().unwrap().cli_seq, 70);
+    }
+
+    #[tokio::test]
+    async fn test_cl_items_at_version_out_of_recorded_history() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+        storage
+            .put_parameter(rocks_db::parameters::Parameter::TopSeenSlot, 99u64)
+            .await
+            .unwrap();
+        storage.set_cl_items_history_enabled(true);
+        storage.init_cl_items_history_start_slot().await.unwrap();
+        assert_eq!(
+            storage.cl_items_history_start_slot().await.unwrap(),
+            Some(100)
+        );
+
+        // the tree was created before the history was recorded
+        let tree = Pubkey::new_unique();
+        for seq in 5..=15 {
+            save_leaf_change(&storage, tree, 2, seq).await;
+        }
+        save_leaf_change(&storage, tree, 3, 16).await;
+        // the node 3 is not known to be empty since the changes before the start aren't recorded
+        assert!(storage
+            .get_cl_items_at_version(vec![ClItemKey::new(3, tree)], ClItemsVersion::Seq(12))
+            .await
+            .is_err());
+        // the version was updated before the start slot
+        assert!(storage
+            .get_cl_items_at_version(vec![ClItemKey::new(2, tree)], ClItemsVersion::Seq(9))
+            .await
+            .is_err());
+        let items = storage
+            .get_cl_items_at_version(vec![ClItemKey::new(2, tree)], ClItemsVersion::Seq(12))
+            .await
+            .unwrap();
+        assert_eq!(items[0].as_ref().unwrap().cli_seq, 12);
+
+        // the tree created after the start has the whole history recorded
+        let new_tree = Pubkey::new_unique();
+        save_tree_creation(&storage, new_tree, 100).await;
+        for seq in 11..=15 {
+            save_leaf_change(&storage, new_tree, 2, seq).await;
+        }
+        save_leaf_change(&storage, new_tree, 3, 16).await;
+        let items = storage
+            .get_cl_items_at_version(
+                vec![ClItemKey::new(2, new_tree), ClItemKey::new(3, new_tree)],
+                ClItemsVersion::Seq(12),
+            )
+            .await
+            .unwrap();
+        assert_eq!(items[0].as_ref().unwrap().cli_seq, 12);
+        assert!(items[1].is_none());
+    }
+
+    #[tokio::test]
+    async fn test_drop_forked_cl_items_history() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+        storage.set_cl_items_history_enabled(true);
+        storage.init_cl_items_history_start_slot().await.unwrap();
+
+        let tree = Pubkey::new_unique();
+        save_tree_creation(&storage, tree, 0).await;
+        for seq in 1..=3 {
+            save_leaf_change(&storage, tree, 2, seq).await;
+        }
+        // a version from another slot with the same seq is kept
+        storage
+            .cl_items_history
+            .merge(
+                ClItemKey::new(2, tree),
+                ClItemsHistory {
+                    forked: vec![(2, 20), (3, 31)],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let history = storage
+            .cl_items_history
+            .get(ClItemKey::new(2, tree))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            history
+                .items
+                .iter()
+                .map(|item| item.cli_seq)
+                .collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert!(history.forked.is_empty());
+        // the dropped version isn't served, so the node at the seq is unknown
+        let items = storage
+            .get_cl_items_at_version(vec![ClItemKey::new(2, tree)], ClItemsVersion::Seq(2))
+            .await
+            .unwrap();
+        assert_eq!(items[0].as_ref().unwrap().cli_seq, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cl_items_at_version_without_history() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+
+        let tree = Pubkey::new_unique();
+        save_leaf_change(&storage, tree, 2, 5).await;
+        let keys = vec![ClItemKey::new(1, tree), ClItemKey::new(2, tree)];
+
+        // the latest version is served while it is not newer than the requested one
+        let items = storage
+            .get_cl_items_at_version(keys.clone(), ClItemsVersion::Seq(10))
+            .await
+            .unwrap();
+        assert_eq!(items[1].as_ref().unwrap().cli_seq, 5);
+        assert!(storage
+            .get_cl_items_at_version(keys, ClItemsVersion::Seq(4))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use rocks_db::dead_letters::{DeadLetter, DeadLetterStream};
+    use setup::rocks::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[tokio::test]
+    async fn test_dead_letters_pagination() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+
+        let account = Pubkey::new_unique();
+        let first_id = storage
+            .put_dead_letter(DeadLetter::account(
+                account,
+                10,
+                "account error".to_string(),
+            ))
+            .unwrap();
+        let second_id = storage
+            .put_dead_letter(DeadLetter::message(
+                DeadLetterStream::Transactions,
+                vec![1, 2, 3],
+                false,
+                "transaction error".to_string(),
+            ))
+            .unwrap();
+        let third_id = storage
+            .put_dead_letter(DeadLetter::message(
+                DeadLetterStream::Accounts,
+                vec![4, 5],
+                true,
+                "parsing error".to_string(),
+            ))
+            .unwrap();
+        assert!(first_id < second_id && second_id < third_id);
+        assert_eq!(storage.dead_letters_count(), 3);
+
+        let first_page = storage.get_dead_letters(None, 2);
+        assert_eq!(
+            first_page.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![first_id, second_id]
+        );
+        assert_eq!(first_page[0].1.pubkey, Some(account));
+        assert_eq!(first_page[1].1.data, vec![1, 2, 3]);
+
+        // the cursor stays valid even if the dead letter it points to was redriven
+        storage.dead_letters.delete(second_id).unwrap();
+        let second_page = storage.get_dead_letters(Some(second_id), 2);
+        assert_eq!(second_page.len(), 1);
+        assert_eq!(second_page[0].0, third_id);
+        assert!(second_page[0].1.map_flatbuffer);
+        assert_eq!(storage.dead_letters_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_purge() {
+        let storage = RocksTestEnvironment::new(&[]).storage;
+
+        storage
+            .put_dead_letter(DeadLetter::account(
+                Pubkey::new_unique(),
+                10,
+                "account error".to_string(),
+            ))
+            .unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        storage.purge_dead_letters(now - 60).await.unwrap();
+        assert_eq!(storage.dead_letters_count(), 1);
+
+        storage.purge_dead_letters(now + 1).await.unwrap();
+        assert_eq!(storage.dead_letters_count(), 0);
+    }
+}
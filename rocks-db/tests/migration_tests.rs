@@ -1,12 +1,19 @@
 #[cfg(test)]
 mod tests {
     use bincode::serialize;
+    use entities::enums::{RoyaltyTargetType, SpecificationAssetClass, TokenMetadataEdition};
+    use entities::models::{EditionV1, Updated};
     use metrics_utils::red::RequestErrorDurationMetrics;
     use rocks_db::asset::AssetCollection;
     use rocks_db::column::TypedColumn;
+    use rocks_db::dead_letters::{DeadLetter, DeadLetterStream};
     use rocks_db::migrations::collection_authority::AssetCollectionVersion0;
-    use rocks_db::migrator::MigrationState;
-    use rocks_db::Storage;
+    use rocks_db::migrations::dead_letters::{
+        DeadLetterWithoutWriteVersion, DeadLetterWriteVersionMigration,
+    };
+    use rocks_db::migrations::edition_indexes::EditionIndexesBackfill;
+    use rocks_db::migrator::{MigrationState, MigrationVersions, RocksBackfill, RocksMigration};
+    use rocks_db::{AssetDynamicDetails, AssetStaticDetails, Storage};
     use solana_sdk::pubkey::Pubkey;
     use std::sync::Arc;
     use tempfile::TempDir;
@@ -120,4 +127,186 @@ mod tests {
 
         assert_eq!(selected_val.pubkey, val.pubkey)
     }
+
+    #[tokio::test]
+    async fn test_edition_indexes_backfill() {
+        let dir = TempDir::new().unwrap();
+        let parent = Pubkey::new_unique();
+        let (mint, edition) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (burnt_mint, burnt_edition) = (Pubkey::new_unique(), Pubkey::new_unique());
+        {
+            let old_storage = Storage::open(
+                dir.path().to_str().unwrap(),
+                Arc::new(Mutex::new(JoinSet::new())),
+                Arc::new(RequestErrorDurationMetrics::new()),
+                MigrationState::Last,
+            )
+            .unwrap();
+            // the column migrations are already applied
+            for version in 0..EditionIndexesBackfill::VERSION {
+                old_storage
+                    .migration_version
+                    .put_async(version, MigrationVersions {})
+                    .await
+                    .unwrap();
+            }
+            for (edition_number, (mint, edition)) in [(mint, edition), (burnt_mint, burnt_edition)]
+                .into_iter()
+                .enumerate()
+            {
+                old_storage
+                    .asset_static_data
+                    .put(
+                        mint,
+                        AssetStaticDetails {
+                            pubkey: mint,
+                            specification_asset_class: SpecificationAssetClass::Nft,
+                            royalty_target_type: RoyaltyTargetType::Creators,
+                            created_at: 1,
+                            edition_address: Some(edition),
+                        },
+                    )
+                    .unwrap();
+                old_storage
+                    .token_metadata_edition_cbor
+                    .put_cbor_encoded(
+                        edition,
+                        TokenMetadataEdition::EditionV1(EditionV1 {
+                            key: edition,
+                            parent,
+                            edition: edition_number as u64,
+                            write_version: 1,
+                        }),
+                    )
+                    .await
+                    .unwrap();
+            }
+            old_storage
+                .asset_dynamic_data
+                .put(
+                    burnt_mint,
+                    AssetDynamicDetails {
+                        pubkey: burnt_mint,
+                        is_burnt: Updated::new(1, None, true),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        }
+
+        let secondary_storage_dir = TempDir::new().unwrap();
+        let migration_version_manager = Storage::open_secondary(
+            dir.path().to_str().unwrap(),
+            secondary_storage_dir.path().to_str().unwrap(),
+            Arc::new(Mutex::new(JoinSet::new())),
+            Arc::new(RequestErrorDurationMetrics::new()),
+            MigrationState::Last,
+        )
+        .unwrap();
+        Storage::apply_all_migrations(
+            dir.path().to_str().unwrap(),
+            TempDir::new().unwrap().path().to_str().unwrap(),
+            Arc::new(migration_version_manager),
+        )
+        .await
+        .unwrap();
+
+        let new_storage = Storage::open(
+            dir.path().to_str().unwrap(),
+            Arc::new(Mutex::new(JoinSet::new())),
+            Arc::new(RequestErrorDurationMetrics::new()),
+            MigrationState::Last,
+        )
+        .unwrap();
+        assert_eq!(
+            new_storage.get_editions_by_parent(parent, None, None, None, 10),
+            vec![(0, edition)]
+        );
+        assert_eq!(
+            new_storage
+                .edition_mint_map
+                .get(edition)
+                .unwrap()
+                .unwrap()
+                .mint_key,
+            mint
+        );
+        assert!(new_storage
+            .edition_mint_map
+            .get(burnt_edition)
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_write_version_migration() {
+        let dir = TempDir::new().unwrap();
+        let pubkey = Pubkey::new_unique();
+        {
+            let old_storage = Storage::open(
+                dir.path().to_str().unwrap(),
+                Arc::new(Mutex::new(JoinSet::new())),
+                Arc::new(RequestErrorDurationMetrics::new()),
+                MigrationState::Last,
+            )
+            .unwrap();
+            for version in 0..DeadLetterWriteVersionMigration::VERSION {
+                old_storage
+                    .migration_version
+                    .put_async(version, MigrationVersions {})
+                    .await
+                    .unwrap();
+            }
+            old_storage
+                .dead_letters
+                .backend
+                .put_cf(
+                    &old_storage
+                        .dead_letters
+                        .backend
+                        .cf_handle(DeadLetter::NAME)
+                        .unwrap(),
+                    DeadLetter::encode_key(1),
+                    serialize(&DeadLetterWithoutWriteVersion {
+                        stream: DeadLetterStream::Accounts,
+                        data: Vec::new(),
+                        map_flatbuffer: false,
+                        pubkey: Some(pubkey),
+                        error: "failed".to_string(),
+                        failed_at: 10,
+                    })
+                    .unwrap(),
+                )
+                .unwrap();
+        }
+
+        let secondary_storage_dir = TempDir::new().unwrap();
+        let migration_version_manager = Storage::open_secondary(
+            dir.path().to_str().unwrap(),
+            secondary_storage_dir.path().to_str().unwrap(),
+            Arc::new(Mutex::new(JoinSet::new())),
+            Arc::new(RequestErrorDurationMetrics::new()),
+            MigrationState::Last,
+        )
+        .unwrap();
+        Storage::apply_all_migrations(
+            dir.path().to_str().unwrap(),
+            TempDir::new().unwrap().path().to_str().unwrap(),
+            Arc::new(migration_version_manager),
+        )
+        .await
+        .unwrap();
+
+        let new_storage = Storage::open(
+            dir.path().to_str().unwrap(),
+            Arc::new(Mutex::new(JoinSet::new())),
+            Arc::new(RequestErrorDurationMetrics::new()),
+            MigrationState::Last,
+        )
+        .unwrap();
+        let dead_letter = new_storage.dead_letters.get(1).unwrap().unwrap();
+        assert_eq!(dead_letter.pubkey, Some(pubkey));
+        assert_eq!(dead_letter.write_version, 0);
+        assert_eq!(dead_letter.error, "failed");
+    }
 }
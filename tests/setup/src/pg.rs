@@ -249,6 +249,9 @@ pub fn generate_asset_index_records(n: usize) -> Vec<AssetIndex> {
             }],
             owner_type: Some(OwnerType::Single),
             fungible_tokens: vec![],
+            name: Some(format!("Test Asset #{}", i)),
+            core_plugins: vec![],
+            core_attributes: vec![],
         };
         asset_indexes.push(asset_index);
     }